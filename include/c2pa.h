@@ -38,6 +38,234 @@
 
 
 
+/**
+ * A stable number for the kind of the last error, returned by c2pa_error_code.
+ *
+ * Codes are never renumbered, so callers can branch on them rather than on messages.
+ * Errors from the c2pa library get the most specific code known for them, so new codes
+ * may be added for kinds of error that currently get a more general code.
+ */
+typedef enum C2paErrorCode {
+  /**
+   * There is no last error.
+   */
+  ErrorNone = 0,
+  /**
+   * An error with no more specific code.
+   */
+  ErrorOther = 1,
+  /**
+   * A required parameter was NULL.
+   */
+  ErrorNullParameter = 2,
+  /**
+   * Reading or writing a file or stream failed.
+   */
+  ErrorIo = 3,
+  /**
+   * JSON could not be parsed or did not match what was expected.
+   */
+  ErrorJson = 4,
+  /**
+   * A file was not found.
+   */
+  ErrorFileNotFound = 5,
+  /**
+   * The format or operation is not supported.
+   */
+  ErrorNotSupported = 6,
+  /**
+   * A resource was not found.
+   */
+  ErrorResourceNotFound = 7,
+  /**
+   * An assertion is invalid.
+   */
+  ErrorAssertion = 8,
+  /**
+   * An assertion was not found.
+   */
+  ErrorAssertionNotFound = 9,
+  /**
+   * Data could not be decoded.
+   */
+  ErrorDecoding = 10,
+  /**
+   * Data could not be encoded.
+   */
+  ErrorEncoding = 11,
+  /**
+   * A manifest is invalid.
+   */
+  ErrorManifest = 12,
+  /**
+   * A manifest was not found.
+   */
+  ErrorManifestNotFound = 13,
+  /**
+   * The asset has no C2PA data.
+   */
+  ErrorJumbfNotFound = 14,
+  /**
+   * The manifest is remote, at the URL in the message.
+   */
+  ErrorRemoteManifestUrl = 15,
+  /**
+   * A remote manifest could not be fetched.
+   */
+  ErrorRemoteManifestFetch = 16,
+  /**
+   * The manifest is remote and fetching remote manifests is disabled.
+   */
+  ErrorRemoteFetchDisabled = 17,
+  /**
+   * Fetching a remote manifest timed out.
+   */
+  ErrorRemoteTimeout = 18,
+  /**
+   * Signing failed or a signature is invalid.
+   */
+  ErrorSignature = 19,
+  /**
+   * A signing certificate is invalid.
+   */
+  ErrorCoseInvalidCert = 20,
+  /**
+   * A signing certificate had expired when the manifest was signed.
+   */
+  ErrorCoseCertExpiration = 21,
+  /**
+   * A signing certificate has been revoked.
+   */
+  ErrorCoseCertRevoked = 22,
+  /**
+   * A signing certificate is not trusted.
+   */
+  ErrorCoseCertUntrusted = 23,
+  /**
+   * A time stamp is invalid.
+   */
+  ErrorCoseInvalidTimeStamp = 24,
+  /**
+   * A time stamp is outside the validity of the signing certificate.
+   */
+  ErrorCoseTimeStampValidity = 25,
+  /**
+   * A time stamp does not match the signed data.
+   */
+  ErrorCoseTimeStampMismatch = 26,
+  /**
+   * A time stamp could not be generated.
+   */
+  ErrorCoseTimeStampGeneration = 27,
+  /**
+   * The time stamp authority could not be reached or refused the request.
+   */
+  ErrorCoseTimeStampAuthority = 28,
+  /**
+   * The space reserved for the signature is too small.
+   */
+  ErrorCoseSigboxTooSmall = 29,
+  /**
+   * Verification failed.
+   */
+  ErrorVerify = 30,
+  /**
+   * A hash of the asset does not match the manifest.
+   */
+  ErrorHashMismatch = 31,
+  /**
+   * The claim failed verification.
+   */
+  ErrorClaimVerification = 32,
+  /**
+   * A signature was not completed in time.
+   */
+  ErrorSignatureTimeout = 33,
+} C2paErrorCode;
+
+/**
+ * The severity of a log record passed to a log callback.
+ */
+typedef enum C2paLogLevel {
+  /**
+   * No records, which removes the log callback.
+   */
+  LogOff,
+  /**
+   * Failures.
+   */
+  LogError,
+  /**
+   * Problems that did not stop an operation, such as a failed attempt that is retried.
+   */
+  LogWarn,
+  /**
+   * Notable steps, such as fetching a remote manifest.
+   */
+  LogInfo,
+  /**
+   * Details for diagnosing failures, such as the stages of hash verification.
+   */
+  LogDebug,
+  /**
+   * Everything, which may be a great many records.
+   */
+  LogTrace,
+} C2paLogLevel;
+
+/**
+ * The stage of a long running operation reported to a progress callback.
+ */
+typedef enum C2paProgressPhase {
+  /**
+   * Reading an asset to validate its manifest store.
+   */
+  Validate,
+  /**
+   * Reading the source asset while signing.
+   */
+  Hash,
+  /**
+   * Writing the signed asset.
+   */
+  Write,
+} C2paProgressPhase;
+
+/**
+ * The result of checking a C2paSignerInfo with c2pa_signer_validate.
+ */
+typedef enum C2paSignerProblem {
+  /**
+   * No problems were found.
+   */
+  SignerValid,
+  /**
+   * The signing algorithm is not one of the supported algorithms.
+   */
+  UnknownAlgorithm,
+  /**
+   * The certificate chain is not PEM, or a certificate in it can't be decoded.
+   */
+  CertificateDecode,
+  /**
+   * The private key is not PEM, or is a kind of key the crypto library can't decode.
+   */
+  PrivateKeyDecode,
+  /**
+   * The signing algorithm can't be used with the key type of the signing certificate.
+   */
+  AlgorithmMismatch,
+  /**
+   * The private key does not belong to the signing certificate.
+   */
+  KeyMismatch,
+  /**
+   * A certificate in the chain was not issued by the certificate that follows it.
+   */
+  BrokenChain,
+} C2paSignerProblem;
+
 /**
  * List of supported signing algorithms.
  */
@@ -51,8 +279,136 @@ typedef enum C2paSigningAlg {
   Ed25519,
 } C2paSigningAlg;
 
+/**
+ * The cause of a stream callback failure, reported with c2pa_stream_set_error.
+ */
+typedef enum C2paStreamError {
+  /**
+   * A failure with no more specific cause.
+   */
+  StreamOther,
+  /**
+   * The stream or its backing resource was not found.
+   */
+  StreamNotFound,
+  /**
+   * Access to the stream was denied.
+   */
+  StreamPermissionDenied,
+  /**
+   * The connection behind the stream was reset.
+   */
+  StreamConnectionReset,
+  /**
+   * The operation timed out.
+   */
+  StreamTimedOut,
+  /**
+   * The operation was interrupted and may be retried.
+   */
+  StreamInterrupted,
+  /**
+   * The stream ended before the operation completed.
+   */
+  StreamUnexpectedEof,
+  /**
+   * There was no space left to write to.
+   */
+  StreamStorageFull,
+  /**
+   * The stream does not support the operation, such as writing to a read-only stream.
+   */
+  StreamUnsupported,
+} C2paStreamError;
+
+/**
+ * The last error, with its code and the errors that caused it.
+ */
+typedef struct C2paError C2paError;
+
 typedef struct C2paSigner C2paSigner;
 
+/**
+ * A manifest template prepared once for signing many assets with one signer.
+ */
+typedef struct C2paSigningSession C2paSigningSession;
+
+/**
+ * Where the manifest store of a Reader was read from.
+ */
+typedef struct ManifestSource ManifestSource;
+
+/**
+ * The buffered state of a stream, applied to the unbuffered stream passed to each call.
+ */
+typedef struct StreamBuffer StreamBuffer;
+
+/**
+ * The verify settings of the SDK.
+ *
+ * The SDK keeps one set of settings for the process. c2pa_set_verify_options sets them
+ * for every Reader and Builder, and c2pa_reader_from_stream_with_verify_options and
+ * c2pa_builder_sign_with_verify_options for one read or signing.
+ */
+typedef struct C2paVerifyOptions {
+  /**
+   * Validate manifest stores when they are read.
+   */
+  bool verify_after_reading;
+  /**
+   * Read back and validate each signed asset, which roughly doubles the time to sign.
+   */
+  bool verify_after_sign;
+  /**
+   * Check signing certificates against the trust lists, see c2pa_set_trust_anchors.
+   */
+  bool verify_trust;
+  /**
+   * Fetch OCSP responses for signing certificates that have none stapled.
+   */
+  bool ocsp_fetch;
+  /**
+   * Fetch remote manifests, as set by c2pa_set_remote_fetch_enabled.
+   */
+  bool fetch_remote;
+  /**
+   * Check the trust of the signing certificates of ingredients.
+   */
+  bool check_ingredient_trust;
+} C2paVerifyOptions;
+
+/**
+ * Describes the version and capabilities of the library.
+ *
+ * List fields are comma separated.
+ */
+typedef struct C2paVersionInfo {
+  /**
+   * The version of this library.
+   */
+  const char *version;
+  /**
+   * The version of the c2pa-rs SDK.
+   */
+  const char *c2pa_version;
+  /**
+   * The supported asset formats as extensions and mime types.
+   */
+  const char *formats;
+  /**
+   * The crypto backend.
+   */
+  const char *crypto_backend;
+  /**
+   * The HTTP client, or NULL if network access is not compiled in.
+   */
+  const char *network;
+  /**
+   * The compiled features.
+   */
+  const char *features;
+} C2paVersionInfo;
+
 /**
  * Defines the configuration for a Signer.
  *
@@ -100,6 +456,13 @@ typedef intptr_t (*ReadCallback)(const struct StreamContext *context, uint8_t *d
  */
 typedef int (*SeekCallback)(const struct StreamContext *context, long offset, int mode);
 
+/**
+ * Defines a callback to seek to a 64 bit offset in a stream, returning the new position
+ *
+ * Unlike SeekCallback, this handles assets over 2GB where long is 32 bits, such as on Windows.
+ */
+typedef int64_t (*SeekCallback64)(const struct StreamContext *context, int64_t offset, int mode);
+
 /**
  * Defines a callback to write to a stream
  */
@@ -109,6 +472,11 @@ typedef intptr_t (*WriteCallback)(const struct StreamContext *context,
 
 typedef intptr_t (*FlushCallback)(const struct StreamContext *context);
 
+/**
+ * Defines a callback to free the context of a stream when the stream is released
+ */
+typedef void (*FreeCallback)(struct StreamContext *context);
+
 /**
  * A CStream is a Rust Read/Write/Seek stream that can be created in C
  */
@@ -118,18 +486,108 @@ typedef struct CStream {
   SeekCallback seeker;
   WriteCallback writer;
   FlushCallback flusher;
+  FreeCallback free;
+  SeekCallback64 seeker64;
+  struct StreamBuffer *buffer;
 } CStream;
 
+/**
+ * The signature of the active manifest of a C2paReader.
+ *
+ * Each field is NULL when the SDK does not report it.
+ */
+typedef struct C2paSignatureInfo {
+  /**
+   * The signing algorithm, such as "es256".
+   */
+  char *alg;
+  /**
+   * The organization that signed the manifest.
+   */
+  char *issuer;
+  /**
+   * The common name of the signing certificate.
+   */
+  char *common_name;
+  /**
+   * The serial number of the signing certificate.
+   */
+  char *cert_serial_number;
+  /**
+   * The signing time from the time-stamp authority, in RFC 3339 format.
+   */
+  char *time;
+} C2paSignatureInfo;
+
 typedef struct C2paBuilder {
 
 } C2paBuilder;
 
 /**
- * Defines a callback to read from a stream.
+ * Defines a callback that starts a signature request, such as a call to a remote KMS.
  *
  * # Parameters
- * * context: A generic context value to used by the C code, often a file or stream reference.
+ * * context: A generic context value to used by the C code.
+ * * request_id: The id to pass to c2pa_signer_complete with the signature.
+ * * data: The bytes to sign, only valid during the callback.
+ * * len: The length of the bytes to sign.
+ *
+ * Returns 0 if the request was started or a negative value if it could not be.
+ * A request that was not started must not be completed.
+ */
+typedef int (*AsyncSignerCallback)(const void *context,
+                                   uint64_t request_id,
+                                   const unsigned char *data,
+                                   uintptr_t len);
+
+/**
+ * Defines a callback to upload a signed manifest store to a remote URL.
+ *
+ * # Parameters
+ * * context: A generic context value to used by the C code.
+ * * url: The remote URL the manifest store should be available from.
+ * * data: The manifest store bytes.
+ * * len: The length of the manifest store bytes.
+ *
+ * Returns 0 on success or a negative value if the upload failed.
+ */
+typedef int (*UploadCallback)(const void *context,
+                              const char *url,
+                              const unsigned char *data,
+                              uintptr_t len);
+
+/**
+ * Bytes to write at an offset of an asset, returned by c2pa_builder_sign_placeholder.
+ */
+typedef struct C2paManifestPatch {
+  /**
+   * The offset in the asset to write at.
+   */
+  uint64_t offset;
+  /**
+   * The bytes to write.
+   */
+  const unsigned char *data;
+  /**
+   * The number of bytes in data.
+   */
+  uintptr_t len;
+} C2paManifestPatch;
+
+/**
+ * Defines a callback to sign data.
  *
+ * # Parameters
+ * * context: A generic context value to used by the C code, often a file or stream reference.
+ * * data: The bytes to sign.
+ * * len: The length of the bytes to sign.
+ * * signed_bytes: A buffer to write the signature into.
+ * * signed_len: The size of the buffer.
+ *
+ * Returns the size of the signature or a negative value if signing failed.
+ * If the returned size is larger than signed_len nothing should be written,
+ * the callback will be invoked again with a buffer of the returned size.
+ * Signing fails if it then asks for a larger buffer again, or for more than 64 KiB.
  */
 typedef intptr_t (*SignerCallback)(const void *context,
                                    const unsigned char *data,
@@ -137,6 +595,10 @@ typedef intptr_t (*SignerCallback)(const void *context,
                                    unsigned char *signed_bytes,
                                    uintptr_t signed_len);
 
+
+
+
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -151,464 +613,2909 @@ extern "C" {
 IMPORT extern char *c2pa_version(void);
 
 /**
- * Returns the last error message.
+ * Returns the version and capabilities of the library.
  *
  * # Safety
- * The returned value MUST be released by calling release_string
- * and it is no longer valid after that call.
+ * The returned value is owned by the library and must not be freed.
  */
-IMPORT extern char *c2pa_error(void);
+IMPORT extern const struct C2paVersionInfo *c2pa_version_info(void);
 
 /**
- * Load Settings from a string.
+ * Returns 1 if manifests can be read from the format, otherwise 0.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
  *
  * # Errors
- * Returns -1 if there were errors, otherwise returns 0.
- * The error string can be retrieved by calling c2pa_error.
+ * Returns -1 if format is NULL.
  *
  * # Safety
  * Reads from NULL-terminated C strings.
  */
-IMPORT extern int c2pa_load_settings(const char *settings, const char *format);
+IMPORT extern int c2pa_can_read(const char *format);
 
 /**
- * Returns a ManifestStore JSON string from a file path.
+ * Returns 1 if manifests can be signed into the format, otherwise 0.
  *
- * Any thumbnails or other binary resources will be written to data_dir if provided.
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
  *
  * # Errors
- * Returns NULL if there were errors, otherwise returns a JSON string.
- * The error string can be retrieved by calling c2pa_error.
+ * Returns -1 if format is NULL.
  *
  * # Safety
  * Reads from NULL-terminated C strings.
- * The returned value MUST be released by calling release_string
- * and it is no longer valid after that call.
  */
-IMPORT extern char *c2pa_read_file(const char *path, const char *data_dir);
+IMPORT extern int c2pa_can_sign(const char *format);
 
 /**
- * Returns an Ingredient JSON string from a file path.
+ * Returns the mime types of the formats the library can read manifests from or sign.
  *
- * Any thumbnail or C2PA data will be written to data_dir if provided.
+ * The mime types are sorted and comma separated, like the formats field of c2pa_version_info.
+ * No error is set, so this can be used to route unsupported formats before reading them.
  *
- * # Errors
- * Returns NULL if there were errors, otherwise returns a JSON string
- * containing the Ingredient.
- * The error string can be retrieved by calling c2pa_error.
+ * # Parameters
+ * * for_signing: 0 for the formats that can be read, any other value for those that can be signed.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
- * The returned value MUST be released by calling release_string
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_supported_mime_types(int for_signing);
+
+/**
+ * Returns 1 if the named feature was compiled into the library, otherwise 0.
+ *
+ * The feature names are listed in the features field of c2pa_version_info.
+ *
+ * # Errors
+ * Returns -1 if name is NULL.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_has_feature(const char *name);
+
+/**
+ * Returns the last error message.
+ *
+ * # Safety
+ * The returned value MUST be released by calling release_string
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_error(void);
+
+/**
+ * Returns a stable code for the kind of the last error.
+ *
+ * Use this to branch on the kind of error rather than parsing the message
+ * from c2pa_error, which may be reworded between releases.
+ * Returns ErrorNone if there is no last error.
+ */
+IMPORT extern enum C2paErrorCode c2pa_error_code(void);
+
+/**
+ * Takes the last error, with its code and the errors that caused it, and clears it.
+ *
+ * The message alone flattens or drops the causes, such as the io or crypto error
+ * behind a failure to read a certificate, which the sources keep.
+ *
+ * Returns NULL if there is no last error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_error_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern struct C2paError *c2pa_error_take(void);
+
+/**
+ * Returns a copy of the last error, with its code and the errors that caused it,
+ * leaving it set, so c2pa_error and c2pa_error_code still return it.
+ *
+ * Returns NULL if there is no last error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_error_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern struct C2paError *c2pa_error_copy(void);
+
+/**
+ * Returns the code of a C2paError, or ErrorNone if the error is NULL.
+ *
+ * # Safety
+ * The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+ */
+IMPORT extern enum C2paErrorCode c2pa_error_get_code(const struct C2paError *error);
+
+/**
+ * Returns the message of a C2paError, the same as c2pa_error would have returned.
+ *
+ * # Errors
+ * Returns NULL if the error is NULL.
+ *
+ * # Safety
+ * The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+ * The message belongs to the error and is no longer valid after c2pa_error_free.
+ */
+IMPORT extern const char *c2pa_error_get_message(const struct C2paError *error);
+
+/**
+ * Returns the number of errors that caused a C2paError, or 0 if the error is NULL.
+ *
+ * # Safety
+ * The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+ */
+IMPORT extern uintptr_t c2pa_error_source_count(const struct C2paError *error);
+
+/**
+ * Returns the message of an error that caused a C2paError.
+ *
+ * Index 0 is the most direct cause, and each following index caused the one before it.
+ *
+ * # Errors
+ * Returns NULL if the error is NULL or the index is not less than c2pa_error_source_count.
+ *
+ * # Safety
+ * The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+ * The message belongs to the error and is no longer valid after c2pa_error_free.
+ */
+IMPORT extern const char *c2pa_error_source(const struct C2paError *error, uintptr_t index);
+
+/**
+ * Frees a C2paError returned by c2pa_error_take or c2pa_error_copy.
+ *
+ * # Safety
+ * The error can only be freed once and is invalid after this call.
+ */
+IMPORT extern void c2pa_error_free(struct C2paError *error);
+
+/**
+ * Load Settings from a string.
+ *
+ * The settings are those of the c2pa SDK, in the same form c2patool reads them,
+ * such as the trust lists in the trust section, the verification flags in the verify
+ * section and the thumbnail behavior in the builder section. Settings that are not
+ * given keep their current values. For example, in TOML:
+ * `[verify]\nverify_after_sign = false`
+ * verify.remote_manifest_fetch enables or disables all remote manifest fetching,
+ * including the get callback, like fetch_remote in c2pa_set_verify_options.
+ * Two SDK settings are then overridden by the state this library keeps:
+ * verify.remote_manifest_fetch stays off inside the SDK while a get callback or
+ * remote fetch options are set, since the library fetches remote manifests itself, and
+ * builder.auto_thumbnail stays off while c2pa_set_thumbnail_options disables thumbnails.
+ * A loaded builder.auto_thumbnail takes effect once thumbnails are enabled again.
+ *
+ * # Parameters
+ * * settings: pointer to a C string with the settings.
+ * * format: pointer to a C string with the format of the settings, "json" or "toml".
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_load_settings(const char *settings, const char *format);
+
+/**
+ * Sets the trust anchors that readers check signing certificates against.
+ *
+ * Signing certificates that chain to an anchor are reported as signingCredential.trusted
+ * and others as signingCredential.untrusted. Setting anchors turns on the verify_trust
+ * setting, which c2pa_set_verify_options can turn off again. Clearing them does not.
+ *
+ * # Parameters
+ * * pem: pointer to a C string with the PEM certificates of the anchors, or NULL to clear them.
+ *
+ * # Errors
+ * Returns -1 if there were errors, such as certificates that can't be decoded, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_set_trust_anchors(const char *pem);
+
+/**
+ * Sets the allowed list of end-entity certificates trusted whatever they chain to.
+ *
+ * Setting the list turns on the verify_trust setting, as c2pa_set_trust_anchors does.
+ *
+ * # Parameters
+ * * pem: pointer to a C string with the PEM certificates, or base64 SHA-256 hashes of
+ *   their DER encodings one per line, or NULL to clear the list.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_set_allowed_list(const char *pem);
+
+/**
+ * Sets the trust configuration, the extended key usage OIDs a trusted signing
+ * certificate may have, one per line.
+ *
+ * # Parameters
+ * * text: pointer to a C string with the configuration, or NULL to clear it.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_set_trust_config(const char *text);
+
+/**
+ * Initializes optional background services.
+ *
+ * The options are a JSON object. Setting trust_refresh starts a background task
+ * that periodically reloads the trust settings from files or http(s) URLs, for example:
+ * `{"trust_refresh": {"interval_secs": 3600, "trust_anchors": "/etc/c2pa/anchors.pem"}}`
+ * Setting single_threaded to true starts no threads; the host calls c2pa_refresh
+ * when it wants the refresh work done, which keeps timing deterministic.
+ * Services started by an earlier call are stopped first.
+ *
+ * # Parameters
+ * * options_json: pointer to a C string with the options JSON (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_init(const char *options_json);
+
+/**
+ * Runs one refresh cycle on the caller's thread.
+ *
+ * Reloads the trust data when c2pa_init was called with single_threaded
+ * and removes expired OCSP responses from the cache.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Must not be called from a callback invoked by the library.
+ */
+IMPORT extern int c2pa_refresh(void);
+
+/**
+ * Stops any background services started by c2pa_init.
+ *
+ * # Safety
+ * Must not be called from a callback invoked by the library.
+ */
+IMPORT extern void c2pa_shutdown(void);
+
+/**
+ * Registers a manifest publisher used for remote manifests.
+ *
+ * When put is set, c2pa_builder_sign publishes the manifest store to the Builder's remote URL.
+ * When get is set, readers retrieve remote manifests through it instead of the internal HTTP client.
+ * Passing NULL for both callbacks removes the publisher.
+ *
+ * # Parameters
+ * * context: a context value passed to the callbacks.
+ * * put: a callback to store a manifest store at a URL (optional, can be NULL).
+ * * get: a callback to retrieve a manifest store from a URL (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The context must remain valid until the publisher is removed.
+ */
+IMPORT extern
+int c2pa_set_manifest_publisher(const void *context,
+                                int (*put)(const void *context,
+                                           const char *url,
+                                           const unsigned char *data,
+                                           uintptr_t len),
+                                intptr_t (*get)(const void *context,
+                                                const char *url,
+                                                unsigned char *buffer,
+                                                uintptr_t buffer_len));
+
+/**
+ * Registers a callback that retrieves remote manifests with the host's HTTP stack.
+ *
+ * Readers and ingredients fetch remote manifests through the callback instead of the
+ * internal HTTP client, so host proxies, authentication and certificate pinning apply.
+ * This replaces the get callback of c2pa_set_manifest_publisher and keeps its put callback.
+ * Passing NULL restores the built-in fetching.
+ *
+ * # Parameters
+ * * context: a context value passed to the callback.
+ * * fetch: a callback to retrieve a manifest store from a URL (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The context must remain valid until the fetcher is removed.
+ */
+IMPORT extern
+int c2pa_set_remote_fetcher(const void *context,
+                            intptr_t (*fetch)(const void *context,
+                                              const char *url,
+                                              unsigned char *buffer,
+                                              uintptr_t buffer_len));
+
+/**
+ * Sets options for fetching remote manifests, such as authentication headers.
+ *
+ * The options are a JSON object, for example:
+ * `{"headers": {"Authorization": "Bearer <token>"}, "retry": {"retries": 3, "backoff_ms": 250},
+ * "timeout_ms": 5000}`
+ * Failed requests are retried with exponential backoff and the error lists every attempt.
+ * A request that takes longer than timeout_ms is abandoned. If the last attempt timed out,
+ * reading fails with an error starting with "RemoteTimeout" rather than "Remote".
+ * A get callback registered with c2pa_set_manifest_publisher or c2pa_set_remote_fetcher
+ * takes precedence over these options, but retry and timeout_ms still apply to it.
+ * The callback is not interrupted, so a manifest store it returns after timeout_ms
+ * is discarded as timed out.
+ *
+ * # Parameters
+ * * options_json: pointer to a C string with the options JSON (NULL restores the defaults).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_set_remote_fetch_options(const char *options_json);
+
+/**
+ * Enables or disables fetching remote manifests.
+ *
+ * While disabled the library makes no remote manifest requests, whether through a get
+ * callback, c2pa_set_remote_fetch_options or the SDK's own fetcher. Reading an asset that
+ * refers to a remote manifest then fails with an error starting with "RemoteFetchDisabled",
+ * followed by the manifest URL. Fetching is enabled by default.
+ *
+ * # Parameters
+ * * enabled: 0 to disable fetching, any other value to enable it.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * This function is always safe to call.
+ */
+IMPORT extern int c2pa_set_remote_fetch_enabled(int enabled);
+
+/**
+ * Gets the verify settings, such as to change some of them with c2pa_set_verify_options.
+ *
+ * # Parameters
+ * * options: pointer to a C2paVerifyOptions set to the current settings.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The options must point to a writable C2paVerifyOptions.
+ */
+IMPORT extern int c2pa_get_verify_options(struct C2paVerifyOptions *options);
+
+/**
+ * Sets the verify settings for every Reader and Builder.
+ *
+ * For example, a signing service may turn off verify_after_sign to sign faster,
+ * while a validator turns on verify_trust.
+ *
+ * # Parameters
+ * * options: pointer to the settings.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The options must point to a valid C2paVerifyOptions.
+ */
+IMPORT extern int c2pa_set_verify_options(const struct C2paVerifyOptions *options);
+
+/**
+ * Enables or disables looking for a sidecar manifest store when reading files.
+ *
+ * When enabled, c2pa_read_file and c2pa_reader_from_file read an asset without a manifest
+ * store against a sidecar file next to it with the extension ".c2pa", such as asset.c2pa
+ * for asset.jpg. c2pa_reader_sidecar_path then returns the path of the sidecar, and
+ * c2pa_read_file and c2pa_verify_directory report it in a sidecar_path field.
+ * Discovery is disabled by default.
+ *
+ * # Parameters
+ * * enabled: 0 to disable discovery, any other value to enable it.
+ *
+ * # Errors
+ * Returns 0.
+ *
+ * # Safety
+ * This function is always safe to call.
+ */
+IMPORT extern int c2pa_set_sidecar_discovery(int enabled);
+
+/**
+ * Registers a resolver that looks up candidate manifest stores for a soft binding.
+ *
+ * The resolver is used by c2pa_reader_from_soft_binding to recover the manifest
+ * of an asset whose manifest store was removed, for example by a screenshot or re-encode.
+ * Passing NULL removes the resolver.
+ *
+ * # Parameters
+ * * context: a context value passed to the callback.
+ * * resolve: a callback returning candidate manifest stores (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The context must remain valid until the resolver is removed.
+ */
+IMPORT extern
+int c2pa_set_soft_binding_resolver(const void *context,
+                                   intptr_t (*resolve)(const void *context,
+                                                       const char *alg,
+                                                       const char *value,
+                                                       uintptr_t index,
+                                                       unsigned char *buffer,
+                                                       uintptr_t buffer_len));
+
+/**
+ * Registers a callback that reports progress while reading and signing assets.
+ *
+ * The callback is invoked as the asset streams of reader creation and builder signing
+ * are processed, at most once per megabyte and when a stream is complete.
+ * The bytes processed are how far through the stream the operation is, so parts of an
+ * asset read again are not counted twice. An operation uses the callback registered when
+ * it started, and the callback is not called with a library lock held.
+ * Returning a non-zero value from the callback cancels the operation, which then fails.
+ * Passing NULL removes the callback.
+ *
+ * # Parameters
+ * * context: a context value passed to the callback.
+ * * progress: a callback receiving the phase, bytes processed and total (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The context must remain valid until the callback is removed.
+ */
+IMPORT extern
+int c2pa_set_progress_callback(const void *context,
+                               int (*progress)(const void *context,
+                                               enum C2paProgressPhase phase,
+                                               uint64_t processed,
+                                               uint64_t total));
+
+/**
+ * Registers a callback that receives the log records of this library and the SDK.
+ *
+ * Records at the level and more severe ones are passed to the callback, from whichever
+ * thread logged them, such as remote manifest fetch attempts, time stamp requests and
+ * the stages of hash verification. Passing NULL or LogOff removes the callback.
+ * The callback may call back into the library, but records logged on its thread while
+ * it runs are dropped rather than passed to it again.
+ *
+ * # Parameters
+ * * context: a context value passed to the callback.
+ * * level: the least severe level of record to pass to the callback.
+ * * callback: a callback receiving each record (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, such as another logger being installed in the
+ * process by an application using this library from Rust, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The context must remain valid until the callback is removed.
+ */
+IMPORT extern
+int c2pa_set_log_callback(const void *context,
+                          enum C2paLogLevel level,
+                          void (*callback)(const void *context,
+                                           enum C2paLogLevel level,
+                                           const char *target,
+                                           const char *message));
+
+/**
+ * Sets options for the thumbnails generated while signing and adding ingredients.
+ *
+ * Once options are set, thumbnails are color managed for JPEG, PNG and WebP assets
+ * with an embedded ICC profile. Other thumbnails are still made by the SDK,
+ * which also makes all thumbnails when no options are set, unless a format,
+ * longest_edge or quality other than the SDK's is asked for. Thumbnails of other
+ * asset types, such as GIF, TIFF, HEIC or video, are made by the SDK in its own
+ * format and size whatever the options.
+ * The options are a JSON object, for example:
+ * `{"enabled": true, "color_space": "srgb", "format": "webp", "longest_edge": 1024, "quality": 80}`
+ * A color_space of "source" keeps the source pixels and embeds the source profile,
+ * while "srgb" converts to sRGB, embedding the source profile if it cannot be converted.
+ * The format is "jpeg", "png" or "webp", and WebP thumbnails are lossless.
+ * Setting enabled to false signs without a generated claim thumbnail, which saves
+ * decoding and re-encoding the asset. Ingredient thumbnails are still made by the SDK.
+ * This turns off builder.auto_thumbnail in the SDK settings until thumbnails are
+ * enabled again or the options are cleared, which puts back its earlier value.
+ *
+ * # Parameters
+ * * options_json: pointer to a C string with the options JSON (NULL leaves thumbnails to the SDK).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_set_thumbnail_options(const char *options_json);
+
+/**
+ * Sets options for hashing RIFF assets such as WAV, AVI and WebP when signing.
+ *
+ * The data of the listed top level chunks is left out of the hard binding, so tools
+ * that rewrite those chunks in place, such as broadcast tools updating bext timestamps,
+ * do not invalidate the manifest. The chunk ids and sizes are still bound.
+ * The options are a JSON object, for example: `{"exclude_chunks": ["bext", "iXML"]}`
+ * At most 9 chunks can be excluded. Assets signed with a remote URL or without
+ * embedding are hashed in full.
+ *
+ * # Parameters
+ * * options_json: pointer to a C string with the options JSON (NULL hashes RIFF assets in full).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_set_riff_hash_options(const char *options_json);
+
+/**
+ * Returns a ManifestStore JSON string from a file path.
+ *
+ * Any thumbnails or other binary resources will be written to data_dir if provided.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling release_string
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_read_file(const char *path, const char *data_dir);
+
+/**
+ * Returns an Ingredient JSON string from a file path.
+ *
+ * Any thumbnail or C2PA data will be written to data_dir if provided.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string
+ * containing the Ingredient.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling release_string
  * and it is no longer valid after that call.
  */
 IMPORT extern char *c2pa_read_ingredient_file(const char *path, const char *data_dir);
 
 /**
- * Add a signed manifest to the file at path with the given signer information.
+ * Add a signed manifest to the file at path with the given signer information.
+ *
+ * # Errors
+ * Returns an error field if there were errors.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling release_string
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_sign_file(const char *source_path,
+                     const char *dest_path,
+                     const char *manifest,
+                     const struct C2paSignerInfo *signer_info,
+                     const char *data_dir);
+
+/**
+ * Verifies a COSE_Sign1 signature, such as a detached claim signature.
+ *
+ * Returns a JSON object with the alg, the base64 encoded payload and the subject,
+ * common_name, issuer and serial_number of the signing certificate.
+ * Its trusted field is true if the certificate chain is on the allowed list or chains to
+ * a trust anchor, see c2pa_set_trust_anchors; an untrusted chain is not an error.
+ *
+ * # Parameters
+ * * cose: pointer to the COSE_Sign1 bytes.
+ * * cose_len: the length of the COSE_Sign1 bytes.
+ * * payload: pointer to the detached payload bytes (optional, can be NULL if the payload is embedded).
+ * * payload_len: the length of the payload bytes.
+ * * certs: pointer to a C string with a PEM certificate chain to verify against
+ *   (optional, can be NULL to use the certificates in the signature).
+ *
+ * # Errors
+ * Returns NULL if the signature is not valid or there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * cose must point to at least cose_len bytes and payload to at least payload_len bytes.
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_cose_verify(const unsigned char *cose,
+                       uintptr_t cose_len,
+                       const unsigned char *payload,
+                       uintptr_t payload_len,
+                       const char *certs);
+
+/**
+ * Validates every supported asset in a directory and returns an aggregated JSON report.
+ *
+ * The report has counts of valid, untrusted, invalid, no_manifest and errors,
+ * and a files array with the verdict and validation status codes for each asset.
+ * Assets are validated concurrently unless c2pa_init was called with single_threaded.
+ * Symbolic links to directories are not followed when recursing.
+ *
+ * # Parameters
+ * * dir: pointer to a C string with the directory path.
+ * * options_json: pointer to a C string with JSON options (optional, can be NULL),
+ *   for example `{"recursive": true, "threads": 4}`.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_verify_directory(const char *dir, const char *options_json);
+
+/**
+ * Validates a list of assets and returns an aggregated JSON report.
+ *
+ * The report has the same form as the one returned by c2pa_verify_directory.
+ *
+ * # Parameters
+ * * paths_json: pointer to a C string with a JSON array of file paths.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_verify_files(const char *paths_json);
+
+/**
+ * Returns a normalized, pretty printed version of a manifest definition JSON string.
+ *
+ * Defaults are filled in, shorthand is expanded and object keys are sorted,
+ * so equivalent definitions produce identical output suitable for diffing.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_manifest_json_canonicalize(const char *manifest_json);
+
+/**
+ * Frees a string allocated by Rust.
+ *
+ * Deprecated: for backward api compatibility only.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The string must not have been modified in C.
+ * The string can only be freed once and is invalid after this call.
+ */
+IMPORT extern void c2pa_release_string(char *s);
+
+/**
+ * Frees a string allocated by Rust.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The string must not have been modified in C.
+ * The string can only be freed once and is invalid after this call.
+ */
+IMPORT extern void c2pa_string_free(char *s);
+
+/**
+ * Creates and verifies a C2paReader from an asset stream with the given format.
+ *
+ * Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * stream: pointer to a CStream.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ *
+ * # Example
+ * ```c
+ * auto result = c2pa_reader_from_stream("image/jpeg", stream);
+ * if (result == NULL) {
+ *     printf("Error: %s\n", c2pa_error());
+ * }
+ * ```
+ */
+IMPORT extern
+struct C2paReader *c2pa_reader_from_stream(const char *format,
+                                           struct CStream *stream);
+
+/**
+ * Creates and verifies a C2paReader from an asset stream, with verify settings for this read.
+ *
+ * The settings apply while the stream is read and the previous settings are put back after.
+ * See c2pa_set_verify_options, which sets them for every Reader and Builder.
+ * Reads and signs with settings of their own run one at a time.
+ *
+ * Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * stream: pointer to a CStream.
+ * * options: pointer to the verify settings.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paReader.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The options must point to a valid C2paVerifyOptions.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+struct C2paReader *c2pa_reader_from_stream_with_verify_options(const char *format,
+                                                              struct CStream *stream,
+                                                              const struct C2paVerifyOptions *options);
+
+/**
+ * Creates and verifies a C2paReader from an asset stream on a worker thread.
+ *
+ * Returns immediately and invokes the completion callback from the worker thread
+ * once the reader is created, with either the reader or an error message.
+ * The reader is owned by the callback and MUST be released by calling c2pa_reader_free.
+ * The error message is only valid during the callback.
+ * If c2pa_init was called with single_threaded, the read runs on the caller's thread
+ * and the callback is invoked before this function returns.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * stream: pointer to a CStream, which must remain valid until the callback is invoked.
+ * * completion: a callback receiving the user data, the reader or NULL, and the error or NULL.
+ * * user_data: a context value passed to the callback.
+ *
+ * # Errors
+ * Returns -1 if the read could not be started, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The stream callbacks and the completion callback may be called from another thread.
+ */
+IMPORT extern
+int c2pa_reader_from_stream_async(const char *format,
+                                  struct CStream *stream,
+                                  void (*completion)(const void *user_data,
+                                                     struct C2paReader *reader,
+                                                     const char *error),
+                                  const void *user_data);
+
+/**
+ * Creates and verifies a C2paReader from the init segment of a fragmented BMFF asset
+ * and its fragments, such as the segments of a DASH presentation.
+ *
+ * Each fragment is validated against the manifest store carried in the init segment.
+ * The validation status is that of the first fragment that failed, if any, and
+ * c2pa_reader_fragment_status returns the status of each fragment.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * init_stream: pointer to a CStream with the init segment.
+ * * fragment_streams: pointer to an array of CStream pointers, one per fragment.
+ * * count: the number of fragment streams.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * fragment_streams must point to count valid CStream pointers.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+struct C2paReader *c2pa_reader_from_fragmented_stream(const char *format,
+                                                      struct CStream *init_stream,
+                                                      struct CStream *const *fragment_streams,
+                                                      uintptr_t count);
+
+/**
+ * Creates and verifies a C2paReader from a file path.
+ *
+ * The format is taken from the file extension.
+ *
+ * # Parameters
+ * * path: pointer to a C string with the path to the asset.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ *
+ * # Example
+ * ```c
+ * auto result = c2pa_reader_from_file("tests/fixtures/C.jpg");
+ * if (result == NULL) {
+ *     printf("Error: %s\n", c2pa_error());
+ * }
+ * ```
+ */
+IMPORT extern struct C2paReader *c2pa_reader_from_file(const char *path);
+
+/**
+ * Creates and verifies a C2paReader from an asset held in memory.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * data: pointer to the asset bytes.
+ * * len: the number of bytes in data.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The data is only read during this call and remains owned by the caller.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+struct C2paReader *c2pa_reader_from_bytes(const char *format,
+                                          const unsigned char *data,
+                                          uintptr_t len);
+
+/**
+ * Creates and verifies a C2paReader from detached manifest store bytes and the asset they describe.
+ *
+ * The manifest store is validated against the asset without being embedded in it.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension of the asset.
+ * * manifest_data: pointer to the manifest store bytes, such as the contents of a .c2pa file.
+ * * manifest_len: the number of bytes in manifest_data.
+ * * stream: pointer to a readable CStream with the asset.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The manifest data is only read during this call and remains owned by the caller.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+struct C2paReader *c2pa_reader_from_manifest_data_and_stream(const char *format,
+                                                             const unsigned char *manifest_data,
+                                                             uintptr_t manifest_len,
+                                                             struct CStream *stream);
+
+/**
+ * Creates and verifies a C2paReader, recovering the manifest through a soft binding if needed.
+ *
+ * If the asset has no manifest store, the resolver registered with
+ * c2pa_set_soft_binding_resolver is asked for candidates and the first one whose
+ * active manifest declares the soft binding is validated against the asset.
+ * Hard binding failures are expected in the validation status of a recovered manifest.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * stream: pointer to a readable CStream.
+ * * alg: pointer to a C string with the soft binding algorithm.
+ * * value: pointer to a C string with the soft binding value computed from the asset.
+ *
+ * # Errors
+ * Returns NULL if there were errors or no candidate matched, otherwise returns a pointer to a ManifestStore.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_reader_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+struct C2paReader *c2pa_reader_from_soft_binding(const char *format,
+                                                 struct CStream *stream,
+                                                 const char *alg,
+                                                 const char *value);
+
+/**
+ * Frees a C2paReader allocated by Rust.
+ *
+ * # Safety
+ * The C2paReader can only be freed once and is invalid after this call.
+ */
+IMPORT extern void c2pa_reader_free(struct C2paReader *reader_ptr);
+
+/**
+ * Returns a JSON string generated from a C2paReader.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_json(struct C2paReader *reader_ptr);
+
+/**
+ * Writes a C2paReader resource to a stream given a URI.
+ *
+ * The resource uri should match an identifier in the the manifest store.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * uri: pointer to a C string with the URI to identify the resource.
+ * * stream: pointer to a writable CStream.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns size of stream written.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ *
+ * # Example
+ * ```c
+ * result c2pa_reader_resource_to_stream(store, "uri", stream);
+ * if (result < 0) {
+ *     printf("Error: %s\n", c2pa_error());
+ * }
+ * ```
+ */
+IMPORT extern
+int c2pa_reader_resource_to_stream(struct C2paReader *reader_ptr,
+                                   const char *uri,
+                                   struct CStream *stream);
+
+/**
+ * Returns a C2paReader resource in a buffer allocated by the library, given a URI.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * uri: pointer to a C string with the URI to identify the resource.
+ * * data_ptr: pointer to a pointer to a c_uchar to return the resource bytes.
+ * * data_len: pointer to a usize to return the size of the resource.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the resource.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned data MUST be released by calling c2pa_resource_bytes_free with its length
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_reader_resource_to_buffer(struct C2paReader *reader_ptr,
+                                   const char *uri,
+                                   const unsigned char **data_ptr,
+                                   uintptr_t *data_len);
+
+/**
+ * Frees bytes returned by c2pa_reader_resource_to_buffer, c2pa_reader_manifest_bytes,
+ * c2pa_builder_sign_bytes, c2pa_builder_manifest_placeholder or c2pa_format_embeddable.
+ *
+ * # Parameters
+ * * data_ptr: pointer to the bytes.
+ * * data_len: the size returned with the bytes.
+ *
+ * # Safety
+ * The bytes can only be freed once and are invalid after this call.
+ */
+IMPORT extern void c2pa_resource_bytes_free(const unsigned char *data_ptr, uintptr_t data_len);
+
+/**
+ * Writes the thumbnail of an ingredient in a C2paReader to a stream.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * manifest_label: pointer to a C string with the label of the manifest holding the ingredient,
+ *   or NULL for the active manifest.
+ * * ingredient_index: the index of the ingredient in the manifest's ingredients.
+ * * stream: pointer to a writable CStream.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the thumbnail.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern
+int c2pa_reader_ingredient_thumbnail(struct C2paReader *reader_ptr,
+                                     const char *manifest_label,
+                                     uintptr_t ingredient_index,
+                                     struct CStream *stream);
+
+/**
+ * Returns a JSON array describing every resource in a C2paReader.
+ *
+ * Each entry has the "manifest" label, the "uri" to pass to c2pa_reader_resource_to_stream,
+ * the "format" mime type when the manifest references the resource with one and the "size" in bytes.
+ * Resources include thumbnails, icons and data boxes.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_reader_resource_list(struct C2paReader *reader_ptr);
+
+/**
+ * Writes the JSON report and all resources from a C2paReader to a folder.
+ *
+ * The report is written to manifest.json and resources are written to
+ * sub-folders named for their manifest labels, as c2pa_read_file does with a data_dir.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * dir: pointer to a C string with the folder path, which is created if needed.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern int c2pa_reader_to_folder(struct C2paReader *reader_ptr, const char *dir);
+
+/**
+ * Returns the URL a C2paReader fetched its manifest store from.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the manifest store was not fetched from a remote URL,
+ * otherwise returns a C string.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_remote_url(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the path of the sidecar file a C2paReader read its manifest store from.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the manifest store was not read from a sidecar,
+ * otherwise returns a C string.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_sidecar_path(struct C2paReader *reader_ptr);
+
+/**
+ * Returns whether the manifest store of a C2paReader was embedded in the asset.
+ *
+ * Manifest stores fetched from a remote URL, read from detached manifest data or a sidecar,
+ * or recovered through a soft binding are not embedded.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns -1 if there were errors, 1 if the manifest store was embedded, otherwise 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * reader_ptr must be a valid pointer to a Reader.
+ */
+IMPORT extern int c2pa_reader_is_embedded(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the manifest store bytes of a C2paReader exactly as they were read.
+ *
+ * These are the bytes embedded in the asset, fetched from a remote URL
+ * or supplied as detached manifest data.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * data_ptr: pointer to a pointer to a c_uchar to return the manifest store bytes.
+ * * data_len: pointer to a usize to return the size of the manifest store.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned data MUST be released by calling c2pa_resource_bytes_free with its length
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_reader_manifest_bytes(struct C2paReader *reader_ptr,
+                               const unsigned char **data_ptr,
+                               uintptr_t *data_len);
+
+/**
+ * Returns the label of the active manifest of a C2paReader.
+ *
+ * The label can be used to build JUMBF URIs for c2pa_reader_resource_to_stream.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors or there is no active manifest, otherwise returns a C string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_active_manifest_label(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the number of manifests in a C2paReader.
+ *
+ * Use c2pa_reader_manifest_label_at to walk the manifests in provenance order.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the number of manifests.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * reader_ptr must be a valid pointer to a Reader.
+ */
+IMPORT extern int c2pa_reader_manifest_count(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the label of the manifest at an index of a C2paReader.
+ *
+ * The manifests are in provenance order: the active manifest is at index 0, followed by
+ * the manifests of its ingredients breadth first, then any manifests no ingredient refers to.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * index: the index of the manifest, less than c2pa_reader_manifest_count.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the index is out of range, otherwise returns a C string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_manifest_label_at(struct C2paReader *reader_ptr, uintptr_t index);
+
+/**
+ * Returns a JSON string for one manifest of a C2paReader.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * label: pointer to a C string with the manifest label.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the manifest was not found, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_manifest_json(struct C2paReader *reader_ptr, const char *label);
+
+/**
+ * Returns the body of one assertion of a C2paReader as a JSON string.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * manifest_label: pointer to a C string with the manifest label, or NULL for the active manifest.
+ * * assertion_label: pointer to a C string with the assertion label, such as "c2pa.actions".
+ * * instance: the instance of the assertion label, starting at 1.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the assertion was not found, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_reader_get_assertion(struct C2paReader *reader_ptr,
+                                const char *manifest_label,
+                                const char *assertion_label,
+                                uintptr_t instance);
+
+/**
+ * Returns the CBOR of one assertion of a C2paReader exactly as it is stored.
+ *
+ * Unlike c2pa_reader_get_assertion, byte strings and other CBOR values are kept.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * manifest_label: pointer to a C string with the manifest label, or NULL for the active manifest.
+ * * assertion_label: pointer to a C string with the assertion label, such as "c2pa.actions".
+ * * instance: the instance of the assertion label, starting at 1.
+ * * data_ptr: pointer to a pointer to a c_uchar to return the CBOR data.
+ * * data_len: pointer to a usize to return the size of the CBOR data.
+ *
+ * # Errors
+ * Returns -1 if there were errors or the assertion was not found,
+ * otherwise returns the size of the CBOR data.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned data MUST be released by calling c2pa_resource_bytes_free with its length
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_reader_get_assertion_cbor(struct C2paReader *reader_ptr,
+                                   const char *manifest_label,
+                                   const char *assertion_label,
+                                   uintptr_t instance,
+                                   const unsigned char **data_ptr,
+                                   uintptr_t *data_len);
+
+/**
+ * Returns the validation status of a C2paReader as a JSON array.
+ *
+ * Each entry has a "code", such as "assertion.dataHash.mismatch", and when known
+ * the "url" of the manifest part it refers to and an "explanation".
+ * The array is empty if the manifest store is valid.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_validation_status(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the validation status of each fragment a C2paReader was created from
+ * by c2pa_reader_from_fragmented_stream, as a JSON array with one entry per fragment.
+ *
+ * Each entry is an array like that of c2pa_reader_validation_status, empty if the fragment
+ * is valid. The array is empty if the Reader was not created from fragments.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_fragment_status(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the validation results of a C2paReader as a JSON object.
+ *
+ * The "active_manifest" entry holds the statuses of the active manifest and
+ * "ingredient_deltas" holds the statuses recorded for each ingredient, with the label of the
+ * manifest containing it. Each group of statuses has "success", "informational" and "failure" arrays.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_reader_validation_results(struct C2paReader *reader_ptr);
+
+/**
+ * Returns the CAWG identity assertions of a C2paReader as a JSON array.
+ *
+ * Each entry has the "manifest" and "label" of the assertion, its "sig_type" and
+ * "credential_type", the "roles" and "referenced_assertions" of the signer payload,
+ * the "named_actor" described by the credential and whether the signature is valid.
+ * X.509 signatures are verified over the stored signer payload, the referenced assertions
+ * are checked against the claim and the signer must chain to a configured trust anchor
+ * or be on the allowed list. Identity claims aggregation credentials are only decoded.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_identity_assertions(struct C2paReader *reader_ptr);
+
+/**
+ * Returns a short human-readable summary of the active manifest of a C2paReader.
+ *
+ * The summary describes the tool and date of creation, the signer, the editing tools,
+ * any generative AI tools used and the number of ingredients,
+ * and notes when the content credentials could not be verified.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * locale: pointer to a C string with a locale such as "en-US" or "fr"
+ *   (optional, can be NULL for English). Untranslated locales use English.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a C string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_reader_summary(struct C2paReader *reader_ptr, const char *locale);
+
+/**
+ * Returns a JSON array describing the certificate chain that signed a manifest.
+ *
+ * Each entry has the subject, issuer, serial number, validity period, algorithms,
+ * key usages, subject alternative names, extensions and SHA-256 fingerprint
+ * of one certificate, starting with the signing certificate.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * label: pointer to a C string with a manifest label (optional, can be NULL for the active manifest).
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_reader_certificates(struct C2paReader *reader_ptr,
+                               const char *label);
+
+/**
+ * Returns the PEM certificate chain that signed a manifest of a C2paReader.
+ *
+ * The signing certificate comes first, so the chain can be evaluated independently.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ * * manifest_label: pointer to a C string with the manifest label, or NULL for the active manifest.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the manifest is not signed, otherwise returns a PEM string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+char *c2pa_reader_cert_chain(struct C2paReader *reader_ptr,
+                             const char *manifest_label);
+
+/**
+ * Returns the signature info of the active manifest of a C2paReader.
+ *
+ * # Parameters
+ * * reader_ptr: pointer to a Reader.
+ *
+ * # Errors
+ * Returns NULL if there were errors or the manifest is not signed.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_signature_info_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern struct C2paSignatureInfo *c2pa_reader_signature_info(struct C2paReader *reader_ptr);
+
+/**
+ * Frees a C2paSignatureInfo returned by c2pa_reader_signature_info.
+ *
+ * # Safety
+ * The signature info can only be freed once and is invalid after this call.
+ */
+IMPORT extern void c2pa_signature_info_free(struct C2paSignatureInfo *info);
+
+/**
+ * Returns a JSON array describing hash validation failures in an asset stream.
+ *
+ * Each entry reports the validation status code, the failing assertion and
+ * manifest labels and, for data hash assertions, the expected and computed digests.
+ * An empty array is returned if no hash failures were found.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * stream: pointer to a readable CStream.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_hash_failures(const char *format, struct CStream *stream);
+
+/**
+ * Returns a JSON object with the provenance identifiers of an asset stream.
+ *
+ * The object has the instance_id, document_id, active_manifest label,
+ * the hard_bindings of the active manifest with their digests and the remote_url,
+ * each of which may be null, so assets can be indexed without storing full reports.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * stream: pointer to a readable CStream.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_content_id(const char *format, struct CStream *stream);
+
+/**
+ * Creates a C2paBuilder from a JSON manifest definition string.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_builder_free
+ * and it is no longer valid after that call.
+ *
+ * # Example
+ * ```c
+ * auto result = c2pa_builder_from_json(manifest_json);
+ * if (result == NULL) {
+ *     printf("Error: %s\n", c2pa_error());
+ * }
+ * ```
+ */
+IMPORT extern struct C2paBuilder *c2pa_builder_from_json(const char *manifest_json);
+
+/**
+ * Create a C2paBuilder from an archive stream.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned value MUST be released by calling c2pa_builder_free
+ * and it is no longer valid after that call.
+ *
+ * # Example
+ * ```c
+ * auto result = c2pa_builder_from_archive(stream);
+ * if (result == NULL) {
+ *     printf("Error: %s\n", c2pa_error());
+ * }
+ * ```
+ */
+IMPORT extern struct C2paBuilder *c2pa_builder_from_archive(struct CStream *stream);
+
+/**
+ * Frees a C2paBuilder allocated by Rust.
+ *
+ * # Safety
+ * The C2paBuilder can only be freed once and is invalid after this call.
+ */
+IMPORT extern void c2pa_builder_free(struct C2paBuilder *builder_ptr);
+
+/**
+ * Sets the no-embed flag on the Builder.
+ * When set, the builder will not embed a C2PA manifest store into the asset when signing.
+ * This is useful when creating cloud or sidecar manifests.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * no_embed: 0 to embed the manifest store, any other value to leave it out of the asset.
+ * # Errors
+ * Returns -1 if builder_ptr is NULL, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern int c2pa_builder_set_no_embed(struct C2paBuilder *builder_ptr, int no_embed);
+
+/**
+ * Sets the remote URL on the Builder.
+ * When set, the builder will embed a remote URL into the asset when signing.
+ * This is useful when creating cloud based Manifests.
+ * The URL may contain `{instance_id}`, `{filename}` and `{manifest_hash}` placeholders,
+ * which are substituted when signing. The instance_id and filename (the title) are
+ * percent-encoded as a path segment.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * remote_url: pointer to a C string with the remote URL.
+ * # Errors
+ * Returns -1 if builder_ptr or remote_url is NULL, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern
+int c2pa_builder_set_remote_url(struct C2paBuilder *builder_ptr,
+                                const char *remote_url);
+
+/**
+ * Sets the title of the C2paBuilder's manifest, replacing the title in the manifest definition.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * title: pointer to a C string with the title, such as the asset file name.
+ * # Errors
+ * Returns -1 if builder_ptr or title is NULL, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern int c2pa_builder_set_title(struct C2paBuilder *builder_ptr, const char *title);
+
+/**
+ * Sets the format of the C2paBuilder's manifest, replacing the format in the manifest definition.
+ * Signing an asset sets the format to that of the asset, so this is only needed
+ * when the manifest is archived or embedded by the caller.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type or extension.
+ * # Errors
+ * Returns -1 if builder_ptr or format is NULL, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern int c2pa_builder_set_format(struct C2paBuilder *builder_ptr, const char *format);
+
+/**
+ * Sets the claim generator of the C2paBuilder's manifest.
+ * The claim generator info in the manifest definition is replaced by a single entry.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * name: pointer to a C string with the name of the application, such as "Editor".
+ * * version: pointer to a C string with the version of the application, or NULL.
+ * # Errors
+ * Returns -1 if builder_ptr or name is NULL, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern
+int c2pa_builder_set_claim_generator_info(struct C2paBuilder *builder_ptr,
+                                          const char *name,
+                                          const char *version);
+
+/**
+ * Checks that the C2paBuilder can create claims of a version when signing.
+ * The bundled SDK only creates version 1 claims, so version 1 is accepted and changes nothing,
+ * and any other version is an error rather than a different claim than expected.
+ * The version is not stored, so it does not keep a later SDK from creating other versions.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * version: the claim version, such as 1.
+ * # Errors
+ * Returns -1 if builder_ptr is NULL or the version is not supported, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern int c2pa_builder_set_claim_version(struct C2paBuilder *builder_ptr, int version);
+
+/**
+ * Sets the directory the C2paBuilder resolves resource identifiers from when signing.
+ * Resources in the manifest definition, such as a thumbnail with the identifier
+ * "thumbnail.jpg", are then read from files relative to the directory,
+ * as with a c2patool manifest folder.
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * base_path: pointer to a C string with the path to the directory.
+ * # Errors
+ * Returns -1 if there were errors or the directory does not exist, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern
+int c2pa_builder_set_base_path(struct C2paBuilder *builder_ptr,
+                               const char *base_path);
+
+/**
+ * Sets the claim thumbnail of the C2paBuilder from a stream.
+ *
+ * The thumbnail replaces any thumbnail in the manifest definition, and no thumbnail
+ * is generated from the asset when signing.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type of the thumbnail, such as "image/jpeg".
+ * * stream: pointer to a CStream with the thumbnail image.
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern
+int c2pa_builder_set_thumbnail(struct C2paBuilder *builder_ptr,
+                               const char *format,
+                               struct CStream *stream);
+
+/**
+ * Adds a resource to the C2paBuilder.
+ *
+ * The resource uri should match an identifier in the manifest definition.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * uri: pointer to a C string with the URI to identify the resource.
+ * * stream: pointer to a CStream.
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ */
+IMPORT extern
+int c2pa_builder_add_resource(struct C2paBuilder *builder_ptr,
+                              const char *uri,
+                              struct CStream *stream);
+
+/**
+ * Adds an assertion with a label and JSON data to the C2paBuilder.
+ *
+ * The assertion is added to those in the manifest definition and stored as CBOR,
+ * for example with the label "org.example.review" and the data `{"approved": true}`.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * label: pointer to a C string with the assertion label.
+ * * assertion_json: pointer to a C string with the JSON assertion data.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ */
+IMPORT extern
+int c2pa_builder_add_assertion(struct C2paBuilder *builder_ptr,
+                               const char *label,
+                               const char *assertion_json);
+
+/**
+ * Adds an assertion with a label and CBOR data to the C2paBuilder.
+ *
+ * Use this for assertions with byte strings or other values that JSON cannot hold.
+ * The data is decoded and encoded again when the manifest is signed,
+ * so map keys may be reordered, but the values are kept.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * label: pointer to a C string with the assertion label.
+ * * data: pointer to the CBOR assertion data.
+ * * len: the number of bytes in data.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from a NULL-terminated C string.
+ * The data must be valid for reads of len bytes.
+ */
+IMPORT extern
+int c2pa_builder_add_assertion_cbor(struct C2paBuilder *builder_ptr,
+                                    const char *label,
+                                    const unsigned char *data,
+                                    uintptr_t len);
+
+/**
+ * Adds a redaction to the C2paBuilder.
+ *
+ * The assertion is removed from the parent ingredient's manifest when signing and the
+ * redaction is recorded in the new claim, for example to remove location data with
+ * "stds.exif" or "self#jumbf=/c2pa/urn:uuid:.../c2pa.assertions/stds.exif".
+ * A URI must name the active manifest of the parent ingredient, so the parent must be added first.
+ * Signing fails if the parent ingredient has no such assertion or it is an actions assertion.
+ * The SDK does not yet take redactions into account when validating ingredients,
+ * so readers report a mismatched ingredient hash and the redacted assertion as missing.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * assertion_uri: pointer to a C string with the label or JUMBF URI of the assertion.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ */
+IMPORT extern
+int c2pa_builder_add_redaction(struct C2paBuilder *builder_ptr,
+                               const char *assertion_uri);
+
+/**
+ * Adds an action to the C2paBuilder's actions assertion, creating the assertion if needed.
+ *
+ * Actions already in the manifest definition are kept, so editing applications can
+ * record each edit as it is made. The action is a JSON object, for example:
+ * `{"action": "c2pa.cropped", "softwareAgent": "Editor 1.0"}`
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * action_json: pointer to a C string with the JSON action.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ */
+IMPORT extern int c2pa_builder_add_action(struct C2paBuilder *builder_ptr, const char *action_json);
+
+/**
+ * Adds an action over a time range to the C2paBuilder's actions assertion.
+ *
+ * The action is a JSON object with an edit of trim, splice or overlay, or an action label,
+ * and optional start and end times as seconds or Normal Play Time strings, for example:
+ * `{"edit": "splice", "start": "00:01:10", "end": "00:01:25", "ingredient": "xmp:iid:1234"}`
+ * The ingredient is the instance_id of an ingredient added to the Builder.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * action_json: pointer to a C string with the JSON timed action.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ */
+IMPORT extern
+int c2pa_builder_add_timed_action(struct C2paBuilder *builder_ptr,
+                                  const char *action_json);
+
+/**
+ * Adds an ingredient to the C2paBuilder.
+ *
+ * If thumbnail options were set with c2pa_set_thumbnail_options and neither the
+ * ingredient definition nor its manifest provides a thumbnail, the generated thumbnail is color managed.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * ingredient_json: pointer to a C string with the JSON ingredient definition.
+ * * format: pointer to a C string with the mime type or extension.
+ * * source: pointer to a CStream.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ */
+IMPORT extern
+int c2pa_builder_add_ingredient_from_stream(struct C2paBuilder *builder_ptr,
+                                            const char *ingredient_json,
+                                            const char *format,
+                                            struct CStream *source);
+
+/**
+ * Adds an ingredient to the C2paBuilder from an asset that has already been read.
+ *
+ * The manifest store and validation status of the reader are used, so the asset
+ * is not read again. The title, format and instance_id default to those of the
+ * active manifest, and its thumbnail is used if the definition has none.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * ingredient_json: pointer to a C string with the JSON ingredient definition.
+ * * reader_ptr: pointer to a C2paReader of the ingredient asset.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ * It is an error if the reader has no active manifest, or if it was not created
+ * with this API, since the manifest store bytes are then not available.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The reader is not consumed and must still be released with c2pa_reader_free.
+ */
+IMPORT extern
+int c2pa_builder_add_ingredient_from_reader(struct C2paBuilder *builder_ptr,
+                                            const char *ingredient_json,
+                                            const struct C2paReader *reader_ptr);
+
+/**
+ * Writes an Archive of the Builder to the destination stream.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * stream: pointer to a writable CStream.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ *
+ * # Example
+ * ```c
+ * auto result = c2pa_builder_to_archive(builder, stream);
+ * if (result < 0) {
+ *     printf("Error: %s\n", c2pa_error());
+ * }
+ * ```
+ */
+IMPORT extern int c2pa_builder_to_archive(struct C2paBuilder *builder_ptr, struct CStream *stream);
+
+/**
+ * Returns the current state of a C2paBuilder as a JSON string.
+ *
+ * This is the manifest definition with any ingredients, actions and assertions added
+ * since the Builder was created, plus its remote_url and no_embed settings,
+ * so it can be compared with the input JSON without signing.
+ * Resources are only listed by identifier.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a JSON string.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern char *c2pa_builder_to_json(struct C2paBuilder *builder_ptr);
+
+/**
+ * Checks the manifest definition of a C2paBuilder for spec violations before signing.
+ *
+ * Reports more than one parentOf ingredient, actions assertions that cannot be decoded,
+ * c2pa.created or c2pa.opened used other than as the first action, c2pa.opened without
+ * the parentOf ingredient, c2pa.placed without an ingredient, and actions that refer
+ * to ingredients missing from the definition.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ *
+ * # Errors
+ * Returns -1 if there were errors or the manifest definition is invalid, otherwise returns 0.
+ * The error string, listing every problem found, can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * builder_ptr must be a valid pointer to a Builder.
+ */
+IMPORT extern int c2pa_builder_validate(struct C2paBuilder *builder_ptr);
+
+/**
+ * Creates and writes signed manifest from the C2paBuilder to the destination stream.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type or extension.
+ * * source: pointer to a CStream.
+ * * dest: pointer to a writable CStream.
+ * * signer: pointer to a C2paSigner.
+ * * c2pa_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_sign(struct C2paBuilder *builder_ptr,
+                      const char *format,
+                      struct CStream *source,
+                      struct CStream *dest,
+                      struct C2paSigner *signer,
+                      const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Signs like c2pa_builder_sign, with verify settings for this signing.
+ *
+ * The settings apply while signing and the previous settings are put back after,
+ * for example to turn off verify_after_sign for one latency-sensitive signing.
+ * See c2pa_set_verify_options, which sets them for every Reader and Builder.
+ * Reads and signs with settings of their own run one at a time.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type or extension.
+ * * source: pointer to a CStream.
+ * * dest: pointer to a writable CStream.
+ * * signer: pointer to a C2paSigner.
+ * * options: pointer to the verify settings.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * The options must point to a valid C2paVerifyOptions.
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_sign_with_verify_options(struct C2paBuilder *builder_ptr,
+                                          const char *format,
+                                          struct CStream *source,
+                                          struct CStream *dest,
+                                          struct C2paSigner *signer,
+                                          const struct C2paVerifyOptions *options,
+                                          const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Signs like c2pa_builder_sign on a worker thread.
+ *
+ * Returns immediately and invokes the completion callback from the worker thread
+ * once the signed asset is written, with either the manifest bytes or an error message.
+ * The manifest bytes and the error message are only valid during the callback.
+ * If c2pa_init was called with single_threaded, signing runs on the caller's thread
+ * and the callback is invoked before this function returns.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type or extension.
+ * * source: pointer to a CStream.
+ * * dest: pointer to a writable CStream.
+ * * signer: pointer to a C2paSigner.
+ * * completion: a callback receiving the user data, the manifest bytes or NULL,
+ *   their length and the error or NULL.
+ * * user_data: a context value passed to the callback.
+ *
+ * # Errors
+ * Returns -1 if signing could not be started, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The builder, streams and signer must remain valid, and the builder must not be used,
+ * until the callback is invoked.
+ * The stream, signer and completion callbacks may be called from another thread.
+ */
+IMPORT extern
+int c2pa_builder_sign_async(struct C2paBuilder *builder_ptr,
+                            const char *format,
+                            struct CStream *source,
+                            struct CStream *dest,
+                            struct C2paSigner *signer,
+                            void (*completion)(const void *user_data,
+                                               const unsigned char *manifest_bytes,
+                                               uintptr_t manifest_len,
+                                               const char *error),
+                            const void *user_data);
+
+/**
+ * Creates a signed manifest from the C2paBuilder for an asset held in memory.
+ *
+ * The signed asset is returned in a buffer allocated by the library.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type or extension.
+ * * data: pointer to the source asset bytes.
+ * * len: the number of bytes in data.
+ * * signer: pointer to a C2paSigner.
+ * * output_ptr: pointer to a pointer to a c_uchar to return the signed asset.
+ * * output_len: pointer to a usize to return the size of the signed asset.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * The returned output MUST be released by calling c2pa_resource_bytes_free with output_len,
+ * and manifest_bytes with the returned size, and they are no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_sign_bytes(struct C2paBuilder *builder_ptr,
+                            const char *format,
+                            const unsigned char *data,
+                            uintptr_t len,
+                            struct C2paSigner *signer,
+                            const unsigned char **output_ptr,
+                            uintptr_t *output_len,
+                            const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Creates a signed manifest from the C2paBuilder and writes the signed asset to a file.
+ *
+ * The format is taken from the file extensions, which must match.
+ * The destination file is created or replaced and its name is used as the title of this sign
+ * if none is set. The builder keeps no title afterwards, so the next sign defaults it again.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * source_path: pointer to a C string with the path to the source file.
+ * * dest_path: pointer to a C string with the path to the destination file.
+ * * signer: pointer to a C2paSigner.
+ * * c2pa_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_sign_file(struct C2paBuilder *builder_ptr,
+                           const char *source_path,
+                           const char *dest_path,
+                           struct C2paSigner *signer,
+                           const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Signs a fragmented BMFF asset, such as a DASH presentation, with the C2paBuilder.
+ *
+ * The init segment and fragments are copied to the output directory, which is created
+ * if needed, replacing files with the same names. The manifest store is embedded in the
+ * copied init segment and every copied fragment gets a Merkle tree box so that it can be
+ * validated on its own. The title defaults to the init segment file name.
+ * The SDK only signs fragments as files, and not ones that already have a manifest,
+ * and the manifest definition must have at least one assertion.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * signer: pointer to a C2paSigner.
+ * * init_path: pointer to a C string with the path to the init segment.
+ * * fragment_paths: pointer to an array of C strings with the paths to the fragments.
+ * * count: the number of fragment paths.
+ * * output_dir: pointer to a C string with the path to the output directory.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * fragment_paths must point to count valid C strings.
+ */
+IMPORT extern
+int c2pa_builder_sign_fragmented_files(struct C2paBuilder *builder_ptr,
+                                       struct C2paSigner *signer,
+                                       const char *init_path,
+                                       const char *const *fragment_paths,
+                                       uintptr_t count,
+                                       const char *output_dir);
+
+/**
+ * Signs like c2pa_builder_sign and then uploads the manifest store to the Builder's remote URL.
+ *
+ * The upload callback is invoked with the remote URL and the manifest bytes after signing,
+ * so a cloud manifest can be produced and published in a single call.
+ * A remote URL must have been set with c2pa_builder_set_remote_url.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * format: pointer to a C string with the mime type or extension.
+ * * source: pointer to a CStream.
+ * * dest: pointer to a writable CStream.
+ * * signer: pointer to a C2paSigner.
+ * * context: a context value passed to the upload callback.
+ * * upload: a callback to upload the manifest store.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_sign_and_upload(struct C2paBuilder *builder_ptr,
+                                 const char *format,
+                                 struct CStream *source,
+                                 struct CStream *dest,
+                                 struct C2paSigner *signer,
+                                 const void *context,
+                                 UploadCallback upload,
+                                 const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Frees a C2PA manifest returned by c2pa_builder_sign.
+ *
+ * # Safety
+ * The bytes can only be freed once and are invalid after this call.
+ */
+IMPORT extern
+void c2pa_manifest_bytes_free(const unsigned char *manifest_bytes_ptr);
+
+/**
+ * Creates a hashed placeholder from a Builder.
+ * The placeholder is used to reserve size in an asset for later signing.
+ *
+ * The host writes the placeholder into the asset itself, hashes the asset excluding the
+ * placeholder and passes the hash to c2pa_builder_sign_data_hashed_embeddable.
+ * The signed manifest is the same size as the placeholder, so it can replace it in place.
+ * Use "application/c2pa" as the format for an unframed manifest store.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * reserved_size: the size required for a signature from the intended signer.
+ * * format: pointer to a C string with the mime type or extension.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_data_hashed_placeholder(struct C2paBuilder *builder_ptr,
+                                         uintptr_t reserved_size,
+                                         const char *format,
+                                         const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Sign a Builder using the specified signer and data hash.
+ * The data hash is a JSON string containing DataHash information for the asset.
+ * This is a low-level method for advanced use cases where the caller handles embedding the manifest.
+ *
+ * The data hash has an "exclusions" array of {"start", "length"} ranges covering the
+ * placeholder, the "alg", such as "sha256", and the "hash" bytes as an array of numbers.
+ * The asset stream is never read.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * signer: pointer to a C2paSigner.
+ * * data_hash: pointer to a C string with the JSON data hash.
+ * * format: pointer to a C string with the mime type or extension.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
  *
  * # Errors
- * Returns an error field if there were errors.
+ * Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
  * Reads from NULL-terminated C strings.
- * The returned value MUST be released by calling release_string
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
  * and it is no longer valid after that call.
  */
 IMPORT extern
-char *c2pa_sign_file(const char *source_path,
-                     const char *dest_path,
-                     const char *manifest,
-                     const struct C2paSignerInfo *signer_info,
-                     const char *data_dir);
+int c2pa_builder_sign_data_hashed_embeddable(struct C2paBuilder *builder_ptr,
+                                             struct C2paSigner *signer,
+                                             const char *data_hash,
+                                             const char *format,
+                                             const unsigned char **manifest_bytes_ptr);
 
 /**
- * Frees a string allocated by Rust.
+ * Frames a signed manifest store for embedding in an asset of the given format.
  *
- * Deprecated: for backward api compatibility only.
+ * For a JPEG this returns the APP11 segments holding the manifest store, ready to be
+ * inserted into the asset. Use it with the manifest bytes returned by the signing APIs
+ * when the caller does its own embedding.
+ *
+ * # Parameters
+ * * format: pointer to a C string with the mime type or extension.
+ * * manifest_bytes: pointer to the signed manifest store bytes.
+ * * len: the number of bytes in manifest_bytes.
+ * * result_bytes_ptr: pointer to a pointer to a c_uchar to return the framed bytes.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the size of the framed bytes.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
  * Reads from NULL-terminated C strings.
- * The string must not have been modified in C.
- * The string can only be freed once and is invalid after this call.
+ * The returned value MUST be released by calling c2pa_resource_bytes_free with the returned size
+ * and it is no longer valid after that call.
  */
-IMPORT extern void c2pa_release_string(char *s);
+IMPORT extern
+int c2pa_format_embeddable(const char *format,
+                           const unsigned char *manifest_bytes,
+                           uintptr_t len,
+                           const unsigned char **result_bytes_ptr);
 
 /**
- * Frees a string allocated by Rust.
+ * Estimates the size of the manifest store the Builder will produce with the given signer.
+ *
+ * The estimate includes the signature reserve and any resources added to the Builder.
+ * It does not include thumbnails generated automatically while signing or the
+ * container specific framing used when embedding into an asset.
+ * The Builder is not modified.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * signer: pointer to a C2paSigner.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the estimated size in bytes.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
- * The string must not have been modified in C.
- * The string can only be freed once and is invalid after this call.
+ * builder_ptr and signer must be valid pointers.
  */
-IMPORT extern void c2pa_string_free(char *s);
+IMPORT extern
+int64_t c2pa_builder_estimate_size(struct C2paBuilder *builder_ptr,
+                                   struct C2paSigner *signer);
 
 /**
- * Creates and verifies a C2paReader from an asset stream with the given format.
+ * Creates a placeholder manifest, framed for the format, for a muxer to write into an asset.
  *
- * Parameters
+ * The placeholder reserves space for the signed manifest, which is later written over it
+ * with the patches returned by c2pa_builder_sign_placeholder. The asset is never rewritten.
+ * Use "application/c2pa" as the format for an unframed manifest store.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to a Builder.
+ * * signer: pointer to the C2paSigner that will sign the asset.
  * * format: pointer to a C string with the mime type or extension.
- * * stream: pointer to a CStream.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return the placeholder.
  *
  * # Errors
- * Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+ * Returns -1 if there were errors, otherwise returns the size of the placeholder.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
  * Reads from NULL-terminated C strings.
- * The returned value MUST be released by calling c2pa_reader_free
+ * The returned value MUST be released by calling c2pa_resource_bytes_free with the returned size
+ * and it is no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_manifest_placeholder(struct C2paBuilder *builder_ptr,
+                                      struct C2paSigner *signer,
+                                      const char *format,
+                                      const unsigned char **manifest_bytes_ptr);
+
+/**
+ * Signs an asset holding a placeholder from c2pa_builder_manifest_placeholder.
+ *
+ * Everything but the placeholder is hashed, so the asset must be complete.
+ * Writing each patch at its offset replaces the placeholder with the signed manifest;
+ * the patches never write outside the placeholder.
+ *
+ * # Parameters
+ * * builder_ptr: pointer to the Builder that made the placeholder.
+ * * signer: pointer to a C2paSigner.
+ * * format: pointer to a C string with the mime type or extension used for the placeholder.
+ * * stream: pointer to a readable CStream with the finished asset.
+ * * offset: the offset of the placeholder in the asset.
+ * * placeholder_len: the size of the placeholder.
+ * * patches_ptr: pointer to a pointer to return an array of C2paManifestPatch.
+ *
+ * # Errors
+ * Returns -1 if there were errors, otherwise returns the number of patches.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings.
+ * The returned patches MUST be released by calling c2pa_manifest_patches_free
+ * and are no longer valid after that call.
+ */
+IMPORT extern
+int c2pa_builder_sign_placeholder(struct C2paBuilder *builder_ptr,
+                                  struct C2paSigner *signer,
+                                  const char *format,
+                                  struct CStream *stream,
+                                  uint64_t offset,
+                                  uintptr_t placeholder_len,
+                                  const struct C2paManifestPatch **patches_ptr);
+
+/**
+ * Frees the patches returned by c2pa_builder_sign_placeholder.
+ *
+ * # Parameters
+ * * patches: pointer to the patches.
+ * * count: the number of patches returned.
+ *
+ * # Safety
+ * The patches can only be freed once and are invalid after this call.
+ */
+IMPORT extern
+void c2pa_manifest_patches_free(const struct C2paManifestPatch *patches,
+                                uintptr_t count);
+
+/**
+ * Creates a C2paSigner from a callback and configuration.
+ *
+ * # Parameters
+ * * callback: a callback function to sign data.
+ * * alg: the signing algorithm.
+ * * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+ * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * The returned value MUST be released by calling c2pa_signer_free
  * and it is no longer valid after that call.
  *
  * # Example
  * ```c
- * auto result = c2pa_reader_from_stream("image/jpeg", stream);
+ * auto result = c2pa_signer_create(callback, alg, certs, tsa_url);
  * if (result == NULL) {
  *     printf("Error: %s\n", c2pa_error());
  * }
  * ```
  */
 IMPORT extern
-struct C2paReader *c2pa_reader_from_stream(const char *format,
-                                           struct CStream *stream);
+struct C2paSigner *c2pa_signer_create(const void *context,
+                                      SignerCallback callback,
+                                      enum C2paSigningAlg alg,
+                                      const char *certs,
+                                      const char *tsa_url);
 
 /**
- * Frees a C2paReader allocated by Rust.
+ * Creates a C2paSigner from a callback and a DER encoded certificate chain.
+ *
+ * This is c2pa_signer_create for certificates pulled from a key store, which need not
+ * be re-encoded as PEM.
+ *
+ * # Parameters
+ * * context: a generic context value passed to the callback.
+ * * callback: a callback function to sign data.
+ * * alg: the signing algorithm.
+ * * certs: pointer to the DER encoded certificates concatenated, starting with the
+ *   signing certificate.
+ * * certs_len: the length of certs in bytes.
+ * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * The C2paReader can only be freed once and is invalid after this call.
+ * certs must be valid for certs_len bytes.
+ * The returned value MUST be released by calling c2pa_signer_free
+ * and it is no longer valid after that call.
  */
-IMPORT extern void c2pa_reader_free(struct C2paReader *reader_ptr);
+IMPORT extern
+struct C2paSigner *c2pa_signer_create_der(const void *context,
+                                          SignerCallback callback,
+                                          enum C2paSigningAlg alg,
+                                          const unsigned char *certs,
+                                          uintptr_t certs_len,
+                                          const char *tsa_url);
 
 /**
- * Returns a JSON string generated from a C2paReader.
+ * Creates a C2paSigner whose signatures are completed later by c2pa_signer_complete.
+ *
+ * The callback starts a signature request and returns without waiting for the signature,
+ * which the host passes to c2pa_signer_complete with the request id once it is available,
+ * from any thread. Signing waits for the signature on the signing thread, so use
+ * c2pa_builder_sign_async to keep the caller's thread free.
+ * A request not completed within two minutes fails the sign with a SignatureTimeout error,
+ * and completing it later fails.
+ *
+ * # Parameters
+ * * context: a generic context value passed to the callback.
+ * * callback: a callback that starts a signature request.
+ * * alg: the signing algorithm.
+ * * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+ * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * The returned value MUST be released by calling c2pa_string_free
+ * Reads from NULL-terminated C strings
+ * The returned value MUST be released by calling c2pa_signer_free
  * and it is no longer valid after that call.
+ * Every started request MUST be completed, or signing does not finish.
  */
-IMPORT extern char *c2pa_reader_json(struct C2paReader *reader_ptr);
+IMPORT extern
+struct C2paSigner *c2pa_signer_create_async(const void *context,
+                                            AsyncSignerCallback callback,
+                                            enum C2paSigningAlg alg,
+                                            const char *certs,
+                                            const char *tsa_url);
 
 /**
- * Writes a C2paReader resource to a stream given a URI.
- *
- * The resource uri should match an identifier in the the manifest store.
+ * Completes a signature request started by the callback of a c2pa_signer_create_async signer.
  *
  * # Parameters
- * * reader_ptr: pointer to a Reader.
- * * uri: pointer to a C string with the URI to identify the resource.
- * * stream: pointer to a writable CStream.
+ * * request_id: the id the callback was given.
+ * * signature: pointer to the signature bytes, or NULL if signing failed.
+ * * len: the number of bytes in signature.
  *
  * # Errors
- * Returns -1 if there were errors, otherwise returns size of stream written.
+ * Returns -1 if there is no pending request with the id, otherwise returns 0.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
+ * signature must be NULL or point to len bytes, which are copied.
+ */
+IMPORT extern int c2pa_signer_complete(uint64_t request_id, const unsigned char *signature, uintptr_t len);
+
+/**
+ * Creates a C2paSigner that posts to a signing service over HTTP.
+ *
+ * The bytes to sign, or their digest, are posted as application/octet-stream and the
+ * response body, of at most 64 KiB, is used as the raw signature.
+ * The options are a JSON object, for example:
+ * `{"url": "https://sign.example.com/es256", "headers": {"Authorization": "Bearer <token>"},
+ * "payload": "digest", "retry": {"retries": 2}, "timeout_ms": 5000}`
+ * * url: the signing service endpoint, which must be https unless it is on this machine.
+ * * headers: headers added to every signing request.
+ * * payload: "data" to post the bytes to sign, or "digest" to post their hash for the algorithm.
+ * * retry: retries for failed requests, with exponential backoff.
+ *   Requests rejected with a 4xx status are not retried.
+ * * timeout_ms: the time allowed for each request.
  *
- * # Example
- * ```c
- * result c2pa_reader_resource_to_stream(store, "uri", stream);
- * if (result < 0) {
- *     printf("Error: %s\n", c2pa_error());
- * }
- * ```
+ * # Parameters
+ * * options_json: pointer to a C string with the options JSON.
+ * * alg: the signing algorithm.
+ * * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+ * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+ * The error string can be retrieved by calling c2pa_error.
+ *
+ * # Safety
+ * Reads from NULL-terminated C strings
+ * The returned value MUST be released by calling c2pa_signer_free
+ * and it is no longer valid after that call.
  */
 IMPORT extern
-int c2pa_reader_resource_to_stream(struct C2paReader *reader_ptr,
-                                   const char *uri,
-                                   struct CStream *stream);
+struct C2paSigner *c2pa_signer_create_remote(const char *options_json,
+                                             enum C2paSigningAlg alg,
+                                             const char *certs,
+                                             const char *tsa_url);
 
+#if defined(C2PA_PKCS11)
 /**
- * Creates a C2paBuilder from a JSON manifest definition string.
+ * Creates a C2paSigner that signs with a private key on a PKCS#11 token, such as an HSM.
+ *
+ * The token's module is loaded once and shared by every signer for it. A session is opened,
+ * and logged in with the PIN if one is given, for the life of the signer.
+ * ECDSA and RSA-PSS keys sign a digest computed by the library, so the token only needs
+ * the CKM_ECDSA, CKM_RSA_PKCS_PSS or CKM_EDDSA mechanism.
+ * Only available when the library is built with the pkcs11 feature.
+ *
+ * # Parameters
+ * * module_path: a pointer to a NULL-terminated string with the path to the PKCS#11 module.
+ * * slot: the id of the slot holding the token.
+ * * pin: a pointer to a NULL-terminated string with the user PIN, or NULL to skip the login.
+ * * key_label: a pointer to a NULL-terminated string with the label of the private key.
+ * * alg: the signing algorithm, which must match the key.
+ * * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+ * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
  *
  * # Errors
- * Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
- * The returned value MUST be released by calling c2pa_builder_free
+ * Reads from NULL-terminated C strings
+ * The returned value MUST be released by calling c2pa_signer_free
  * and it is no longer valid after that call.
- *
- * # Example
- * ```c
- * auto result = c2pa_builder_from_json(manifest_json);
- * if (result == NULL) {
- *     printf("Error: %s\n", c2pa_error());
- * }
- * ```
  */
-IMPORT extern struct C2paBuilder *c2pa_builder_from_json(const char *manifest_json);
+IMPORT extern
+struct C2paSigner *c2pa_signer_create_pkcs11(const char *module_path,
+                                             uint64_t slot,
+                                             const char *pin,
+                                             const char *key_label,
+                                             enum C2paSigningAlg alg,
+                                             const char *certs,
+                                             const char *tsa_url);
+#endif
 
 /**
- * Create a C2paBuilder from an archive stream.
+ * Creates a C2paSigner from a PEM certificate chain and private key.
+ *
+ * The signer signs locally, so no signing callback is needed.
+ *
+ * # Parameters
+ * * signer_info: pointer to a C2paSignerInfo with the algorithm, certificates, key and
+ *   optional timestamp authority URL.
  *
  * # Errors
- * Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
- * The returned value MUST be released by calling c2pa_builder_free
+ * Reads from NULL-terminated C strings
+ * The returned value MUST be released by calling c2pa_signer_free
  * and it is no longer valid after that call.
- *
- * # Example
- * ```c
- * auto result = c2pa_builder_from_archive(stream);
- * if (result == NULL) {
- *     printf("Error: %s\n", c2pa_error());
- * }
- * ```
  */
-IMPORT extern struct C2paBuilder *c2pa_builder_from_archive(struct CStream *stream);
+IMPORT extern struct C2paSigner *c2pa_signer_from_info(const struct C2paSignerInfo *signer_info);
 
 /**
- * Frees a C2paBuilder allocated by Rust.
+ * Creates a C2paSigner from a DER encoded certificate chain and PKCS#8 private key.
+ *
+ * This is c2pa_signer_from_info for credentials pulled from a key store, which need not
+ * be re-encoded as PEM.
+ *
+ * # Parameters
+ * * alg: the signing algorithm.
+ * * certs: pointer to the DER encoded certificates concatenated, starting with the
+ *   signing certificate.
+ * * certs_len: the length of certs in bytes.
+ * * private_key: pointer to the DER encoded PKCS#8 private key.
+ * * private_key_len: the length of private_key in bytes.
+ * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+ *
+ * # Errors
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+ * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * The C2paBuilder can only be freed once and is invalid after this call.
+ * certs and private_key must be valid for their lengths in bytes.
+ * The returned value MUST be released by calling c2pa_signer_free
+ * and it is no longer valid after that call.
  */
-IMPORT extern void c2pa_builder_free(struct C2paBuilder *builder_ptr);
+IMPORT extern
+struct C2paSigner *c2pa_signer_from_der(enum C2paSigningAlg alg,
+                                        const unsigned char *certs,
+                                        uintptr_t certs_len,
+                                        const unsigned char *private_key,
+                                        uintptr_t private_key_len,
+                                        const char *tsa_url);
 
 /**
- * Sets the no-embed flag on the Builder.
- * When set, the builder will not embed a C2PA manifest store into the asset when signing.
- * This is useful when creating cloud or sidecar manifests.
+ * Checks a signer configuration so certificate and key problems are found before signing.
+ *
+ * Checks that the certificates and private key decode, that the algorithm can be used
+ * with the key type of the signing certificate, that each certificate in the chain was
+ * issued by the one that follows it, and that the private key belongs to the signing
+ * certificate. The chain may end at a self-signed root or at an intermediate.
+ *
  * # Parameters
- * * builder_ptr: pointer to a Builder.
+ * * signer_info: pointer to a C2paSignerInfo with the algorithm, certificates and key.
+ *
+ * # Errors
+ * Returns -1 if a parameter is NULL, otherwise returns a C2paSignerProblem, which is
+ * SignerValid (0) if no problems were found.
+ * The error string, describing the problem, can be retrieved by calling c2pa_error.
+ *
  * # Safety
- * builder_ptr must be a valid pointer to a Builder.
+ * Reads from NULL-terminated C strings
  */
-IMPORT extern void c2pa_builder_set_no_embed(struct C2paBuilder *builder_ptr);
+IMPORT extern int c2pa_signer_validate(const struct C2paSignerInfo *signer_info);
 
 /**
- * Sets the remote URL on the Builder.
- * When set, the builder will embed a remote URL into the asset when signing.
- * This is useful when creating cloud based Manifests.
+ * Returns the size to reserve for the signature for this signer.
+ *
  * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * remote_url: pointer to a C string with the remote URL.
+ * * signer_ptr: pointer to a C2paSigner.
+ *
  * # Errors
- * Returns -1 if there were errors, otherwise returns 0.
+ * Returns -1 if there were errors, otherwise returns the size to reserve.
  * The error string can be retrieved by calling c2pa_error.
+ *
  * # Safety
- * Reads from NULL-terminated C strings.
+ * The signer_ptr must be a valid pointer to a C2paSigner.
  */
-IMPORT extern
-int c2pa_builder_set_remote_url(struct C2paBuilder *builder_ptr,
-                                const char *remote_url);
+IMPORT extern int64_t c2pa_signer_reserve_size(struct C2paSigner *signer_ptr);
 
 /**
- * Adds a resource to the C2paBuilder.
+ * Signs arbitrary bytes with a C2paSigner.
  *
- * The resource uri should match an identifier in the manifest definition.
+ * This runs the signer directly, without building a manifest, so the signer wiring
+ * can be tested against known vectors.
+ * If signature is NULL, the size of the signature is returned without copying it.
  *
  * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * uri: pointer to a C string with the URI to identify the resource.
- * * stream: pointer to a CStream.
+ * * signer_ptr: pointer to a C2paSigner.
+ * * data: pointer to the bytes to sign.
+ * * len: the length of the bytes to sign.
+ * * signature: pointer to a buffer for the signature (optional, can be NULL).
+ * * signature_len: the size of the signature buffer.
+ *
  * # Errors
- * Returns -1 if there were errors, otherwise returns 0.
+ * Returns -1 if there were errors, otherwise returns the size of the signature.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * Reads from NULL-terminated C strings
+ * The signer_ptr must be a valid pointer to a C2paSigner.
+ * data must point to at least len bytes and signature to at least signature_len bytes.
  */
 IMPORT extern
-int c2pa_builder_add_resource(struct C2paBuilder *builder_ptr,
-                              const char *uri,
-                              struct CStream *stream);
+intptr_t c2pa_signer_sign(const struct C2paSigner *signer_ptr,
+                          const unsigned char *data,
+                          uintptr_t len,
+                          unsigned char *signature,
+                          uintptr_t signature_len);
 
 /**
- * Adds an ingredient to the C2paBuilder.
+ * Sets options on a C2paSigner.
+ *
+ * The options are a JSON object, for example `{"use_ocsp": true}`.
+ * Options it does not give keep the values set before, and a null option is reset.
+ * * use_ocsp: staple an OCSP response for the signing certificate, cached across signing operations.
+ *   The response is fetched when the reserve size is computed, so that it can include it.
+ * * tsa: time stamp request options, an object with digest_alg (sha256, sha384 or sha512),
+ *   policy_oid (dotted OID string), nonce (true or false) and headers (name to value).
+ * * retry: retries for failed TSA and OCSP requests, an object with retries,
+ *   backoff_ms (doubled for each retry) and max_backoff_ms.
+ * * tsa_failure: "fail" to fail the sign when the TSA cannot be reached (the default),
+ *   or "proceed" to sign without a time stamp, see c2pa_timestamp_warning.
  *
  * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * ingredient_json: pointer to a C string with the JSON ingredient definition.
- * * format: pointer to a C string with the mime type or extension.
- * * source: pointer to a CStream.
+ * * signer_ptr: pointer to a C2paSigner.
+ * * options_json: pointer to a C string with the options JSON.
  *
  * # Errors
  * Returns -1 if there were errors, otherwise returns 0.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
+ * The signer_ptr must be a valid pointer to a C2paSigner.
  * Reads from NULL-terminated C strings.
  */
 IMPORT extern
-int c2pa_builder_add_ingredient_from_stream(struct C2paBuilder *builder_ptr,
-                                            const char *ingredient_json,
-                                            const char *format,
-                                            struct CStream *source);
+int c2pa_signer_set_options(struct C2paSigner *signer_ptr,
+                            const char *options_json);
 
 /**
- * Writes an Archive of the Builder to the destination stream.
+ * Returns the warning of the last sign on the calling thread, if it proceeded without a time stamp.
  *
- * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * stream: pointer to a writable CStream.
+ * Like c2pa_error, the warning belongs to the calling thread, so call this after a sign
+ * returns and before the next sign on the same thread.
+ * Signing only proceeds without a time stamp when the tsa_failure option is "proceed".
  *
  * # Errors
- * Returns -1 if there were errors, otherwise returns 0.
- * The error string can be retrieved by calling c2pa_error.
+ * Returns NULL if the last sign was time stamped or did not request one,
+ * otherwise returns the warning.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
- *
- * # Example
- * ```c
- * auto result = c2pa_builder_to_archive(builder, stream);
- * if (result < 0) {
- *     printf("Error: %s\n", c2pa_error());
- * }
- * ```
+ * The returned value MUST be released by calling c2pa_string_free
+ * and it is no longer valid after that call.
  */
-IMPORT extern int c2pa_builder_to_archive(struct C2paBuilder *builder_ptr, struct CStream *stream);
+IMPORT extern char *c2pa_timestamp_warning(void);
 
 /**
- * Creates and writes signed manifest from the C2paBuilder to the destination stream.
+ * Sets the maximum number of simultaneous outbound requests made by the library.
  *
- * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * format: pointer to a C string with the mime type or extension.
- * * source: pointer to a CStream.
- * * dest: pointer to a writable CStream.
- * * signer: pointer to a C2paSigner.
- * * c2pa_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+ * The limit applies to TSA, OCSP, trust list and remote manifest requests. While a limit
+ * is set, remote manifests are fetched by the library rather than by the SDK, so that
+ * they count against it. Requests over the limit wait for a connection to be released.
+ * A max_connections of 0 removes the limit.
  *
- * # Errors
- * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
- * The error string can be retrieved by calling c2pa_error.
+ * OCSP requests the SDK makes itself while validating, when the ocsp_fetch verify option
+ * is on, are not limited.
  *
  * # Safety
- * Reads from NULL-terminated C strings
- * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
- * and it is no longer valid after that call.
+ * This function is always safe to call.
  */
-IMPORT extern
-int c2pa_builder_sign(struct C2paBuilder *builder_ptr,
-                      const char *format,
-                      struct CStream *source,
-                      struct CStream *dest,
-                      struct C2paSigner *signer,
-                      const unsigned char **manifest_bytes_ptr);
+IMPORT extern void c2pa_set_max_connections(uintptr_t max_connections);
 
 /**
- * Frees a C2PA manifest returned by c2pa_builder_sign.
+ * Removes all cached OCSP responses.
  *
  * # Safety
- * The bytes can only be freed once and are invalid after this call.
+ * This function is always safe to call.
  */
-IMPORT extern void c2pa_manifest_bytes_free(const unsigned char *manifest_bytes_ptr);
+IMPORT extern void c2pa_ocsp_cache_clear(void);
 
 /**
- * Creates a hashed placeholder from a Builder.
- * The placeholder is used to reserve size in an asset for later signing.
+ * Sets the maximum age in seconds of cached OCSP responses.
  *
- * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * reserved_size: the size required for a signature from the intended signer.
- * * format: pointer to a C string with the mime type or extension.
- * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes.
+ * Responses are never used past their next update time.
+ * A max_age_secs of 0 or less removes the limit.
  *
- * # Errors
- * Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
- * The error string can be retrieved by calling c2pa_error.
+ * # Safety
+ * This function is always safe to call.
+ */
+IMPORT extern void c2pa_ocsp_cache_set_max_age(int64_t max_age_secs);
+
+/**
+ * Frees a C2paSigner allocated by Rust.
  *
  * # Safety
- * Reads from NULL-terminated C strings.
- * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
- * and it is no longer valid after that call.
+ * The C2paSigner can only be freed once and is invalid after this call.
  */
-IMPORT extern
-int c2pa_builder_data_hashed_placeholder(struct C2paBuilder *builder_ptr,
-                                         uintptr_t reserved_size,
-                                         const char *format,
-                                         const unsigned char **manifest_bytes_ptr);
+IMPORT extern void c2pa_signer_free(const struct C2paSigner *signer_ptr);
 
 /**
- * Sign a Builder using the specified signer and data hash.
- * The data hash is a JSON string containing DataHash information for the asset.
- * This is a low-level method for advanced use cases where the caller handles embedding the manifest.
+ * Creates a signing session for a manifest definition and a signer.
+ *
+ * The definition is parsed and validated once, and the signer's certificate chain
+ * and reserve size are cached, so each asset signed in the session only pays
+ * for hashing and the signature.
  *
  * # Parameters
- * * builder_ptr: pointer to a Builder.
- * * signer: pointer to a C2paSigner.
- * * data_hash: pointer to a C string with the JSON data hash.
- * * format: pointer to a C string with the mime type or extension.
- * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+ * * manifest_json: pointer to a C string with the JSON manifest definition.
+ * * signer: pointer to a C2paSigner, which must remain valid until the session is freed.
  *
  * # Errors
- * Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+ * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigningSession.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
  * Reads from NULL-terminated C strings.
- * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * The returned value MUST be released by calling c2pa_signing_session_free
  * and it is no longer valid after that call.
  */
 IMPORT extern
-int c2pa_builder_sign_data_hashed_embeddable(struct C2paBuilder *builder_ptr,
-                                             struct C2paSigner *signer,
-                                             const char *data_hash,
-                                             const char *format,
-                                             const unsigned char **manifest_bytes_ptr);
+struct C2paSigningSession *c2pa_signing_session_new(const char *manifest_json,
+                                                    const struct C2paSigner *signer);
 
 /**
- * Creates a C2paSigner from a callback and configuration.
+ * Adds a resource, such as an icon, that is included with every asset signed in the session.
  *
  * # Parameters
- * * callback: a callback function to sign data.
- * * alg: the signing algorithm.
- * * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
- * * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+ * * session: pointer to a C2paSigningSession.
+ * * uri: pointer to a C string with the URI to identify the resource.
+ * * stream: pointer to a CStream.
  *
  * # Errors
- * Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+ * Returns -1 if there were errors, otherwise returns 0.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
  * Reads from NULL-terminated C strings
- * The returned value MUST be released by calling c2pa_signer_free
- * and it is no longer valid after that call.
- *
- * # Example
- * ```c
- * auto result = c2pa_signer_create(callback, alg, certs, tsa_url);
- * if (result == NULL) {
- *     printf("Error: %s\n", c2pa_error());
- * }
- * ```
  */
 IMPORT extern
-struct C2paSigner *c2pa_signer_create(const void *context,
-                                      SignerCallback callback,
-                                      enum C2paSigningAlg alg,
-                                      const char *certs,
-                                      const char *tsa_url);
+int c2pa_signing_session_add_resource(struct C2paSigningSession *session,
+                                      const char *uri,
+                                      struct CStream *stream);
 
 /**
- * Returns the size to reserve for the signature for this signer.
+ * Signs one asset with a signing session and writes it to the destination stream.
+ *
+ * Assets in a session are signed one at a time, each waiting for the one before.
  *
  * # Parameters
- * * signer_ptr: pointer to a C2paSigner.
+ * * session: pointer to a C2paSigningSession.
+ * * format: pointer to a C string with the mime type or extension.
+ * * source: pointer to a CStream.
+ * * dest: pointer to a writable CStream.
+ * * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
  *
  * # Errors
- * Returns -1 if there were errors, otherwise returns the size to reserve.
+ * Returns -1 if there were errors, otherwise returns the size of the c2pa data.
  * The error string can be retrieved by calling c2pa_error.
  *
  * # Safety
- * The signer_ptr must be a valid pointer to a C2paSigner.
+ * Reads from NULL-terminated C strings
+ * If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+ * and it is no longer valid after that call.
  */
-IMPORT extern int64_t c2pa_signer_reserve_size(struct C2paSigner *signer_ptr);
+IMPORT extern
+int c2pa_signing_session_sign(const struct C2paSigningSession *session,
+                              const char *format,
+                              struct CStream *source,
+                              struct CStream *dest,
+                              const unsigned char **manifest_bytes_ptr);
 
 /**
- * Frees a C2paSigner allocated by Rust.
+ * Frees a C2paSigningSession allocated by Rust.
+ *
+ * The signer used to create the session is not freed.
  *
  * # Safety
- * The C2paSigner can only be freed once and is invalid after this call.
+ * The C2paSigningSession can only be freed once and is invalid after this call.
  */
-IMPORT extern void c2pa_signer_free(const struct C2paSigner *signer_ptr);
+IMPORT extern void c2pa_signing_session_free(struct C2paSigningSession *session);
 
 /**
  * Signs a byte array using the Ed25519 algorithm.
@@ -641,6 +3548,10 @@ IMPORT extern void c2pa_signature_free(const uint8_t *signature_ptr);
  * * `seek` - a SeekCallback to seek in the stream
  * * `write` - a WriteCallback to write to the stream
  *
+ * Callbacks for operations the stream is not used for may be NULL, such as the write
+ * and flush callbacks of a stream that is only read. Attempting an operation without a
+ * callback returns an error.
+ *
  * # Safety
  * The context must remain valid for the lifetime of the C2paStream
  * The resulting C2paStream must be released by calling c2pa_release_stream
@@ -653,6 +3564,161 @@ struct CStream *c2pa_create_stream(struct StreamContext *context,
                                    WriteCallback writer,
                                    FlushCallback flusher);
 
+/**
+ * Creates a new C2paStream from context with callbacks, seeking with 64 bit offsets
+ *
+ * Use this rather than c2pa_create_stream for assets that may be over 2GB, since
+ * long offsets are only 32 bits on some platforms, such as Windows.
+ * c2pa_has_feature("stream_seek64") reports that this is available.
+ *
+ * # Arguments
+ * * `context` - a pointer to a StreamContext
+ * * `read` - a ReadCallback to read from the stream
+ * * `seek` - a SeekCallback64 to seek in the stream
+ * * `write` - a WriteCallback to write to the stream
+ *
+ * As with c2pa_create_stream, callbacks for operations the stream is not used for may be NULL.
+ *
+ * # Safety
+ * The context must remain valid for the lifetime of the C2paStream
+ * The resulting C2paStream must be released by calling c2pa_release_stream
+ *
+ */
+IMPORT extern
+struct CStream *c2pa_create_stream64(struct StreamContext *context,
+                                     ReadCallback reader,
+                                     SeekCallback64 seeker,
+                                     WriteCallback writer,
+                                     FlushCallback flusher);
+
+/**
+ * Creates a read-only C2paStream over a caller-owned buffer
+ *
+ * The bytes are read in place, so the host needs no stream callbacks and makes no copy.
+ *
+ * # Arguments
+ * * `data` - a pointer to the bytes of the stream
+ * * `len` - the number of bytes
+ *
+ * # Safety
+ * The bytes must remain valid and unchanged for the lifetime of the C2paStream
+ * The resulting C2paStream must be released by calling c2pa_release_stream
+ *
+ */
+IMPORT extern struct CStream *c2pa_stream_from_memory(const uint8_t *data, uintptr_t len);
+
+/**
+ * Creates a readable and writable C2paStream over memory the library manages
+ *
+ * The memory grows as the stream is written, such as by c2pa_builder_sign, and the
+ * bytes written can be retrieved with c2pa_stream_memory_writer_data.
+ *
+ * # Safety
+ * The resulting C2paStream must be released by calling c2pa_release_stream
+ *
+ */
+IMPORT extern struct CStream *c2pa_stream_memory_writer_create(void);
+
+/**
+ * Gets the bytes written to a C2paStream created by c2pa_stream_memory_writer_create
+ *
+ * # Arguments
+ * * `stream` - a pointer to a C2paStream created by c2pa_stream_memory_writer_create
+ * * `data` - set to a pointer to the bytes, which the stream still owns
+ * * `len` - set to the number of bytes
+ *
+ * # Errors
+ * Returns -1 if a parameter is NULL, the stream was not created by
+ * c2pa_stream_memory_writer_create, or buffered writes can't be flushed, otherwise returns 0
+ *
+ * # Safety
+ * The stream must be a valid C2paStream
+ * The bytes are only valid until the stream is written to again or released
+ *
+ */
+IMPORT extern
+int c2pa_stream_memory_writer_data(struct CStream *stream,
+                                   const uint8_t **data,
+                                   uintptr_t *len);
+
+/**
+ * Reports why a stream callback failed, before the callback returns a negative result
+ *
+ * The error is returned by the library call that invoked the callback, and its message
+ * can be retrieved by calling c2pa_error. Callbacks that don't call this report their
+ * errors through errno.
+ *
+ * # Arguments
+ * * `error` - the cause of the failure
+ * * `message` - a NULL-terminated description of the failure, or NULL
+ *
+ * # Safety
+ * Must be called on the thread running the callback
+ *
+ */
+IMPORT extern void c2pa_stream_set_error(enum C2paStreamError error, const char *message);
+
+/**
+ * Sets a callback that frees the context of a stream when the stream is released
+ *
+ * The context of a stream is borrowed unless a free callback is set, so the host keeps
+ * ownership of contexts it did not allocate for the stream alone, such as C++ objects.
+ *
+ * # Arguments
+ * * `stream` - a pointer to a C2paStream created by c2pa_create_stream or c2pa_create_stream64
+ * * `free` - a FreeCallback called with the context by c2pa_release_stream, or NULL
+ *
+ * # Errors
+ * Returns -1 if the stream is NULL or a memory stream, whose context the library frees,
+ * otherwise returns 0
+ *
+ * # Safety
+ * The stream must be a valid C2paStream
+ *
+ */
+IMPORT extern int c2pa_stream_set_free(struct CStream *stream, FreeCallback free);
+
+/**
+ * Sets the size of a buffer that coalesces reads and writes into larger blocks
+ *
+ * The library reads and writes streams in many small pieces, which is slow when each
+ * callback goes over a network or is marshaled into a managed language. With a buffer,
+ * reads fetch whole blocks ahead and writes are held until a block fills, the stream is
+ * flushed, or it is read or seeked. Streams are unbuffered by default.
+ *
+ * # Arguments
+ * * `stream` - a pointer to a C2paStream
+ * * `size` - the size of the buffer in bytes, or 0 to stop buffering
+ *
+ * # Errors
+ * Returns -1 if the stream is NULL or the writes held by a previous buffer can't be
+ * flushed, otherwise returns 0
+ *
+ * # Safety
+ * The stream must be a valid C2paStream
+ *
+ */
+IMPORT extern int c2pa_stream_set_buffer_size(struct CStream *stream, uintptr_t size);
+
+/**
+ * Writes the bytes held by the buffer of a stream and calls its flush callback
+ *
+ * The signing functions flush their destination, so this is only needed after writing
+ * to a buffered stream in other ways. Releasing a stream also flushes it, but can't
+ * report errors.
+ *
+ * # Arguments
+ * * `stream` - a pointer to a C2paStream
+ *
+ * # Errors
+ * Returns -1 if the stream is NULL or can't be flushed, otherwise returns 0
+ *
+ * # Safety
+ * The stream must be a valid C2paStream
+ *
+ */
+IMPORT extern int c2pa_stream_flush(struct CStream *stream);
+
 /**
  * Releases a CStream allocated by Rust
  *
@@ -63,6 +63,8 @@ namespace c2pa
     {
     private:
         string message;
+        C2paErrorCode error_code;
+        vector<string> error_sources;
 
     public:
         Exception();
@@ -70,15 +72,40 @@ namespace c2pa
         Exception(string what);
 
         virtual const char *what() const throw();
+
+        /// Returns a stable code for the kind of error, to branch on rather than the message.
+        C2paErrorCode code() const;
+
+        /// Returns the messages of the errors that caused this one, from the most direct cause.
+        const vector<string> &sources() const;
     };
 
     /// Returns the version of the C2pa library.
     string C2PA_EXPORT version();
 
+    /// Returns the version and capabilities of the C2pa library.
+    const C2paVersionInfo C2PA_EXPORT &version_info();
+
+    /// Returns true if manifests can be read from a format.
+    /// @param format the mime type or extension.
+    bool C2PA_EXPORT can_read(const string &format);
+
+    /// Returns true if manifests can be signed into a format.
+    /// @param format the mime type or extension.
+    bool C2PA_EXPORT can_sign(const string &format);
+
+    /// Returns the mime types of the formats manifests can be read from or signed into.
+    /// @param for_signing true for the formats that can be signed.
+    std::vector<string> C2PA_EXPORT supported_mime_types(bool for_signing = false);
+
+    /// Returns true if the named feature was compiled into the library.
+    /// @param name the feature name.
+    bool C2PA_EXPORT has_feature(const string &name);
+
     /// Loads C2PA settings from a string in a given format.
-    /// @param format the mime format of the string.
+    /// @param format the format of the string, "json" or "toml".
     /// @param data the string to load.
-    void C2PA_EXPORT load_settings(const string format, const string data);
+    void C2PA_EXPORT load_settings(const string &format, const string &data);
 
     /// Reads a file and returns the manifest json as a C2pa::String.
     /// Note: Paths are UTF-8 encoded, use std.filename.u8string().c_str() if needed.
@@ -108,11 +135,58 @@ namespace c2pa
                                SignerInfo *signer_info,
                                const std::optional<path> data_dir = std::nullopt);
 
+    /// Validates every supported asset in a directory.
+    /// @param dir the directory to walk.
+    /// @param options_json optional json options such as {"recursive": true}.
+    /// @return a json report with verdict counts and a result for each asset.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    std::string C2PA_EXPORT verify_directory(const path &dir, const std::optional<string> &options_json = std::nullopt);
+
+    /// Returns a normalized, pretty printed version of a manifest definition.
+    /// @param manifest_json the manifest definition json.
+    /// @return the canonical json with defaults filled in and sorted keys.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    std::string C2PA_EXPORT canonicalize_manifest_json(const string &manifest_json);
+
+    /// Verifies a COSE_Sign1 signature such as a detached claim signature.
+    /// @param cose the COSE_Sign1 bytes.
+    /// @param payload the detached payload, if the signature does not embed it.
+    /// @param certs an optional PEM certificate chain to verify against.
+    /// @return a json string with the payload and signer information, and whether the signer is trusted.
+    /// @throws a C2pa::Exception if the signature is not valid.
+    std::string C2PA_EXPORT verify_cose(const std::vector<unsigned char> &cose,
+                                        const std::optional<std::vector<unsigned char>> &payload = std::nullopt,
+                                        const std::optional<string> &certs = std::nullopt);
+
+    /// Frames a signed manifest store for embedding in an asset of the given format.
+    /// @param format the mime type or extension of the asset.
+    /// @param manifest_bytes the signed manifest store bytes.
+    /// @return the bytes to insert into the asset, such as JPEG APP11 segments.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    std::vector<unsigned char> C2PA_EXPORT format_embeddable(const string &format, const std::vector<unsigned char> &manifest_bytes);
+
     // Forward declaration of the opaque CppIOStream classes
     class C2PA_EXPORT CppIStream;
     class C2PA_EXPORT CppOStream;
     class C2PA_EXPORT CppIOStream;
 
+    /// Returns the provenance identifiers of an asset for indexing.
+    /// @param format the mime format of the stream.
+    /// @param stream the input stream.
+    /// @return a json string with the instance_id, document_id, active_manifest, hard_bindings and remote_url.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    std::string C2PA_EXPORT content_id(const string &format, std::istream &stream);
+
+    /// @brief The signature of a manifest; fields the library does not report are empty.
+    struct SignatureInfo
+    {
+        string alg;
+        string issuer;
+        string common_name;
+        string cert_serial_number;
+        string time;
+    };
+
     /// @brief Reader class for reading a manifest.
     /// @details This class is used to read and validate a manifest from a stream or file.
     class C2PA_EXPORT Reader
@@ -120,6 +194,7 @@ namespace c2pa
     private:
         C2paReader *c2pa_reader;
         CppIStream *cpp_stream = NULL;
+        friend class Builder;
 
     public:
         /// @brief Create a Reader from a stream.
@@ -129,10 +204,41 @@ namespace c2pa
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         Reader(const std::string &format, std::istream &stream);
 
+        /// @brief Create a Reader from the init segment of a fragmented BMFF asset and its fragments.
+        /// @details Each fragment is validated against the manifest store in the init segment,
+        /// and fragment_status returns the status of each.
+        /// @param format The mime format of the asset.
+        /// @param init_stream The init segment.
+        /// @param fragments The fragment streams, which are only read while the Reader is created.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        Reader(const std::string &format, std::istream &init_stream, const std::vector<std::istream *> &fragments);
+
         /// @brief Create a Reader from a file path.
         /// @param source_path  the path to the file to read.
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         Reader(const std::filesystem::path &source_path);
+
+        /// @brief Create a Reader from an asset held in memory.
+        /// @param format The mime format of the data.
+        /// @param data The asset bytes, which are only read during construction.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        Reader(const std::string &format, const std::vector<unsigned char> &data);
+
+        /// @brief Create a Reader from detached manifest store bytes and the asset they describe.
+        /// @param format The mime format of the asset.
+        /// @param manifest_data The manifest store bytes, such as the contents of a .c2pa file.
+        /// @param stream The asset stream to validate the manifest against.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        Reader(const std::string &format, const std::vector<unsigned char> &manifest_data, std::istream &stream);
+
+        /// @brief Create a Reader, recovering the manifest through a soft binding if the stream has none.
+        /// @details Candidates come from the resolver registered with c2pa_set_soft_binding_resolver.
+        /// @param format The mime format of the stream.
+        /// @param stream The input stream to read from.
+        /// @param alg The soft binding algorithm.
+        /// @param value The soft binding value computed from the asset, such as a decoded watermark.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        Reader(const std::string &format, std::istream &stream, const std::string &alg, const std::string &value);
         ~Reader();
 
         /// @brief Get the manifest as a json string.
@@ -153,6 +259,123 @@ namespace c2pa
         /// @return The number of bytes written.
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         int get_resource(const string &uri, std::ostream &stream);
+
+        /// @brief  Get a resource from the reader as bytes.
+        /// @param uri The uri of the resource.
+        /// @return The bytes of the resource.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<unsigned char> get_resource(const string &uri);
+
+        /// @brief  Get the thumbnail of an ingredient and write it to an output stream.
+        /// @param manifest_label The label of the manifest holding the ingredient, or empty for the active manifest.
+        /// @param ingredient_index The index of the ingredient in the manifest.
+        /// @param stream The output stream to write the thumbnail to.
+        /// @return The number of bytes written.
+        /// @throws C2pa::Exception if the ingredient has no thumbnail or for other errors.
+        int get_ingredient_thumbnail(const string &manifest_label, size_t ingredient_index, std::ostream &stream);
+
+        /// @brief  List the resources of every manifest, such as thumbnails and icons.
+        /// @return A JSON array of entries with the manifest label, uri, format and size of each resource.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string resource_list();
+
+        /// @brief  Write the manifest json and all resources to a folder.
+        /// @param dir The folder to write to, created if needed.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void to_folder(const std::filesystem::path &dir);
+
+        /// @brief  Get the certificate chain that signed a manifest.
+        /// @param label The manifest label, or an empty string for the active manifest.
+        /// @return A JSON array with the details of each certificate, starting with the signing certificate.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string certificates(const string &label = "");
+
+        /// @brief  Get the PEM certificate chain that signed a manifest.
+        /// @param label The manifest label, or an empty string for the active manifest.
+        /// @return The PEM chain, starting with the signing certificate.
+        /// @throws C2pa::Exception if the manifest is not signed or for other errors.
+        string cert_chain(const string &label = "");
+
+        /// @brief  Get the manifest store bytes exactly as they were read.
+        /// @return The JUMBF manifest store.
+        /// @throws C2pa::Exception if the bytes are not available.
+        std::vector<unsigned char> manifest_bytes();
+
+        /// @brief  Get the signature info of the active manifest.
+        /// @return The algorithm, issuer, common name, certificate serial number and time-stamp time.
+        /// @throws C2pa::Exception if the manifest is not signed or for other errors.
+        SignatureInfo signature_info();
+
+        /// @brief  Get a short human-readable summary of the active manifest.
+        /// @param locale The locale of the summary, such as "en-US" or "fr". Untranslated locales use English.
+        /// @return The summary, such as "Created with X on DATE. AI tools used: none."
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string summary(const string &locale = "en");
+
+        /// @brief  Get the URL the manifest store was fetched from.
+        /// @return The URL, or an empty string if the manifest store was not remote.
+        string remote_url();
+
+        /// @brief  Get the path of the sidecar file the manifest store was read from.
+        /// @return The path, or an empty string if the manifest store was not read from a sidecar.
+        string sidecar_path();
+
+        /// @brief  Check whether the manifest store was embedded in the asset.
+        /// @return False for remote, detached, sidecar and soft binding recovered manifest stores.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        bool is_embedded();
+
+        /// @brief  Get the label of the active manifest.
+        /// @return The label, for building JUMBF URIs to resources.
+        /// @throws C2pa::Exception if there is no active manifest.
+        string active_manifest_label();
+
+        /// @brief  Get the labels of the manifests, in provenance order.
+        /// @return The labels, starting with the active manifest and followed by its ingredients' manifests.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<string> manifest_labels();
+
+        /// @brief  Get one manifest as JSON.
+        /// @param label The manifest label.
+        /// @return The JSON of the manifest.
+        /// @throws C2pa::Exception if the manifest is not found.
+        string manifest_json(const string &label);
+
+        /// @brief  Get the body of one assertion as JSON.
+        /// @param manifest_label The manifest label, or empty for the active manifest.
+        /// @param assertion_label The assertion label, such as "c2pa.actions".
+        /// @param instance The instance of the assertion label, starting at 1.
+        /// @return The JSON of the assertion.
+        /// @throws C2pa::Exception if the assertion is not found.
+        string get_assertion(const string &manifest_label, const string &assertion_label, size_t instance = 1);
+
+        /// @brief  Get the CBOR of one assertion exactly as it is stored.
+        /// @param manifest_label The manifest label, or empty for the active manifest.
+        /// @param assertion_label The assertion label, such as "c2pa.actions".
+        /// @param instance The instance of the assertion label, starting at 1.
+        /// @return The CBOR data of the assertion.
+        /// @throws C2pa::Exception if the assertion is not found.
+        std::vector<unsigned char> get_assertion_cbor(const string &manifest_label, const string &assertion_label, size_t instance = 1);
+
+        /// @brief  Get the validation status of the manifest store.
+        /// @return A JSON array of entries with a code and, when known, a url and explanation; empty if valid.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string validation_status();
+
+        /// @brief  Get the validation status of each fragment the Reader was created from.
+        /// @return A JSON array with one validation status array per fragment; empty if not fragmented.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string fragment_status();
+
+        /// @brief  Get the validation results of the active manifest and of each ingredient.
+        /// @return A JSON object with success, informational and failure statuses per manifest.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string validation_results();
+
+        /// @brief  Get the CAWG identity assertions, decoded and validated.
+        /// @return A JSON array with the named actor, credential type and signature validity of each assertion.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string identity_assertions();
     };
 
     /// @brief  Signer Callback function type.
@@ -176,18 +399,50 @@ namespace c2pa
         /// @param tsa_uri  The TSA URI to use for time-stamping.
         Signer(SignerFunc *callback, C2paSigningAlg alg, const string &sign_cert, const string &tsa_uri);
 
+        /// @brief Create a Signer from a PEM certificate chain and private key, without a callback.
+        /// @param signer_info  The signing algorithm, certificates, key and optional TSA URL.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        Signer(const SignerInfo &signer_info);
+
+        /// @brief Create a Signer from a DER encoded certificate chain and PKCS#8 private key.
+        /// @param alg  The signing algorithm to use.
+        /// @param certs  The DER encoded certificates concatenated, starting with the signing certificate.
+        /// @param private_key  The DER encoded PKCS#8 private key.
+        /// @param tsa_uri  The TSA URI to use for time-stamping, or empty for none.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        Signer(C2paSigningAlg alg, const std::vector<unsigned char> &certs, const std::vector<unsigned char> &private_key, const string &tsa_uri);
+
         Signer(C2paSigner *signer) : signer(signer) {}
 
         ~Signer();
 
+        /// @brief  Check a signer configuration for certificate and key problems before signing.
+        /// @param signer_info  The signing algorithm, certificates and key to check.
+        /// @return SignerValid, or the first problem found. c2pa_error() describes the problem.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        static C2paSignerProblem validate(const SignerInfo &signer_info);
+
         /// @brief  Get the size to reserve for a signature for this signer.
         /// @return Reserved size for the signature.
         uintptr_t reserve_size();
 
+        /// @brief  Sign arbitrary bytes with this signer, for testing the signer wiring.
+        /// @param data the bytes to sign.
+        /// @return the signature bytes.
+        /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<unsigned char> sign(const std::vector<unsigned char> &data);
+
         /// @brief  Get the C2paSigner
         C2paSigner *c2pa_signer();
     };
 
+    /// @brief Bytes to write at an offset of an asset to embed a signed manifest.
+    struct ManifestPatch
+    {
+        uint64_t offset;
+        std::vector<unsigned char> data;
+    };
+
     /// @brief Builder class for creating a manifest.
     /// @details This class is used to create a manifest from a json string and add resources and ingredients to the manifest.
     class C2PA_EXPORT Builder
@@ -204,13 +459,47 @@ namespace c2pa
         ~Builder();
 
         /// @brief  Set the no embed flag.
-        void set_no_embed();
+        /// @param no_embed  True to leave the manifest store out of the asset, for remote or sidecar manifests.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void set_no_embed(bool no_embed = true);
 
         /// @brief  Set the remote URL.
         /// @param remote_url  The remote URL to set.
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         void set_remote_url(const string &remote_url);
 
+        /// @brief  Set the title of the manifest, such as the asset file name.
+        /// @param title  The title to set.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void set_title(const string &title);
+
+        /// @brief  Set the format of the manifest; signing an asset sets it to the asset's format.
+        /// @param format  The mime type or extension.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void set_format(const string &format);
+
+        /// @brief  Set the claim generator, replacing the claim generator info in the manifest definition.
+        /// @param name  The name of the application.
+        /// @param version  The version of the application, or empty for none.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void set_claim_generator_info(const string &name, const string &version = "");
+
+        /// @brief  Check that claims of a version can be created; only version 1 can be.
+        /// @param version  The claim version, such as 1.
+        /// @throws C2pa::Exception if the version is not supported.
+        void set_claim_version(int version);
+
+        /// @brief  Set the directory to read resources from, such as a thumbnail identified by a file name.
+        /// @param base_path  The path to the directory.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void set_base_path(const path &base_path);
+
+        /// @brief  Set the claim thumbnail, instead of generating one when signing.
+        /// @param format  The mime type of the thumbnail, such as "image/jpeg".
+        /// @param source  The input stream to read the thumbnail from.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void set_thumbnail(const string &format, istream &source);
+
         /// @brief  Add a resource to the builder.
         /// @param uri  The uri of the resource.
         /// @param source  The input stream to read the resource from.
@@ -236,6 +525,41 @@ namespace c2pa
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         void add_ingredient(const string &ingredient_json, const std::filesystem::path &source_path);
 
+        /// @brief Add an ingredient to the builder from an asset that has already been read.
+        /// @details The asset is not read again. The title, format and instance_id default to those of its active manifest.
+        /// @param ingredient_json  Any fields of the ingredient you want to define.
+        /// @param reader  The Reader of the ingredient asset.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_ingredient(const string &ingredient_json, Reader &reader);
+
+        /// @brief Add an assertion to the manifest.
+        /// @param label  The assertion label, such as "org.example.review".
+        /// @param assertion_json  The assertion data as json.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_assertion(const string &label, const string &assertion_json);
+
+        /// @brief Add an assertion with CBOR data to the manifest.
+        /// @param label  The assertion label, such as "org.example.digest".
+        /// @param data  The assertion data as CBOR.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_assertion_cbor(const string &label, const std::vector<unsigned char> &data);
+
+        /// @brief Redact an assertion from the parent ingredient's manifest when signing.
+        /// @param assertion_uri  The label or JUMBF URI of the assertion, such as "stds.exif".
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_redaction(const string &assertion_uri);
+
+        /// @brief Add an action to the actions assertion, keeping the actions already added.
+        /// @param action_json  The action json, such as {"action": "c2pa.cropped"}.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_action(const string &action_json);
+
+        /// @brief Add an action over a time range to the actions assertion.
+        /// @param action_json  The timed action json, with an edit of trim, splice or overlay
+        ///                     or an action label, and optional start and end times.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_timed_action(const string &action_json);
+
         /// @brief Sign an input stream and write the signed data to an output stream.
         /// @param format The format of the output stream.
         /// @param source The input stream to sign.
@@ -245,6 +569,15 @@ namespace c2pa
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         std::vector<unsigned char> sign(const string &format, istream &source, ostream &dest, Signer &signer);
 
+        /// @brief Sign an asset held in memory.
+        /// @param format The format of the asset.
+        /// @param source The asset bytes to sign.
+        /// @param dest Receives the signed asset bytes.
+        /// @param signer A signer object to use when signing.
+        /// @return A vector containing the signed manifest bytes.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<unsigned char> sign(const string &format, const std::vector<unsigned char> &source, std::vector<unsigned char> &dest, Signer &signer);
+
         /// @brief Sign a file and write the signed data to an output file.
         /// @param source_path The path to the file to sign.
         /// @param dest_path The path to write the signed file to.
@@ -253,6 +586,15 @@ namespace c2pa
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         std::vector<unsigned char> sign(const path &source_path, const path &dest_path, Signer &signer);
 
+        /// @brief Sign a fragmented BMFF asset, such as a DASH presentation.
+        /// @details The signed init segment and fragments are written to the output directory.
+        /// @param init_path The path to the init segment.
+        /// @param fragment_paths The paths to the fragments.
+        /// @param output_dir The directory to write the signed files to.
+        /// @param signer A signer object to use when signing.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void sign_fragmented(const path &init_path, const std::vector<path> &fragment_paths, const path &output_dir, Signer &signer);
+
         /// @brief Create a Builder from an archive.
         /// @param archive  The input stream to read the archive from.
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
@@ -273,6 +615,15 @@ namespace c2pa
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         void to_archive(const path &dest_path);
 
+        /// @brief Get the current state of the builder as JSON, without signing.
+        /// @return The manifest definition with everything added so far, as JSON.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        string to_json();
+
+        /// @brief Check the manifest definition for spec violations before signing.
+        /// @throws C2pa::Exception listing every problem found.
+        void validate();
+
         /// @brief Create a hashed placeholder from the builder.
         /// @param reserved_size  The size required for a signature from the intended signer.
         /// @param format  The format of the mime type or extension.
@@ -288,10 +639,65 @@ namespace c2pa
         /// @throws C2pa::Exception for errors encountered by the C2PA library.
         std::vector<unsigned char> sign_data_hashed_embeddable(Signer &signer, const string &data_hash, const string &format);
 
+        /// @brief Estimate the size of the manifest store this builder will produce.
+        /// @param signer  The signer that will be used for signing.
+        /// @return The estimated size in bytes.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        int64_t estimate_size(Signer &signer);
+
+        /// @brief Create a placeholder manifest for a muxer to write into an asset.
+        /// @param signer  The signer that will sign the asset.
+        /// @param format  The mime type or extension of the asset, or "application/c2pa" for an unframed manifest.
+        /// @return A vector containing the placeholder, framed for the format.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<unsigned char> manifest_placeholder(Signer &signer, const string &format);
+
+        /// @brief Sign a finished asset holding a placeholder from manifest_placeholder.
+        /// @param signer  The signer to use for signing.
+        /// @param format  The format used for the placeholder.
+        /// @param asset  The finished asset.
+        /// @param offset  The offset of the placeholder in the asset.
+        /// @param placeholder_size  The size of the placeholder.
+        /// @return The patches that replace the placeholder with the signed manifest.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<ManifestPatch> sign_placeholder(Signer &signer, const string &format, std::istream &asset, uint64_t offset, size_t placeholder_size);
+
     private:
         // Private constructor for Builder from an archive (todo: find a better way to handle this)
         Builder(istream &archive);
     };
+
+    /// @brief SigningSession class for signing many assets with one manifest template.
+    /// @details The template and signer details are prepared once, so each asset only
+    ///          pays for hashing and the signature.
+    class C2PA_EXPORT SigningSession
+    {
+    private:
+        C2paSigningSession *session;
+
+    public:
+        /// @brief Create a SigningSession from a manifest definition and a signer.
+        /// @param manifest_json The manifest definition used for every asset.
+        /// @param signer The signer, which must outlive the session.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        SigningSession(const string &manifest_json, Signer &signer);
+
+        ~SigningSession();
+
+        /// @brief Add a resource included with every asset signed in the session.
+        /// @param uri The uri identifier of the resource.
+        /// @param source The input stream to read the resource from.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        void add_resource(const string &uri, istream &source);
+
+        /// @brief Sign an input stream and write the signed data to an output stream.
+        /// @param format The format of the output stream.
+        /// @param source The input stream to sign.
+        /// @param dest The output stream to write the signed data to.
+        /// @return A vector containing the signed manifest bytes.
+        /// @throws C2pa::Exception for errors encountered by the C2PA library.
+        std::vector<unsigned char> sign(const string &format, istream &source, ostream &dest);
+    };
 }
 
 // Restore warnings
@@ -0,0 +1,133 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Tracks signature requests that the host completes later, such as requests
+//! to a remote KMS or HSM service made with an asynchronous client.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{sync_channel, RecvTimeoutError, SyncSender},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::{Error, Result};
+
+// a None signature reports that the request failed
+type Pending = HashMap<u64, SyncSender<Option<Vec<u8>>>>;
+
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn with_pending<T>(f: impl FnOnce(&mut Pending) -> T) -> T {
+    let mut pending = PENDING.lock().unwrap_or_else(|err| err.into_inner());
+    f(pending.get_or_insert_with(HashMap::new))
+}
+
+// how long a signature request is waited for before signing fails
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Starts a signature request and waits until it is completed or times out.
+///
+/// The start function is given the request id and returns false if the request
+/// could not be started, in which case it must not be completed.
+pub(crate) fn request(start: impl FnOnce(u64) -> bool) -> Result<Vec<u8>> {
+    request_with_timeout(start, REQUEST_TIMEOUT)
+}
+
+fn request_with_timeout(start: impl FnOnce(u64) -> bool, timeout: Duration) -> Result<Vec<u8>> {
+    let request_id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = sync_channel(1);
+    with_pending(|pending| pending.insert(request_id, sender));
+    if !start(request_id) {
+        with_pending(|pending| pending.remove(&request_id));
+        return Err(Error::Signature(format!(
+            "signature request {request_id} was not started"
+        )));
+    }
+    match receiver.recv_timeout(timeout) {
+        Ok(Some(signature)) => Ok(signature),
+        Err(RecvTimeoutError::Timeout) => {
+            // a late completion then finds no pending request
+            with_pending(|pending| pending.remove(&request_id));
+            Err(Error::SignatureTimeout(format!(
+                "signature request {request_id} was not completed in {} ms",
+                timeout.as_millis()
+            )))
+        }
+        _ => Err(Error::Signature(format!(
+            "signature request {request_id} failed"
+        ))),
+    }
+}
+
+/// Completes a signature request with the signature, or None if signing failed.
+pub fn complete(request_id: u64, signature: Option<Vec<u8>>) -> Result<()> {
+    let sender = with_pending(|pending| pending.remove(&request_id))
+        .ok_or_else(|| Error::Other(format!("no pending signature request {request_id}")))?;
+    // the waiting signer only goes away when the request is removed
+    let _ = sender.send(signature);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_request_completed_later() {
+        let signature = request(|request_id| {
+            thread::spawn(move || complete(request_id, Some(vec![1, 2, 3])).unwrap());
+            true
+        })
+        .unwrap();
+        assert_eq!(signature, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_request_failures() {
+        assert!(request(|request_id| {
+            thread::spawn(move || complete(request_id, None).unwrap());
+            true
+        })
+        .is_err());
+
+        let mut started = 0;
+        assert!(request(|request_id| {
+            started = request_id;
+            false
+        })
+        .is_err());
+        assert!(complete(started, Some(vec![1])).is_err());
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let mut started = 0;
+        let err = request_with_timeout(
+            |request_id| {
+                started = request_id;
+                true
+            },
+            Duration::from_millis(10),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::SignatureTimeout(_)));
+        assert!(complete(started, Some(vec![1])).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Validates many assets at once and summarizes the results.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use c2pa::Error as C2paError;
+use c2pa_status_tracker::validation_codes::SIGNING_CREDENTIAL_UNTRUSTED;
+use serde::{Deserialize, Serialize};
+
+use crate::{capabilities, refresh, remote, Error, Result};
+
+/// Options for verifying a directory of assets.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BatchOptions {
+    /// Include assets in sub-directories, not following symbolic links to directories.
+    pub recursive: bool,
+    /// The number of assets validated at once, or zero to use the available parallelism.
+    pub threads: usize,
+}
+
+/// The outcome of validating one asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// The manifest store validated without errors.
+    Valid,
+    /// The only errors were untrusted signing credentials.
+    Untrusted,
+    /// The manifest store has validation errors.
+    Invalid,
+    /// The asset has no manifest store.
+    NoManifest,
+    /// The asset could not be read.
+    Error,
+}
+
+/// The validation result for one asset.
+#[derive(Debug, Serialize)]
+pub struct AssetReport {
+    pub path: String,
+    pub verdict: Verdict,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_manifest: Option<String>,
+    /// The validation status codes reported for the asset.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub status: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The sidecar file the manifest store was read from, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidecar_path: Option<String>,
+}
+
+/// Counts of each verdict and the result for every asset, in input order.
+#[derive(Debug, Default, Serialize)]
+pub struct BatchReport {
+    pub total: usize,
+    pub valid: usize,
+    pub untrusted: usize,
+    pub invalid: usize,
+    pub no_manifest: usize,
+    pub errors: usize,
+    pub files: Vec<AssetReport>,
+}
+
+impl BatchReport {
+    /// Returns the report as a JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::Json(e.to_string()))
+    }
+}
+
+fn verify_asset(path: &Path) -> AssetReport {
+    let mut report = AssetReport {
+        path: path.to_string_lossy().into_owned(),
+        verdict: Verdict::Error,
+        active_manifest: None,
+        status: Vec::new(),
+        error: None,
+        sidecar_path: None,
+    };
+    match remote::read_file_with_source(path) {
+        Ok((reader, source)) => {
+            report.sidecar_path = source.sidecar_path;
+            report.active_manifest = reader.active_label().map(String::from);
+            report.status = reader
+                .validation_status()
+                .unwrap_or_default()
+                .iter()
+                .map(|status| status.code().to_string())
+                .collect();
+            report.verdict = if report.status.is_empty() {
+                Verdict::Valid
+            } else if report
+                .status
+                .iter()
+                .all(|code| code == SIGNING_CREDENTIAL_UNTRUSTED)
+            {
+                Verdict::Untrusted
+            } else {
+                Verdict::Invalid
+            };
+        }
+        Err(C2paError::JumbfNotFound) => report.verdict = Verdict::NoManifest,
+        Err(err) => report.error = Some(err.to_string()),
+    }
+    report
+}
+
+/// Validates each asset and returns the aggregated report.
+///
+/// Assets are validated concurrently unless single threaded mode was requested with c2pa_init.
+pub fn verify_paths(paths: &[PathBuf], threads: usize) -> BatchReport {
+    let threads = if refresh::single_threaded() {
+        1
+    } else if threads == 0 {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    };
+    let threads = threads.min(paths.len()).max(1);
+
+    let results: Vec<Mutex<Option<AssetReport>>> = paths.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let work = || loop {
+        let index = next.fetch_add(1, Ordering::Relaxed);
+        let Some(path) = paths.get(index) else {
+            break;
+        };
+        let report = verify_asset(path);
+        if let Ok(mut result) = results[index].lock() {
+            *result = Some(report);
+        }
+    };
+    if threads == 1 {
+        work();
+    } else {
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(work);
+            }
+        });
+    }
+
+    let mut report = BatchReport::default();
+    for result in results {
+        let Some(asset) = result.into_inner().ok().flatten() else {
+            continue;
+        };
+        match asset.verdict {
+            Verdict::Valid => report.valid += 1,
+            Verdict::Untrusted => report.untrusted += 1,
+            Verdict::Invalid => report.invalid += 1,
+            Verdict::NoManifest => report.no_manifest += 1,
+            Verdict::Error => report.errors += 1,
+        }
+        report.files.push(asset);
+    }
+    report.total = report.files.len();
+    report
+}
+
+// Symbolic links to directories are not followed, so a link to the directory itself
+// or one of its parents cannot recurse without end.
+fn collect_assets(dir: &Path, recursive: bool, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = std::fs::read_dir(dir).map_err(|e| Error::Io(format!("{dir:?}: {e}")))?;
+    let mut entries: Vec<(PathBuf, std::fs::FileType)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            Some((entry.path(), entry.file_type().ok()?))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    for (path, file_type) in entries {
+        if file_type.is_dir() {
+            if recursive {
+                collect_assets(&path, recursive, paths)?;
+            }
+        } else if file_type.is_symlink() && path.is_dir() {
+            continue;
+        } else if path
+            .extension()
+            .is_some_and(|ext| capabilities::can_read(&ext.to_string_lossy()))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Validates every supported asset in a directory and returns the aggregated report.
+///
+/// Files are sorted by path and files without a supported extension are skipped.
+pub fn verify_directory(dir: &Path, options: &BatchOptions) -> Result<BatchReport> {
+    let mut paths = Vec::new();
+    collect_assets(dir, options.recursive, &mut paths)?;
+    Ok(verify_paths(&paths, options.threads))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_directory() {
+        let options = BatchOptions {
+            threads: 2,
+            ..Default::default()
+        };
+        let report = verify_directory(Path::new("tests/fixtures"), &options).unwrap();
+        assert_eq!(report.total, report.files.len());
+        let c = report
+            .files
+            .iter()
+            .find(|file| file.path.ends_with("C.jpg"))
+            .unwrap();
+        assert_eq!(c.verdict, Verdict::Valid);
+        let a = report
+            .files
+            .iter()
+            .find(|file| file.path.ends_with("A.jpg"))
+            .unwrap();
+        assert_eq!(a.verdict, Verdict::NoManifest);
+        assert!(report.valid >= 1 && report.no_manifest >= 1);
+
+        let missing = verify_paths(&[PathBuf::from("tests/fixtures/missing.jpg")], 0);
+        assert_eq!(missing.errors, 1);
+        assert!(missing.files[0].error.is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_directory_symlink_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::copy("tests/fixtures/C.jpg", sub.join("C.jpg")).unwrap();
+        // links to the directory itself and to its parent
+        std::os::unix::fs::symlink(&sub, sub.join("self")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), sub.join("parent")).unwrap();
+        // a link to a file is still verified
+        std::os::unix::fs::symlink(sub.join("C.jpg"), dir.path().join("linked.jpg")).unwrap();
+
+        let options = BatchOptions {
+            recursive: true,
+            ..Default::default()
+        };
+        let report = verify_directory(dir.path(), &options).unwrap();
+        assert_eq!(report.total, 2);
+        assert_eq!(report.valid, 2);
+    }
+}
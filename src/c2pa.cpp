@@ -21,6 +21,7 @@
 #include <string.h>
 #include <optional>   // C++17
 #include <filesystem> // C++17
+#include <memory>
 
 #include "c2pa.hpp"
 #include "cpp_io_stream.h"
@@ -35,14 +36,24 @@ namespace c2pa
     /// Exception class for C2PA errors.
     /// This class is used to throw exceptions for errors encountered by the C2PA library via c2pa_error().
 
-    Exception::Exception() : message(c2pa_error())
+    Exception::Exception() : error_code(ErrorNone)
     {
-        auto result = c2pa_error();
-        message = string(result);
-        c2pa_release_string(result);
+        // copied rather than taken, so c2pa_error still returns the error afterwards
+        auto error = c2pa_error_copy();
+        if (error == NULL)
+        {
+            return;
+        }
+        error_code = c2pa_error_get_code(error);
+        message = string(c2pa_error_get_message(error));
+        for (size_t i = 0; i < c2pa_error_source_count(error); i++)
+        {
+            error_sources.push_back(string(c2pa_error_source(error, i)));
+        }
+        c2pa_error_free(error);
     }
 
-    Exception::Exception(string what) : message(what)
+    Exception::Exception(string what) : message(what), error_code(ErrorOther)
     {
     }
 
@@ -51,6 +62,16 @@ namespace c2pa
         return message.c_str();
     }
 
+    C2paErrorCode Exception::code() const
+    {
+        return error_code;
+    }
+
+    const vector<string> &Exception::sources() const
+    {
+        return error_sources;
+    }
+
     /// Returns the version of the C2PA library.
     string version()
     {
@@ -60,13 +81,58 @@ namespace c2pa
         return str;
     }
 
+    /// Returns the version and capabilities of the C2pa library.
+    const C2paVersionInfo &version_info()
+    {
+        return *c2pa_version_info();
+    }
+
+    bool can_read(const string &format)
+    {
+        return c2pa_can_read(format.c_str()) == 1;
+    }
+
+    bool can_sign(const string &format)
+    {
+        return c2pa_can_sign(format.c_str()) == 1;
+    }
+
+    std::vector<string> supported_mime_types(bool for_signing)
+    {
+        char *result = c2pa_supported_mime_types(for_signing ? 1 : 0);
+        std::vector<string> mime_types;
+        if (result == NULL)
+        {
+            return mime_types;
+        }
+        string list = string(result);
+        c2pa_release_string(result);
+        size_t start = 0;
+        while (start < list.size())
+        {
+            size_t end = list.find(',', start);
+            if (end == string::npos)
+            {
+                end = list.size();
+            }
+            mime_types.push_back(list.substr(start, end - start));
+            start = end + 1;
+        }
+        return mime_types;
+    }
+
+    bool has_feature(const string &name)
+    {
+        return c2pa_has_feature(name.c_str()) == 1;
+    }
+
     /// Loads C2PA settings from a string in a given format.
-    /// @param format the mime format of the string.
+    /// @param format the format of the string, "json" or "toml".
     /// @param data the string to load.
     /// @throws a C2pa::Exception for errors encountered by the C2PA library.
     void load_settings(const string &format, const string &data)
     {
-        auto result = c2pa_load_settings(format.c_str(), data.c_str());
+        auto result = c2pa_load_settings(data.c_str(), format.c_str());
         if (result != 0)
         {
             throw c2pa::Exception();
@@ -138,13 +204,88 @@ namespace c2pa
         return;
     }
 
+    /// Validates every supported asset in a directory.
+    /// @param dir the directory to walk.
+    /// @param options_json optional json options such as {"recursive": true}.
+    /// @return a json report with verdict counts and a result for each asset.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    string verify_directory(const path &dir, const std::optional<string> &options_json)
+    {
+        char *result = c2pa_verify_directory(dir.c_str(), options_json ? options_json->c_str() : NULL);
+        if (result == NULL)
+        {
+            throw c2pa::Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    /// Returns a normalized, pretty printed version of a manifest definition.
+    /// @param manifest_json the manifest definition json.
+    /// @return the canonical json with defaults filled in and sorted keys.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    string canonicalize_manifest_json(const string &manifest_json)
+    {
+        char *result = c2pa_manifest_json_canonicalize(manifest_json.c_str());
+        if (result == NULL)
+        {
+            throw c2pa::Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    /// Verifies a COSE_Sign1 signature such as a detached claim signature.
+    /// @param cose the COSE_Sign1 bytes.
+    /// @param payload the detached payload, if the signature does not embed it.
+    /// @param certs an optional PEM certificate chain to verify against.
+    /// @return a json string with the payload and signer information.
+    /// @throws a C2pa::Exception if the signature is not valid.
+    string verify_cose(const std::vector<unsigned char> &cose,
+                       const std::optional<std::vector<unsigned char>> &payload,
+                       const std::optional<string> &certs)
+    {
+        char *result = c2pa_cose_verify(cose.data(), cose.size(),
+                                        payload ? payload->data() : NULL,
+                                        payload ? payload->size() : 0,
+                                        certs ? certs->c_str() : NULL);
+        if (result == NULL)
+        {
+            throw c2pa::Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    /// Frames a signed manifest store for embedding in an asset of the given format.
+    /// @param format the mime type or extension of the asset.
+    /// @param manifest_bytes the signed manifest store bytes.
+    /// @return the bytes to insert into the asset, such as JPEG APP11 segments.
+    /// @throws a C2pa::Exception for errors encountered by the C2PA library.
+    std::vector<unsigned char> format_embeddable(const string &format, const std::vector<unsigned char> &manifest_bytes)
+    {
+        const unsigned char *result_bytes = NULL;
+        auto result = c2pa_format_embeddable(format.c_str(), manifest_bytes.data(), manifest_bytes.size(), &result_bytes);
+        if (result < 0 || result_bytes == NULL)
+        {
+            throw c2pa::Exception();
+        }
+
+        auto data = std::vector<unsigned char>(result_bytes, result_bytes + result);
+        c2pa_resource_bytes_free(result_bytes, result);
+        return data;
+    }
+
     /// IStream Class wrapper for CStream.
     template <typename IStream>
     CppIStream::CppIStream(IStream &istream) : CStream()
     {
         // assert(std::is_base_of<std::istream, IStream>::value, "Stream must be derived from std::istream");
 
-        c_stream = c2pa_create_stream(reinterpret_cast<StreamContext *>(&istream), (ReadCallback)reader, (SeekCallback)seeker, (WriteCallback)writer, (FlushCallback)flusher);
+        c_stream = c2pa_create_stream64(reinterpret_cast<StreamContext *>(&istream), (ReadCallback)reader, (SeekCallback64)seeker, (WriteCallback)writer, (FlushCallback)flusher);
     }
 
     CppIStream::~CppIStream()
@@ -175,7 +316,7 @@ namespace c2pa
         return gcount;
     }
 
-    long CppIStream::seeker(StreamContext *context, long int offset, int whence)
+    int64_t CppIStream::seeker(StreamContext *context, int64_t offset, int whence)
     {
         std::istream *istream = (std::istream *)context;
 
@@ -205,7 +346,7 @@ namespace c2pa
             errno = EIO;
             return -1;
         }
-        int pos = istream->tellg();
+        std::streamoff pos = istream->tellg();
         if (pos < 0)
         {
             errno = EIO;
@@ -245,7 +386,7 @@ namespace c2pa
     CppOStream::CppOStream(OStream &ostream) : CStream()
     {
         static_assert(std::is_base_of<std::ostream, OStream>::value, "Stream must be derived from std::ostream");
-        c_stream = c2pa_create_stream(reinterpret_cast<StreamContext *>(&ostream), (ReadCallback)reader, (SeekCallback)seeker, (WriteCallback)writer, (FlushCallback)flusher);
+        c_stream = c2pa_create_stream64(reinterpret_cast<StreamContext *>(&ostream), (ReadCallback)reader, (SeekCallback64)seeker, (WriteCallback)writer, (FlushCallback)flusher);
     }
 
     CppOStream::~CppOStream()
@@ -259,7 +400,7 @@ namespace c2pa
         return -1;
     }
 
-    long CppOStream::seeker(StreamContext *context, long int offset, int whence)
+    int64_t CppOStream::seeker(StreamContext *context, int64_t offset, int whence)
     {
         std::ostream *ostream = (std::ostream *)context;
         // printf("seeker ofstream = %p\n", ostream);
@@ -296,7 +437,7 @@ namespace c2pa
             errno = EIO; // Input/output error
             return -1;
         }
-        int pos = ostream->tellp();
+        std::streamoff pos = ostream->tellp();
         if (pos < 0)
         {
             errno = EIO; // Input/output error
@@ -335,7 +476,7 @@ namespace c2pa
     CppIOStream::CppIOStream(IOStream &iostream)
     {
         static_assert(std::is_base_of<std::iostream, IOStream>::value, "Stream must be derived from std::iostream");
-        c_stream = c2pa_create_stream(reinterpret_cast<StreamContext *>(&iostream), (ReadCallback)reader, (SeekCallback)seeker, (WriteCallback)writer, (FlushCallback)flusher);
+        c_stream = c2pa_create_stream64(reinterpret_cast<StreamContext *>(&iostream), (ReadCallback)reader, (SeekCallback64)seeker, (WriteCallback)writer, (FlushCallback)flusher);
     }
     CppIOStream::~CppIOStream()
     {
@@ -365,7 +506,7 @@ namespace c2pa
         return gcount;
     }
 
-    long CppIOStream::seeker(StreamContext *context, long int offset, int whence)
+    int64_t CppIOStream::seeker(StreamContext *context, int64_t offset, int whence)
     {
         iostream *iostream = (std::iostream *)context;
 
@@ -402,7 +543,7 @@ namespace c2pa
             errno = EIO; // Input/output error
             return -1;
         }
-        int pos = iostream->tellg();
+        std::streamoff pos = iostream->tellg();
         if (pos < 0)
         {
             errno = EIO; // Input/output error
@@ -454,6 +595,19 @@ namespace c2pa
     }
 
     /// Reader class for reading a manifest implementation.
+    string content_id(const string &format, std::istream &stream)
+    {
+        CppIStream cpp_stream(stream);
+        char *result = c2pa_content_id(format.c_str(), cpp_stream.c_stream);
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
     Reader::Reader(const string &format, std::istream &stream)
     {
         cpp_stream = new CppIStream(stream); // keep this allocated for life of Reader
@@ -464,6 +618,43 @@ namespace c2pa
         }
     }
 
+    Reader::Reader(const string &format, const std::vector<unsigned char> &manifest_data, std::istream &stream)
+    {
+        cpp_stream = new CppIStream(stream); // keep this allocated for life of Reader
+        c2pa_reader = c2pa_reader_from_manifest_data_and_stream(format.c_str(), manifest_data.data(), manifest_data.size(), cpp_stream->c_stream);
+        if (c2pa_reader == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    Reader::Reader(const string &format, std::istream &stream, const string &alg, const string &value)
+    {
+        cpp_stream = new CppIStream(stream); // keep this allocated for life of Reader
+        c2pa_reader = c2pa_reader_from_soft_binding(format.c_str(), cpp_stream->c_stream, alg.c_str(), value.c_str());
+        if (c2pa_reader == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    Reader::Reader(const string &format, std::istream &init_stream, const std::vector<std::istream *> &fragments)
+    {
+        cpp_stream = new CppIStream(init_stream); // keep this allocated for life of Reader
+        std::vector<std::unique_ptr<CppIStream>> fragment_streams;
+        std::vector<CStream *> c_streams;
+        for (std::istream *fragment : fragments)
+        {
+            fragment_streams.push_back(std::make_unique<CppIStream>(*fragment));
+            c_streams.push_back(fragment_streams.back()->c_stream);
+        }
+        c2pa_reader = c2pa_reader_from_fragmented_stream(format.c_str(), cpp_stream->c_stream, c_streams.data(), c_streams.size());
+        if (c2pa_reader == NULL)
+        {
+            throw Exception();
+        }
+    }
+
     Reader::Reader(const std::filesystem::path &source_path)
     {
         std::ifstream file_stream(source_path, std::ios::binary);
@@ -471,14 +662,19 @@ namespace c2pa
         {
             throw Exception("Failed to open file: " + source_path.string() + " - " + std::strerror(errno));
         }
-        string extension = source_path.extension().string();
-        if (!extension.empty())
+        file_stream.close();
+
+        // the library opens the file itself so it stays readable for the life of the Reader
+        c2pa_reader = c2pa_reader_from_file(source_path.string().c_str());
+        if (c2pa_reader == NULL)
         {
-            extension = extension.substr(1); // Skip the dot
+            throw Exception();
         }
+    }
 
-        cpp_stream = new CppIStream(file_stream); // keep this allocated for life of Reader
-        c2pa_reader = c2pa_reader_from_stream(extension.c_str(), cpp_stream->c_stream);
+    Reader::Reader(const string &format, const std::vector<unsigned char> &data)
+    {
+        c2pa_reader = c2pa_reader_from_bytes(format.c_str(), data.data(), data.size());
         if (c2pa_reader == NULL)
         {
             throw Exception();
@@ -527,145 +723,579 @@ namespace c2pa
         return result;
     }
 
-    intptr_t signer_passthrough(const void *context, const unsigned char *data, uintptr_t len, unsigned char *signature, uintptr_t sig_max_len)
+    int Reader::get_ingredient_thumbnail(const string &manifest_label, size_t ingredient_index, std::ostream &stream)
     {
-        try
+        CppOStream cpp_stream(stream);
+        const char *label = manifest_label.empty() ? NULL : manifest_label.c_str();
+        int result = c2pa_reader_ingredient_thumbnail(c2pa_reader, label, ingredient_index, cpp_stream.c_stream);
+        if (result < 0)
         {
-            // the context is a pointer to the C++ callback function
-            SignerFunc *callback = (SignerFunc *)context;
-            std::vector<uint8_t> data_vec(data, data + len);
-            std::vector<uint8_t> signature_vec = (callback)(data_vec);
-            if (signature_vec.size() > sig_max_len)
-            {
-                return -1;
-            }
-            std::copy(signature_vec.begin(), signature_vec.end(), signature);
-            return signature_vec.size();
+            throw Exception();
         }
-        catch (std::exception const &e)
+        return result;
+    }
+
+    std::vector<unsigned char> Reader::get_resource(const string &uri)
+    {
+        const unsigned char *data = nullptr;
+        uintptr_t len = 0;
+        if (c2pa_reader_resource_to_buffer(c2pa_reader, uri.c_str(), &data, &len) < 0)
         {
-            // todo pass exceptions to Rust error handling
-            // printf("Error: signer_passthrough - %s\n", e.what());
-            return -1;
+            throw Exception();
         }
-        catch (...)
+        std::vector<unsigned char> resource(data, data + len);
+        c2pa_resource_bytes_free(data, len);
+        return resource;
+    }
+
+    string Reader::resource_list()
+    {
+        char *result = c2pa_reader_resource_list(c2pa_reader);
+        if (result == NULL)
         {
-            // printf("Error: signer_passthrough - unknown exception\n");
-            return -1;
+            throw Exception();
         }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    Signer::Signer(SignerFunc *callback, C2paSigningAlg alg, const string &sign_cert, const string &tsa_uri)
+    void Reader::to_folder(const std::filesystem::path &dir)
     {
-        // Pass the C++ callback as a context to our static callback wrapper.
-        signer = c2pa_signer_create((const void *)callback, &signer_passthrough, alg, sign_cert.c_str(), tsa_uri.c_str());
+        if (c2pa_reader_to_folder(c2pa_reader, dir.c_str()) < 0)
+        {
+            throw Exception();
+        }
     }
 
-    Signer::~Signer()
+    string Reader::certificates(const string &label)
     {
-        c2pa_signer_free(signer);
+        char *result = c2pa_reader_certificates(c2pa_reader, label.empty() ? NULL : label.c_str());
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    /// @brief  Get the C2paSigner
-    C2paSigner *Signer::c2pa_signer()
+    string Reader::cert_chain(const string &label)
     {
-        return signer;
+        char *result = c2pa_reader_cert_chain(c2pa_reader, label.empty() ? NULL : label.c_str());
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    /// @brief  Get the size to reserve for a signature for this signer.
-    uintptr_t Signer::reserve_size()
+    std::vector<unsigned char> Reader::manifest_bytes()
     {
-        return c2pa_signer_reserve_size(signer);
+        const unsigned char *data = nullptr;
+        uintptr_t len = 0;
+        if (c2pa_reader_manifest_bytes(c2pa_reader, &data, &len) < 0)
+        {
+            throw Exception();
+        }
+        std::vector<unsigned char> bytes(data, data + len);
+        c2pa_resource_bytes_free(data, len);
+        return bytes;
     }
 
-    /// @brief  Builder class for creating a manifest implementation.
-    Builder::Builder(const string &manifest_json)
+    SignatureInfo Reader::signature_info()
     {
-        builder = c2pa_builder_from_json(manifest_json.c_str());
-        if (builder == NULL)
+        C2paSignatureInfo *result = c2pa_reader_signature_info(c2pa_reader);
+        if (result == NULL)
         {
             throw Exception();
         }
+        auto field = [](const char *value)
+        { return value == NULL ? string() : string(value); };
+        SignatureInfo info = {field(result->alg), field(result->issuer), field(result->common_name),
+                              field(result->cert_serial_number), field(result->time)};
+        c2pa_signature_info_free(result);
+        return info;
     }
 
-    /// @brief Create a Builder from an archive.
-    /// @param archive  The input stream to read the archive from.
-    /// @throws C2pa::Exception for errors encountered by the C2PA library.
-    Builder::Builder(istream &archive)
+    string Reader::summary(const string &locale)
     {
-        CppIStream c_archive = CppIStream(archive);
-        builder = c2pa_builder_from_archive(c_archive.c_stream);
-        if (builder == NULL)
+        char *result = c2pa_reader_summary(c2pa_reader, locale.c_str());
+        if (result == NULL)
         {
             throw Exception();
         }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    Builder::~Builder()
+    string Reader::remote_url()
     {
-        c2pa_builder_free(builder);
+        char *result = c2pa_reader_remote_url(c2pa_reader);
+        if (result == NULL)
+        {
+            return "";
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    void Builder::set_no_embed()
+    string Reader::sidecar_path()
     {
-        c2pa_builder_set_no_embed(builder);
+        char *result = c2pa_reader_sidecar_path(c2pa_reader);
+        if (result == NULL)
+        {
+            return "";
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    void Builder::set_remote_url(const string &remote_url)
+    bool Reader::is_embedded()
     {
-        int result = c2pa_builder_set_remote_url(builder, remote_url.c_str());
+        auto result = c2pa_reader_is_embedded(c2pa_reader);
         if (result < 0)
         {
             throw Exception();
         }
+        return result == 1;
     }
 
-    void Builder::add_resource(const string &uri, istream &source)
+    string Reader::active_manifest_label()
     {
-        CppIStream c_source = CppIStream(source);
-        int result = c2pa_builder_add_resource(builder, uri.c_str(), c_source.c_stream);
-        if (result < 0)
+        char *result = c2pa_reader_active_manifest_label(c2pa_reader);
+        if (result == NULL)
         {
             throw Exception();
         }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    void Builder::add_resource(const string &uri, const std::filesystem::path &source_path)
+    std::vector<string> Reader::manifest_labels()
     {
-        ifstream stream = ifstream(source_path);
-        if (!stream.is_open())
+        auto count = c2pa_reader_manifest_count(c2pa_reader);
+        if (count < 0)
         {
-            throw std::runtime_error("Failed to open source file: " + source_path.string());
+            throw Exception();
         }
-        add_resource(uri, stream);
+        std::vector<string> labels;
+        for (int i = 0; i < count; i++)
+        {
+            char *result = c2pa_reader_manifest_label_at(c2pa_reader, i);
+            if (result == NULL)
+            {
+                throw Exception();
+            }
+            labels.push_back(string(result));
+            c2pa_release_string(result);
+        }
+        return labels;
     }
 
-    void Builder::add_ingredient(const string &ingredient_json, const string &format, istream &source)
+    string Reader::manifest_json(const string &label)
     {
-        CppIStream c_source = CppIStream(source);
-        int result = c2pa_builder_add_ingredient_from_stream(builder, ingredient_json.c_str(), format.c_str(), c_source.c_stream);
-        if (result < 0)
+        char *result = c2pa_reader_manifest_json(c2pa_reader, label.c_str());
+        if (result == NULL)
         {
             throw Exception();
         }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
     }
 
-    void Builder::add_ingredient(const string &ingredient_json, const std::filesystem::path &source_path)
+    string Reader::get_assertion(const string &manifest_label, const string &assertion_label, size_t instance)
     {
-        ifstream stream = ifstream(source_path);
-        if (!stream.is_open())
+        const char *label = manifest_label.empty() ? NULL : manifest_label.c_str();
+        char *result = c2pa_reader_get_assertion(c2pa_reader, label, assertion_label.c_str(), instance);
+        if (result == NULL)
         {
-            throw std::runtime_error("Failed to open source file: " + source_path.string());
+            throw Exception();
         }
-        auto format = source_path.extension().string();
-        if (!format.empty())
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    std::vector<unsigned char> Reader::get_assertion_cbor(const string &manifest_label, const string &assertion_label, size_t instance)
+    {
+        const char *label = manifest_label.empty() ? NULL : manifest_label.c_str();
+        const unsigned char *data = nullptr;
+        uintptr_t len = 0;
+        if (c2pa_reader_get_assertion_cbor(c2pa_reader, label, assertion_label.c_str(), instance, &data, &len) < 0)
         {
-            format = format.substr(1); // Skip the dot.
+            throw Exception();
         }
-        add_ingredient(ingredient_json, format.c_str(), stream);
+        std::vector<unsigned char> bytes(data, data + len);
+        c2pa_resource_bytes_free(data, len);
+        return bytes;
     }
 
-    std::vector<unsigned char> Builder::sign(const string &format, istream &source, ostream &dest, Signer &signer)
+    string Reader::validation_status()
+    {
+        char *result = c2pa_reader_validation_status(c2pa_reader);
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    string Reader::fragment_status()
+    {
+        char *result = c2pa_reader_fragment_status(c2pa_reader);
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    string Reader::validation_results()
+    {
+        char *result = c2pa_reader_validation_results(c2pa_reader);
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    string Reader::identity_assertions()
+    {
+        char *result = c2pa_reader_identity_assertions(c2pa_reader);
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    intptr_t signer_passthrough(const void *context, const unsigned char *data, uintptr_t len, unsigned char *signature, uintptr_t sig_max_len)
+    {
+        try
+        {
+            // a signature too large for the buffer, kept for the call with a larger buffer
+            // so the data is not signed twice
+            thread_local std::vector<uint8_t> pending_data;
+            thread_local std::vector<uint8_t> pending_signature;
+            // the context is a pointer to the C++ callback function
+            SignerFunc *callback = (SignerFunc *)context;
+            std::vector<uint8_t> data_vec(data, data + len);
+            std::vector<uint8_t> signature_vec;
+            if (!pending_signature.empty() && pending_data == data_vec)
+            {
+                signature_vec.swap(pending_signature);
+                pending_data.clear();
+            }
+            else
+            {
+                signature_vec = (callback)(data_vec);
+            }
+            // report the size needed, the callback is invoked again with a larger buffer
+            if (signature_vec.size() > sig_max_len)
+            {
+                pending_data.swap(data_vec);
+                pending_signature.swap(signature_vec);
+                return pending_signature.size();
+            }
+            std::copy(signature_vec.begin(), signature_vec.end(), signature);
+            return signature_vec.size();
+        }
+        catch (std::exception const &e)
+        {
+            // todo pass exceptions to Rust error handling
+            // printf("Error: signer_passthrough - %s\n", e.what());
+            return -1;
+        }
+        catch (...)
+        {
+            // printf("Error: signer_passthrough - unknown exception\n");
+            return -1;
+        }
+    }
+
+    Signer::Signer(SignerFunc *callback, C2paSigningAlg alg, const string &sign_cert, const string &tsa_uri)
+    {
+        // Pass the C++ callback as a context to our static callback wrapper.
+        signer = c2pa_signer_create((const void *)callback, &signer_passthrough, alg, sign_cert.c_str(), tsa_uri.c_str());
+    }
+
+    Signer::Signer(const SignerInfo &signer_info)
+    {
+        signer = c2pa_signer_from_info(&signer_info);
+        if (signer == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    Signer::Signer(C2paSigningAlg alg, const std::vector<unsigned char> &certs, const std::vector<unsigned char> &private_key, const string &tsa_uri)
+    {
+        signer = c2pa_signer_from_der(alg, certs.data(), certs.size(), private_key.data(), private_key.size(), tsa_uri.empty() ? NULL : tsa_uri.c_str());
+        if (signer == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    Signer::~Signer()
+    {
+        c2pa_signer_free(signer);
+    }
+
+    C2paSignerProblem Signer::validate(const SignerInfo &signer_info)
+    {
+        int result = c2pa_signer_validate(&signer_info);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+        return (C2paSignerProblem)result;
+    }
+
+    /// @brief  Get the C2paSigner
+    C2paSigner *Signer::c2pa_signer()
+    {
+        return signer;
+    }
+
+    /// @brief  Get the size to reserve for a signature for this signer.
+    uintptr_t Signer::reserve_size()
+    {
+        return c2pa_signer_reserve_size(signer);
+    }
+
+    /// @brief  Sign arbitrary bytes with this signer.
+    std::vector<unsigned char> Signer::sign(const std::vector<unsigned char> &data)
+    {
+        auto size = c2pa_signer_sign(signer, data.data(), data.size(), NULL, 0);
+        if (size < 0)
+        {
+            throw c2pa::Exception();
+        }
+        std::vector<unsigned char> signature(size);
+        size = c2pa_signer_sign(signer, data.data(), data.size(), signature.data(), signature.size());
+        if (size < 0)
+        {
+            throw c2pa::Exception();
+        }
+        signature.resize(size);
+        return signature;
+    }
+
+    /// @brief  Builder class for creating a manifest implementation.
+    Builder::Builder(const string &manifest_json)
+    {
+        builder = c2pa_builder_from_json(manifest_json.c_str());
+        if (builder == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    /// @brief Create a Builder from an archive.
+    /// @param archive  The input stream to read the archive from.
+    /// @throws C2pa::Exception for errors encountered by the C2PA library.
+    Builder::Builder(istream &archive)
+    {
+        CppIStream c_archive = CppIStream(archive);
+        builder = c2pa_builder_from_archive(c_archive.c_stream);
+        if (builder == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    Builder::~Builder()
+    {
+        c2pa_builder_free(builder);
+    }
+
+    void Builder::set_no_embed(bool no_embed)
+    {
+        int result = c2pa_builder_set_no_embed(builder, no_embed ? 1 : 0);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_remote_url(const string &remote_url)
+    {
+        int result = c2pa_builder_set_remote_url(builder, remote_url.c_str());
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_title(const string &title)
+    {
+        int result = c2pa_builder_set_title(builder, title.c_str());
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_format(const string &format)
+    {
+        int result = c2pa_builder_set_format(builder, format.c_str());
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_claim_generator_info(const string &name, const string &version)
+    {
+        const char *c_version = version.empty() ? NULL : version.c_str();
+        int result = c2pa_builder_set_claim_generator_info(builder, name.c_str(), c_version);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_claim_version(int version)
+    {
+        int result = c2pa_builder_set_claim_version(builder, version);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_base_path(const path &base_path)
+    {
+        int result = c2pa_builder_set_base_path(builder, base_path.string().c_str());
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_assertion(const string &label, const string &assertion_json)
+    {
+        if (c2pa_builder_add_assertion(builder, label.c_str(), assertion_json.c_str()) < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_assertion_cbor(const string &label, const std::vector<unsigned char> &data)
+    {
+        if (c2pa_builder_add_assertion_cbor(builder, label.c_str(), data.data(), data.size()) < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_redaction(const string &assertion_uri)
+    {
+        if (c2pa_builder_add_redaction(builder, assertion_uri.c_str()) < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_action(const string &action_json)
+    {
+        if (c2pa_builder_add_action(builder, action_json.c_str()) < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_timed_action(const string &action_json)
+    {
+        if (c2pa_builder_add_timed_action(builder, action_json.c_str()) < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::set_thumbnail(const string &format, istream &source)
+    {
+        CppIStream c_source = CppIStream(source);
+        int result = c2pa_builder_set_thumbnail(builder, format.c_str(), c_source.c_stream);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_resource(const string &uri, istream &source)
+    {
+        CppIStream c_source = CppIStream(source);
+        int result = c2pa_builder_add_resource(builder, uri.c_str(), c_source.c_stream);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_resource(const string &uri, const std::filesystem::path &source_path)
+    {
+        ifstream stream = ifstream(source_path);
+        if (!stream.is_open())
+        {
+            throw std::runtime_error("Failed to open source file: " + source_path.string());
+        }
+        add_resource(uri, stream);
+    }
+
+    void Builder::add_ingredient(const string &ingredient_json, const string &format, istream &source)
+    {
+        CppIStream c_source = CppIStream(source);
+        int result = c2pa_builder_add_ingredient_from_stream(builder, ingredient_json.c_str(), format.c_str(), c_source.c_stream);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    void Builder::add_ingredient(const string &ingredient_json, const std::filesystem::path &source_path)
+    {
+        ifstream stream = ifstream(source_path);
+        if (!stream.is_open())
+        {
+            throw std::runtime_error("Failed to open source file: " + source_path.string());
+        }
+        auto format = source_path.extension().string();
+        if (!format.empty())
+        {
+            format = format.substr(1); // Skip the dot.
+        }
+        add_ingredient(ingredient_json, format.c_str(), stream);
+    }
+
+    void Builder::add_ingredient(const string &ingredient_json, Reader &reader)
+    {
+        int result = c2pa_builder_add_ingredient_from_reader(builder, ingredient_json.c_str(), reader.c2pa_reader);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    std::vector<unsigned char> Builder::sign(const string &format, istream &source, ostream &dest, Signer &signer)
     {
         CppIStream c_source = CppIStream(source);
         CppOStream c_dest = CppOStream(dest);
@@ -681,6 +1311,31 @@ namespace c2pa
         return manifest_bytes;
     }
 
+    /// @brief Sign an asset held in memory.
+    /// @param format The format of the asset.
+    /// @param source The asset bytes to sign.
+    /// @param dest Receives the signed asset bytes.
+    /// @param signer A signer object to use when signing.
+    /// @return A vector containing the signed manifest bytes.
+    /// @throws C2pa::Exception for errors encountered by the C2PA library.
+    std::vector<unsigned char> Builder::sign(const string &format, const std::vector<unsigned char> &source, std::vector<unsigned char> &dest, Signer &signer)
+    {
+        const unsigned char *c2pa_output = NULL;
+        uintptr_t output_len = 0;
+        const unsigned char *c2pa_manifest_bytes = NULL;
+        auto result = c2pa_builder_sign_bytes(builder, format.c_str(), source.data(), source.size(), signer.c2pa_signer(), &c2pa_output, &output_len, &c2pa_manifest_bytes);
+        if (result < 0 || c2pa_manifest_bytes == NULL)
+        {
+            throw Exception();
+        }
+
+        dest.assign(c2pa_output, c2pa_output + output_len);
+        c2pa_resource_bytes_free(c2pa_output, output_len);
+        auto manifest_bytes = std::vector<unsigned char>(c2pa_manifest_bytes, c2pa_manifest_bytes + result);
+        c2pa_resource_bytes_free(c2pa_manifest_bytes, result);
+        return manifest_bytes;
+    }
+
     /// @brief Sign a file and write the signed data to an output file.
     /// @param source_path The path to the file to sign.
     /// @param dest_path The path to write the signed file to.
@@ -689,29 +1344,35 @@ namespace c2pa
     /// @throws C2pa::Exception for errors encountered by the C2PA library.
     std::vector<unsigned char> Builder::sign(const path &source_path, const path &dest_path, Signer &signer)
     {
-        std::ifstream source(source_path, std::ios::binary);
-        if (!source.is_open())
+        const unsigned char *c2pa_manifest_bytes = NULL;
+        auto result = c2pa_builder_sign_file(builder, source_path.c_str(), dest_path.c_str(), signer.c2pa_signer(), &c2pa_manifest_bytes);
+        if (result < 0 || c2pa_manifest_bytes == NULL)
         {
-            throw std::runtime_error("Failed to open source file: " + source_path.string());
+            throw Exception();
         }
-        // Ensure the destination directory exists
-        std::filesystem::path dest_dir = dest_path.parent_path();
-        if (!std::filesystem::exists(dest_dir))
+
+        auto manifest_bytes = std::vector<unsigned char>(c2pa_manifest_bytes, c2pa_manifest_bytes + result);
+        c2pa_manifest_bytes_free(c2pa_manifest_bytes);
+        return manifest_bytes;
+    }
+
+    void Builder::sign_fragmented(const path &init_path, const std::vector<path> &fragment_paths, const path &output_dir, Signer &signer)
+    {
+        std::vector<string> fragments;
+        for (const auto &fragment_path : fragment_paths)
         {
-            std::filesystem::create_directories(dest_dir);
+            fragments.push_back(fragment_path.string());
         }
-        std::ofstream dest(dest_path, std::ios::binary);
-        if (!dest.is_open())
+        std::vector<const char *> c_fragments;
+        for (const auto &fragment : fragments)
         {
-            throw std::runtime_error("Failed to open destination file: " + dest_path.string());
+            c_fragments.push_back(fragment.c_str());
         }
-        auto format = dest_path.extension().string();
-        if (!format.empty())
+        int result = c2pa_builder_sign_fragmented_files(builder, signer.c2pa_signer(), init_path.string().c_str(), c_fragments.data(), c_fragments.size(), output_dir.string().c_str());
+        if (result < 0)
         {
-            format = format.substr(1); // Skip the dot
+            throw Exception();
         }
-        auto result = sign(format.c_str(), source, dest, signer);
-        return result;
     }
 
     /// @brief Create a Builder from an archive stream.
@@ -761,6 +1422,26 @@ namespace c2pa
         to_archive(dest);
     }
 
+    string Builder::to_json()
+    {
+        char *result = c2pa_builder_to_json(builder);
+        if (result == NULL)
+        {
+            throw Exception();
+        }
+        string str = string(result);
+        c2pa_release_string(result);
+        return str;
+    }
+
+    void Builder::validate()
+    {
+        if (c2pa_builder_validate(builder) < 0)
+        {
+            throw Exception();
+        }
+    }
+
     std::vector<unsigned char> Builder::data_hashed_placeholder(uintptr_t reserve_size, const string &format)
     {
         const unsigned char *c2pa_manifest_bytes = NULL;
@@ -788,4 +1469,88 @@ namespace c2pa
         c2pa_manifest_bytes_free(c2pa_manifest_bytes);
         return data;
     }
+
+    int64_t Builder::estimate_size(Signer &signer)
+    {
+        auto result = c2pa_builder_estimate_size(builder, signer.c2pa_signer());
+        if (result < 0)
+        {
+            throw Exception();
+        }
+        return result;
+    }
+
+    std::vector<unsigned char> Builder::manifest_placeholder(Signer &signer, const string &format)
+    {
+        const unsigned char *c2pa_manifest_bytes = NULL;
+        auto result = c2pa_builder_manifest_placeholder(builder, signer.c2pa_signer(), format.c_str(), &c2pa_manifest_bytes);
+        if (result < 0 || c2pa_manifest_bytes == NULL)
+        {
+            throw Exception();
+        }
+
+        auto data = std::vector<unsigned char>(c2pa_manifest_bytes, c2pa_manifest_bytes + result);
+        c2pa_resource_bytes_free(c2pa_manifest_bytes, result);
+        return data;
+    }
+
+    std::vector<ManifestPatch> Builder::sign_placeholder(Signer &signer, const string &format, std::istream &asset, uint64_t offset, size_t placeholder_size)
+    {
+        CppIStream cpp_stream(asset);
+        const C2paManifestPatch *c2pa_patches = NULL;
+        auto result = c2pa_builder_sign_placeholder(builder, signer.c2pa_signer(), format.c_str(), cpp_stream.c_stream, offset, placeholder_size, &c2pa_patches);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+
+        std::vector<ManifestPatch> patches;
+        for (int i = 0; i < result; i++)
+        {
+            auto &patch = c2pa_patches[i];
+            patches.push_back({patch.offset, std::vector<unsigned char>(patch.data, patch.data + patch.len)});
+        }
+        c2pa_manifest_patches_free(c2pa_patches, result);
+        return patches;
+    }
+
+    SigningSession::SigningSession(const string &manifest_json, Signer &signer)
+    {
+        session = c2pa_signing_session_new(manifest_json.c_str(), signer.c2pa_signer());
+        if (session == NULL)
+        {
+            throw Exception();
+        }
+    }
+
+    SigningSession::~SigningSession()
+    {
+        c2pa_signing_session_free(session);
+    }
+
+    void SigningSession::add_resource(const string &uri, istream &source)
+    {
+        CppIStream c_source = CppIStream(source);
+        int result = c2pa_signing_session_add_resource(session, uri.c_str(), c_source.c_stream);
+        if (result < 0)
+        {
+            throw Exception();
+        }
+    }
+
+    std::vector<unsigned char> SigningSession::sign(const string &format, istream &source, ostream &dest)
+    {
+        CppIStream c_source = CppIStream(source);
+        CppOStream c_dest = CppOStream(dest);
+        const unsigned char *c2pa_manifest_bytes = NULL;
+        auto result = c2pa_signing_session_sign(session, format.c_str(), c_source.c_stream, c_dest.c_stream, &c2pa_manifest_bytes);
+        if (result < 0 || c2pa_manifest_bytes == NULL)
+        {
+            throw Exception();
+        }
+
+        auto manifest_bytes = std::vector<unsigned char>(c2pa_manifest_bytes, c2pa_manifest_bytes + result);
+        c2pa_manifest_bytes_free(c2pa_manifest_bytes);
+        return manifest_bytes;
+    }
 } // namespace c2pa
\ No newline at end of file
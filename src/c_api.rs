@@ -13,20 +13,51 @@
 
 use std::{
     ffi::CString,
+    io::Cursor,
+    ops::{Deref, DerefMut},
     os::raw::{c_char, c_int, c_uchar, c_void},
+    path::{Path, PathBuf},
+    sync::OnceLock,
 };
 
 // C has no namespace so we prefix things with C2PA to make them unique
 use c2pa::{
-    assertions::DataHash, settings::load_settings_from_str, Builder as C2paBuilder, CallbackSigner,
-    Reader as C2paReader, SigningAlg,
+    assertions::DataHash, validation_status::ValidationStatus, Builder as C2paBuilder,
+    CallbackSigner, ClaimGeneratorInfo, Reader, Signer, SigningAlg,
 };
 
 use crate::{
+    async_signer,
+    batch::{self, BatchOptions},
     c_stream::CStream,
-    error::Error,
-    json_api::{read_file, read_ingredient_file, sign_file},
-    signer_info::SignerInfo,
+    capabilities,
+    certs::{der_certs_pem, reader_cert_chain, reader_certificates_json, signature_details},
+    content_id::content_id_json,
+    cose::verify_cose_sign1_json,
+    error::{C2paErrorCode, Error},
+    identity::identity_assertions_json,
+    json_api::{
+        assertion_json, builder_json, canonicalize_manifest_json, hash_failures_json,
+        ingredient_from_reader, ingredient_thumbnail, manifest_json, manifest_labels, read_file,
+        read_ingredient_file, resource_list_json, sign_file, validation_results_json,
+        validation_status_json,
+    },
+    jumbf::assertion_cbor,
+    logging, ocsp,
+    patch::{self, ManifestPatch},
+    preflight::validate_builder,
+    progress,
+    refresh::{self, InitOptions},
+    remote::{self, ManifestSource},
+    remote_signer::{remote_signer, RemoteSignerOptions},
+    riff,
+    session::SigningSession,
+    settings,
+    signer_info::{SignerInfo, SignerProblem},
+    signer_options::{last_timestamp_warning, ConfiguredSigner, SignerOptions},
+    soft_binding, summary, thumbnail,
+    timeline::{add_action_json, add_timed_action, TimedAction},
+    trust, verify,
 };
 
 // Work around limitations in cbindgen.
@@ -69,6 +100,27 @@ impl From<C2paSigningAlg> for SigningAlg {
 #[repr(C)]
 pub struct C2paSigner {
     pub signer: Box<dyn c2pa::Signer>,
+    pub options: SignerOptions,
+}
+
+impl C2paSigner {
+    pub fn new(signer: Box<dyn c2pa::Signer>) -> Self {
+        Self {
+            signer,
+            options: SignerOptions::default(),
+        }
+    }
+
+    // Returns the signer with its options applied.
+    pub(crate) fn configured(&self) -> ConfiguredSigner<'_> {
+        ConfiguredSigner::new(self.signer.as_ref(), &self.options)
+    }
+
+    // Signs with the options applied, keeping the time stamp warning of the sign
+    // for c2pa_timestamp_warning.
+    pub(crate) fn sign_with<T>(&self, sign: impl FnOnce(&ConfiguredSigner) -> T) -> T {
+        self.configured().sign_with(sign).0
+    }
 }
 
 // Internal routine to test for null and return null error
@@ -139,11 +191,19 @@ macro_rules! from_cstr_option {
     };
 }
 
-/// Defines a callback to read from a stream.
+/// Defines a callback to sign data.
 ///
 /// # Parameters
 /// * context: A generic context value to used by the C code, often a file or stream reference.
+/// * data: The bytes to sign.
+/// * len: The length of the bytes to sign.
+/// * signed_bytes: A buffer to write the signature into.
+/// * signed_len: The size of the buffer.
 ///
+/// Returns the size of the signature or a negative value if signing failed.
+/// If the returned size is larger than signed_len nothing should be written,
+/// the callback will be invoked again with a buffer of the returned size.
+/// Signing fails if it then asks for a larger buffer again, or for more than 64 KiB.
 pub type SignerCallback = unsafe extern "C" fn(
     context: *const (),
     data: *const c_uchar,
@@ -152,6 +212,144 @@ pub type SignerCallback = unsafe extern "C" fn(
     signed_len: usize,
 ) -> isize;
 
+/// Defines a callback that starts a signature request, such as a call to a remote KMS.
+///
+/// # Parameters
+/// * context: A generic context value to used by the C code.
+/// * request_id: The id to pass to c2pa_signer_complete with the signature.
+/// * data: The bytes to sign, only valid during the callback.
+/// * len: The length of the bytes to sign.
+///
+/// Returns 0 if the request was started or a negative value if it could not be.
+/// A request that was not started must not be completed.
+pub type AsyncSignerCallback = unsafe extern "C" fn(
+    context: *const (),
+    request_id: u64,
+    data: *const c_uchar,
+    len: usize,
+) -> c_int;
+
+/// Defines a callback to upload a signed manifest store to a remote URL.
+///
+/// # Parameters
+/// * context: A generic context value to used by the C code.
+/// * url: The remote URL the manifest store should be available from.
+/// * data: The manifest store bytes.
+/// * len: The length of the manifest store bytes.
+///
+/// Returns 0 on success or a negative value if the upload failed.
+pub type UploadCallback = unsafe extern "C" fn(
+    context: *const c_void,
+    url: *const c_char,
+    data: *const c_uchar,
+    len: usize,
+) -> c_int;
+
+/// Defines a callback to retrieve a manifest store from a remote URL.
+///
+/// # Parameters
+/// * context: A generic context value to used by the C code.
+/// * url: The remote URL of the manifest store.
+/// * buffer: A buffer to write the manifest store bytes into.
+/// * buffer_len: The size of the buffer.
+///
+/// Returns the size of the manifest store or a negative value if the fetch failed.
+/// If the returned size is larger than buffer_len nothing should be written,
+/// the callback will be invoked again with a buffer of the returned size.
+/// The fetch fails if it then asks for a larger buffer again, or for more than 10 MiB.
+pub type FetchCallback = unsafe extern "C" fn(
+    context: *const c_void,
+    url: *const c_char,
+    buffer: *mut c_uchar,
+    buffer_len: usize,
+) -> isize;
+
+/// Defines a callback to look up candidate manifest stores for a soft binding.
+///
+/// # Parameters
+/// * context: A generic context value to used by the C code.
+/// * alg: The soft binding algorithm, such as a watermark or fingerprint identifier.
+/// * value: The soft binding value computed from the asset.
+/// * index: The index of the candidate requested, starting at 0.
+/// * buffer: A buffer to write the candidate manifest store bytes into.
+/// * buffer_len: The size of the buffer.
+///
+/// Returns the size of the candidate, 0 if there are no more candidates,
+/// or a negative value if the lookup failed.
+/// If the returned size is larger than buffer_len nothing should be written,
+/// the callback will be invoked again with a buffer of the returned size.
+/// The lookup fails if it then asks for a larger buffer again, or for more than 10 MiB.
+pub type SoftBindingCallback = unsafe extern "C" fn(
+    context: *const c_void,
+    alg: *const c_char,
+    value: *const c_char,
+    index: usize,
+    buffer: *mut c_uchar,
+    buffer_len: usize,
+) -> isize;
+
+/// The stage of a long running operation reported to a progress callback.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum C2paProgressPhase {
+    /// Reading an asset to validate its manifest store.
+    Validate,
+    /// Reading the source asset while signing.
+    Hash,
+    /// Writing the signed asset.
+    Write,
+}
+
+/// Defines a callback to report progress through an asset stream.
+///
+/// # Parameters
+/// * context: A generic context value to used by the C code.
+/// * phase: The stage of the operation.
+/// * processed: The number of bytes of the stream processed so far.
+/// * total: The size of the stream, or for writes the size of the source asset.
+///
+/// Returns 0 to continue or a non-zero value to cancel the operation.
+pub type ProgressCallback = unsafe extern "C" fn(
+    context: *const c_void,
+    phase: C2paProgressPhase,
+    processed: u64,
+    total: u64,
+) -> c_int;
+
+/// The severity of a log record passed to a log callback.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum C2paLogLevel {
+    /// No records, which removes the log callback.
+    LogOff,
+    /// Failures.
+    LogError,
+    /// Problems that did not stop an operation, such as a failed attempt that is retried.
+    LogWarn,
+    /// Notable steps, such as fetching a remote manifest.
+    LogInfo,
+    /// Details for diagnosing failures, such as the stages of hash verification.
+    LogDebug,
+    /// Everything, which may be a great many records.
+    LogTrace,
+}
+
+/// Defines a callback to receive log records.
+///
+/// # Parameters
+/// * context: A generic context value to used by the C code.
+/// * level: The severity of the record.
+/// * target: The module that logged the record, such as c2pa::jumbf::boxes.
+/// * message: The message of the record.
+///
+/// The target and message are only valid during the callback.
+pub type LogCallback = unsafe extern "C" fn(
+    context: *const c_void,
+    level: C2paLogLevel,
+    target: *const c_char,
+    message: *const c_char,
+);
+
 // Internal routine to return a rust String reference to C as *mut c_char.
 // The returned value MUST be released by calling release_string
 // and it is no longer valid after that call.
@@ -179,443 +377,530 @@ pub unsafe extern "C" fn c2pa_version() -> *mut c_char {
     to_c_string(version)
 }
 
-/// Returns the last error message.
+/// Describes the version and capabilities of the library.
+///
+/// List fields are comma separated.
+#[repr(C)]
+pub struct C2paVersionInfo {
+    /// The version of this library.
+    pub version: *const c_char,
+    /// The version of the c2pa-rs SDK.
+    pub c2pa_version: *const c_char,
+    /// The supported asset formats as extensions and mime types.
+    pub formats: *const c_char,
+    /// The crypto backend.
+    pub crypto_backend: *const c_char,
+    /// The HTTP client, or NULL if network access is not compiled in.
+    pub network: *const c_char,
+    /// The compiled features.
+    pub features: *const c_char,
+}
+
+// The strings are created once and never freed.
+unsafe impl Send for C2paVersionInfo {}
+unsafe impl Sync for C2paVersionInfo {}
+
+/// Returns the version and capabilities of the library.
 ///
 /// # Safety
-/// The returned value MUST be released by calling release_string
-/// and it is no longer valid after that call.
+/// The returned value is owned by the library and must not be freed.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_error() -> *mut c_char {
-    to_c_string(Error::last_message().unwrap_or_default())
+pub unsafe extern "C" fn c2pa_version_info() -> *const C2paVersionInfo {
+    static VERSION_INFO: OnceLock<C2paVersionInfo> = OnceLock::new();
+    VERSION_INFO.get_or_init(|| {
+        let static_str = |s: &str| CString::new(s).unwrap_or_default().into_raw() as *const c_char;
+        let info = capabilities::version_info();
+        C2paVersionInfo {
+            version: static_str(info.version),
+            c2pa_version: static_str(info.c2pa_version),
+            formats: static_str(&info.formats.join(",")),
+            crypto_backend: static_str(info.crypto_backend),
+            network: info.network.map_or(std::ptr::null(), static_str),
+            features: static_str(&info.features.join(",")),
+        }
+    })
 }
 
-/// Load Settings from a string.
+/// Returns 1 if manifests can be read from the format, otherwise 0.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
 ///
 /// # Errors
-/// Returns -1 if there were errors, otherwise returns 0.
-/// The error string can be retrieved by calling c2pa_error.
+/// Returns -1 if format is NULL.
 ///
 /// # Safety
 /// Reads from NULL-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_load_settings(
-    settings: *const c_char,
-    format: *const c_char,
-) -> c_int {
-    let settings = from_cstr_null_check_int!(settings);
+pub unsafe extern "C" fn c2pa_can_read(format: *const c_char) -> c_int {
     let format = from_cstr_null_check_int!(format);
-    let result = load_settings_from_str(&settings, &format);
-    match result {
-        Ok(_) => 0,
-        Err(err) => {
-            Error::from_c2pa_error(err).set_last();
-            -1
-        }
-    }
+    capabilities::can_read(&format) as c_int
 }
 
-/// Returns a ManifestStore JSON string from a file path.
+/// Returns 1 if manifests can be signed into the format, otherwise 0.
 ///
-/// Any thumbnails or other binary resources will be written to data_dir if provided.
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
 ///
 /// # Errors
-/// Returns NULL if there were errors, otherwise returns a JSON string.
-/// The error string can be retrieved by calling c2pa_error.
+/// Returns -1 if format is NULL.
 ///
 /// # Safety
 /// Reads from NULL-terminated C strings.
-/// The returned value MUST be released by calling release_string
-/// and it is no longer valid after that call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_read_file(
-    path: *const c_char,
-    data_dir: *const c_char,
-) -> *mut c_char {
-    let path = from_cstr_null_check!(path);
-    let data_dir = from_cstr_option!(data_dir);
-
-    let result = read_file(&path, data_dir);
+pub unsafe extern "C" fn c2pa_can_sign(format: *const c_char) -> c_int {
+    let format = from_cstr_null_check_int!(format);
+    capabilities::can_sign(&format) as c_int
+}
 
-    match result {
-        Ok(json) => to_c_string(json),
-        Err(e) => {
-            e.set_last();
-            std::ptr::null_mut()
-        }
-    }
+/// Returns the mime types of the formats the library can read manifests from or sign.
+///
+/// The mime types are sorted and comma separated, like the formats field of c2pa_version_info.
+/// No error is set, so this can be used to route unsupported formats before reading them.
+///
+/// # Parameters
+/// * for_signing: 0 for the formats that can be read, any other value for those that can be signed.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_supported_mime_types(for_signing: c_int) -> *mut c_char {
+    to_c_string(capabilities::supported_mime_types(for_signing != 0).join(","))
 }
 
-/// Returns an Ingredient JSON string from a file path.
+/// Returns 1 if the named feature was compiled into the library, otherwise 0.
 ///
-/// Any thumbnail or C2PA data will be written to data_dir if provided.
+/// The feature names are listed in the features field of c2pa_version_info.
 ///
 /// # Errors
-/// Returns NULL if there were errors, otherwise returns a JSON string
-/// containing the Ingredient.
-/// The error string can be retrieved by calling c2pa_error.
+/// Returns -1 if name is NULL.
 ///
 /// # Safety
 /// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_has_feature(name: *const c_char) -> c_int {
+    let name = from_cstr_null_check_int!(name);
+    capabilities::has_feature(&name) as c_int
+}
+
+/// Returns the last error message.
+///
+/// # Safety
 /// The returned value MUST be released by calling release_string
 /// and it is no longer valid after that call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_read_ingredient_file(
-    path: *const c_char,
-    data_dir: *const c_char,
-) -> *mut c_char {
-    let path = from_cstr_null_check!(path);
-    let data_dir = from_cstr_null_check!(data_dir);
+pub unsafe extern "C" fn c2pa_error() -> *mut c_char {
+    to_c_string(Error::last_message().unwrap_or_default())
+}
 
-    let result = read_ingredient_file(&path, &data_dir);
+/// Returns a stable code for the kind of the last error.
+///
+/// Use this to branch on the kind of error rather than parsing the message
+/// from c2pa_error, which may be reworded between releases.
+/// Returns ErrorNone if there is no last error.
+#[no_mangle]
+pub extern "C" fn c2pa_error_code() -> C2paErrorCode {
+    Error::last_code()
+}
 
-    match result {
-        Ok(json) => to_c_string(json),
-        Err(e) => {
-            e.set_last();
-            std::ptr::null_mut()
-        }
-    }
+/// The last error, with its code and the errors that caused it.
+pub struct C2paError {
+    code: C2paErrorCode,
+    message: CString,
+    sources: Vec<CString>,
 }
 
-#[repr(C)]
-/// Defines the configuration for a Signer.
+/// Takes the last error, with its code and the errors that caused it, and clears it.
 ///
-/// The signer is created from the sign_cert and private_key fields.
-/// an optional url to an RFC 3161 compliant time server will ensure the signature is timestamped.
+/// The message alone flattens or drops the causes, such as the io or crypto error
+/// behind a failure to read a certificate, which the sources keep.
 ///
-pub struct C2paSignerInfo {
-    /// The signing algorithm.
-    pub alg: *const c_char,
-    /// The public certificate chain in PEM format.
-    pub sign_cert: *const c_char,
-    /// The private key in PEM format.
-    pub private_key: *const c_char,
-    /// The timestamp authority URL or NULL.
-    pub ta_url: *const c_char,
+/// Returns NULL if there is no last error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_error_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_error_take() -> *mut C2paError {
+    match Error::take_last() {
+        Some(error) => new_c2pa_error(error.code(), error.to_string(), error.sources().to_vec()),
+        None => std::ptr::null_mut(),
+    }
 }
 
-/// Add a signed manifest to the file at path with the given signer information.
+/// Returns a copy of the last error, with its code and the errors that caused it,
+/// leaving it set, so c2pa_error and c2pa_error_code still return it.
 ///
-/// # Errors
-/// Returns an error field if there were errors.
+/// Returns NULL if there is no last error.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings.
-/// The returned value MUST be released by calling release_string
+/// The returned value MUST be released by calling c2pa_error_free
 /// and it is no longer valid after that call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_sign_file(
-    source_path: *const c_char,
-    dest_path: *const c_char,
-    manifest: *const c_char,
-    signer_info: &C2paSignerInfo,
-    data_dir: *const c_char,
-) -> *mut c_char {
-    // Convert C pointers into Rust.
-    let source_path = from_cstr_null_check!(source_path);
-    let dest_path = from_cstr_null_check!(dest_path);
-    let manifest = from_cstr_null_check!(manifest);
-    let data_dir = from_cstr_option!(data_dir);
+pub unsafe extern "C" fn c2pa_error_copy() -> *mut C2paError {
+    match Error::last_details() {
+        Some((code, message, sources)) => new_c2pa_error(code, message, sources),
+        None => std::ptr::null_mut(),
+    }
+}
 
-    let signer_info = SignerInfo {
-        alg: from_cstr_null_check!(signer_info.alg),
-        sign_cert: from_cstr_null_check!(signer_info.sign_cert).into_bytes(),
-        private_key: from_cstr_null_check!(signer_info.private_key).into_bytes(),
-        ta_url: from_cstr_option!(signer_info.ta_url),
-    };
-    // Read manifest from JSON and then sign and write it.
-    let result = sign_file(&source_path, &dest_path, &manifest, &signer_info, data_dir);
+fn new_c2pa_error(code: C2paErrorCode, message: String, sources: Vec<String>) -> *mut C2paError {
+    Box::into_raw(Box::new(C2paError {
+        code,
+        message: CString::new(message).unwrap_or_default(),
+        sources: sources
+            .into_iter()
+            .map(|source| CString::new(source).unwrap_or_default())
+            .collect(),
+    }))
+}
 
-    match result {
-        Ok(_c2pa_data) => to_c_string("".to_string()),
-        Err(e) => {
-            e.set_last();
-            std::ptr::null_mut()
-        }
-    }
+/// Returns the code of a C2paError, or ErrorNone if the error is NULL.
+///
+/// # Safety
+/// The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_error_get_code(error: *const C2paError) -> C2paErrorCode {
+    error
+        .as_ref()
+        .map_or(C2paErrorCode::ErrorNone, |error| error.code)
 }
 
-/// Frees a string allocated by Rust.
+/// Returns the message of a C2paError, the same as c2pa_error would have returned.
 ///
-/// Deprecated: for backward api compatibility only.
+/// # Errors
+/// Returns NULL if the error is NULL.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings.
-/// The string must not have been modified in C.
-/// The string can only be freed once and is invalid after this call.
+/// The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+/// The message belongs to the error and is no longer valid after c2pa_error_free.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_release_string(s: *mut c_char) {
-    c2pa_string_free(s)
+pub unsafe extern "C" fn c2pa_error_get_message(error: *const C2paError) -> *const c_char {
+    null_check!(error);
+    (*error).message.as_ptr()
 }
 
-/// Frees a string allocated by Rust.
+/// Returns the number of errors that caused a C2paError, or 0 if the error is NULL.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings.
-/// The string must not have been modified in C.
-/// The string can only be freed once and is invalid after this call.
+/// The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_string_free(s: *mut c_char) {
-    if !s.is_null() {
-        drop(CString::from_raw(s));
-    }
+pub unsafe extern "C" fn c2pa_error_source_count(error: *const C2paError) -> usize {
+    error.as_ref().map_or(0, |error| error.sources.len())
 }
 
-/// Creates and verifies a C2paReader from an asset stream with the given format.
+/// Returns the message of an error that caused a C2paError.
 ///
-/// Parameters
-/// * format: pointer to a C string with the mime type or extension.
-/// * stream: pointer to a CStream.
+/// Index 0 is the most direct cause, and each following index caused the one before it.
 ///
 /// # Errors
-/// Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
-/// The error string can be retrieved by calling c2pa_error.
+/// Returns NULL if the error is NULL or the index is not less than c2pa_error_source_count.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings.
-/// The returned value MUST be released by calling c2pa_reader_free
-/// and it is no longer valid after that call.
-///
-/// # Example
-/// ```c
-/// auto result = c2pa_reader_from_stream("image/jpeg", stream);
-/// if (result == NULL) {
-///     printf("Error: %s\n", c2pa_error());
-/// }
-/// ```
+/// The error must be a valid C2paError returned by c2pa_error_take or c2pa_error_copy.
+/// The message belongs to the error and is no longer valid after c2pa_error_free.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_reader_from_stream(
-    format: *const c_char,
-    stream: *mut CStream,
-) -> *mut C2paReader {
-    let format = from_cstr_null_check!(format);
-
-    let result = C2paReader::from_stream(&format, &mut (*stream));
-    match result {
-        Ok(reader) => Box::into_raw(Box::new(reader)),
-        Err(err) => {
-            Error::from_c2pa_error(err).set_last();
-            std::ptr::null_mut()
-        }
+pub unsafe extern "C" fn c2pa_error_source(error: *const C2paError, index: usize) -> *const c_char {
+    null_check!(error);
+    let error = &*error;
+    // a bad index leaves the last error alone, since the error may have been copied from it
+    match error.sources.get(index) {
+        Some(source) => source.as_ptr(),
+        None => std::ptr::null(),
     }
 }
 
-/// Frees a C2paReader allocated by Rust.
+/// Frees a C2paError returned by c2pa_error_take or c2pa_error_copy.
 ///
 /// # Safety
-/// The C2paReader can only be freed once and is invalid after this call.
+/// The error can only be freed once and is invalid after this call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_reader_free(reader_ptr: *mut C2paReader) {
-    if !reader_ptr.is_null() {
-        drop(Box::from_raw(reader_ptr));
+pub unsafe extern "C" fn c2pa_error_free(error: *mut C2paError) {
+    if !error.is_null() {
+        drop(Box::from_raw(error));
     }
 }
 
-/// Returns a JSON string generated from a C2paReader.
+/// Load Settings from a string.
 ///
-/// # Safety
-/// The returned value MUST be released by calling c2pa_string_free
-/// and it is no longer valid after that call.
-#[no_mangle]
-pub unsafe extern "C" fn c2pa_reader_json(reader_ptr: *mut C2paReader) -> *mut c_char {
-    let c2pa_reader: Box<C2paReader> = Box::from_raw(reader_ptr);
-    let json = c2pa_reader.json();
-    let _ = Box::into_raw(c2pa_reader);
-    to_c_string(json)
-}
-
-/// Writes a C2paReader resource to a stream given a URI.
-///
-/// The resource uri should match an identifier in the the manifest store.
+/// The settings are those of the c2pa SDK, in the same form c2patool reads them,
+/// such as the trust lists in the trust section, the verification flags in the verify
+/// section and the thumbnail behavior in the builder section. Settings that are not
+/// given keep their current values. For example, in TOML:
+/// `[verify]\nverify_after_sign = false`
+/// verify.remote_manifest_fetch enables or disables all remote manifest fetching,
+/// including the get callback, like fetch_remote in c2pa_set_verify_options.
+/// Two SDK settings are then overridden by the state this library keeps:
+/// verify.remote_manifest_fetch stays off inside the SDK while a get callback or
+/// remote fetch options are set, since the library fetches remote manifests itself, and
+/// builder.auto_thumbnail stays off while c2pa_set_thumbnail_options disables thumbnails.
+/// A loaded builder.auto_thumbnail takes effect once thumbnails are enabled again.
 ///
 /// # Parameters
-/// * reader_ptr: pointer to a Reader.
-/// * uri: pointer to a C string with the URI to identify the resource.
-/// * stream: pointer to a writable CStream.
+/// * settings: pointer to a C string with the settings.
+/// * format: pointer to a C string with the format of the settings, "json" or "toml".
 ///
 /// # Errors
-/// Returns -1 if there were errors, otherwise returns size of stream written.
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
 /// Reads from NULL-terminated C strings.
-///
-/// # Example
-/// ```c
-/// result c2pa_reader_resource_to_stream(store, "uri", stream);
-/// if (result < 0) {
-///     printf("Error: %s\n", c2pa_error());
-/// }
-/// ```
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_reader_resource_to_stream(
-    reader_ptr: *mut C2paReader,
-    uri: *const c_char,
-    stream: *mut CStream,
+pub unsafe extern "C" fn c2pa_load_settings(
+    settings: *const c_char,
+    format: *const c_char,
 ) -> c_int {
-    let reader: Box<C2paReader> = Box::from_raw(reader_ptr);
-    let uri = from_cstr_null_check_int!(uri);
-    let result = reader.resource_to_stream(&uri, &mut (*stream));
-    let _ = Box::into_raw(reader);
-    match result {
-        Ok(len) => len as c_int,
+    let settings = from_cstr_null_check_int!(settings);
+    let format = from_cstr_null_check_int!(format);
+    match settings::load_settings(&settings, &format) {
+        Ok(_) => 0,
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
+            err.set_last();
             -1
         }
     }
 }
 
-/// Creates a C2paBuilder from a JSON manifest definition string.
+/// Sets the trust anchors that readers check signing certificates against.
+///
+/// Signing certificates that chain to an anchor are reported as signingCredential.trusted
+/// and others as signingCredential.untrusted. Setting anchors turns on the verify_trust
+/// setting, which c2pa_set_verify_options can turn off again. Clearing them does not.
+///
+/// # Parameters
+/// * pem: pointer to a C string with the PEM certificates of the anchors, or NULL to clear them.
 ///
 /// # Errors
-/// Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+/// Returns -1 if there were errors, such as certificates that can't be decoded, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
 /// Reads from NULL-terminated C strings.
-/// The returned value MUST be released by calling c2pa_builder_free
-/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_trust_anchors(pem: *const c_char) -> c_int {
+    set_trust("trust_anchors", pem)
+}
+
+/// Sets the allowed list of end-entity certificates trusted whatever they chain to.
 ///
-/// # Example
-/// ```c
-/// auto result = c2pa_builder_from_json(manifest_json);
-/// if (result == NULL) {
-///     printf("Error: %s\n", c2pa_error());
-/// }
-/// ```
+/// Setting the list turns on the verify_trust setting, as c2pa_set_trust_anchors does.
+///
+/// # Parameters
+/// * pem: pointer to a C string with the PEM certificates, or base64 SHA-256 hashes of
+///   their DER encodings one per line, or NULL to clear the list.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_from_json(manifest_json: *const c_char) -> *mut C2paBuilder {
-    let manifest_json = from_cstr_null_check!(manifest_json);
-    let result = C2paBuilder::from_json(&manifest_json);
-    match result {
-        Ok(builder) => Box::into_raw(Box::new(builder)),
+pub unsafe extern "C" fn c2pa_set_allowed_list(pem: *const c_char) -> c_int {
+    set_trust("allowed_list", pem)
+}
+
+/// Sets the trust configuration, the extended key usage OIDs a trusted signing
+/// certificate may have, one per line.
+///
+/// # Parameters
+/// * text: pointer to a C string with the configuration, or NULL to clear it.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_trust_config(text: *const c_char) -> c_int {
+    set_trust("trust_config", text)
+}
+
+// Sets or clears one trust setting for the trust functions.
+unsafe fn set_trust(key: &str, value: *const c_char) -> c_int {
+    match trust::set_trust(key, from_cstr_option!(value).as_deref()) {
+        Ok(_) => 0,
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
-            std::ptr::null_mut()
+            err.set_last();
+            -1
         }
     }
 }
 
-/// Create a C2paBuilder from an archive stream.
+/// Initializes optional background services.
+///
+/// The options are a JSON object. Setting trust_refresh starts a background task
+/// that periodically reloads the trust settings from files or http(s) URLs, for example:
+/// `{"trust_refresh": {"interval_secs": 3600, "trust_anchors": "/etc/c2pa/anchors.pem"}}`
+/// Setting single_threaded to true starts no threads; the host calls c2pa_refresh
+/// when it wants the refresh work done, which keeps timing deterministic.
+/// Services started by an earlier call are stopped first.
+///
+/// # Parameters
+/// * options_json: pointer to a C string with the options JSON (optional, can be NULL).
 ///
 /// # Errors
-/// Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+/// Returns -1 if there were errors, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
 /// Reads from NULL-terminated C strings.
-/// The returned value MUST be released by calling c2pa_builder_free
-/// and it is no longer valid after that call.
-///
-/// # Example
-/// ```c
-/// auto result = c2pa_builder_from_archive(stream);
-/// if (result == NULL) {
-///     printf("Error: %s\n", c2pa_error());
-/// }
-/// ```
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_from_archive(stream: *mut CStream) -> *mut C2paBuilder {
-    let result = C2paBuilder::from_archive(&mut (*stream));
-    match result {
-        Ok(builder) => Box::into_raw(Box::new(builder)),
+pub unsafe extern "C" fn c2pa_init(options_json: *const c_char) -> c_int {
+    let options = match from_cstr_option!(options_json) {
+        Some(json) => match serde_json::from_str::<InitOptions>(&json) {
+            Ok(options) => options,
+            Err(err) => {
+                Error::Json(err.to_string()).set_last();
+                return -1;
+            }
+        },
+        None => InitOptions::default(),
+    };
+    match refresh::init(options) {
+        Ok(_) => 0,
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
-            std::ptr::null_mut()
+            err.set_last();
+            -1
         }
     }
 }
 
-/// Frees a C2paBuilder allocated by Rust.
+/// Runs one refresh cycle on the caller's thread.
+///
+/// Reloads the trust data when c2pa_init was called with single_threaded
+/// and removes expired OCSP responses from the cache.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// The C2paBuilder can only be freed once and is invalid after this call.
+/// Must not be called from a callback invoked by the library.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_free(builder_ptr: *mut C2paBuilder) {
-    if !builder_ptr.is_null() {
-        drop(Box::from_raw(builder_ptr));
+pub unsafe extern "C" fn c2pa_refresh() -> c_int {
+    match refresh::refresh() {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
     }
 }
 
-/// Sets the no-embed flag on the Builder.
-/// When set, the builder will not embed a C2PA manifest store into the asset when signing.
-/// This is useful when creating cloud or sidecar manifests.
-/// # Parameters
-/// * builder_ptr: pointer to a Builder.
+/// Stops any background services started by c2pa_init.
+///
 /// # Safety
-/// builder_ptr must be a valid pointer to a Builder.   
+/// Must not be called from a callback invoked by the library.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_set_no_embed(builder_ptr: *mut C2paBuilder) {
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    builder.set_no_embed(true);
-    let _ = Box::into_raw(builder);
+pub unsafe extern "C" fn c2pa_shutdown() {
+    refresh::shutdown();
 }
 
-/// Sets the remote URL on the Builder.
-/// When set, the builder will embed a remote URL into the asset when signing.
-/// This is useful when creating cloud based Manifests.
+/// Registers a manifest publisher used for remote manifests.
+///
+/// When put is set, c2pa_builder_sign publishes the manifest store to the Builder's remote URL.
+/// When get is set, readers retrieve remote manifests through it instead of the internal HTTP client.
+/// Passing NULL for both callbacks removes the publisher.
+///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * remote_url: pointer to a C string with the remote URL.
+/// * context: a context value passed to the callbacks.
+/// * put: a callback to store a manifest store at a URL (optional, can be NULL).
+/// * get: a callback to retrieve a manifest store from a URL (optional, can be NULL).
+///
 /// # Errors
 /// Returns -1 if there were errors, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
+///
 /// # Safety
-/// Reads from NULL-terminated C strings.
+/// The context must remain valid until the publisher is removed.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_set_remote_url(
-    builder_ptr: *mut C2paBuilder,
-    remote_url: *const c_char,
+pub unsafe extern "C" fn c2pa_set_manifest_publisher(
+    context: *const c_void,
+    // cbindgen does not resolve an Option of a type alias, so the callbacks are spelled out
+    put: Option<
+        unsafe extern "C" fn(
+            context: *const c_void,
+            url: *const c_char,
+            data: *const c_uchar,
+            len: usize,
+        ) -> c_int,
+    >,
+    get: Option<
+        unsafe extern "C" fn(
+            context: *const c_void,
+            url: *const c_char,
+            buffer: *mut c_uchar,
+            buffer_len: usize,
+        ) -> isize,
+    >,
 ) -> c_int {
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    let remote_url = from_cstr_null_check_int!(remote_url);
-    builder.set_remote_url(&remote_url);
-    let _ = Box::into_raw(builder);
-    0 as c_int
+    match remote::set_publisher(context, put, get) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
 }
 
-/// Adds a resource to the C2paBuilder.
+/// Registers a callback that retrieves remote manifests with the host's HTTP stack.
 ///
-/// The resource uri should match an identifier in the manifest definition.
+/// Readers and ingredients fetch remote manifests through the callback instead of the
+/// internal HTTP client, so host proxies, authentication and certificate pinning apply.
+/// This replaces the get callback of c2pa_set_manifest_publisher and keeps its put callback.
+/// Passing NULL restores the built-in fetching.
 ///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * uri: pointer to a C string with the URI to identify the resource.
-/// * stream: pointer to a CStream.
+/// * context: a context value passed to the callback.
+/// * fetch: a callback to retrieve a manifest store from a URL (optional, can be NULL).
+///
 /// # Errors
 /// Returns -1 if there were errors, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings
+/// The context must remain valid until the fetcher is removed.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_add_resource(
-    builder_ptr: *mut C2paBuilder,
-    uri: *const c_char,
-    stream: *mut CStream,
+pub unsafe extern "C" fn c2pa_set_remote_fetcher(
+    context: *const c_void,
+    // spelled out for cbindgen, as with c2pa_set_manifest_publisher
+    fetch: Option<
+        unsafe extern "C" fn(
+            context: *const c_void,
+            url: *const c_char,
+            buffer: *mut c_uchar,
+            buffer_len: usize,
+        ) -> isize,
+    >,
 ) -> c_int {
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    let uri = from_cstr_null_check_int!(uri);
-    let result = builder.add_resource(&uri, &mut (*stream));
-    match result {
-        Ok(_builder) => {
-            let _ = Box::into_raw(builder);
-            0 as c_int
-        }
+    match remote::set_fetcher(context, fetch) {
+        Ok(_) => 0,
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
+            err.set_last();
             -1
         }
     }
 }
 
-/// Adds an ingredient to the C2paBuilder.
+/// Sets options for fetching remote manifests, such as authentication headers.
+///
+/// The options are a JSON object, for example:
+/// `{"headers": {"Authorization": "Bearer <token>"}, "retry": {"retries": 3, "backoff_ms": 250},
+/// "timeout_ms": 5000}`
+/// Failed requests are retried with exponential backoff and the error lists every attempt.
+/// A request that takes longer than timeout_ms is abandoned. If the last attempt timed out,
+/// reading fails with an error starting with "RemoteTimeout" rather than "Remote".
+/// A get callback registered with c2pa_set_manifest_publisher or c2pa_set_remote_fetcher
+/// takes precedence over these options, but retry and timeout_ms still apply to it.
+/// The callback is not interrupted, so a manifest store it returns after timeout_ms
+/// is discarded as timed out.
 ///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * ingredient_json: pointer to a C string with the JSON ingredient definition.
-/// * format: pointer to a C string with the mime type or extension.
-/// * source: pointer to a CStream.
+/// * options_json: pointer to a C string with the options JSON (NULL restores the defaults).
 ///
 /// # Errors
 /// Returns -1 if there were errors, otherwise returns 0.
@@ -624,85 +909,2709 @@ pub unsafe extern "C" fn c2pa_builder_add_resource(
 /// # Safety
 /// Reads from NULL-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_add_ingredient_from_stream(
-    builder_ptr: *mut C2paBuilder,
-    ingredient_json: *const c_char,
-    format: *const c_char,
-    source: *mut CStream,
-) -> c_int {
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    let ingredient_json = from_cstr_null_check_int!(ingredient_json);
-    let format = from_cstr_null_check_int!(format);
-    let result = builder.add_ingredient_from_stream(&ingredient_json, &format, &mut (*source));
-    match result {
-        Ok(_builder) => {
-            let _ = Box::into_raw(builder);
-            0 as c_int
-        }
+pub unsafe extern "C" fn c2pa_set_remote_fetch_options(options_json: *const c_char) -> c_int {
+    let options_json = from_cstr_option!(options_json);
+    match remote::set_fetch_options(options_json.as_deref()) {
+        Ok(_) => 0,
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
+            err.set_last();
             -1
         }
     }
 }
 
-/// Writes an Archive of the Builder to the destination stream.
+/// Enables or disables fetching remote manifests.
+///
+/// While disabled the library makes no remote manifest requests, whether through a get
+/// callback, c2pa_set_remote_fetch_options or the SDK's own fetcher. Reading an asset that
+/// refers to a remote manifest then fails with an error starting with "RemoteFetchDisabled",
+/// followed by the manifest URL. Fetching is enabled by default.
 ///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * stream: pointer to a writable CStream.
+/// * enabled: 0 to disable fetching, any other value to enable it.
 ///
 /// # Errors
 /// Returns -1 if there were errors, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings.
+/// This function is always safe to call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_remote_fetch_enabled(enabled: c_int) -> c_int {
+    match remote::set_fetch_enabled(enabled != 0) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// The verify settings of the SDK.
 ///
-/// # Example
-/// ```c
-/// auto result = c2pa_builder_to_archive(builder, stream);
-/// if (result < 0) {
-///     printf("Error: %s\n", c2pa_error());
-/// }
-/// ```
+/// The SDK keeps one set of settings for the process. c2pa_set_verify_options sets them
+/// for every Reader and Builder, and c2pa_reader_from_stream_with_verify_options and
+/// c2pa_builder_sign_with_verify_options for one read or signing.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct C2paVerifyOptions {
+    /// Validate manifest stores when they are read.
+    pub verify_after_reading: bool,
+    /// Read back and validate each signed asset, which roughly doubles the time to sign.
+    pub verify_after_sign: bool,
+    /// Check signing certificates against the trust lists, see c2pa_set_trust_anchors.
+    pub verify_trust: bool,
+    /// Fetch OCSP responses for signing certificates that have none stapled.
+    pub ocsp_fetch: bool,
+    /// Fetch remote manifests, as set by c2pa_set_remote_fetch_enabled.
+    pub fetch_remote: bool,
+    /// Check the trust of the signing certificates of ingredients.
+    pub check_ingredient_trust: bool,
+}
+
+/// Gets the verify settings, such as to change some of them with c2pa_set_verify_options.
+///
+/// # Parameters
+/// * options: pointer to a C2paVerifyOptions set to the current settings.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The options must point to a writable C2paVerifyOptions.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_to_archive(
-    builder_ptr: *mut C2paBuilder,
-    stream: *mut CStream,
-) -> c_int {
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    let result = builder.to_archive(&mut (*stream));
-    match result {
-        Ok(_builder) => {
-            let _ = Box::into_raw(builder);
-            0 as c_int
+pub unsafe extern "C" fn c2pa_get_verify_options(options: *mut C2paVerifyOptions) -> c_int {
+    null_check_int!(options);
+    match verify::verify_options() {
+        Ok(current) => {
+            *options = current;
+            0
         }
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
+            err.set_last();
             -1
         }
     }
 }
 
-/// Creates and writes signed manifest from the C2paBuilder to the destination stream.
+/// Sets the verify settings for every Reader and Builder.
+///
+/// For example, a signing service may turn off verify_after_sign to sign faster,
+/// while a validator turns on verify_trust.
 ///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * format: pointer to a C string with the mime type or extension.
-/// * source: pointer to a CStream.
-/// * dest: pointer to a writable CStream.
-/// * signer: pointer to a C2paSigner.
-/// * c2pa_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+/// * options: pointer to the settings.
 ///
 /// # Errors
-/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// Returns -1 if there were errors, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings
-/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
-/// and it is no longer valid after that call.
+/// The options must point to a valid C2paVerifyOptions.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_verify_options(options: *const C2paVerifyOptions) -> c_int {
+    null_check_int!(options);
+    match verify::set_verify_options(&*options) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Enables or disables looking for a sidecar manifest store when reading files.
+///
+/// When enabled, c2pa_read_file and c2pa_reader_from_file read an asset without a manifest
+/// store against a sidecar file next to it with the extension ".c2pa", such as asset.c2pa
+/// for asset.jpg. c2pa_reader_sidecar_path then returns the path of the sidecar, and
+/// c2pa_read_file and c2pa_verify_directory report it in a sidecar_path field.
+/// Discovery is disabled by default.
+///
+/// # Parameters
+/// * enabled: 0 to disable discovery, any other value to enable it.
+///
+/// # Errors
+/// Returns 0.
+///
+/// # Safety
+/// This function is always safe to call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_sidecar_discovery(enabled: c_int) -> c_int {
+    remote::set_sidecar_discovery(enabled != 0);
+    0
+}
+
+/// Registers a resolver that looks up candidate manifest stores for a soft binding.
+///
+/// The resolver is used by c2pa_reader_from_soft_binding to recover the manifest
+/// of an asset whose manifest store was removed, for example by a screenshot or re-encode.
+/// Passing NULL removes the resolver.
+///
+/// # Parameters
+/// * context: a context value passed to the callback.
+/// * resolve: a callback returning candidate manifest stores (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The context must remain valid until the resolver is removed.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_soft_binding_resolver(
+    context: *const c_void,
+    // spelled out for cbindgen, as with c2pa_set_manifest_publisher
+    resolve: Option<
+        unsafe extern "C" fn(
+            context: *const c_void,
+            alg: *const c_char,
+            value: *const c_char,
+            index: usize,
+            buffer: *mut c_uchar,
+            buffer_len: usize,
+        ) -> isize,
+    >,
+) -> c_int {
+    match soft_binding::set_resolver(context, resolve) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Registers a callback that reports progress while reading and signing assets.
+///
+/// The callback is invoked as the asset streams of reader creation and builder signing
+/// are processed, at most once per megabyte and when a stream is complete.
+/// The bytes processed are how far through the stream the operation is, so parts of an
+/// asset read again are not counted twice. An operation uses the callback registered when
+/// it started, and the callback is not called with a library lock held.
+/// Returning a non-zero value from the callback cancels the operation, which then fails.
+/// Passing NULL removes the callback.
+///
+/// # Parameters
+/// * context: a context value passed to the callback.
+/// * progress: a callback receiving the phase, bytes processed and total (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The context must remain valid until the callback is removed.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_progress_callback(
+    context: *const c_void,
+    // spelled out for cbindgen, as with c2pa_set_manifest_publisher
+    progress: Option<
+        unsafe extern "C" fn(
+            context: *const c_void,
+            phase: C2paProgressPhase,
+            processed: u64,
+            total: u64,
+        ) -> c_int,
+    >,
+) -> c_int {
+    match progress::set_callback(context, progress) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Registers a callback that receives the log records of this library and the SDK.
+///
+/// Records at the level and more severe ones are passed to the callback, from whichever
+/// thread logged them, such as remote manifest fetch attempts, time stamp requests and
+/// the stages of hash verification. Passing NULL or LogOff removes the callback.
+/// The callback may call back into the library, but records logged on its thread while
+/// it runs are dropped rather than passed to it again.
+///
+/// # Parameters
+/// * context: a context value passed to the callback.
+/// * level: the least severe level of record to pass to the callback.
+/// * callback: a callback receiving each record (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, such as another logger being installed in the
+/// process by an application using this library from Rust, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The context must remain valid until the callback is removed.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_log_callback(
+    context: *const c_void,
+    level: C2paLogLevel,
+    // spelled out for cbindgen, as with c2pa_set_progress_callback
+    callback: Option<
+        unsafe extern "C" fn(
+            context: *const c_void,
+            level: C2paLogLevel,
+            target: *const c_char,
+            message: *const c_char,
+        ),
+    >,
+) -> c_int {
+    match logging::set_callback(context, level, callback) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Sets options for the thumbnails generated while signing and adding ingredients.
+///
+/// Once options are set, thumbnails are color managed for JPEG, PNG and WebP assets
+/// with an embedded ICC profile. Other thumbnails are still made by the SDK,
+/// which also makes all thumbnails when no options are set, unless a format,
+/// longest_edge or quality other than the SDK's is asked for. Thumbnails of other
+/// asset types, such as GIF, TIFF, HEIC or video, are made by the SDK in its own
+/// format and size whatever the options.
+/// The options are a JSON object, for example:
+/// `{"enabled": true, "color_space": "srgb", "format": "webp", "longest_edge": 1024, "quality": 80}`
+/// A color_space of "source" keeps the source pixels and embeds the source profile,
+/// while "srgb" converts to sRGB, embedding the source profile if it cannot be converted.
+/// The format is "jpeg", "png" or "webp", and WebP thumbnails are lossless.
+/// Setting enabled to false signs without a generated claim thumbnail, which saves
+/// decoding and re-encoding the asset. Ingredient thumbnails are still made by the SDK.
+/// This turns off builder.auto_thumbnail in the SDK settings until thumbnails are
+/// enabled again or the options are cleared, which puts back its earlier value.
+///
+/// # Parameters
+/// * options_json: pointer to a C string with the options JSON (NULL leaves thumbnails to the SDK).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_thumbnail_options(options_json: *const c_char) -> c_int {
+    let options_json = from_cstr_option!(options_json);
+    match thumbnail::set_options(options_json.as_deref()) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Sets options for hashing RIFF assets such as WAV, AVI and WebP when signing.
+///
+/// The data of the listed top level chunks is left out of the hard binding, so tools
+/// that rewrite those chunks in place, such as broadcast tools updating bext timestamps,
+/// do not invalidate the manifest. The chunk ids and sizes are still bound.
+/// The options are a JSON object, for example: `{"exclude_chunks": ["bext", "iXML"]}`
+/// At most 9 chunks can be excluded. Assets signed with a remote URL or without
+/// embedding are hashed in full.
+///
+/// # Parameters
+/// * options_json: pointer to a C string with the options JSON (NULL hashes RIFF assets in full).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_set_riff_hash_options(options_json: *const c_char) -> c_int {
+    let options_json = from_cstr_option!(options_json);
+    match riff::set_options(options_json.as_deref()) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Returns a ManifestStore JSON string from a file path.
+///
+/// Any thumbnails or other binary resources will be written to data_dir if provided.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling release_string
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_read_file(
+    path: *const c_char,
+    data_dir: *const c_char,
+) -> *mut c_char {
+    let path = from_cstr_null_check!(path);
+    let data_dir = from_cstr_option!(data_dir);
+
+    let result = read_file(&path, data_dir);
+
+    match result {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns an Ingredient JSON string from a file path.
+///
+/// Any thumbnail or C2PA data will be written to data_dir if provided.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string
+/// containing the Ingredient.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling release_string
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_read_ingredient_file(
+    path: *const c_char,
+    data_dir: *const c_char,
+) -> *mut c_char {
+    let path = from_cstr_null_check!(path);
+    let data_dir = from_cstr_null_check!(data_dir);
+
+    let result = read_ingredient_file(&path, &data_dir);
+
+    match result {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[repr(C)]
+/// Defines the configuration for a Signer.
+///
+/// The signer is created from the sign_cert and private_key fields.
+/// an optional url to an RFC 3161 compliant time server will ensure the signature is timestamped.
+///
+pub struct C2paSignerInfo {
+    /// The signing algorithm.
+    pub alg: *const c_char,
+    /// The public certificate chain in PEM format.
+    pub sign_cert: *const c_char,
+    /// The private key in PEM format.
+    pub private_key: *const c_char,
+    /// The timestamp authority URL or NULL.
+    pub ta_url: *const c_char,
+}
+
+/// Add a signed manifest to the file at path with the given signer information.
+///
+/// # Errors
+/// Returns an error field if there were errors.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling release_string
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_sign_file(
+    source_path: *const c_char,
+    dest_path: *const c_char,
+    manifest: *const c_char,
+    signer_info: &C2paSignerInfo,
+    data_dir: *const c_char,
+) -> *mut c_char {
+    // Convert C pointers into Rust.
+    let source_path = from_cstr_null_check!(source_path);
+    let dest_path = from_cstr_null_check!(dest_path);
+    let manifest = from_cstr_null_check!(manifest);
+    let data_dir = from_cstr_option!(data_dir);
+
+    let signer_info = SignerInfo {
+        alg: from_cstr_null_check!(signer_info.alg),
+        sign_cert: from_cstr_null_check!(signer_info.sign_cert).into_bytes(),
+        private_key: from_cstr_null_check!(signer_info.private_key).into_bytes(),
+        ta_url: from_cstr_option!(signer_info.ta_url),
+    };
+    // Read manifest from JSON and then sign and write it.
+    let result = sign_file(&source_path, &dest_path, &manifest, &signer_info, data_dir);
+
+    match result {
+        Ok(_c2pa_data) => to_c_string("".to_string()),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Verifies a COSE_Sign1 signature, such as a detached claim signature.
+///
+/// Returns a JSON object with the alg, the base64 encoded payload and the subject,
+/// common_name, issuer and serial_number of the signing certificate.
+/// Its trusted field is true if the certificate chain is on the allowed list or chains to
+/// a trust anchor, see c2pa_set_trust_anchors; an untrusted chain is not an error.
+///
+/// # Parameters
+/// * cose: pointer to the COSE_Sign1 bytes.
+/// * cose_len: the length of the COSE_Sign1 bytes.
+/// * payload: pointer to the detached payload bytes (optional, can be NULL if the payload is embedded).
+/// * payload_len: the length of the payload bytes.
+/// * certs: pointer to a C string with a PEM certificate chain to verify against
+///   (optional, can be NULL to use the certificates in the signature).
+///
+/// # Errors
+/// Returns NULL if the signature is not valid or there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// cose must point to at least cose_len bytes and payload to at least payload_len bytes.
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_cose_verify(
+    cose: *const c_uchar,
+    cose_len: usize,
+    payload: *const c_uchar,
+    payload_len: usize,
+    certs: *const c_char,
+) -> *mut c_char {
+    null_check!(cose);
+    let cose = std::slice::from_raw_parts(cose, cose_len);
+    let payload = (!payload.is_null()).then(|| std::slice::from_raw_parts(payload, payload_len));
+    let certs = from_cstr_option!(certs);
+    match verify_cose_sign1_json(cose, payload, certs.as_deref()) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Validates every supported asset in a directory and returns an aggregated JSON report.
+///
+/// The report has counts of valid, untrusted, invalid, no_manifest and errors,
+/// and a files array with the verdict and validation status codes for each asset.
+/// Assets are validated concurrently unless c2pa_init was called with single_threaded.
+/// Symbolic links to directories are not followed when recursing.
+///
+/// # Parameters
+/// * dir: pointer to a C string with the directory path.
+/// * options_json: pointer to a C string with JSON options (optional, can be NULL),
+///   for example `{"recursive": true, "threads": 4}`.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_verify_directory(
+    dir: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    let dir = from_cstr_null_check!(dir);
+    let options = match from_cstr_option!(options_json) {
+        Some(json) => match serde_json::from_str::<BatchOptions>(&json) {
+            Ok(options) => options,
+            Err(err) => {
+                Error::Json(err.to_string()).set_last();
+                return std::ptr::null_mut();
+            }
+        },
+        None => BatchOptions::default(),
+    };
+    match batch::verify_directory(Path::new(&dir), &options).and_then(|report| report.to_json()) {
+        Ok(json) => to_c_string(json),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Validates a list of assets and returns an aggregated JSON report.
+///
+/// The report has the same form as the one returned by c2pa_verify_directory.
+///
+/// # Parameters
+/// * paths_json: pointer to a C string with a JSON array of file paths.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_verify_files(paths_json: *const c_char) -> *mut c_char {
+    let paths_json = from_cstr_null_check!(paths_json);
+    let result = serde_json::from_str::<Vec<std::path::PathBuf>>(&paths_json)
+        .map_err(|e| Error::Json(e.to_string()))
+        .and_then(|paths| batch::verify_paths(&paths, 0).to_json());
+    match result {
+        Ok(json) => to_c_string(json),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a normalized, pretty printed version of a manifest definition JSON string.
+///
+/// Defaults are filled in, shorthand is expanded and object keys are sorted,
+/// so equivalent definitions produce identical output suitable for diffing.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_manifest_json_canonicalize(
+    manifest_json: *const c_char,
+) -> *mut c_char {
+    let manifest_json = from_cstr_null_check!(manifest_json);
+
+    let result = canonicalize_manifest_json(&manifest_json);
+    match result {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a string allocated by Rust.
+///
+/// Deprecated: for backward api compatibility only.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The string must not have been modified in C.
+/// The string can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_release_string(s: *mut c_char) {
+    c2pa_string_free(s)
+}
+
+/// Frees a string allocated by Rust.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The string must not have been modified in C.
+/// The string can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// A Reader returned to C, with where its manifest store was read from.
+pub struct C2paReader {
+    reader: Reader,
+    source: ManifestSource,
+    // the validation statuses of each fragment the Reader was created from
+    fragment_status: Vec<Vec<ValidationStatus>>,
+}
+
+impl Deref for C2paReader {
+    type Target = Reader;
+
+    fn deref(&self) -> &Reader {
+        &self.reader
+    }
+}
+
+impl DerefMut for C2paReader {
+    fn deref_mut(&mut self) -> &mut Reader {
+        &mut self.reader
+    }
+}
+
+fn into_reader_ptr(reader: Reader, source: ManifestSource) -> *mut C2paReader {
+    Box::into_raw(Box::new(C2paReader {
+        reader,
+        source,
+        fragment_status: Vec::new(),
+    }))
+}
+
+/// Creates and verifies a C2paReader from an asset stream with the given format.
+///
+/// Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * stream: pointer to a CStream.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+///
+/// # Example
+/// ```c
+/// auto result = c2pa_reader_from_stream("image/jpeg", stream);
+/// if (result == NULL) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_stream(
+    format: *const c_char,
+    stream: *mut CStream,
+) -> *mut C2paReader {
+    let format = from_cstr_null_check!(format);
+
+    let result = remote::read_with_source(&format, &mut (*stream));
+    match result {
+        Ok((reader, source)) => into_reader_ptr(reader, source),
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader from an asset stream, with verify settings for this read.
+///
+/// The settings apply while the stream is read and the previous settings are put back after.
+/// See c2pa_set_verify_options, which sets them for every Reader and Builder.
+/// Reads and signs with settings of their own run one at a time.
+///
+/// Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * stream: pointer to a CStream.
+/// * options: pointer to the verify settings.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paReader.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The options must point to a valid C2paVerifyOptions.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_stream_with_verify_options(
+    format: *const c_char,
+    stream: *mut CStream,
+    options: *const C2paVerifyOptions,
+) -> *mut C2paReader {
+    null_check!(stream);
+    null_check!(options);
+    let format = from_cstr_null_check!(format);
+    let result = verify::with_verify_options(&*options, || {
+        remote::read_with_source(&format, &mut (*stream)).map_err(Error::from_c2pa_error)
+    });
+    match result {
+        Ok((reader, source)) => into_reader_ptr(reader, source),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// A read started by c2pa_reader_from_stream_async.
+struct AsyncRead {
+    format: String,
+    stream: *mut CStream,
+    user_data: *const c_void,
+    completion: unsafe extern "C" fn(
+        user_data: *const c_void,
+        reader: *mut C2paReader,
+        error: *const c_char,
+    ),
+}
+
+// The host is responsible for making the stream and context usable from any thread.
+unsafe impl Send for AsyncRead {}
+
+impl AsyncRead {
+    unsafe fn run(self) {
+        match remote::read_with_source(&self.format, &mut (*self.stream)) {
+            Ok((reader, source)) => (self.completion)(
+                self.user_data,
+                into_reader_ptr(reader, source),
+                std::ptr::null(),
+            ),
+            Err(err) => {
+                let message =
+                    CString::new(Error::from_c2pa_error(err).to_string()).unwrap_or_default();
+                (self.completion)(self.user_data, std::ptr::null_mut(), message.as_ptr())
+            }
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader from an asset stream on a worker thread.
+///
+/// Returns immediately and invokes the completion callback from the worker thread
+/// once the reader is created, with either the reader or an error message.
+/// The reader is owned by the callback and MUST be released by calling c2pa_reader_free.
+/// The error message is only valid during the callback.
+/// If c2pa_init was called with single_threaded, the read runs on the caller's thread
+/// and the callback is invoked before this function returns.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * stream: pointer to a CStream, which must remain valid until the callback is invoked.
+/// * completion: a callback receiving the user data, the reader or NULL, and the error or NULL.
+/// * user_data: a context value passed to the callback.
+///
+/// # Errors
+/// Returns -1 if the read could not be started, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The stream callbacks and the completion callback may be called from another thread.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_stream_async(
+    format: *const c_char,
+    stream: *mut CStream,
+    // spelled out for cbindgen, as with c2pa_set_manifest_publisher
+    completion: Option<
+        unsafe extern "C" fn(
+            user_data: *const c_void,
+            reader: *mut C2paReader,
+            error: *const c_char,
+        ),
+    >,
+    user_data: *const c_void,
+) -> c_int {
+    null_check_int!(stream);
+    let format = from_cstr_null_check_int!(format);
+    let Some(completion) = completion else {
+        Error::NullParameter("completion".to_string()).set_last();
+        return -1;
+    };
+    let read = AsyncRead {
+        format,
+        stream,
+        user_data,
+        completion,
+    };
+    if refresh::single_threaded() {
+        read.run();
+        return 0;
+    }
+    let worker = std::thread::Builder::new()
+        .name("c2pa-reader".to_string())
+        .spawn(move || unsafe { read.run() });
+    match worker {
+        Ok(_) => 0,
+        Err(err) => {
+            Error::Other(err.to_string()).set_last();
+            -1
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader from the init segment of a fragmented BMFF asset
+/// and its fragments, such as the segments of a DASH presentation.
+///
+/// Each fragment is validated against the manifest store carried in the init segment.
+/// The validation status is that of the first fragment that failed, if any, and
+/// c2pa_reader_fragment_status returns the status of each fragment.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * init_stream: pointer to a CStream with the init segment.
+/// * fragment_streams: pointer to an array of CStream pointers, one per fragment.
+/// * count: the number of fragment streams.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// fragment_streams must point to count valid CStream pointers.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_fragmented_stream(
+    format: *const c_char,
+    init_stream: *mut CStream,
+    fragment_streams: *const *mut CStream,
+    count: usize,
+) -> *mut C2paReader {
+    null_check!(init_stream);
+    let format = from_cstr_null_check!(format);
+    let mut fragments = Vec::with_capacity(count);
+    if count > 0 {
+        null_check!(fragment_streams);
+        for &stream in std::slice::from_raw_parts(fragment_streams, count) {
+            null_check!(stream);
+            fragments.push(&mut *stream);
+        }
+    }
+
+    let result = remote::read_fragments_with_source(&format, &mut *init_stream, &mut fragments);
+    match result {
+        Ok((reader, source, fragment_status)) => Box::into_raw(Box::new(C2paReader {
+            reader,
+            source,
+            fragment_status,
+        })),
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader from a file path.
+///
+/// The format is taken from the file extension.
+///
+/// # Parameters
+/// * path: pointer to a C string with the path to the asset.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+///
+/// # Example
+/// ```c
+/// auto result = c2pa_reader_from_file("tests/fixtures/C.jpg");
+/// if (result == NULL) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_file(path: *const c_char) -> *mut C2paReader {
+    let path = from_cstr_null_check!(path);
+
+    let result = remote::read_file_with_source(Path::new(&path));
+    match result {
+        Ok((reader, source)) => into_reader_ptr(reader, source),
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader from an asset held in memory.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * data: pointer to the asset bytes.
+/// * len: the number of bytes in data.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The data is only read during this call and remains owned by the caller.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_bytes(
+    format: *const c_char,
+    data: *const c_uchar,
+    len: usize,
+) -> *mut C2paReader {
+    null_check!(data);
+    let format = from_cstr_null_check!(format);
+    let data = std::slice::from_raw_parts(data, len);
+
+    let result = remote::read_with_source(&format, Cursor::new(data));
+    match result {
+        Ok((reader, source)) => into_reader_ptr(reader, source),
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader from detached manifest store bytes and the asset they describe.
+///
+/// The manifest store is validated against the asset without being embedded in it.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension of the asset.
+/// * manifest_data: pointer to the manifest store bytes, such as the contents of a .c2pa file.
+/// * manifest_len: the number of bytes in manifest_data.
+/// * stream: pointer to a readable CStream with the asset.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a ManifestStore.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The manifest data is only read during this call and remains owned by the caller.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_manifest_data_and_stream(
+    format: *const c_char,
+    manifest_data: *const c_uchar,
+    manifest_len: usize,
+    stream: *mut CStream,
+) -> *mut C2paReader {
+    null_check!(manifest_data);
+    null_check!(stream);
+    let format = from_cstr_null_check!(format);
+    let manifest_data = std::slice::from_raw_parts(manifest_data, manifest_len);
+
+    let result = Reader::from_manifest_data_and_stream(manifest_data, &format, &mut *stream);
+    match result {
+        Ok(reader) => {
+            let source = ManifestSource {
+                manifest_data: Some(manifest_data.to_vec()),
+                ..ManifestSource::DETACHED
+            };
+            into_reader_ptr(reader, source)
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates and verifies a C2paReader, recovering the manifest through a soft binding if needed.
+///
+/// If the asset has no manifest store, the resolver registered with
+/// c2pa_set_soft_binding_resolver is asked for candidates and the first one whose
+/// active manifest declares the soft binding is validated against the asset.
+/// Hard binding failures are expected in the validation status of a recovered manifest.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * stream: pointer to a readable CStream.
+/// * alg: pointer to a C string with the soft binding algorithm.
+/// * value: pointer to a C string with the soft binding value computed from the asset.
+///
+/// # Errors
+/// Returns NULL if there were errors or no candidate matched, otherwise returns a pointer to a ManifestStore.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_reader_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_from_soft_binding(
+    format: *const c_char,
+    stream: *mut CStream,
+    alg: *const c_char,
+    value: *const c_char,
+) -> *mut C2paReader {
+    null_check!(stream);
+    let format = from_cstr_null_check!(format);
+    let alg = from_cstr_null_check!(alg);
+    let value = from_cstr_null_check!(value);
+
+    match soft_binding::read_with_soft_binding(&format, &mut (*stream), &alg, &value) {
+        Ok((reader, source)) => into_reader_ptr(reader, source),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a C2paReader allocated by Rust.
+///
+/// # Safety
+/// The C2paReader can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_free(reader_ptr: *mut C2paReader) {
+    if !reader_ptr.is_null() {
+        drop(Box::from_raw(reader_ptr));
+    }
+}
+
+/// Returns a JSON string generated from a C2paReader.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_json(reader_ptr: *mut C2paReader) -> *mut c_char {
+    let c2pa_reader: Box<C2paReader> = Box::from_raw(reader_ptr);
+    let json = c2pa_reader.json();
+    let _ = Box::into_raw(c2pa_reader);
+    to_c_string(json)
+}
+
+/// Writes a C2paReader resource to a stream given a URI.
+///
+/// The resource uri should match an identifier in the the manifest store.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * uri: pointer to a C string with the URI to identify the resource.
+/// * stream: pointer to a writable CStream.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns size of stream written.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+///
+/// # Example
+/// ```c
+/// result c2pa_reader_resource_to_stream(store, "uri", stream);
+/// if (result < 0) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_resource_to_stream(
+    reader_ptr: *mut C2paReader,
+    uri: *const c_char,
+    stream: *mut CStream,
+) -> c_int {
+    let reader: Box<C2paReader> = Box::from_raw(reader_ptr);
+    let uri = from_cstr_null_check_int!(uri);
+    let result = reader.resource_to_stream(&uri, &mut (*stream));
+    let _ = Box::into_raw(reader);
+    match result {
+        Ok(len) => len as c_int,
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Returns a C2paReader resource in a buffer allocated by the library, given a URI.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * uri: pointer to a C string with the URI to identify the resource.
+/// * data_ptr: pointer to a pointer to a c_uchar to return the resource bytes.
+/// * data_len: pointer to a usize to return the size of the resource.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the resource.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned data MUST be released by calling c2pa_resource_bytes_free with its length
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_resource_to_buffer(
+    reader_ptr: *mut C2paReader,
+    uri: *const c_char,
+    data_ptr: *mut *const c_uchar,
+    data_len: *mut usize,
+) -> c_int {
+    null_check_int!(reader_ptr);
+    null_check_int!(data_ptr);
+    null_check_int!(data_len);
+    let uri = from_cstr_null_check_int!(uri);
+    let mut data = Cursor::new(Vec::new());
+    match (*reader_ptr).resource_to_stream(&uri, &mut data) {
+        Ok(len) => {
+            let data = data.into_inner();
+            *data_len = data.len();
+            *data_ptr = Box::into_raw(data.into_boxed_slice()) as *const c_uchar;
+            len as c_int
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Frees bytes returned by c2pa_reader_resource_to_buffer, c2pa_reader_manifest_bytes,
+/// c2pa_builder_sign_bytes, c2pa_builder_manifest_placeholder or c2pa_format_embeddable.
+///
+/// # Parameters
+/// * data_ptr: pointer to the bytes.
+/// * data_len: the size returned with the bytes.
+///
+/// # Safety
+/// The bytes can only be freed once and are invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_resource_bytes_free(data_ptr: *const c_uchar, data_len: usize) {
+    if !data_ptr.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            data_ptr as *mut c_uchar,
+            data_len,
+        )));
+    }
+}
+
+/// Writes the thumbnail of an ingredient in a C2paReader to a stream.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * manifest_label: pointer to a C string with the label of the manifest holding the ingredient,
+///   or NULL for the active manifest.
+/// * ingredient_index: the index of the ingredient in the manifest's ingredients.
+/// * stream: pointer to a writable CStream.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the thumbnail.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_ingredient_thumbnail(
+    reader_ptr: *mut C2paReader,
+    manifest_label: *const c_char,
+    ingredient_index: usize,
+    stream: *mut CStream,
+) -> c_int {
+    null_check_int!(reader_ptr);
+    null_check_int!(stream);
+    let manifest_label = from_cstr_option!(manifest_label);
+    match ingredient_thumbnail(
+        &*reader_ptr,
+        manifest_label.as_deref(),
+        ingredient_index,
+        &mut *stream,
+    ) {
+        Ok(len) => len as c_int,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Returns a JSON array describing every resource in a C2paReader.
+///
+/// Each entry has the "manifest" label, the "uri" to pass to c2pa_reader_resource_to_stream,
+/// the "format" mime type when the manifest references the resource with one and the "size" in bytes.
+/// Resources include thumbnails, icons and data boxes.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_resource_list(reader_ptr: *mut C2paReader) -> *mut c_char {
+    null_check!(reader_ptr);
+    match resource_list_json(&*reader_ptr) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Writes the JSON report and all resources from a C2paReader to a folder.
+///
+/// The report is written to manifest.json and resources are written to
+/// sub-folders named for their manifest labels, as c2pa_read_file does with a data_dir.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * dir: pointer to a C string with the folder path, which is created if needed.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_to_folder(
+    reader_ptr: *mut C2paReader,
+    dir: *const c_char,
+) -> c_int {
+    null_check_int!(reader_ptr);
+    let dir = from_cstr_null_check_int!(dir);
+    match (*reader_ptr).to_folder(&dir) {
+        Ok(()) => 0,
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Returns the URL a C2paReader fetched its manifest store from.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors or the manifest store was not fetched from a remote URL,
+/// otherwise returns a C string.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_remote_url(reader_ptr: *mut C2paReader) -> *mut c_char {
+    null_check!(reader_ptr);
+    match (*reader_ptr).source.remote_url.clone() {
+        Some(url) => to_c_string(url),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the path of the sidecar file a C2paReader read its manifest store from.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors or the manifest store was not read from a sidecar,
+/// otherwise returns a C string.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_sidecar_path(reader_ptr: *mut C2paReader) -> *mut c_char {
+    null_check!(reader_ptr);
+    match (*reader_ptr).source.sidecar_path.clone() {
+        Some(path) => to_c_string(path),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Returns whether the manifest store of a C2paReader was embedded in the asset.
+///
+/// Manifest stores fetched from a remote URL, read from detached manifest data or a sidecar,
+/// or recovered through a soft binding are not embedded.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns -1 if there were errors, 1 if the manifest store was embedded, otherwise 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// reader_ptr must be a valid pointer to a Reader.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_is_embedded(reader_ptr: *mut C2paReader) -> c_int {
+    null_check_int!(reader_ptr);
+    (*reader_ptr).source.embedded as c_int
+}
+
+/// Returns the manifest store bytes of a C2paReader exactly as they were read.
+///
+/// These are the bytes embedded in the asset, fetched from a remote URL
+/// or supplied as detached manifest data.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * data_ptr: pointer to a pointer to a c_uchar to return the manifest store bytes.
+/// * data_len: pointer to a usize to return the size of the manifest store.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned data MUST be released by calling c2pa_resource_bytes_free with its length
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_manifest_bytes(
+    reader_ptr: *mut C2paReader,
+    data_ptr: *mut *const c_uchar,
+    data_len: *mut usize,
+) -> c_int {
+    null_check_int!(reader_ptr);
+    null_check_int!(data_ptr);
+    null_check_int!(data_len);
+    match (*reader_ptr).source.manifest_data.clone() {
+        Some(data) => {
+            *data_len = data.len();
+            *data_ptr = Box::into_raw(data.into_boxed_slice()) as *const c_uchar;
+            0
+        }
+        None => {
+            Error::ManifestNotFound("the manifest store bytes are not available".to_string())
+                .set_last();
+            -1
+        }
+    }
+}
+
+/// Returns the label of the active manifest of a C2paReader.
+///
+/// The label can be used to build JUMBF URIs for c2pa_reader_resource_to_stream.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors or there is no active manifest, otherwise returns a C string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_active_manifest_label(
+    reader_ptr: *mut C2paReader,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    match (*reader_ptr).active_label() {
+        Some(label) => to_c_string(label.to_string()),
+        None => {
+            Error::ManifestNotFound("no active manifest".to_string()).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the number of manifests in a C2paReader.
+///
+/// Use c2pa_reader_manifest_label_at to walk the manifests in provenance order.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the number of manifests.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// reader_ptr must be a valid pointer to a Reader.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_manifest_count(reader_ptr: *mut C2paReader) -> c_int {
+    null_check_int!(reader_ptr);
+    (*reader_ptr).iter_manifests().count() as c_int
+}
+
+/// Returns the label of the manifest at an index of a C2paReader.
+///
+/// The manifests are in provenance order: the active manifest is at index 0, followed by
+/// the manifests of its ingredients breadth first, then any manifests no ingredient refers to.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * index: the index of the manifest, less than c2pa_reader_manifest_count.
+///
+/// # Errors
+/// Returns NULL if there were errors or the index is out of range, otherwise returns a C string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_manifest_label_at(
+    reader_ptr: *mut C2paReader,
+    index: usize,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    match manifest_labels(&*reader_ptr).into_iter().nth(index) {
+        Some(label) => to_c_string(label),
+        None => {
+            Error::ManifestNotFound(format!("no manifest at index {index}")).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a JSON string for one manifest of a C2paReader.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * label: pointer to a C string with the manifest label.
+///
+/// # Errors
+/// Returns NULL if there were errors or the manifest was not found, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_manifest_json(
+    reader_ptr: *mut C2paReader,
+    label: *const c_char,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    let label = from_cstr_null_check!(label);
+    match manifest_json(&*reader_ptr, &label) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the body of one assertion of a C2paReader as a JSON string.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * manifest_label: pointer to a C string with the manifest label, or NULL for the active manifest.
+/// * assertion_label: pointer to a C string with the assertion label, such as "c2pa.actions".
+/// * instance: the instance of the assertion label, starting at 1.
+///
+/// # Errors
+/// Returns NULL if there were errors or the assertion was not found, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_get_assertion(
+    reader_ptr: *mut C2paReader,
+    manifest_label: *const c_char,
+    assertion_label: *const c_char,
+    instance: usize,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    let manifest_label = from_cstr_option!(manifest_label);
+    let assertion_label = from_cstr_null_check!(assertion_label);
+    match assertion_json(
+        &*reader_ptr,
+        manifest_label.as_deref(),
+        &assertion_label,
+        instance,
+    ) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the CBOR of one assertion of a C2paReader exactly as it is stored.
+///
+/// Unlike c2pa_reader_get_assertion, byte strings and other CBOR values are kept.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * manifest_label: pointer to a C string with the manifest label, or NULL for the active manifest.
+/// * assertion_label: pointer to a C string with the assertion label, such as "c2pa.actions".
+/// * instance: the instance of the assertion label, starting at 1.
+/// * data_ptr: pointer to a pointer to a c_uchar to return the CBOR data.
+/// * data_len: pointer to a usize to return the size of the CBOR data.
+///
+/// # Errors
+/// Returns -1 if there were errors or the assertion was not found,
+/// otherwise returns the size of the CBOR data.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned data MUST be released by calling c2pa_resource_bytes_free with its length
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_get_assertion_cbor(
+    reader_ptr: *mut C2paReader,
+    manifest_label: *const c_char,
+    assertion_label: *const c_char,
+    instance: usize,
+    data_ptr: *mut *const c_uchar,
+    data_len: *mut usize,
+) -> c_int {
+    null_check_int!(reader_ptr);
+    null_check_int!(data_ptr);
+    null_check_int!(data_len);
+    let manifest_label = from_cstr_option!(manifest_label);
+    let assertion_label = from_cstr_null_check_int!(assertion_label);
+    let Some(manifest_label) =
+        manifest_label.or_else(|| (*reader_ptr).active_label().map(String::from))
+    else {
+        Error::ManifestNotFound("no active manifest".to_string()).set_last();
+        return -1;
+    };
+    let result = (*reader_ptr)
+        .source
+        .manifest_data
+        .clone()
+        .ok_or_else(|| {
+            Error::ManifestNotFound("the manifest store bytes are not available".to_string())
+        })
+        .and_then(|store| assertion_cbor(&store, &manifest_label, &assertion_label, instance));
+    match result {
+        Ok(data) => {
+            *data_len = data.len();
+            let len = data.len() as c_int;
+            *data_ptr = Box::into_raw(data.into_boxed_slice()) as *const c_uchar;
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Returns the validation status of a C2paReader as a JSON array.
+///
+/// Each entry has a "code", such as "assertion.dataHash.mismatch", and when known
+/// the "url" of the manifest part it refers to and an "explanation".
+/// The array is empty if the manifest store is valid.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_validation_status(reader_ptr: *mut C2paReader) -> *mut c_char {
+    null_check!(reader_ptr);
+    match validation_status_json(&*reader_ptr) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the validation status of each fragment a C2paReader was created from
+/// by c2pa_reader_from_fragmented_stream, as a JSON array with one entry per fragment.
+///
+/// Each entry is an array like that of c2pa_reader_validation_status, empty if the fragment
+/// is valid. The array is empty if the Reader was not created from fragments.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_fragment_status(reader_ptr: *mut C2paReader) -> *mut c_char {
+    null_check!(reader_ptr);
+    match serde_json::to_string_pretty(&(*reader_ptr).fragment_status) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            Error::Json(e.to_string()).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the validation results of a C2paReader as a JSON object.
+///
+/// The "active_manifest" entry holds the statuses of the active manifest and
+/// "ingredient_deltas" holds the statuses recorded for each ingredient, with the label of the
+/// manifest containing it. Each group of statuses has "success", "informational" and "failure" arrays.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_validation_results(
+    reader_ptr: *mut C2paReader,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    match validation_results_json(&*reader_ptr) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the CAWG identity assertions of a C2paReader as a JSON array.
+///
+/// Each entry has the "manifest" and "label" of the assertion, its "sig_type" and
+/// "credential_type", the "roles" and "referenced_assertions" of the signer payload,
+/// the "named_actor" described by the credential and whether the signature is valid.
+/// X.509 signatures are verified over the stored signer payload, the referenced assertions
+/// are checked against the claim and the signer must chain to a configured trust anchor
+/// or be on the allowed list. Identity claims aggregation credentials are only decoded.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_identity_assertions(
+    reader_ptr: *mut C2paReader,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    let result = (*reader_ptr)
+        .source
+        .manifest_data
+        .clone()
+        .ok_or_else(|| {
+            Error::ManifestNotFound("the manifest store bytes are not available".to_string())
+        })
+        .and_then(|store| identity_assertions_json(&*reader_ptr, &store));
+    match result {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a short human-readable summary of the active manifest of a C2paReader.
+///
+/// The summary describes the tool and date of creation, the signer, the editing tools,
+/// any generative AI tools used and the number of ingredients,
+/// and notes when the content credentials could not be verified.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * locale: pointer to a C string with a locale such as "en-US" or "fr"
+///   (optional, can be NULL for English). Untranslated locales use English.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a C string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_summary(
+    reader_ptr: *mut C2paReader,
+    locale: *const c_char,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    let locale = from_cstr_option!(locale);
+    match summary::summary(&*reader_ptr, locale.as_deref().unwrap_or("en")) {
+        Ok(summary) => to_c_string(summary),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a JSON array describing the certificate chain that signed a manifest.
+///
+/// Each entry has the subject, issuer, serial number, validity period, algorithms,
+/// key usages, subject alternative names, extensions and SHA-256 fingerprint
+/// of one certificate, starting with the signing certificate.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * label: pointer to a C string with a manifest label (optional, can be NULL for the active manifest).
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_certificates(
+    reader_ptr: *mut C2paReader,
+    label: *const c_char,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    let label = from_cstr_option!(label);
+    match reader_certificates_json(&*reader_ptr, label.as_deref()) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the PEM certificate chain that signed a manifest of a C2paReader.
+///
+/// The signing certificate comes first, so the chain can be evaluated independently.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+/// * manifest_label: pointer to a C string with the manifest label, or NULL for the active manifest.
+///
+/// # Errors
+/// Returns NULL if there were errors or the manifest is not signed, otherwise returns a PEM string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_cert_chain(
+    reader_ptr: *mut C2paReader,
+    manifest_label: *const c_char,
+) -> *mut c_char {
+    null_check!(reader_ptr);
+    let manifest_label = from_cstr_option!(manifest_label);
+    match reader_cert_chain(&*reader_ptr, manifest_label.as_deref()) {
+        Ok(pem) => to_c_string(pem),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// The signature of the active manifest of a C2paReader.
+///
+/// Each field is NULL when the SDK does not report it.
+#[repr(C)]
+pub struct C2paSignatureInfo {
+    /// The signing algorithm, such as "es256".
+    pub alg: *mut c_char,
+    /// The organization that signed the manifest.
+    pub issuer: *mut c_char,
+    /// The common name of the signing certificate.
+    pub common_name: *mut c_char,
+    /// The serial number of the signing certificate.
+    pub cert_serial_number: *mut c_char,
+    /// The signing time from the time-stamp authority, in RFC 3339 format.
+    pub time: *mut c_char,
+}
+
+/// Returns the signature info of the active manifest of a C2paReader.
+///
+/// # Parameters
+/// * reader_ptr: pointer to a Reader.
+///
+/// # Errors
+/// Returns NULL if there were errors or the manifest is not signed.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_signature_info_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_reader_signature_info(
+    reader_ptr: *mut C2paReader,
+) -> *mut C2paSignatureInfo {
+    null_check!(reader_ptr);
+    let field = |value: Option<String>| value.map_or(std::ptr::null_mut(), |v| to_c_string(v));
+    match signature_details(&*reader_ptr, None) {
+        Ok(details) => Box::into_raw(Box::new(C2paSignatureInfo {
+            alg: field(details.alg),
+            issuer: field(details.issuer),
+            common_name: field(details.common_name),
+            cert_serial_number: field(details.cert_serial_number),
+            time: field(details.time),
+        })),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a C2paSignatureInfo returned by c2pa_reader_signature_info.
+///
+/// # Safety
+/// The signature info can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signature_info_free(info: *mut C2paSignatureInfo) {
+    if !info.is_null() {
+        let info = Box::from_raw(info);
+        c2pa_string_free(info.alg);
+        c2pa_string_free(info.issuer);
+        c2pa_string_free(info.common_name);
+        c2pa_string_free(info.cert_serial_number);
+        c2pa_string_free(info.time);
+    }
+}
+
+/// Returns a JSON array describing hash validation failures in an asset stream.
+///
+/// Each entry reports the validation status code, the failing assertion and
+/// manifest labels and, for data hash assertions, the expected and computed digests.
+/// An empty array is returned if no hash failures were found.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * stream: pointer to a readable CStream.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_hash_failures(
+    format: *const c_char,
+    stream: *mut CStream,
+) -> *mut c_char {
+    null_check!(stream);
+    let format = from_cstr_null_check!(format);
+
+    let result = hash_failures_json(&format, &mut (*stream));
+    match result {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a JSON object with the provenance identifiers of an asset stream.
+///
+/// The object has the instance_id, document_id, active_manifest label,
+/// the hard_bindings of the active manifest with their digests and the remote_url,
+/// each of which may be null, so assets can be indexed without storing full reports.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * stream: pointer to a readable CStream.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_content_id(
+    format: *const c_char,
+    stream: *mut CStream,
+) -> *mut c_char {
+    null_check!(stream);
+    let format = from_cstr_null_check!(format);
+
+    match content_id_json(&format, &mut (*stream)) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a C2paBuilder from a JSON manifest definition string.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_builder_free
+/// and it is no longer valid after that call.
+///
+/// # Example
+/// ```c
+/// auto result = c2pa_builder_from_json(manifest_json);
+/// if (result == NULL) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_from_json(manifest_json: *const c_char) -> *mut C2paBuilder {
+    let manifest_json = from_cstr_null_check!(manifest_json);
+    let result = C2paBuilder::from_json(&manifest_json);
+    match result {
+        Ok(builder) => Box::into_raw(Box::new(builder)),
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Create a C2paBuilder from an archive stream.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a Builder.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_builder_free
+/// and it is no longer valid after that call.
+///
+/// # Example
+/// ```c
+/// auto result = c2pa_builder_from_archive(stream);
+/// if (result == NULL) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_from_archive(stream: *mut CStream) -> *mut C2paBuilder {
+    let result = C2paBuilder::from_archive(&mut (*stream));
+    match result {
+        Ok(builder) => Box::into_raw(Box::new(builder)),
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a C2paBuilder allocated by Rust.
+///
+/// # Safety
+/// The C2paBuilder can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_free(builder_ptr: *mut C2paBuilder) {
+    if !builder_ptr.is_null() {
+        drop(Box::from_raw(builder_ptr));
+    }
+}
+
+/// Sets the no-embed flag on the Builder.
+/// When set, the builder will not embed a C2PA manifest store into the asset when signing.
+/// This is useful when creating cloud or sidecar manifests.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * no_embed: 0 to embed the manifest store, any other value to leave it out of the asset.
+/// # Errors
+/// Returns -1 if builder_ptr is NULL, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_no_embed(
+    builder_ptr: *mut C2paBuilder,
+    no_embed: c_int,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    (*builder_ptr).set_no_embed(no_embed != 0);
+    0
+}
+
+/// Sets the remote URL on the Builder.
+/// When set, the builder will embed a remote URL into the asset when signing.
+/// This is useful when creating cloud based Manifests.
+/// The URL may contain `{instance_id}`, `{filename}` and `{manifest_hash}` placeholders,
+/// which are substituted when signing. The instance_id and filename (the title) are
+/// percent-encoded as a path segment.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * remote_url: pointer to a C string with the remote URL.
+/// # Errors
+/// Returns -1 if builder_ptr or remote_url is NULL, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_remote_url(
+    builder_ptr: *mut C2paBuilder,
+    remote_url: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let remote_url = from_cstr_null_check_int!(remote_url);
+    (*builder_ptr).set_remote_url(&remote_url);
+    0 as c_int
+}
+
+/// Sets the title of the C2paBuilder's manifest, replacing the title in the manifest definition.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * title: pointer to a C string with the title, such as the asset file name.
+/// # Errors
+/// Returns -1 if builder_ptr or title is NULL, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_title(
+    builder_ptr: *mut C2paBuilder,
+    title: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let title = from_cstr_null_check_int!(title);
+    (*builder_ptr).definition.title = Some(title);
+    0
+}
+
+/// Sets the format of the C2paBuilder's manifest, replacing the format in the manifest definition.
+/// Signing an asset sets the format to that of the asset, so this is only needed
+/// when the manifest is archived or embedded by the caller.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type or extension.
+/// # Errors
+/// Returns -1 if builder_ptr or format is NULL, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_format(
+    builder_ptr: *mut C2paBuilder,
+    format: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let format = from_cstr_null_check_int!(format);
+    (*builder_ptr).set_format(format);
+    0
+}
+
+/// Sets the claim generator of the C2paBuilder's manifest.
+/// The claim generator info in the manifest definition is replaced by a single entry.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * name: pointer to a C string with the name of the application, such as "Editor".
+/// * version: pointer to a C string with the version of the application, or NULL.
+/// # Errors
+/// Returns -1 if builder_ptr or name is NULL, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_claim_generator_info(
+    builder_ptr: *mut C2paBuilder,
+    name: *const c_char,
+    version: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let name = from_cstr_null_check_int!(name);
+    let version = from_cstr_option!(version);
+    let mut info = ClaimGeneratorInfo::new(name);
+    if let Some(version) = version {
+        info.set_version(version);
+    }
+    (*builder_ptr).definition.claim_generator_info = vec![info];
+    0
+}
+
+/// Checks that the C2paBuilder can create claims of a version when signing.
+/// The bundled SDK only creates version 1 claims, so version 1 is accepted and changes nothing,
+/// and any other version is an error rather than a different claim than expected.
+/// The version is not stored, so it does not keep a later SDK from creating other versions.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * version: the claim version, such as 1.
+/// # Errors
+/// Returns -1 if builder_ptr is NULL or the version is not supported, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_claim_version(
+    builder_ptr: *mut C2paBuilder,
+    version: c_int,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    if version != 1 {
+        Error::NotSupported(format!(
+            "claim version {version}, only version 1 claims can be created"
+        ))
+        .set_last();
+        return -1;
+    }
+    0
+}
+
+/// Sets the directory the C2paBuilder resolves resource identifiers from when signing.
+/// Resources in the manifest definition, such as a thumbnail with the identifier
+/// "thumbnail.jpg", are then read from files relative to the directory,
+/// as with a c2patool manifest folder.
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * base_path: pointer to a C string with the path to the directory.
+/// # Errors
+/// Returns -1 if there were errors or the directory does not exist, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_base_path(
+    builder_ptr: *mut C2paBuilder,
+    base_path: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let base_path = from_cstr_null_check_int!(base_path);
+    if !Path::new(&base_path).is_dir() {
+        Error::FileNotFound(base_path).set_last();
+        return -1;
+    }
+    (*builder_ptr).base_path = Some(base_path.into());
+    0
+}
+
+/// Sets the claim thumbnail of the C2paBuilder from a stream.
+///
+/// The thumbnail replaces any thumbnail in the manifest definition, and no thumbnail
+/// is generated from the asset when signing.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type of the thumbnail, such as "image/jpeg".
+/// * stream: pointer to a CStream with the thumbnail image.
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_set_thumbnail(
+    builder_ptr: *mut C2paBuilder,
+    format: *const c_char,
+    stream: *mut CStream,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(stream);
+    let format = from_cstr_null_check_int!(format);
+    match (*builder_ptr).set_thumbnail(format, &mut (*stream)) {
+        Ok(_) => 0,
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Adds a resource to the C2paBuilder.
+///
+/// The resource uri should match an identifier in the manifest definition.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * uri: pointer to a C string with the URI to identify the resource.
+/// * stream: pointer to a CStream.
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_resource(
+    builder_ptr: *mut C2paBuilder,
+    uri: *const c_char,
+    stream: *mut CStream,
+) -> c_int {
+    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
+    let uri = from_cstr_null_check_int!(uri);
+    let result = builder.add_resource(&uri, &mut (*stream));
+    match result {
+        Ok(_builder) => {
+            let _ = Box::into_raw(builder);
+            0 as c_int
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Adds an assertion with a label and JSON data to the C2paBuilder.
+///
+/// The assertion is added to those in the manifest definition and stored as CBOR,
+/// for example with the label "org.example.review" and the data `{"approved": true}`.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * label: pointer to a C string with the assertion label.
+/// * assertion_json: pointer to a C string with the JSON assertion data.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_assertion(
+    builder_ptr: *mut C2paBuilder,
+    label: *const c_char,
+    assertion_json: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let label = from_cstr_null_check_int!(label);
+    let assertion_json = from_cstr_null_check_int!(assertion_json);
+    let result = serde_json::from_str::<serde_json::Value>(&assertion_json)
+        .map_err(|e| Error::Json(e.to_string()))
+        .and_then(|data| {
+            (*builder_ptr)
+                .add_assertion(label, &data)
+                .map_err(Error::from_c2pa_error)
+        });
+    match result {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Adds an assertion with a label and CBOR data to the C2paBuilder.
+///
+/// Use this for assertions with byte strings or other values that JSON cannot hold.
+/// The data is decoded and encoded again when the manifest is signed,
+/// so map keys may be reordered, but the values are kept.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * label: pointer to a C string with the assertion label.
+/// * data: pointer to the CBOR assertion data.
+/// * len: the number of bytes in data.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from a NULL-terminated C string.
+/// The data must be valid for reads of len bytes.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_assertion_cbor(
+    builder_ptr: *mut C2paBuilder,
+    label: *const c_char,
+    data: *const c_uchar,
+    len: usize,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(data);
+    let label = from_cstr_null_check_int!(label);
+    let data = std::slice::from_raw_parts(data, len);
+    let result = coset::cbor::de::from_reader::<coset::cbor::value::Value, _>(data)
+        .map_err(|e| Error::Decoding(e.to_string()))
+        .and_then(|value| {
+            (*builder_ptr)
+                .add_assertion(label, &value)
+                .map_err(Error::from_c2pa_error)
+        });
+    match result {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Adds a redaction to the C2paBuilder.
+///
+/// The assertion is removed from the parent ingredient's manifest when signing and the
+/// redaction is recorded in the new claim, for example to remove location data with
+/// "stds.exif" or "self#jumbf=/c2pa/urn:uuid:.../c2pa.assertions/stds.exif".
+/// A URI must name the active manifest of the parent ingredient, so the parent must be added first.
+/// Signing fails if the parent ingredient has no such assertion or it is an actions assertion.
+/// The SDK does not yet take redactions into account when validating ingredients,
+/// so readers report a mismatched ingredient hash and the redacted assertion as missing.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * assertion_uri: pointer to a C string with the label or JUMBF URI of the assertion.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_redaction(
+    builder_ptr: *mut C2paBuilder,
+    assertion_uri: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let assertion_uri = from_cstr_null_check_int!(assertion_uri);
+    // the SDK applies redactions by label to the parent ingredient
+    let (manifest, label) = match assertion_uri.split_once("/c2pa.assertions/") {
+        Some((manifest, label)) => (manifest.rsplit_once("/c2pa/").map(|(_, m)| m), label),
+        None => (None, assertion_uri.as_str()),
+    };
+    if label.is_empty() || label.contains(['/', '#', '=']) {
+        Error::Assertion(format!("{assertion_uri} is not an assertion label or URI")).set_last();
+        return -1;
+    }
+    if assertion_uri.contains("/c2pa.assertions/") {
+        let parent = (*builder_ptr)
+            .definition
+            .ingredients
+            .iter()
+            .find(|ingredient| ingredient.is_parent());
+        let parent_manifest = parent.and_then(|parent| parent.active_manifest());
+        if manifest.is_none() || manifest != parent_manifest {
+            Error::Assertion(format!(
+                "{assertion_uri} is not in the active manifest of the parent ingredient"
+            ))
+            .set_last();
+            return -1;
+        }
+    }
+    let redactions = (*builder_ptr)
+        .definition
+        .redactions
+        .get_or_insert_with(Vec::new);
+    if !redactions.iter().any(|r| r == label) {
+        redactions.push(label.to_string());
+    }
+    0
+}
+
+/// Adds an action to the C2paBuilder's actions assertion, creating the assertion if needed.
+///
+/// Actions already in the manifest definition are kept, so editing applications can
+/// record each edit as it is made. The action is a JSON object, for example:
+/// `{"action": "c2pa.cropped", "softwareAgent": "Editor 1.0"}`
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * action_json: pointer to a C string with the JSON action.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_action(
+    builder_ptr: *mut C2paBuilder,
+    action_json: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let action_json = from_cstr_null_check_int!(action_json);
+    match add_action_json(&mut *builder_ptr, &action_json) {
+        Ok(()) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Adds an action over a time range to the C2paBuilder's actions assertion.
+///
+/// The action is a JSON object with an edit of trim, splice or overlay, or an action label,
+/// and optional start and end times as seconds or Normal Play Time strings, for example:
+/// `{"edit": "splice", "start": "00:01:10", "end": "00:01:25", "ingredient": "xmp:iid:1234"}`
+/// The ingredient is the instance_id of an ingredient added to the Builder.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * action_json: pointer to a C string with the JSON timed action.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_timed_action(
+    builder_ptr: *mut C2paBuilder,
+    action_json: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    let action_json = from_cstr_null_check_int!(action_json);
+    let result = TimedAction::from_json(&action_json)
+        .and_then(|action| add_timed_action(&mut *builder_ptr, &action));
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Adds an ingredient to the C2paBuilder.
+///
+/// If thumbnail options were set with c2pa_set_thumbnail_options and neither the
+/// ingredient definition nor its manifest provides a thumbnail, the generated thumbnail is color managed.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * ingredient_json: pointer to a C string with the JSON ingredient definition.
+/// * format: pointer to a C string with the mime type or extension.
+/// * source: pointer to a CStream.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_ingredient_from_stream(
+    builder_ptr: *mut C2paBuilder,
+    ingredient_json: *const c_char,
+    format: *const c_char,
+    source: *mut CStream,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(source);
+    let ingredient_json = from_cstr_null_check_int!(ingredient_json);
+    let format = from_cstr_null_check_int!(format);
+    let result = thumbnail::add_ingredient_from_stream(
+        &mut *builder_ptr,
+        &ingredient_json,
+        &format,
+        &mut (*source),
+    );
+    match result {
+        Ok(()) => 0 as c_int,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Adds an ingredient to the C2paBuilder from an asset that has already been read.
+///
+/// The manifest store and validation status of the reader are used, so the asset
+/// is not read again. The title, format and instance_id default to those of the
+/// active manifest, and its thumbnail is used if the definition has none.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * ingredient_json: pointer to a C string with the JSON ingredient definition.
+/// * reader_ptr: pointer to a C2paReader of the ingredient asset.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+/// It is an error if the reader has no active manifest, or if it was not created
+/// with this API, since the manifest store bytes are then not available.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The reader is not consumed and must still be released with c2pa_reader_free.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_add_ingredient_from_reader(
+    builder_ptr: *mut C2paBuilder,
+    ingredient_json: *const c_char,
+    reader_ptr: *const C2paReader,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(reader_ptr);
+    let ingredient_json = from_cstr_null_check_int!(ingredient_json);
+    let result = (*reader_ptr)
+        .source
+        .manifest_data
+        .clone()
+        .ok_or_else(|| {
+            Error::ManifestNotFound("the manifest store bytes are not available".to_string())
+        })
+        .and_then(|store| ingredient_from_reader(&ingredient_json, &*reader_ptr, store));
+    match result {
+        Ok(ingredient) => {
+            (*builder_ptr).add_ingredient(ingredient);
+            0
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Writes an Archive of the Builder to the destination stream.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * stream: pointer to a writable CStream.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+///
+/// # Example
+/// ```c
+/// auto result = c2pa_builder_to_archive(builder, stream);
+/// if (result < 0) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_to_archive(
+    builder_ptr: *mut C2paBuilder,
+    stream: *mut CStream,
+) -> c_int {
+    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
+    let result = builder.to_archive(&mut (*stream));
+    match result {
+        Ok(_builder) => {
+            let _ = Box::into_raw(builder);
+            0 as c_int
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Returns the current state of a C2paBuilder as a JSON string.
+///
+/// This is the manifest definition with any ingredients, actions and assertions added
+/// since the Builder was created, plus its remote_url and no_embed settings,
+/// so it can be compared with the input JSON without signing.
+/// Resources are only listed by identifier.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a JSON string.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// The returned value MUST be released by calling c2pa_string_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_to_json(builder_ptr: *mut C2paBuilder) -> *mut c_char {
+    null_check!(builder_ptr);
+    match builder_json(&*builder_ptr) {
+        Ok(json) => to_c_string(json),
+        Err(e) => {
+            e.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Checks the manifest definition of a C2paBuilder for spec violations before signing.
+///
+/// Reports more than one parentOf ingredient, actions assertions that cannot be decoded,
+/// c2pa.created or c2pa.opened used other than as the first action, c2pa.opened without
+/// the parentOf ingredient, c2pa.placed without an ingredient, and actions that refer
+/// to ingredients missing from the definition.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+///
+/// # Errors
+/// Returns -1 if there were errors or the manifest definition is invalid, otherwise returns 0.
+/// The error string, listing every problem found, can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// builder_ptr must be a valid pointer to a Builder.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_validate(builder_ptr: *mut C2paBuilder) -> c_int {
+    null_check_int!(builder_ptr);
+    match validate_builder(&*builder_ptr) {
+        Ok(_) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+// Signs the asset and publishes the manifest store to the Builder's remote URL, if any.
+unsafe fn sign_streams(
+    builder: &mut C2paBuilder,
+    signer: &C2paSigner,
+    format: &str,
+    source: *mut CStream,
+    dest: *mut CStream,
+) -> Result<Vec<u8>, Error> {
+    let (manifest_bytes, remote_url) = signer.sign_with(|signer| {
+        remote::sign_builder(builder, signer, format, &mut *source, &mut *dest)
+    })?;
+    if let Some(remote_url) = remote_url {
+        remote::publish(&remote_url, &manifest_bytes)?;
+    }
+    Ok(manifest_bytes)
+}
+
+/// Creates and writes signed manifest from the C2paBuilder to the destination stream.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type or extension.
+/// * source: pointer to a CStream.
+/// * dest: pointer to a writable CStream.
+/// * signer: pointer to a C2paSigner.
+/// * c2pa_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
 #[no_mangle]
 pub unsafe extern "C" fn c2pa_builder_sign(
     builder_ptr: *mut C2paBuilder,
@@ -712,82 +3621,1387 @@ pub unsafe extern "C" fn c2pa_builder_sign(
     signer: *mut C2paSigner,
     manifest_bytes_ptr: *mut *const c_uchar,
 ) -> c_int {
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
+    null_check_int!(builder_ptr);
+    null_check_int!(source);
+    null_check_int!(dest);
+    null_check_int!(signer);
+    let format = from_cstr_null_check_int!(format);
+    match sign_streams(&mut *builder_ptr, &*signer, &format, source, dest) {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            if !manifest_bytes_ptr.is_null() {
+                *manifest_bytes_ptr =
+                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            };
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Signs like c2pa_builder_sign, with verify settings for this signing.
+///
+/// The settings apply while signing and the previous settings are put back after,
+/// for example to turn off verify_after_sign for one latency-sensitive signing.
+/// See c2pa_set_verify_options, which sets them for every Reader and Builder.
+/// Reads and signs with settings of their own run one at a time.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type or extension.
+/// * source: pointer to a CStream.
+/// * dest: pointer to a writable CStream.
+/// * signer: pointer to a C2paSigner.
+/// * options: pointer to the verify settings.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The options must point to a valid C2paVerifyOptions.
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_with_verify_options(
+    builder_ptr: *mut C2paBuilder,
+    format: *const c_char,
+    source: *mut CStream,
+    dest: *mut CStream,
+    signer: *mut C2paSigner,
+    options: *const C2paVerifyOptions,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(source);
+    null_check_int!(dest);
+    null_check_int!(signer);
+    null_check_int!(options);
+    let format = from_cstr_null_check_int!(format);
+    let result = verify::with_verify_options(&*options, || {
+        sign_streams(&mut *builder_ptr, &*signer, &format, source, dest)
+    });
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            if !manifest_bytes_ptr.is_null() {
+                *manifest_bytes_ptr =
+                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            };
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+// A signing operation started by c2pa_builder_sign_async.
+struct AsyncSign {
+    builder: *mut C2paBuilder,
+    format: String,
+    source: *mut CStream,
+    dest: *mut CStream,
+    signer: *mut C2paSigner,
+    user_data: *const c_void,
+    completion: unsafe extern "C" fn(
+        user_data: *const c_void,
+        manifest_bytes: *const c_uchar,
+        manifest_len: usize,
+        error: *const c_char,
+    ),
+}
+
+// The host is responsible for making the streams, signer and context usable from any thread.
+unsafe impl Send for AsyncSign {}
+
+impl AsyncSign {
+    unsafe fn run(self) {
+        match sign_streams(
+            &mut *self.builder,
+            &*self.signer,
+            &self.format,
+            self.source,
+            self.dest,
+        ) {
+            Ok(manifest_bytes) => (self.completion)(
+                self.user_data,
+                manifest_bytes.as_ptr(),
+                manifest_bytes.len(),
+                std::ptr::null(),
+            ),
+            Err(err) => {
+                let message = CString::new(err.to_string()).unwrap_or_default();
+                (self.completion)(self.user_data, std::ptr::null(), 0, message.as_ptr())
+            }
+        }
+    }
+}
+
+/// Signs like c2pa_builder_sign on a worker thread.
+///
+/// Returns immediately and invokes the completion callback from the worker thread
+/// once the signed asset is written, with either the manifest bytes or an error message.
+/// The manifest bytes and the error message are only valid during the callback.
+/// If c2pa_init was called with single_threaded, signing runs on the caller's thread
+/// and the callback is invoked before this function returns.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type or extension.
+/// * source: pointer to a CStream.
+/// * dest: pointer to a writable CStream.
+/// * signer: pointer to a C2paSigner.
+/// * completion: a callback receiving the user data, the manifest bytes or NULL,
+///   their length and the error or NULL.
+/// * user_data: a context value passed to the callback.
+///
+/// # Errors
+/// Returns -1 if signing could not be started, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The builder, streams and signer must remain valid, and the builder must not be used,
+/// until the callback is invoked.
+/// The stream, signer and completion callbacks may be called from another thread.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_async(
+    builder_ptr: *mut C2paBuilder,
+    format: *const c_char,
+    source: *mut CStream,
+    dest: *mut CStream,
+    signer: *mut C2paSigner,
+    // spelled out for cbindgen, as with c2pa_set_manifest_publisher
+    completion: Option<
+        unsafe extern "C" fn(
+            user_data: *const c_void,
+            manifest_bytes: *const c_uchar,
+            manifest_len: usize,
+            error: *const c_char,
+        ),
+    >,
+    user_data: *const c_void,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(source);
+    null_check_int!(dest);
+    null_check_int!(signer);
+    let format = from_cstr_null_check_int!(format);
+    let Some(completion) = completion else {
+        Error::NullParameter("completion".to_string()).set_last();
+        return -1;
+    };
+    let sign = AsyncSign {
+        builder: builder_ptr,
+        format,
+        source,
+        dest,
+        signer,
+        user_data,
+        completion,
+    };
+    if refresh::single_threaded() {
+        sign.run();
+        return 0;
+    }
+    let worker = std::thread::Builder::new()
+        .name("c2pa-signer".to_string())
+        .spawn(move || unsafe { sign.run() });
+    match worker {
+        Ok(_) => 0,
+        Err(err) => {
+            Error::Other(err.to_string()).set_last();
+            -1
+        }
+    }
+}
+
+/// Creates a signed manifest from the C2paBuilder for an asset held in memory.
+///
+/// The signed asset is returned in a buffer allocated by the library.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type or extension.
+/// * data: pointer to the source asset bytes.
+/// * len: the number of bytes in data.
+/// * signer: pointer to a C2paSigner.
+/// * output_ptr: pointer to a pointer to a c_uchar to return the signed asset.
+/// * output_len: pointer to a usize to return the size of the signed asset.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The returned output MUST be released by calling c2pa_resource_bytes_free with output_len,
+/// and manifest_bytes with the returned size, and they are no longer valid after that call.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_bytes(
+    builder_ptr: *mut C2paBuilder,
+    format: *const c_char,
+    data: *const c_uchar,
+    len: usize,
+    signer: *mut C2paSigner,
+    output_ptr: *mut *const c_uchar,
+    output_len: *mut usize,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(data);
+    null_check_int!(signer);
+    null_check_int!(output_ptr);
+    null_check_int!(output_len);
+    let format = from_cstr_null_check_int!(format);
+    let mut source = Cursor::new(std::slice::from_raw_parts(data, len));
+    let mut dest = Cursor::new(Vec::new());
+
+    let result = (*signer)
+        .sign_with(|signer| {
+            remote::sign_builder(&mut *builder_ptr, signer, &format, &mut source, &mut dest)
+        })
+        .and_then(|(manifest_bytes, remote_url)| {
+            if let Some(remote_url) = remote_url {
+                remote::publish(&remote_url, &manifest_bytes)?;
+            }
+            Ok(manifest_bytes)
+        });
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            let output = dest.into_inner();
+            *output_len = output.len();
+            *output_ptr = Box::into_raw(output.into_boxed_slice()) as *const c_uchar;
+            if !manifest_bytes_ptr.is_null() {
+                *manifest_bytes_ptr =
+                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            };
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Creates a signed manifest from the C2paBuilder and writes the signed asset to a file.
+///
+/// The format is taken from the file extensions, which must match.
+/// The destination file is created or replaced and its name is used as the title of this sign
+/// if none is set. The builder keeps no title afterwards, so the next sign defaults it again.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * source_path: pointer to a C string with the path to the source file.
+/// * dest_path: pointer to a C string with the path to the destination file.
+/// * signer: pointer to a C2paSigner.
+/// * c2pa_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_file(
+    builder_ptr: *mut C2paBuilder,
+    source_path: *const c_char,
+    dest_path: *const c_char,
+    signer: *mut C2paSigner,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(signer);
+    let source_path = from_cstr_null_check_int!(source_path);
+    let dest_path = from_cstr_null_check_int!(dest_path);
+
+    let result = (*signer)
+        .sign_with(|signer| {
+            remote::sign_builder_file(
+                &mut *builder_ptr,
+                signer,
+                Path::new(&source_path),
+                Path::new(&dest_path),
+            )
+        })
+        .and_then(|(manifest_bytes, remote_url)| {
+            if let Some(remote_url) = remote_url {
+                remote::publish(&remote_url, &manifest_bytes)?;
+            }
+            Ok(manifest_bytes)
+        });
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            if !manifest_bytes_ptr.is_null() {
+                *manifest_bytes_ptr =
+                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            };
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Signs a fragmented BMFF asset, such as a DASH presentation, with the C2paBuilder.
+///
+/// The init segment and fragments are copied to the output directory, which is created
+/// if needed, replacing files with the same names. The manifest store is embedded in the
+/// copied init segment and every copied fragment gets a Merkle tree box so that it can be
+/// validated on its own. The title defaults to the init segment file name.
+/// The SDK only signs fragments as files, and not ones that already have a manifest,
+/// and the manifest definition must have at least one assertion.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * signer: pointer to a C2paSigner.
+/// * init_path: pointer to a C string with the path to the init segment.
+/// * fragment_paths: pointer to an array of C strings with the paths to the fragments.
+/// * count: the number of fragment paths.
+/// * output_dir: pointer to a C string with the path to the output directory.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// fragment_paths must point to count valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_fragmented_files(
+    builder_ptr: *mut C2paBuilder,
+    signer: *mut C2paSigner,
+    init_path: *const c_char,
+    fragment_paths: *const *const c_char,
+    count: usize,
+    output_dir: *const c_char,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(signer);
+    let init_path = from_cstr_null_check_int!(init_path);
+    let output_dir = from_cstr_null_check_int!(output_dir);
+    let mut fragments = Vec::with_capacity(count);
+    if count > 0 {
+        null_check_int!(fragment_paths);
+        for &path in std::slice::from_raw_parts(fragment_paths, count) {
+            fragments.push(PathBuf::from(from_cstr_null_check_int!(path)));
+        }
+    }
+    let result = (*signer).sign_with(|signer| {
+        remote::sign_fragmented_files(
+            &mut *builder_ptr,
+            signer,
+            Path::new(&init_path),
+            &fragments,
+            Path::new(&output_dir),
+        )
+    });
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Signs like c2pa_builder_sign and then uploads the manifest store to the Builder's remote URL.
+///
+/// The upload callback is invoked with the remote URL and the manifest bytes after signing,
+/// so a cloud manifest can be produced and published in a single call.
+/// A remote URL must have been set with c2pa_builder_set_remote_url.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * format: pointer to a C string with the mime type or extension.
+/// * source: pointer to a CStream.
+/// * dest: pointer to a writable CStream.
+/// * signer: pointer to a C2paSigner.
+/// * context: a context value passed to the upload callback.
+/// * upload: a callback to upload the manifest store.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_and_upload(
+    builder_ptr: *mut C2paBuilder,
+    format: *const c_char,
+    source: *mut CStream,
+    dest: *mut CStream,
+    signer: *mut C2paSigner,
+    context: *const c_void,
+    upload: UploadCallback,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(source);
+    null_check_int!(dest);
+    null_check_int!(signer);
+    let format = from_cstr_null_check_int!(format);
+    let builder = &mut *builder_ptr;
+    if builder.remote_url.is_none() {
+        Error::set_last(Error::RemoteManifest("remote_url is not set".to_string()));
+        return -1;
+    }
+
+    let result = (*signer).sign_with(|signer| {
+        remote::sign_builder(builder, signer, &format, &mut *source, &mut *dest)
+    });
+    match result {
+        Ok((manifest_bytes, remote_url)) => {
+            let remote_url = remote_url.unwrap_or_default();
+            let url = match CString::new(remote_url.clone()) {
+                Ok(url) => url,
+                Err(_) => {
+                    Error::set_last(Error::RemoteManifest(remote_url));
+                    return -1;
+                }
+            };
+            let status = upload(
+                context,
+                url.as_ptr(),
+                manifest_bytes.as_ptr(),
+                manifest_bytes.len(),
+            );
+            if status < 0 {
+                Error::set_last(Error::RemoteManifest(format!(
+                    "upload to {remote_url} failed: {status}"
+                )));
+                return -1;
+            }
+            let len = manifest_bytes.len() as c_int;
+            if !manifest_bytes_ptr.is_null() {
+                *manifest_bytes_ptr =
+                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            };
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Frees a C2PA manifest returned by c2pa_builder_sign.
+///
+/// # Safety
+/// The bytes can only be freed once and are invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_manifest_bytes_free(manifest_bytes_ptr: *const c_uchar) {
+    if !manifest_bytes_ptr.is_null() {
+        drop(Box::from_raw(manifest_bytes_ptr as *mut c_uchar));
+    }
+}
+
+/// Creates a hashed placeholder from a Builder.
+/// The placeholder is used to reserve size in an asset for later signing.
+///
+/// The host writes the placeholder into the asset itself, hashes the asset excluding the
+/// placeholder and passes the hash to c2pa_builder_sign_data_hashed_embeddable.
+/// The signed manifest is the same size as the placeholder, so it can replace it in place.
+/// Use "application/c2pa" as the format for an unframed manifest store.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * reserved_size: the size required for a signature from the intended signer.
+/// * format: pointer to a C string with the mime type or extension.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_data_hashed_placeholder(
+    builder_ptr: *mut C2paBuilder,
+    reserved_size: usize,
+    format: *const c_char,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(manifest_bytes_ptr);
+    let format = from_cstr_null_check_int!(format);
+    let result = (*builder_ptr).data_hashed_placeholder(reserved_size, &format);
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            *manifest_bytes_ptr =
+                Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            len
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Sign a Builder using the specified signer and data hash.
+/// The data hash is a JSON string containing DataHash information for the asset.
+/// This is a low-level method for advanced use cases where the caller handles embedding the manifest.
+///
+/// The data hash has an "exclusions" array of {"start", "length"} ranges covering the
+/// placeholder, the "alg", such as "sha256", and the "hash" bytes as an array of numbers.
+/// The asset stream is never read.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * signer: pointer to a C2paSigner.
+/// * data_hash: pointer to a C string with the JSON data hash.
+/// * format: pointer to a C string with the mime type or extension.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_data_hashed_embeddable(
+    builder_ptr: *mut C2paBuilder,
+    signer: *mut C2paSigner,
+    data_hash: *const c_char,
+    format: *const c_char,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(signer);
+    null_check_int!(manifest_bytes_ptr);
+    let data_hash_json = from_cstr_null_check_int!(data_hash);
+    let data_hash: DataHash = match serde_json::from_str(&data_hash_json) {
+        Ok(data_hash) => data_hash,
+        Err(err) => {
+            Error::from_c2pa_error(c2pa::Error::JsonError(err)).set_last();
+            return -1;
+        }
+    };
+    let format = from_cstr_null_check_int!(format);
+    let result = (*signer).sign_with(|signer| {
+        (*builder_ptr).sign_data_hashed_embeddable(signer, &data_hash, &format)
+    });
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            *manifest_bytes_ptr =
+                Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            len
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Frames a signed manifest store for embedding in an asset of the given format.
+///
+/// For a JPEG this returns the APP11 segments holding the manifest store, ready to be
+/// inserted into the asset. Use it with the manifest bytes returned by the signing APIs
+/// when the caller does its own embedding.
+///
+/// # Parameters
+/// * format: pointer to a C string with the mime type or extension.
+/// * manifest_bytes: pointer to the signed manifest store bytes.
+/// * len: the number of bytes in manifest_bytes.
+/// * result_bytes_ptr: pointer to a pointer to a c_uchar to return the framed bytes.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the framed bytes.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_resource_bytes_free with the returned size
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_format_embeddable(
+    format: *const c_char,
+    manifest_bytes: *const c_uchar,
+    len: usize,
+    result_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(manifest_bytes);
+    null_check_int!(result_bytes_ptr);
+    let format = from_cstr_null_check_int!(format);
+    let manifest_bytes = std::slice::from_raw_parts(manifest_bytes, len);
+    match c2pa::Manifest::composed_manifest(manifest_bytes, &format) {
+        Ok(result_bytes) => {
+            let len = result_bytes.len() as c_int;
+            *result_bytes_ptr = Box::into_raw(result_bytes.into_boxed_slice()) as *const c_uchar;
+            len
+        }
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Estimates the size of the manifest store the Builder will produce with the given signer.
+///
+/// The estimate includes the signature reserve and any resources added to the Builder.
+/// It does not include thumbnails generated automatically while signing or the
+/// container specific framing used when embedding into an asset.
+/// The Builder is not modified.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * signer: pointer to a C2paSigner.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the estimated size in bytes.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// builder_ptr and signer must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_estimate_size(
+    builder_ptr: *mut C2paBuilder,
+    signer: *mut C2paSigner,
+) -> i64 {
+    null_check_int!(builder_ptr);
+    null_check_int!(signer);
+    let builder = &mut *builder_ptr;
+    let reserve_size = (*signer).configured().reserve_size();
+
+    // work on a copy so the placeholder assertions are not added to the caller's Builder
+    let mut archive = std::io::Cursor::new(Vec::new());
+    let result = builder
+        .to_archive(&mut archive)
+        .and_then(|_| {
+            archive.set_position(0);
+            C2paBuilder::from_archive(&mut archive)
+        })
+        .and_then(|mut copy| copy.data_hashed_placeholder(reserve_size, "application/c2pa"));
+    match result {
+        Ok(placeholder) => placeholder.len() as i64,
+        Err(err) => {
+            Error::from_c2pa_error(err).set_last();
+            -1
+        }
+    }
+}
+
+/// Bytes to write at an offset of an asset, returned by c2pa_builder_sign_placeholder.
+#[repr(C)]
+pub struct C2paManifestPatch {
+    /// The offset in the asset to write at.
+    pub offset: u64,
+    /// The bytes to write.
+    pub data: *const c_uchar,
+    /// The number of bytes in data.
+    pub len: usize,
+}
+
+/// Creates a placeholder manifest, framed for the format, for a muxer to write into an asset.
+///
+/// The placeholder reserves space for the signed manifest, which is later written over it
+/// with the patches returned by c2pa_builder_sign_placeholder. The asset is never rewritten.
+/// Use "application/c2pa" as the format for an unframed manifest store.
+///
+/// # Parameters
+/// * builder_ptr: pointer to a Builder.
+/// * signer: pointer to the C2paSigner that will sign the asset.
+/// * format: pointer to a C string with the mime type or extension.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return the placeholder.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the placeholder.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_resource_bytes_free with the returned size
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_manifest_placeholder(
+    builder_ptr: *mut C2paBuilder,
+    signer: *mut C2paSigner,
+    format: *const c_char,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(signer);
+    null_check_int!(manifest_bytes_ptr);
+    let format = from_cstr_null_check_int!(format);
+    let result = patch::manifest_placeholder(&mut *builder_ptr, &(*signer).configured(), &format);
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            *manifest_bytes_ptr =
+                Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Signs an asset holding a placeholder from c2pa_builder_manifest_placeholder.
+///
+/// Everything but the placeholder is hashed, so the asset must be complete.
+/// Writing each patch at its offset replaces the placeholder with the signed manifest;
+/// the patches never write outside the placeholder.
+///
+/// # Parameters
+/// * builder_ptr: pointer to the Builder that made the placeholder.
+/// * signer: pointer to a C2paSigner.
+/// * format: pointer to a C string with the mime type or extension used for the placeholder.
+/// * stream: pointer to a readable CStream with the finished asset.
+/// * offset: the offset of the placeholder in the asset.
+/// * placeholder_len: the size of the placeholder.
+/// * patches_ptr: pointer to a pointer to return an array of C2paManifestPatch.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the number of patches.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings.
+/// The returned patches MUST be released by calling c2pa_manifest_patches_free
+/// and are no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_builder_sign_placeholder(
+    builder_ptr: *mut C2paBuilder,
+    signer: *mut C2paSigner,
+    format: *const c_char,
+    stream: *mut CStream,
+    offset: u64,
+    placeholder_len: usize,
+    patches_ptr: *mut *const C2paManifestPatch,
+) -> c_int {
+    null_check_int!(builder_ptr);
+    null_check_int!(signer);
+    null_check_int!(stream);
+    null_check_int!(patches_ptr);
     let format = from_cstr_null_check_int!(format);
+    let result = (*signer).sign_with(|signer| {
+        patch::sign_placeholder(
+            &mut *builder_ptr,
+            signer,
+            &format,
+            &mut *stream,
+            offset,
+            placeholder_len,
+        )
+    });
+    match result {
+        Ok(patches) => {
+            let patches: Vec<C2paManifestPatch> = patches
+                .into_iter()
+                .map(|ManifestPatch { offset, data }| C2paManifestPatch {
+                    offset,
+                    len: data.len(),
+                    data: Box::into_raw(data.into_boxed_slice()) as *const c_uchar,
+                })
+                .collect();
+            let count = patches.len() as c_int;
+            *patches_ptr = Box::into_raw(patches.into_boxed_slice()) as *const C2paManifestPatch;
+            count
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Frees the patches returned by c2pa_builder_sign_placeholder.
+///
+/// # Parameters
+/// * patches: pointer to the patches.
+/// * count: the number of patches returned.
+///
+/// # Safety
+/// The patches can only be freed once and are invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_manifest_patches_free(
+    patches: *const C2paManifestPatch,
+    count: usize,
+) {
+    if !patches.is_null() {
+        let patches = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            patches as *mut C2paManifestPatch,
+            count,
+        ));
+        for patch in patches.iter() {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                patch.data as *mut c_uchar,
+                patch.len,
+            )));
+        }
+    }
+}
 
-    let c2pa_signer = Box::from_raw(signer);
+/// Creates a C2paSigner from a callback and configuration.
+///
+/// # Parameters
+/// * callback: a callback function to sign data.
+/// * alg: the signing algorithm.
+/// * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+///
+/// # Example
+/// ```c
+/// auto result = c2pa_signer_create(callback, alg, certs, tsa_url);
+/// if (result == NULL) {
+///     printf("Error: %s\n", c2pa_error());
+/// }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_create(
+    context: *const c_void,
+    callback: SignerCallback,
+    alg: C2paSigningAlg,
+    certs: *const c_char,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    let certs = from_cstr_null_check!(certs);
+    let tsa_url = from_cstr_option!(tsa_url);
+    callback_signer(context, callback, alg, certs.into_bytes(), tsa_url)
+}
 
-    let result = builder.sign(
-        c2pa_signer.signer.as_ref(),
-        &format,
-        &mut *source,
-        &mut *dest,
-    );
-    let _ = Box::into_raw(c2pa_signer);
-    let _ = Box::into_raw(builder);
-    match result {
-        Ok(manifest_bytes) => {
-            let len = manifest_bytes.len() as c_int;
-            if !manifest_bytes_ptr.is_null() {
-                *manifest_bytes_ptr =
-                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+/// Creates a C2paSigner from a callback and a DER encoded certificate chain.
+///
+/// This is c2pa_signer_create for certificates pulled from a key store, which need not
+/// be re-encoded as PEM.
+///
+/// # Parameters
+/// * context: a generic context value passed to the callback.
+/// * callback: a callback function to sign data.
+/// * alg: the signing algorithm.
+/// * certs: pointer to the DER encoded certificates concatenated, starting with the
+///   signing certificate.
+/// * certs_len: the length of certs in bytes.
+/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// certs must be valid for certs_len bytes.
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_create_der(
+    context: *const c_void,
+    callback: SignerCallback,
+    alg: C2paSigningAlg,
+    certs: *const c_uchar,
+    certs_len: usize,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    null_check!(certs);
+    let tsa_url = from_cstr_option!(tsa_url);
+    let certs = match der_certs_pem(std::slice::from_raw_parts(certs, certs_len)) {
+        Ok(certs) => certs,
+        Err(err) => {
+            err.set_last();
+            return std::ptr::null_mut();
+        }
+    };
+    callback_signer(context, callback, alg, certs.into_bytes(), tsa_url)
+}
+
+// the largest signature a signer callback may return
+const MAX_SIGNATURE_SIZE: usize = 64 * 1024;
+
+// Creates a signer that calls a C signing callback with the PEM certificate chain.
+unsafe fn callback_signer(
+    context: *const c_void,
+    callback: SignerCallback,
+    alg: C2paSigningAlg,
+    certs: Vec<u8>,
+    tsa_url: Option<String>,
+) -> *mut C2paSigner {
+    let context = context as *const ();
+
+    let c_callback = move |context: *const (), data: &[u8]| {
+        // start with a guess at the signed size, a callback that needs more space
+        // returns the size it needs and is invoked once more with a buffer that big
+        let mut signed_bytes: Vec<u8> = vec![0; (data.len() * 2).min(MAX_SIGNATURE_SIZE)];
+        for _ in 0..2 {
+            let signed_size = unsafe {
+                (callback)(
+                    context,
+                    data.as_ptr(),
+                    data.len(),
+                    signed_bytes.as_mut_ptr(),
+                    signed_bytes.len(),
+                )
             };
-            len
+            if signed_size < 0 {
+                return Err(c2pa::Error::CoseSignature); // todo:: return errors from callback
+            }
+            let signed_size = signed_size as usize;
+            if signed_size <= signed_bytes.len() {
+                signed_bytes.truncate(signed_size);
+                return Ok(signed_bytes);
+            }
+            if signed_size > MAX_SIGNATURE_SIZE {
+                return Err(c2pa::Error::CoseSignature);
+            }
+            signed_bytes.resize(signed_size, 0);
         }
+        // asked for a larger buffer again
+        Err(c2pa::Error::CoseSignature)
+    };
+
+    let mut signer = CallbackSigner::new(c_callback, alg.into(), certs).set_context(context);
+    if let Some(tsa_url) = tsa_url.as_ref() {
+        signer = signer.set_tsa_url(tsa_url);
+    }
+    Box::into_raw(Box::new(C2paSigner::new(Box::new(signer))))
+}
+
+/// Creates a C2paSigner whose signatures are completed later by c2pa_signer_complete.
+///
+/// The callback starts a signature request and returns without waiting for the signature,
+/// which the host passes to c2pa_signer_complete with the request id once it is available,
+/// from any thread. Signing waits for the signature on the signing thread, so use
+/// c2pa_builder_sign_async to keep the caller's thread free.
+/// A request not completed within two minutes fails the sign with a SignatureTimeout error,
+/// and completing it later fails.
+///
+/// # Parameters
+/// * context: a generic context value passed to the callback.
+/// * callback: a callback that starts a signature request.
+/// * alg: the signing algorithm.
+/// * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+/// Every started request MUST be completed, or signing does not finish.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_create_async(
+    context: *const c_void,
+    callback: AsyncSignerCallback,
+    alg: C2paSigningAlg,
+    certs: *const c_char,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    let certs = from_cstr_null_check!(certs);
+    let tsa_url = from_cstr_option!(tsa_url);
+    let context = context as *const ();
+
+    let c_callback = move |context: *const (), data: &[u8]| {
+        async_signer::request(|request_id| unsafe {
+            (callback)(context, request_id, data.as_ptr(), data.len()) >= 0
+        })
+        // carried whole, so a timeout reaches the caller as SignatureTimeout
+        .map_err(|e| c2pa::Error::OtherError(Box::new(e)))
+    };
+
+    let mut signer = CallbackSigner::new(c_callback, alg.into(), certs).set_context(context);
+    if let Some(tsa_url) = tsa_url.as_ref() {
+        signer = signer.set_tsa_url(tsa_url);
+    }
+    Box::into_raw(Box::new(C2paSigner::new(Box::new(signer))))
+}
+
+/// Completes a signature request started by the callback of a c2pa_signer_create_async signer.
+///
+/// # Parameters
+/// * request_id: the id the callback was given.
+/// * signature: pointer to the signature bytes, or NULL if signing failed.
+/// * len: the number of bytes in signature.
+///
+/// # Errors
+/// Returns -1 if there is no pending request with the id, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// signature must be NULL or point to len bytes, which are copied.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_complete(
+    request_id: u64,
+    signature: *const c_uchar,
+    len: usize,
+) -> c_int {
+    let signature =
+        (!signature.is_null()).then(|| std::slice::from_raw_parts(signature, len).to_vec());
+    match async_signer::complete(request_id, signature) {
+        Ok(()) => 0,
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
+            err.set_last();
             -1
         }
     }
 }
 
-/// Frees a C2PA manifest returned by c2pa_builder_sign.
+/// Creates a C2paSigner that posts to a signing service over HTTP.
+///
+/// The bytes to sign, or their digest, are posted as application/octet-stream and the
+/// response body, of at most 64 KiB, is used as the raw signature.
+/// The options are a JSON object, for example:
+/// `{"url": "https://sign.example.com/es256", "headers": {"Authorization": "Bearer <token>"},
+/// "payload": "digest", "retry": {"retries": 2}, "timeout_ms": 5000}`
+/// * url: the signing service endpoint, which must be https unless it is on this machine.
+/// * headers: headers added to every signing request.
+/// * payload: "data" to post the bytes to sign, or "digest" to post their hash for the algorithm.
+/// * retry: retries for failed requests, with exponential backoff.
+///   Requests rejected with a 4xx status are not retried.
+/// * timeout_ms: the time allowed for each request.
+///
+/// # Parameters
+/// * options_json: pointer to a C string with the options JSON.
+/// * alg: the signing algorithm.
+/// * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_create_remote(
+    options_json: *const c_char,
+    alg: C2paSigningAlg,
+    certs: *const c_char,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    let options_json = from_cstr_null_check!(options_json);
+    let certs = from_cstr_null_check!(certs);
+    let tsa_url = from_cstr_option!(tsa_url);
+    let result = RemoteSignerOptions::from_json(&options_json)
+        .and_then(|options| remote_signer(options, alg.into(), certs.as_bytes(), tsa_url));
+    match result {
+        Ok(signer) => Box::into_raw(Box::new(C2paSigner::new(Box::new(signer)))),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a C2paSigner that signs with a private key on a PKCS#11 token, such as an HSM.
+///
+/// The token's module is loaded once and shared by every signer for it. A session is opened,
+/// and logged in with the PIN if one is given, for the life of the signer.
+/// ECDSA and RSA-PSS keys sign a digest computed by the library, so the token only needs
+/// the CKM_ECDSA, CKM_RSA_PKCS_PSS or CKM_EDDSA mechanism.
+/// Only available when the library is built with the pkcs11 feature.
+///
+/// # Parameters
+/// * module_path: a pointer to a NULL-terminated string with the path to the PKCS#11 module.
+/// * slot: the id of the slot holding the token.
+/// * pin: a pointer to a NULL-terminated string with the user PIN, or NULL to skip the login.
+/// * key_label: a pointer to a NULL-terminated string with the label of the private key.
+/// * alg: the signing algorithm, which must match the key.
+/// * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
+/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+#[cfg(feature = "pkcs11")]
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_create_pkcs11(
+    module_path: *const c_char,
+    slot: u64,
+    pin: *const c_char,
+    key_label: *const c_char,
+    alg: C2paSigningAlg,
+    certs: *const c_char,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    let module_path = from_cstr_null_check!(module_path);
+    let pin = from_cstr_option!(pin);
+    let key_label = from_cstr_null_check!(key_label);
+    let certs = from_cstr_null_check!(certs);
+    let tsa_url = from_cstr_option!(tsa_url);
+    let key = crate::pkcs11::Pkcs11Key {
+        module_path: &module_path,
+        slot,
+        pin: pin.as_deref(),
+        key_label: &key_label,
+    };
+    match crate::pkcs11::pkcs11_signer(&key, alg.into(), certs.as_bytes(), tsa_url) {
+        Ok(signer) => Box::into_raw(Box::new(C2paSigner::new(Box::new(signer)))),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a C2paSigner from a PEM certificate chain and private key.
+///
+/// The signer signs locally, so no signing callback is needed.
+///
+/// # Parameters
+/// * signer_info: pointer to a C2paSignerInfo with the algorithm, certificates, key and
+///   optional timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_from_info(signer_info: &C2paSignerInfo) -> *mut C2paSigner {
+    let signer_info = SignerInfo {
+        alg: from_cstr_null_check!(signer_info.alg),
+        sign_cert: from_cstr_null_check!(signer_info.sign_cert).into_bytes(),
+        private_key: from_cstr_null_check!(signer_info.private_key).into_bytes(),
+        ta_url: from_cstr_option!(signer_info.ta_url),
+    };
+    match signer_info.signer() {
+        Ok(signer) => Box::into_raw(Box::new(C2paSigner::new(signer))),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a C2paSigner from a DER encoded certificate chain and PKCS#8 private key.
+///
+/// This is c2pa_signer_from_info for credentials pulled from a key store, which need not
+/// be re-encoded as PEM.
+///
+/// # Parameters
+/// * alg: the signing algorithm.
+/// * certs: pointer to the DER encoded certificates concatenated, starting with the
+///   signing certificate.
+/// * certs_len: the length of certs in bytes.
+/// * private_key: pointer to the DER encoded PKCS#8 private key.
+/// * private_key_len: the length of private_key in bytes.
+/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+///
+/// # Errors
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// certs and private_key must be valid for their lengths in bytes.
+/// The returned value MUST be released by calling c2pa_signer_free
+/// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_from_der(
+    alg: C2paSigningAlg,
+    certs: *const c_uchar,
+    certs_len: usize,
+    private_key: *const c_uchar,
+    private_key_len: usize,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    null_check!(certs);
+    null_check!(private_key);
+    let signer = SignerInfo::from_der(
+        alg.into(),
+        std::slice::from_raw_parts(certs, certs_len),
+        std::slice::from_raw_parts(private_key, private_key_len),
+        from_cstr_option!(tsa_url),
+    )
+    .and_then(|signer_info| signer_info.signer());
+    match signer {
+        Ok(signer) => Box::into_raw(Box::new(C2paSigner::new(signer))),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// The result of checking a C2paSignerInfo with c2pa_signer_validate.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum C2paSignerProblem {
+    /// No problems were found.
+    SignerValid,
+    /// The signing algorithm is not one of the supported algorithms.
+    UnknownAlgorithm,
+    /// The certificate chain is not PEM, or a certificate in it can't be decoded.
+    CertificateDecode,
+    /// The private key is not PEM, or is a kind of key the crypto library can't decode.
+    PrivateKeyDecode,
+    /// The signing algorithm can't be used with the key type of the signing certificate.
+    AlgorithmMismatch,
+    /// The private key does not belong to the signing certificate.
+    KeyMismatch,
+    /// A certificate in the chain was not issued by the certificate that follows it.
+    BrokenChain,
+}
+
+impl From<SignerProblem> for C2paSignerProblem {
+    fn from(problem: SignerProblem) -> Self {
+        match problem {
+            SignerProblem::UnknownAlgorithm => C2paSignerProblem::UnknownAlgorithm,
+            SignerProblem::CertificateDecode => C2paSignerProblem::CertificateDecode,
+            SignerProblem::PrivateKeyDecode => C2paSignerProblem::PrivateKeyDecode,
+            SignerProblem::AlgorithmMismatch => C2paSignerProblem::AlgorithmMismatch,
+            SignerProblem::KeyMismatch => C2paSignerProblem::KeyMismatch,
+            SignerProblem::BrokenChain => C2paSignerProblem::BrokenChain,
+        }
+    }
+}
+
+/// Checks a signer configuration so certificate and key problems are found before signing.
+///
+/// Checks that the certificates and private key decode, that the algorithm can be used
+/// with the key type of the signing certificate, that each certificate in the chain was
+/// issued by the one that follows it, and that the private key belongs to the signing
+/// certificate. The chain may end at a self-signed root or at an intermediate.
+///
+/// # Parameters
+/// * signer_info: pointer to a C2paSignerInfo with the algorithm, certificates and key.
+///
+/// # Errors
+/// Returns -1 if a parameter is NULL, otherwise returns a C2paSignerProblem, which is
+/// SignerValid (0) if no problems were found.
+/// The error string, describing the problem, can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_validate(signer_info: &C2paSignerInfo) -> c_int {
+    let signer_info = SignerInfo {
+        alg: from_cstr_null_check_int!(signer_info.alg),
+        sign_cert: from_cstr_null_check_int!(signer_info.sign_cert).into_bytes(),
+        private_key: from_cstr_null_check_int!(signer_info.private_key).into_bytes(),
+        ta_url: from_cstr_option!(signer_info.ta_url),
+    };
+    match signer_info.validate() {
+        Ok(()) => C2paSignerProblem::SignerValid as c_int,
+        Err((problem, err)) => {
+            err.set_last();
+            C2paSignerProblem::from(problem) as c_int
+        }
+    }
+}
+
+/// Returns the size to reserve for the signature for this signer.
+///
+/// # Parameters
+/// * signer_ptr: pointer to a C2paSigner.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size to reserve.
+/// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// The bytes can only be freed once and are invalid after this call.
+/// The signer_ptr must be a valid pointer to a C2paSigner.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_manifest_bytes_free(manifest_bytes_ptr: *const c_uchar) {
-    if !manifest_bytes_ptr.is_null() {
-        drop(Box::from_raw(manifest_bytes_ptr as *mut c_uchar));
+pub unsafe extern "C" fn c2pa_signer_reserve_size(signer_ptr: *mut C2paSigner) -> i64 {
+    if signer_ptr.is_null() {
+        Error::set_last(Error::NullParameter(stringify!($ptr).to_string()));
+        return -1;
     }
+    let c2pa_signer: Box<C2paSigner> = Box::from_raw(signer_ptr);
+    let size = c2pa_signer.configured().reserve_size() as i64;
+    let _ = Box::into_raw(c2pa_signer);
+    size
 }
 
-/// Creates a hashed placeholder from a Builder.
-/// The placeholder is used to reserve size in an asset for later signing.
+/// Signs arbitrary bytes with a C2paSigner.
+///
+/// This runs the signer directly, without building a manifest, so the signer wiring
+/// can be tested against known vectors.
+/// If signature is NULL, the size of the signature is returned without copying it.
 ///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * reserved_size: the size required for a signature from the intended signer.
-/// * format: pointer to a C string with the mime type or extension.
-/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes.
+/// * signer_ptr: pointer to a C2paSigner.
+/// * data: pointer to the bytes to sign.
+/// * len: the length of the bytes to sign.
+/// * signature: pointer to a buffer for the signature (optional, can be NULL).
+/// * signature_len: the size of the signature buffer.
 ///
 /// # Errors
-/// Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+/// Returns -1 if there were errors, otherwise returns the size of the signature.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings.
-/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
-/// and it is no longer valid after that call.
+/// The signer_ptr must be a valid pointer to a C2paSigner.
+/// data must point to at least len bytes and signature to at least signature_len bytes.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_data_hashed_placeholder(
-    builder_ptr: *mut C2paBuilder,
-    reserved_size: usize,
-    format: *const c_char,
-    manifest_bytes_ptr: *mut *const c_uchar,
-) -> c_int {
-    null_check_int!(builder_ptr);
-    null_check_int!(manifest_bytes_ptr);
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    let format = from_cstr_null_check_int!(format);
-    let result = builder.data_hashed_placeholder(reserved_size, &format);
-    let _ = Box::into_raw(builder);
-    match result {
-        Ok(manifest_bytes) => {
-            let len = manifest_bytes.len() as c_int;
-            *manifest_bytes_ptr =
-                Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
-            len
+pub unsafe extern "C" fn c2pa_signer_sign(
+    signer_ptr: *const C2paSigner,
+    data: *const c_uchar,
+    len: usize,
+    signature: *mut c_uchar,
+    signature_len: usize,
+) -> isize {
+    null_check_int!(signer_ptr);
+    null_check_int!(data);
+    let data = std::slice::from_raw_parts(data, len);
+    match (*signer_ptr).configured().sign(data) {
+        Ok(signed) => {
+            if !signature.is_null() {
+                if signed.len() > signature_len {
+                    Error::Other(format!(
+                        "signature buffer too small: {} bytes needed",
+                        signed.len()
+                    ))
+                    .set_last();
+                    return -1;
+                }
+                std::ptr::copy_nonoverlapping(signed.as_ptr(), signature, signed.len());
+            }
+            signed.len() as isize
         }
         Err(err) => {
             Error::from_c2pa_error(err).set_last();
@@ -796,162 +5010,269 @@ pub unsafe extern "C" fn c2pa_builder_data_hashed_placeholder(
     }
 }
 
-/// Sign a Builder using the specified signer and data hash.
-/// The data hash is a JSON string containing DataHash information for the asset.
-/// This is a low-level method for advanced use cases where the caller handles embedding the manifest.
+/// Sets options on a C2paSigner.
+///
+/// The options are a JSON object, for example `{"use_ocsp": true}`.
+/// Options it does not give keep the values set before, and a null option is reset.
+/// * use_ocsp: staple an OCSP response for the signing certificate, cached across signing operations.
+///   The response is fetched when the reserve size is computed, so that it can include it.
+/// * tsa: time stamp request options, an object with digest_alg (sha256, sha384 or sha512),
+///   policy_oid (dotted OID string), nonce (true or false) and headers (name to value).
+/// * retry: retries for failed TSA and OCSP requests, an object with retries,
+///   backoff_ms (doubled for each retry) and max_backoff_ms.
+/// * tsa_failure: "fail" to fail the sign when the TSA cannot be reached (the default),
+///   or "proceed" to sign without a time stamp, see c2pa_timestamp_warning.
 ///
 /// # Parameters
-/// * builder_ptr: pointer to a Builder.
-/// * signer: pointer to a C2paSigner.
-/// * data_hash: pointer to a C string with the JSON data hash.
-/// * format: pointer to a C string with the mime type or extension.
-/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+/// * signer_ptr: pointer to a C2paSigner.
+/// * options_json: pointer to a C string with the options JSON.
 ///
 /// # Errors
-/// Returns -1 if there were errors, otherwise returns the size of the manifest_bytes.
+/// Returns -1 if there were errors, otherwise returns 0.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
+/// The signer_ptr must be a valid pointer to a C2paSigner.
 /// Reads from NULL-terminated C strings.
-/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
-/// and it is no longer valid after that call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_builder_sign_data_hashed_embeddable(
-    builder_ptr: *mut C2paBuilder,
-    signer: *mut C2paSigner,
-    data_hash: *const c_char,
-    format: *const c_char,
-    manifest_bytes_ptr: *mut *const c_uchar,
+pub unsafe extern "C" fn c2pa_signer_set_options(
+    signer_ptr: *mut C2paSigner,
+    options_json: *const c_char,
 ) -> c_int {
-    null_check_int!(builder_ptr);
-    null_check_int!(manifest_bytes_ptr);
-
-    let mut builder: Box<C2paBuilder> = Box::from_raw(builder_ptr);
-    let c2pa_signer = Box::from_raw(signer);
-    let data_hash_json = from_cstr_null_check_int!(data_hash);
-    let data_hash: DataHash = match serde_json::from_str(&data_hash_json) {
-        Ok(data_hash) => data_hash,
-        Err(err) => {
-            Error::from_c2pa_error(c2pa::Error::JsonError(err)).set_last();
-            return -1;
-        }
-    };
-    let format = from_cstr_null_check_int!(format);
-    let result =
-        builder.sign_data_hashed_embeddable(c2pa_signer.signer.as_ref(), &data_hash, &format);
-    let _ = Box::into_raw(c2pa_signer);
-    let _ = Box::into_raw(builder);
-    match result {
-        Ok(manifest_bytes) => {
-            let len = manifest_bytes.len() as c_int;
-            *manifest_bytes_ptr =
-                Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
-            len
+    null_check_int!(signer_ptr);
+    let options_json = from_cstr_null_check_int!(options_json);
+    match (*signer_ptr).options.merged_json(&options_json) {
+        Ok(options) => {
+            (*signer_ptr).options = options;
+            0
         }
         Err(err) => {
-            Error::from_c2pa_error(err).set_last();
+            err.set_last();
             -1
         }
     }
 }
 
-/// Creates a C2paSigner from a callback and configuration.
+/// Returns the warning of the last sign on the calling thread, if it proceeded without a time stamp.
 ///
-/// # Parameters
-/// * callback: a callback function to sign data.
-/// * alg: the signing algorithm.
-/// * certs: a pointer to a NULL-terminated string containing the certificate chain in PEM format.
-/// * tsa_url: a pointer to a NULL-terminated string containing the RFC 3161 compliant timestamp authority URL.
+/// Like c2pa_error, the warning belongs to the calling thread, so call this after a sign
+/// returns and before the next sign on the same thread.
+/// Signing only proceeds without a time stamp when the tsa_failure option is "proceed".
 ///
 /// # Errors
-/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigner.
-/// The error string can be retrieved by calling c2pa_error.
+/// Returns NULL if the last sign was time stamped or did not request one,
+/// otherwise returns the warning.
 ///
 /// # Safety
-/// Reads from NULL-terminated C strings
-/// The returned value MUST be released by calling c2pa_signer_free
+/// The returned value MUST be released by calling c2pa_string_free
 /// and it is no longer valid after that call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_timestamp_warning() -> *mut c_char {
+    match last_timestamp_warning() {
+        Some(warning) => to_c_string(warning),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Sets the maximum number of simultaneous outbound requests made by the library.
 ///
-/// # Example
-/// ```c
-/// auto result = c2pa_signer_create(callback, alg, certs, tsa_url);
-/// if (result == NULL) {
-///     printf("Error: %s\n", c2pa_error());
-/// }
-/// ```
+/// The limit applies to TSA, OCSP, trust list and remote manifest requests. While a limit
+/// is set, remote manifests are fetched by the library rather than by the SDK, so that
+/// they count against it. Requests over the limit wait for a connection to be released.
+/// A max_connections of 0 removes the limit.
+///
+/// OCSP requests the SDK makes itself while validating, when the ocsp_fetch verify option
+/// is on, are not limited.
+///
+/// # Safety
+/// This function is always safe to call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_signer_create(
-    context: *const c_void,
-    callback: SignerCallback,
-    alg: C2paSigningAlg,
-    certs: *const c_char,
-    tsa_url: *const c_char,
-) -> *mut C2paSigner {
-    let certs = from_cstr_null_check!(certs);
-    let tsa_url = from_cstr_option!(tsa_url);
-    let context = context as *const ();
+pub unsafe extern "C" fn c2pa_set_max_connections(max_connections: usize) {
+    if let Err(err) = remote::set_max_connections(max_connections) {
+        err.set_last();
+    }
+}
 
-    let c_callback = move |context: *const (), data: &[u8]| {
-        // we need to guess at a max signed size, the callback must verify this is big enough or fail.
-        let signed_len_max = data.len() * 2;
-        let mut signed_bytes: Vec<u8> = vec![0; signed_len_max];
-        let signed_size = unsafe {
-            (callback)(
-                context,
-                data.as_ptr(),
-                data.len(),
-                signed_bytes.as_mut_ptr(),
-                signed_len_max,
-            )
-        };
-        //println!("signed_size: {}", signed_size);
-        if signed_size < 0 {
-            return Err(c2pa::Error::CoseSignature); // todo:: return errors from callback
-        }
-        signed_bytes.set_len(signed_size as usize);
-        Ok(signed_bytes)
-    };
+/// Removes all cached OCSP responses.
+///
+/// # Safety
+/// This function is always safe to call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_ocsp_cache_clear() {
+    ocsp::clear();
+}
 
-    let mut signer = CallbackSigner::new(c_callback, alg.into(), certs).set_context(context);
-    if let Some(tsa_url) = tsa_url.as_ref() {
-        signer = signer.set_tsa_url(tsa_url);
+/// Sets the maximum age in seconds of cached OCSP responses.
+///
+/// Responses are never used past their next update time.
+/// A max_age_secs of 0 or less removes the limit.
+///
+/// # Safety
+/// This function is always safe to call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_ocsp_cache_set_max_age(max_age_secs: i64) {
+    let max_age = (max_age_secs > 0).then(|| std::time::Duration::from_secs(max_age_secs as u64));
+    ocsp::set_max_age(max_age);
+}
+
+/// Frees a C2paSigner allocated by Rust.
+///
+/// # Safety
+/// The C2paSigner can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_free(signer_ptr: *const C2paSigner) {
+    if !signer_ptr.is_null() {
+        drop(Box::from_raw(signer_ptr as *mut C2paSigner));
     }
-    Box::into_raw(Box::new(C2paSigner {
-        signer: Box::new(signer),
-    }))
 }
 
-/// Returns the size to reserve for the signature for this signer.
+/// A manifest template prepared once for signing many assets with one signer.
+pub struct C2paSigningSession {
+    session: SigningSession,
+    signer: *const C2paSigner,
+}
+
+/// Creates a signing session for a manifest definition and a signer.
+///
+/// The definition is parsed and validated once, and the signer's certificate chain
+/// and reserve size are cached, so each asset signed in the session only pays
+/// for hashing and the signature.
 ///
 /// # Parameters
-/// * signer_ptr: pointer to a C2paSigner.
+/// * manifest_json: pointer to a C string with the JSON manifest definition.
+/// * signer: pointer to a C2paSigner, which must remain valid until the session is freed.
 ///
 /// # Errors
-/// Returns -1 if there were errors, otherwise returns the size to reserve.
+/// Returns NULL if there were errors, otherwise returns a pointer to a C2paSigningSession.
 /// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// The signer_ptr must be a valid pointer to a C2paSigner.
+/// Reads from NULL-terminated C strings.
+/// The returned value MUST be released by calling c2pa_signing_session_free
+/// and it is no longer valid after that call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_signer_reserve_size(signer_ptr: *mut C2paSigner) -> i64 {
-    if signer_ptr.is_null() {
-        Error::set_last(Error::NullParameter(stringify!($ptr).to_string()));
+pub unsafe extern "C" fn c2pa_signing_session_new(
+    manifest_json: *const c_char,
+    signer: *const C2paSigner,
+) -> *mut C2paSigningSession {
+    null_check!(signer);
+    let manifest_json = from_cstr_null_check!(manifest_json);
+    match SigningSession::new(&manifest_json, &(*signer).configured()) {
+        Ok(session) => Box::into_raw(Box::new(C2paSigningSession { session, signer })),
+        Err(err) => {
+            err.set_last();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Adds a resource, such as an icon, that is included with every asset signed in the session.
+///
+/// # Parameters
+/// * session: pointer to a C2paSigningSession.
+/// * uri: pointer to a C string with the URI to identify the resource.
+/// * stream: pointer to a CStream.
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns 0.
+/// The error string can be retrieved by calling c2pa_error.
+///
+/// # Safety
+/// Reads from NULL-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signing_session_add_resource(
+    session: *mut C2paSigningSession,
+    uri: *const c_char,
+    stream: *mut CStream,
+) -> c_int {
+    null_check_int!(session);
+    null_check_int!(stream);
+    let uri = from_cstr_null_check_int!(uri);
+    let mut data = Vec::new();
+    if let Err(err) = std::io::Read::read_to_end(&mut *stream, &mut data) {
+        Error::Io(err.to_string()).set_last();
         return -1;
     }
-    let c2pa_signer: Box<C2paSigner> = Box::from_raw(signer_ptr);
-    let size = c2pa_signer.signer.reserve_size() as i64;
-    let _ = Box::into_raw(c2pa_signer);
-    size
+    match (*session).session.add_resource(&uri, data) {
+        Ok(()) => 0,
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
 }
 
-/// Frees a C2paSigner allocated by Rust.
+/// Signs one asset with a signing session and writes it to the destination stream.
+///
+/// Assets in a session are signed one at a time, each waiting for the one before.
+///
+/// # Parameters
+/// * session: pointer to a C2paSigningSession.
+/// * format: pointer to a C string with the mime type or extension.
+/// * source: pointer to a CStream.
+/// * dest: pointer to a writable CStream.
+/// * manifest_bytes_ptr: pointer to a pointer to a c_uchar to return manifest_bytes (optional, can be NULL).
+///
+/// # Errors
+/// Returns -1 if there were errors, otherwise returns the size of the c2pa data.
+/// The error string can be retrieved by calling c2pa_error.
 ///
 /// # Safety
-/// The C2paSigner can only be freed once and is invalid after this call.
+/// Reads from NULL-terminated C strings
+/// If manifest_bytes_ptr is not NULL, the returned value MUST be released by calling c2pa_manifest_bytes_free
+/// and it is no longer valid after that call.
 #[no_mangle]
-pub unsafe extern "C" fn c2pa_signer_free(signer_ptr: *const C2paSigner) {
-    if !signer_ptr.is_null() {
-        drop(Box::from_raw(signer_ptr as *mut C2paSigner));
+pub unsafe extern "C" fn c2pa_signing_session_sign(
+    session: *const C2paSigningSession,
+    format: *const c_char,
+    source: *mut CStream,
+    dest: *mut CStream,
+    manifest_bytes_ptr: *mut *const c_uchar,
+) -> c_int {
+    null_check_int!(session);
+    null_check_int!(source);
+    null_check_int!(dest);
+    let format = from_cstr_null_check_int!(format);
+    let session = &*session;
+
+    let result = (*session.signer)
+        .sign_with(|signer| {
+            session
+                .session
+                .sign(signer, &format, &mut *source, &mut *dest)
+        })
+        .and_then(|(manifest_bytes, remote_url)| {
+            if let Some(remote_url) = remote_url {
+                remote::publish(&remote_url, &manifest_bytes)?;
+            }
+            Ok(manifest_bytes)
+        });
+    match result {
+        Ok(manifest_bytes) => {
+            let len = manifest_bytes.len() as c_int;
+            if !manifest_bytes_ptr.is_null() {
+                *manifest_bytes_ptr =
+                    Box::into_raw(manifest_bytes.into_boxed_slice()) as *const c_uchar;
+            };
+            len
+        }
+        Err(err) => {
+            err.set_last();
+            -1
+        }
+    }
+}
+
+/// Frees a C2paSigningSession allocated by Rust.
+///
+/// The signer used to create the session is not freed.
+///
+/// # Safety
+/// The C2paSigningSession can only be freed once and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signing_session_free(session: *mut C2paSigningSession) {
+    if !session.is_null() {
+        drop(Box::from_raw(session));
     }
 }
 
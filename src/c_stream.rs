@@ -12,10 +12,13 @@
 // each license.
 
 use std::{
-    ffi::{c_int, c_long},
-    io::{Read, Seek, Write},
+    cell::RefCell,
+    ffi::{c_char, c_int, c_long},
+    io::{Cursor, ErrorKind, Read, Seek, SeekFrom, Write},
 };
 
+use crate::{null_check_int, stream_buffer::StreamBuffer, Error};
+
 #[repr(C)]
 #[derive(Debug)]
 /// An Opaque struct to hold a context value for the stream callbacks
@@ -31,21 +34,104 @@ type ReadCallback =
 type SeekCallback =
     unsafe extern "C" fn(context: *const StreamContext, offset: c_long, mode: c_int) -> c_int;
 
+/// Defines a callback to seek to a 64 bit offset in a stream, returning the new position
+///
+/// Unlike SeekCallback, this handles assets over 2GB where long is 32 bits, such as on Windows.
+type SeekCallback64 =
+    unsafe extern "C" fn(context: *const StreamContext, offset: i64, mode: c_int) -> i64;
+
 /// Defines a callback to write to a stream
 type WriteCallback =
     unsafe extern "C" fn(context: *const StreamContext, data: *const u8, len: usize) -> isize;
 
 type FlushCallback = unsafe extern "C" fn(context: *const StreamContext) -> isize;
 
+/// Defines a callback to free the context of a stream when the stream is released
+type FreeCallback = unsafe extern "C" fn(context: *mut StreamContext);
+
+/// The cause of a stream callback failure, reported with c2pa_stream_set_error.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum C2paStreamError {
+    /// A failure with no more specific cause.
+    StreamOther,
+    /// The stream or its backing resource was not found.
+    StreamNotFound,
+    /// Access to the stream was denied.
+    StreamPermissionDenied,
+    /// The connection behind the stream was reset.
+    StreamConnectionReset,
+    /// The operation timed out.
+    StreamTimedOut,
+    /// The operation was interrupted and may be retried.
+    StreamInterrupted,
+    /// The stream ended before the operation completed.
+    StreamUnexpectedEof,
+    /// There was no space left to write to.
+    StreamStorageFull,
+    /// The stream does not support the operation, such as writing to a read-only stream.
+    StreamUnsupported,
+}
+
+impl From<C2paStreamError> for ErrorKind {
+    fn from(error: C2paStreamError) -> Self {
+        match error {
+            C2paStreamError::StreamOther => ErrorKind::Other,
+            C2paStreamError::StreamNotFound => ErrorKind::NotFound,
+            C2paStreamError::StreamPermissionDenied => ErrorKind::PermissionDenied,
+            C2paStreamError::StreamConnectionReset => ErrorKind::ConnectionReset,
+            C2paStreamError::StreamTimedOut => ErrorKind::TimedOut,
+            C2paStreamError::StreamInterrupted => ErrorKind::Interrupted,
+            C2paStreamError::StreamUnexpectedEof => ErrorKind::UnexpectedEof,
+            C2paStreamError::StreamStorageFull => ErrorKind::StorageFull,
+            C2paStreamError::StreamUnsupported => ErrorKind::Unsupported,
+        }
+    }
+}
+
+thread_local! {
+    // the error a stream callback reported with c2pa_stream_set_error
+    static STREAM_ERROR: RefCell<Option<std::io::Error>> = const { RefCell::new(None) };
+}
+
+fn set_stream_error(error: std::io::Error) {
+    STREAM_ERROR.with(|e| *e.borrow_mut() = Some(error));
+}
+
+// Calls a stream callback, returning the error it reported if its result is negative.
+//
+// Callbacks that don't call c2pa_stream_set_error report their errors through errno.
+fn call_stream<T: Default + PartialOrd>(callback: impl FnOnce() -> T) -> std::io::Result<T> {
+    STREAM_ERROR.with(|e| e.borrow_mut().take());
+    let result = callback();
+    if result < T::default() {
+        return Err(STREAM_ERROR
+            .with(|e| e.borrow_mut().take())
+            .unwrap_or_else(std::io::Error::last_os_error));
+    }
+    Ok(result)
+}
+
 #[repr(C)]
 /// A CStream is a Rust Read/Write/Seek stream that can be created in C
 #[derive(Debug)]
 pub struct CStream {
-    context: Box<StreamContext>,
-    reader: ReadCallback,
-    seeker: SeekCallback,
-    writer: WriteCallback,
-    flusher: FlushCallback,
+    context: *mut StreamContext,
+    reader: Option<ReadCallback>,
+    seeker: Option<SeekCallback>,
+    writer: Option<WriteCallback>,
+    flusher: Option<FlushCallback>,
+    free: Option<FreeCallback>,
+    seeker64: Option<SeekCallback64>,
+    buffer: Option<Box<StreamBuffer>>,
+    memory: Option<Memory>,
+}
+
+// The kind of memory stream, whose context the library allocated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Memory {
+    Reader,
+    Writer,
 }
 
 impl CStream {
@@ -56,62 +142,140 @@ impl CStream {
     /// * `seek` - a SeekCallback to seek in the stream
     /// * `write` - a WriteCallback to write to the stream
     /// * `flush` - a FlushCallback to flush the stream
+    ///
+    /// Callbacks for operations the stream is not used for may be None.
     /// # Safety
     /// The context must remain valid for the lifetime of the C2paStream
     /// The read, seek, and write callbacks must be valid for the lifetime of the C2paStream
     /// The resulting C2paStream must be released by calling c2pa_release_stream
     pub unsafe fn new(
         context: *mut StreamContext,
-        reader: ReadCallback,
-        seeker: SeekCallback,
-        writer: WriteCallback,
-        flusher: FlushCallback,
+        reader: Option<ReadCallback>,
+        seeker: Option<SeekCallback>,
+        writer: Option<WriteCallback>,
+        flusher: Option<FlushCallback>,
     ) -> Self {
         Self {
-            context: unsafe { Box::from_raw(context) },
+            context,
             reader,
             seeker,
             writer,
             flusher,
+            free: None,
+            seeker64: None,
+            buffer: None,
+            memory: None,
+        }
+    }
+
+    /// Creates a new CStream from context with callbacks, seeking with 64 bit offsets
+    /// # Safety
+    /// The same as for CStream::new
+    pub unsafe fn new64(
+        context: *mut StreamContext,
+        reader: Option<ReadCallback>,
+        seeker: Option<SeekCallback64>,
+        writer: Option<WriteCallback>,
+        flusher: Option<FlushCallback>,
+    ) -> Self {
+        let mut stream = Self::new(context, reader, None, writer, flusher);
+        stream.seeker64 = seeker;
+        stream
+    }
+
+    // Runs an operation on the buffer against the unbuffered stream, or returns None
+    // if the stream is not buffered.
+    fn buffered<T>(
+        &mut self,
+        operation: impl FnOnce(&mut StreamBuffer, &mut Self) -> std::io::Result<T>,
+    ) -> Option<std::io::Result<T>> {
+        let mut buffer = self.buffer.take()?;
+        let result = operation(&mut buffer, self);
+        self.buffer = Some(buffer);
+        Some(result)
+    }
+}
+
+// The error for an operation whose callback the stream was created without.
+fn missing_callback(operation: &str) -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::Unsupported,
+        format!("the stream has no {operation} callback"),
+    )
+}
+
+// the host is responsible for a context used from another thread, as with the callbacks
+unsafe impl Send for CStream {}
+
+impl Drop for CStream {
+    fn drop(&mut self) {
+        // errors can't be returned from here, so callers should flush before releasing
+        if let Some(Err(err)) = self.buffered(|buffer, stream| buffer.flush(stream)) {
+            log::error!("buffered writes were lost when a stream was released: {err}");
+        }
+        if let Some(free) = self.free {
+            unsafe { free(self.context) };
         }
     }
 }
 
 impl Read for CStream {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let bytes_read = unsafe { (self.reader)(&(*self.context), buf.as_mut_ptr(), buf.len()) };
-        // returns a negative number for errors
-        if bytes_read < 0 {
-            return Err(std::io::Error::last_os_error());
+        if let Some(result) = self.buffered(|buffer, stream| buffer.read(stream, buf)) {
+            return result;
         }
+        let reader = self.reader.ok_or_else(|| missing_callback("read"))?;
+        // returns a negative number for errors
+        let bytes_read =
+            call_stream(|| unsafe { reader(self.context, buf.as_mut_ptr(), buf.len()) })?;
         Ok(bytes_read as usize)
     }
 }
 
 impl Seek for CStream {
     fn seek(&mut self, from: std::io::SeekFrom) -> std::io::Result<u64> {
+        if let Some(result) = self.buffered(|buffer, stream| buffer.seek(stream, from)) {
+            return result;
+        }
         let (pos, mode) = match from {
             std::io::SeekFrom::Current(pos) => (pos, 1),
             std::io::SeekFrom::Start(pos) => (pos as i64, 0),
             std::io::SeekFrom::End(pos) => (pos, 2),
         };
-        let new_pos = unsafe { (self.seeker)(&(*self.context), pos as c_long, mode) };
+        let new_pos = match (self.seeker64, self.seeker) {
+            (Some(seeker64), _) => call_stream(|| unsafe { seeker64(self.context, pos, mode) })?,
+            (None, None) => return Err(missing_callback("seek")),
+            (None, Some(seeker)) => {
+                let pos = c_long::try_from(pos).map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("seek offset {pos} needs a stream created by c2pa_create_stream64"),
+                    )
+                })?;
+                call_stream(|| unsafe { seeker(self.context, pos, mode) })? as i64
+            }
+        };
         Ok(new_pos as u64)
     }
 }
 
 impl Write for CStream {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        let bytes_written = unsafe { (self.writer)(&(*self.context), buf.as_ptr(), buf.len()) };
-        if bytes_written < 0 {
-            return Err(std::io::Error::last_os_error());
+        if let Some(result) = self.buffered(|buffer, stream| buffer.write(stream, buf)) {
+            return result;
         }
+        let writer = self.writer.ok_or_else(|| missing_callback("write"))?;
+        let bytes_written =
+            call_stream(|| unsafe { writer(self.context, buf.as_ptr(), buf.len()) })?;
         Ok(bytes_written as usize)
     }
     fn flush(&mut self) -> std::io::Result<()> {
-        let err = unsafe { (self.flusher)(&(*self.context)) };
-        if err < 0 {
-            return Err(std::io::Error::last_os_error());
+        if let Some(result) = self.buffered(|buffer, stream| buffer.flush(stream)) {
+            return result;
+        }
+        // there is nothing to flush without a flush callback
+        if let Some(flusher) = self.flusher {
+            call_stream(|| unsafe { flusher(self.context) })?;
         }
         Ok(())
     }
@@ -127,6 +291,10 @@ impl Write for CStream {
 /// * `seek` - a SeekCallback to seek in the stream
 /// * `write` - a WriteCallback to write to the stream
 ///
+/// Callbacks for operations the stream is not used for may be NULL, such as the write
+/// and flush callbacks of a stream that is only read. Attempting an operation without a
+/// callback returns an error.
+///
 /// # Safety
 /// The context must remain valid for the lifetime of the C2paStream
 /// The resulting C2paStream must be released by calling c2pa_release_stream
@@ -134,16 +302,306 @@ impl Write for CStream {
 #[no_mangle]
 pub unsafe extern "C" fn c2pa_create_stream(
     context: *mut StreamContext,
-    reader: ReadCallback,
-    seeker: SeekCallback,
-    writer: WriteCallback,
-    flusher: FlushCallback,
+    reader: Option<ReadCallback>,
+    seeker: Option<SeekCallback>,
+    writer: Option<WriteCallback>,
+    flusher: Option<FlushCallback>,
 ) -> *mut CStream {
     Box::into_raw(Box::new(CStream::new(
         context, reader, seeker, writer, flusher,
     )))
 }
 
+// Memory streams use a Cursor as the context, over caller-owned bytes when read-only
+// or over a Vec the stream owns when writable.
+type MemoryReader = Cursor<&'static [u8]>;
+type MemoryWriter = Cursor<Vec<u8>>;
+
+unsafe fn memory_context<'a, T>(context: *const StreamContext) -> &'a mut Cursor<T> {
+    &mut *(context as *mut Cursor<T>)
+}
+
+unsafe extern "C" fn memory_read<T: AsRef<[u8]>>(
+    context: *const StreamContext,
+    data: *mut u8,
+    len: usize,
+) -> isize {
+    let buf = std::slice::from_raw_parts_mut(data, len);
+    memory_context::<T>(context)
+        .read(buf)
+        .map_or(-1, |n| n as isize)
+}
+
+unsafe extern "C" fn memory_seek<T: AsRef<[u8]>>(
+    context: *const StreamContext,
+    offset: i64,
+    mode: c_int,
+) -> i64 {
+    let from = match mode {
+        0 if offset >= 0 => SeekFrom::Start(offset as u64),
+        1 => SeekFrom::Current(offset),
+        2 => SeekFrom::End(offset),
+        _ => return -1,
+    };
+    memory_context::<T>(context)
+        .seek(from)
+        .map_or(-1, |pos| pos as i64)
+}
+
+unsafe extern "C" fn memory_write(
+    context: *const StreamContext,
+    data: *const u8,
+    len: usize,
+) -> isize {
+    let buf = std::slice::from_raw_parts(data, len);
+    memory_context::<Vec<u8>>(context)
+        .write(buf)
+        .map_or(-1, |n| n as isize)
+}
+
+unsafe extern "C" fn memory_free<T>(context: *mut StreamContext) {
+    drop(Box::from_raw(context as *mut Cursor<T>));
+}
+
+/// Creates a new C2paStream from context with callbacks, seeking with 64 bit offsets
+///
+/// Use this rather than c2pa_create_stream for assets that may be over 2GB, since
+/// long offsets are only 32 bits on some platforms, such as Windows.
+/// c2pa_has_feature("stream_seek64") reports that this is available.
+///
+/// # Arguments
+/// * `context` - a pointer to a StreamContext
+/// * `read` - a ReadCallback to read from the stream
+/// * `seek` - a SeekCallback64 to seek in the stream
+/// * `write` - a WriteCallback to write to the stream
+///
+/// As with c2pa_create_stream, callbacks for operations the stream is not used for may be NULL.
+///
+/// # Safety
+/// The context must remain valid for the lifetime of the C2paStream
+/// The resulting C2paStream must be released by calling c2pa_release_stream
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_create_stream64(
+    context: *mut StreamContext,
+    reader: Option<ReadCallback>,
+    seeker: Option<SeekCallback64>,
+    writer: Option<WriteCallback>,
+    flusher: Option<FlushCallback>,
+) -> *mut CStream {
+    Box::into_raw(Box::new(CStream::new64(
+        context, reader, seeker, writer, flusher,
+    )))
+}
+
+/// Creates a read-only C2paStream over a caller-owned buffer
+///
+/// The bytes are read in place, so the host needs no stream callbacks and makes no copy.
+///
+/// # Arguments
+/// * `data` - a pointer to the bytes of the stream
+/// * `len` - the number of bytes
+///
+/// # Safety
+/// The bytes must remain valid and unchanged for the lifetime of the C2paStream
+/// The resulting C2paStream must be released by calling c2pa_release_stream
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_from_memory(data: *const u8, len: usize) -> *mut CStream {
+    if data.is_null() {
+        Error::NullParameter("data".to_string()).set_last();
+        return std::ptr::null_mut();
+    }
+    let context: Box<MemoryReader> = Box::new(Cursor::new(std::slice::from_raw_parts(data, len)));
+    let mut stream = CStream::new64(
+        Box::into_raw(context) as *mut StreamContext,
+        Some(memory_read::<&[u8]>),
+        Some(memory_seek::<&[u8]>),
+        None,
+        None,
+    );
+    stream.free = Some(memory_free::<&[u8]>);
+    stream.memory = Some(Memory::Reader);
+    Box::into_raw(Box::new(stream))
+}
+
+/// Creates a readable and writable C2paStream over memory the library manages
+///
+/// The memory grows as the stream is written, such as by c2pa_builder_sign, and the
+/// bytes written can be retrieved with c2pa_stream_memory_writer_data.
+///
+/// # Safety
+/// The resulting C2paStream must be released by calling c2pa_release_stream
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_memory_writer_create() -> *mut CStream {
+    let context: Box<MemoryWriter> = Box::new(Cursor::new(Vec::new()));
+    let mut stream = CStream::new64(
+        Box::into_raw(context) as *mut StreamContext,
+        Some(memory_read::<Vec<u8>>),
+        Some(memory_seek::<Vec<u8>>),
+        Some(memory_write),
+        None,
+    );
+    stream.free = Some(memory_free::<Vec<u8>>);
+    stream.memory = Some(Memory::Writer);
+    Box::into_raw(Box::new(stream))
+}
+
+/// Gets the bytes written to a C2paStream created by c2pa_stream_memory_writer_create
+///
+/// # Arguments
+/// * `stream` - a pointer to a C2paStream created by c2pa_stream_memory_writer_create
+/// * `data` - set to a pointer to the bytes, which the stream still owns
+/// * `len` - set to the number of bytes
+///
+/// # Errors
+/// Returns -1 if a parameter is NULL, the stream was not created by
+/// c2pa_stream_memory_writer_create, or buffered writes can't be flushed, otherwise returns 0
+///
+/// # Safety
+/// The stream must be a valid C2paStream
+/// The bytes are only valid until the stream is written to again or released
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_memory_writer_data(
+    stream: *mut CStream,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> c_int {
+    null_check_int!(stream);
+    null_check_int!(data);
+    null_check_int!(len);
+    if (*stream).memory != Some(Memory::Writer) {
+        Error::NotSupported(
+            "the stream was not created by c2pa_stream_memory_writer_create".to_string(),
+        )
+        .set_last();
+        return -1;
+    }
+    if let Some(Err(err)) = (*stream).buffered(|buffer, stream| buffer.flush(stream)) {
+        Error::Io(err.to_string()).set_last();
+        return -1;
+    }
+    let bytes = memory_context::<Vec<u8>>((*stream).context).get_ref();
+    *data = bytes.as_ptr();
+    *len = bytes.len();
+    0
+}
+
+/// Reports why a stream callback failed, before the callback returns a negative result
+///
+/// The error is returned by the library call that invoked the callback, and its message
+/// can be retrieved by calling c2pa_error. Callbacks that don't call this report their
+/// errors through errno.
+///
+/// # Arguments
+/// * `error` - the cause of the failure
+/// * `message` - a NULL-terminated description of the failure, or NULL
+///
+/// # Safety
+/// Must be called on the thread running the callback
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_set_error(error: C2paStreamError, message: *const c_char) {
+    let kind = ErrorKind::from(error);
+    let message = match message.is_null() {
+        true => kind.to_string(),
+        false => std::ffi::CStr::from_ptr(message)
+            .to_string_lossy()
+            .into_owned(),
+    };
+    set_stream_error(std::io::Error::new(kind, message));
+}
+
+/// Sets a callback that frees the context of a stream when the stream is released
+///
+/// The context of a stream is borrowed unless a free callback is set, so the host keeps
+/// ownership of contexts it did not allocate for the stream alone, such as C++ objects.
+///
+/// # Arguments
+/// * `stream` - a pointer to a C2paStream created by c2pa_create_stream or c2pa_create_stream64
+/// * `free` - a FreeCallback called with the context by c2pa_release_stream, or NULL
+///
+/// # Errors
+/// Returns -1 if the stream is NULL or a memory stream, whose context the library frees,
+/// otherwise returns 0
+///
+/// # Safety
+/// The stream must be a valid C2paStream
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_set_free(
+    stream: *mut CStream,
+    free: Option<FreeCallback>,
+) -> c_int {
+    null_check_int!(stream);
+    if (*stream).memory.is_some() {
+        Error::NotSupported("the library frees the context of a memory stream".to_string())
+            .set_last();
+        return -1;
+    }
+    (*stream).free = free;
+    0
+}
+
+/// Sets the size of a buffer that coalesces reads and writes into larger blocks
+///
+/// The library reads and writes streams in many small pieces, which is slow when each
+/// callback goes over a network or is marshaled into a managed language. With a buffer,
+/// reads fetch whole blocks ahead and writes are held until a block fills, the stream is
+/// flushed, or it is read or seeked. Streams are unbuffered by default.
+///
+/// # Arguments
+/// * `stream` - a pointer to a C2paStream
+/// * `size` - the size of the buffer in bytes, or 0 to stop buffering
+///
+/// # Errors
+/// Returns -1 if the stream is NULL or the writes held by a previous buffer can't be
+/// flushed, otherwise returns 0
+///
+/// # Safety
+/// The stream must be a valid C2paStream
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_set_buffer_size(stream: *mut CStream, size: usize) -> c_int {
+    null_check_int!(stream);
+    let stream = &mut *stream;
+    if let Some(Err(err)) = stream.buffered(|buffer, stream| buffer.sync(stream)) {
+        Error::Io(err.to_string()).set_last();
+        return -1;
+    }
+    stream.buffer = (size > 0).then(|| Box::new(StreamBuffer::new(size)));
+    0
+}
+
+/// Writes the bytes held by the buffer of a stream and calls its flush callback
+///
+/// The signing functions flush their destination, so this is only needed after writing
+/// to a buffered stream in other ways. Releasing a stream also flushes it, but can't
+/// report errors.
+///
+/// # Arguments
+/// * `stream` - a pointer to a C2paStream
+///
+/// # Errors
+/// Returns -1 if the stream is NULL or can't be flushed, otherwise returns 0
+///
+/// # Safety
+/// The stream must be a valid C2paStream
+///
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_stream_flush(stream: *mut CStream) -> c_int {
+    null_check_int!(stream);
+    match (*stream).flush() {
+        Ok(()) => 0,
+        Err(err) => {
+            Error::Io(err.to_string()).set_last();
+            -1
+        }
+    }
+}
+
 /// Releases a CStream allocated by Rust
 ///
 /// # Safety
@@ -0,0 +1,117 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Describes the version and capabilities this library was built with.
+
+use serde::Serialize;
+
+/// The crypto backend used for validation and built-in signers.
+pub const CRYPTO_BACKEND: &str = "openssl";
+
+/// The HTTP client used for TSA, OCSP and remote manifest requests.
+pub const NETWORK: &str = "ureq";
+
+// Features compiled into the library, named after the c2pa features where they exist.
+const FEATURES: &[&str] = &[
+    "add_thumbnails",
+    "cose_verify",
+    "fetch_remote_manifests",
+    "file_io",
+    "ocsp_cache",
+    #[cfg(feature = "pkcs11")]
+    "pkcs11",
+    "stream_seek64",
+    "trust_refresh",
+];
+
+/// Version and build information for this library.
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    /// The version of this library.
+    pub version: &'static str,
+    /// The version of the c2pa-rs SDK.
+    pub c2pa_version: &'static str,
+    /// The supported asset formats as extensions and mime types.
+    pub formats: Vec<String>,
+    /// The crypto backend.
+    pub crypto_backend: &'static str,
+    /// The HTTP client, if network access is compiled in.
+    pub network: Option<&'static str>,
+    /// The compiled features.
+    pub features: Vec<&'static str>,
+}
+
+/// Returns the supported asset formats, sorted.
+pub fn supported_formats() -> Vec<String> {
+    let mut formats = c2pa::jumbf_io::get_supported_types();
+    formats.sort();
+    formats
+}
+
+/// Returns the mime types of the formats manifests can be read from, or signed into, sorted.
+pub fn supported_mime_types(for_signing: bool) -> Vec<String> {
+    supported_formats()
+        .into_iter()
+        .filter(|format| format.contains('/'))
+        .filter(|format| !for_signing || can_sign(format))
+        .collect()
+}
+
+/// Returns the version and build information for this library.
+pub fn version_info() -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        c2pa_version: c2pa::VERSION,
+        formats: supported_formats(),
+        crypto_backend: CRYPTO_BACKEND,
+        network: Some(NETWORK),
+        features: FEATURES.to_vec(),
+    }
+}
+
+/// Returns true if manifests can be read from the format, given as an extension or mime type.
+pub fn can_read(format: &str) -> bool {
+    let format = format.to_lowercase();
+    c2pa::jumbf_io::get_supported_types().contains(&format)
+}
+
+/// Returns true if manifests can be embedded in the format, given as an extension or mime type.
+pub fn can_sign(format: &str) -> bool {
+    // every format handler compiled into this build can also write manifests
+    can_read(format)
+}
+
+/// Returns true if the named feature was compiled into the library.
+pub fn has_feature(name: &str) -> bool {
+    FEATURES.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        assert!(can_read("image/jpeg"));
+        assert!(can_read("JPG"));
+        assert!(can_sign("png"));
+        assert!(!can_read("application/x-unknown"));
+        let mime_types = supported_mime_types(true);
+        assert!(mime_types.contains(&"image/jpeg".to_string()));
+        assert!(mime_types.iter().all(|mime_type| can_sign(mime_type)));
+        assert!(!supported_mime_types(false).contains(&"jpg".to_string()));
+        assert!(has_feature("file_io"));
+        assert!(has_feature("stream_seek64"));
+        assert!(!has_feature("unknown"));
+    }
+}
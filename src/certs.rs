@@ -0,0 +1,359 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Decodes the certificate chains of signed manifests for inspection.
+
+use std::io::Cursor;
+
+use c2pa::{hash_stream_by_alg, Reader};
+use c2pa_crypto::base64;
+use serde::Serialize;
+use x509_parser::{
+    extensions::{ExtendedKeyUsage, GeneralName, KeyUsage},
+    objects::{oid2sn, oid_registry},
+    oid_registry::Oid,
+    pem::Pem,
+    prelude::*,
+};
+
+use crate::{json_api::to_hex, Error, Result};
+
+/// An extension present in a certificate.
+#[derive(Debug, Serialize)]
+pub struct ExtensionDetails {
+    /// The dotted OID of the extension.
+    pub oid: String,
+    /// The short name of the extension, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub critical: bool,
+}
+
+/// The decoded fields of one certificate in a chain.
+#[derive(Debug, Serialize)]
+pub struct CertificateDetails {
+    pub subject: String,
+    pub issuer: String,
+    /// The serial number as colon separated hex bytes.
+    pub serial_number: String,
+    /// The start of the validity period, in RFC 2822 format.
+    pub not_before: String,
+    /// The end of the validity period, in RFC 2822 format.
+    pub not_after: String,
+    pub signature_algorithm: String,
+    pub public_key_algorithm: String,
+    pub is_ca: bool,
+    /// The key usages, such as "digitalSignature".
+    pub key_usage: Vec<String>,
+    /// The extended key usages, as names for well known usages and OIDs otherwise.
+    pub extended_key_usage: Vec<String>,
+    /// The subject alternative names, such as "DNSName(example.com)".
+    pub subject_alt_names: Vec<String>,
+    pub extensions: Vec<ExtensionDetails>,
+    /// The SHA-256 fingerprint of the DER encoding, as lowercase hex.
+    pub sha256_fingerprint: String,
+}
+
+fn oid_name(oid: &Oid) -> Option<String> {
+    oid2sn(oid, oid_registry()).ok().map(String::from)
+}
+
+fn key_usages(usage: &KeyUsage) -> Vec<String> {
+    let known = [
+        (usage.digital_signature(), "digitalSignature"),
+        (usage.non_repudiation(), "nonRepudiation"),
+        (usage.key_encipherment(), "keyEncipherment"),
+        (usage.data_encipherment(), "dataEncipherment"),
+        (usage.key_agreement(), "keyAgreement"),
+        (usage.key_cert_sign(), "keyCertSign"),
+        (usage.crl_sign(), "cRLSign"),
+        (usage.encipher_only(), "encipherOnly"),
+        (usage.decipher_only(), "decipherOnly"),
+    ];
+    known
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn extended_key_usages(eku: &ExtendedKeyUsage) -> Vec<String> {
+    let known = [
+        (eku.any, "any"),
+        (eku.server_auth, "serverAuth"),
+        (eku.client_auth, "clientAuth"),
+        (eku.code_signing, "codeSigning"),
+        (eku.email_protection, "emailProtection"),
+        (eku.time_stamping, "timeStamping"),
+        (eku.ocsp_signing, "OCSPSigning"),
+    ];
+    known
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, name)| name.to_string())
+        .chain(eku.other.iter().map(|oid| oid.to_id_string()))
+        .collect()
+}
+
+fn general_name(name: &GeneralName) -> String {
+    match name {
+        GeneralName::IPAddress(ip) => match ip.len() {
+            4 => format!(
+                "IPAddress({})",
+                std::net::Ipv4Addr::from([ip[0], ip[1], ip[2], ip[3]])
+            ),
+            16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(ip);
+                format!("IPAddress({})", std::net::Ipv6Addr::from(octets))
+            }
+            _ => name.to_string(),
+        },
+        _ => name.to_string(),
+    }
+}
+
+impl CertificateDetails {
+    /// Decodes a DER encoded certificate.
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let (_, cert) = X509Certificate::from_der(der)
+            .map_err(|e| Error::Signature(format!("invalid certificate: {e}")))?;
+        let invalid =
+            |e: X509Error| Error::Signature(format!("invalid certificate extension: {e}"));
+
+        let key_usage = cert
+            .key_usage()
+            .map_err(invalid)?
+            .map(|ext| key_usages(ext.value))
+            .unwrap_or_default();
+        let extended_key_usage = cert
+            .extended_key_usage()
+            .map_err(invalid)?
+            .map(|ext| extended_key_usages(ext.value))
+            .unwrap_or_default();
+        let subject_alt_names = cert
+            .subject_alternative_name()
+            .map_err(invalid)?
+            .map(|ext| ext.value.general_names.iter().map(general_name).collect())
+            .unwrap_or_default();
+        let extensions = cert
+            .extensions()
+            .iter()
+            .map(|ext| ExtensionDetails {
+                oid: ext.oid.to_id_string(),
+                name: oid_name(&ext.oid),
+                critical: ext.critical,
+            })
+            .collect();
+        let fingerprint = hash_stream_by_alg("sha256", &mut Cursor::new(der), None, true)
+            .map_err(Error::from_c2pa_error)?;
+
+        let signature_algorithm = &cert.signature_algorithm.algorithm;
+        let public_key_algorithm = &cert.public_key().algorithm.algorithm;
+        let validity = cert.validity();
+        Ok(Self {
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            serial_number: cert.raw_serial_as_string(),
+            not_before: validity.not_before.to_rfc2822().map_err(Error::Signature)?,
+            not_after: validity.not_after.to_rfc2822().map_err(Error::Signature)?,
+            signature_algorithm: oid_name(signature_algorithm)
+                .unwrap_or_else(|| signature_algorithm.to_id_string()),
+            public_key_algorithm: oid_name(public_key_algorithm)
+                .unwrap_or_else(|| public_key_algorithm.to_id_string()),
+            is_ca: cert.is_ca(),
+            key_usage,
+            extended_key_usage,
+            subject_alt_names,
+            extensions,
+            sha256_fingerprint: to_hex(&fingerprint),
+        })
+    }
+}
+
+/// Returns the DER encoded certificates in a PEM string, in order.
+pub(crate) fn pem_certs(pem: &str) -> Result<Vec<Vec<u8>>> {
+    let certs = Pem::iter_from_buffer(pem.as_bytes())
+        .map(|pem| {
+            pem.map(|pem| pem.contents)
+                .map_err(|e| Error::Signature(format!("invalid certificate PEM: {e}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(Error::Signature("no certificates found".to_string()));
+    }
+    Ok(certs)
+}
+
+// Encodes DER as a PEM block with the given label.
+fn pem_block(label: &str, der: &[u8]) -> String {
+    let encoded = base64::encode(der);
+    let mut pem = format!("-----BEGIN {label}-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {label}-----\n"));
+    pem
+}
+
+/// Converts concatenated DER encoded certificates to a PEM certificate chain, in order.
+pub(crate) fn der_certs_pem(der: &[u8]) -> Result<String> {
+    let mut pem = String::new();
+    let mut rest = der;
+    while !rest.is_empty() {
+        let (remaining, _) = X509Certificate::from_der(rest)
+            .map_err(|e| Error::Signature(format!("invalid DER certificate: {e}")))?;
+        pem.push_str(&pem_block(
+            "CERTIFICATE",
+            &rest[..rest.len() - remaining.len()],
+        ));
+        rest = remaining;
+    }
+    if pem.is_empty() {
+        return Err(Error::Signature("no certificates found".to_string()));
+    }
+    Ok(pem)
+}
+
+/// Converts a DER encoded PKCS#8 private key to PEM.
+pub(crate) fn pkcs8_pem(der: &[u8]) -> String {
+    pem_block("PRIVATE KEY", der)
+}
+
+/// Decodes every certificate in a PEM certificate chain, starting with the signing certificate.
+pub fn certificate_details(pem: &str) -> Result<Vec<CertificateDetails>> {
+    pem_certs(pem)?
+        .iter()
+        .map(|der| CertificateDetails::from_der(der))
+        .collect()
+}
+
+/// Decodes the certificate chain that signed a manifest in the Reader.
+///
+/// The active manifest is used if no label is given.
+pub fn reader_certificates(
+    reader: &Reader,
+    label: Option<&str>,
+) -> Result<Vec<CertificateDetails>> {
+    certificate_details(&reader_cert_chain(reader, label)?)
+}
+
+/// Returns the PEM certificate chain that signed a manifest in the Reader, or the active manifest.
+///
+/// The signing certificate comes first.
+pub fn reader_cert_chain(reader: &Reader, label: Option<&str>) -> Result<String> {
+    let manifest = match label {
+        Some(label) => reader.get_manifest(label),
+        None => reader.active_manifest(),
+    }
+    .ok_or_else(|| Error::Other("manifest not found".to_string()))?;
+    let signature_info = manifest
+        .signature_info()
+        .ok_or_else(|| Error::Signature("manifest has no signature".to_string()))?;
+    Ok(signature_info.cert_chain().to_string())
+}
+
+/// Returns the certificate chain that signed a manifest in the Reader as a JSON array.
+pub fn reader_certificates_json(reader: &Reader, label: Option<&str>) -> Result<String> {
+    let certs = reader_certificates(reader, label)?;
+    serde_json::to_string_pretty(&certs).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// The signature of a manifest, with the fields compliance logs usually record.
+#[derive(Debug, Default, Serialize)]
+pub struct SignatureDetails {
+    /// The signing algorithm, such as "es256".
+    pub alg: Option<String>,
+    /// The organization that signed the manifest, as reported by the SDK.
+    pub issuer: Option<String>,
+    /// The common name of the signing certificate.
+    pub common_name: Option<String>,
+    /// The serial number of the signing certificate, as reported by the SDK.
+    pub cert_serial_number: Option<String>,
+    /// The signing time from the time-stamp authority, in RFC 3339 format.
+    /// Claims carry no time of their own, so this is the only signing time.
+    pub time: Option<String>,
+}
+
+/// Returns the signature details of a manifest in the Reader, or of the active manifest.
+pub fn signature_details(reader: &Reader, label: Option<&str>) -> Result<SignatureDetails> {
+    let manifest = match label {
+        Some(label) => reader.get_manifest(label),
+        None => reader.active_manifest(),
+    }
+    .ok_or_else(|| Error::Other("manifest not found".to_string()))?;
+    let signature_info = manifest
+        .signature_info()
+        .ok_or_else(|| Error::Signature("manifest has no signature".to_string()))?;
+    let common_name = pem_certs(signature_info.cert_chain())
+        .ok()
+        .and_then(|certs| certs.into_iter().next())
+        .and_then(|der| {
+            let (_, cert) = X509Certificate::from_der(&der).ok()?;
+            let common_name = cert.subject().iter_common_name().next()?;
+            common_name.as_str().ok().map(String::from)
+        });
+    Ok(SignatureDetails {
+        alg: signature_info.alg.map(|alg| alg.to_string()),
+        issuer: signature_info.issuer.clone(),
+        common_name,
+        cert_serial_number: signature_info.cert_serial_number.clone(),
+        time: signature_info.time.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERTS: &str = include_str!("../tests/fixtures/es256_certs.pem");
+
+    #[test]
+    fn test_certificate_details() {
+        let certs = certificate_details(CERTS).unwrap();
+        assert!(certs.len() > 1);
+        let leaf = &certs[0];
+        assert!(!leaf.is_ca);
+        assert!(leaf
+            .key_usage
+            .iter()
+            .any(|usage| usage == "digitalSignature"));
+        assert_eq!(leaf.sha256_fingerprint.len(), 64);
+        assert!(certs[1..].iter().all(|cert| cert.is_ca));
+        assert_eq!(leaf.issuer, certs[1].subject);
+        assert!(certificate_details("not a certificate").is_err());
+
+        let mut stream = std::fs::File::open("tests/fixtures/C.jpg").unwrap();
+        let reader = Reader::from_stream("image/jpeg", &mut stream).unwrap();
+        let chain = reader_certificates(&reader, None).unwrap();
+        assert!(!chain.is_empty());
+        assert!(reader_certificates(&reader, Some("missing")).is_err());
+        let pem = reader_cert_chain(&reader, None).unwrap();
+        assert_eq!(certificate_details(&pem).unwrap().len(), chain.len());
+
+        let details = signature_details(&reader, None).unwrap();
+        assert!(details.alg.is_some());
+        assert!(details.common_name.is_some());
+        assert!(details.cert_serial_number.is_some());
+        assert!(signature_details(&reader, Some("missing")).is_err());
+    }
+    #[test]
+    fn test_der_certs_pem() {
+        let der = pem_certs(CERTS).unwrap().concat();
+        let pem = der_certs_pem(&der).unwrap();
+        assert_eq!(pem_certs(&pem).unwrap(), pem_certs(CERTS).unwrap());
+        assert!(der_certs_pem(b"").is_err());
+        assert!(der_certs_pem(&der[..der.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Compact provenance identifiers for indexing assets without storing full reports.
+
+use std::io::{Read, Seek};
+
+use c2pa::{assertions::labels, Error as C2paError};
+use fast_xml::{events::Event, Reader as XmlReader};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    json_api::{manifest_store_report, to_hex},
+    remote, Error, Result,
+};
+
+/// A hard binding assertion of a manifest and the digest it records.
+#[derive(Debug, Serialize)]
+pub struct HardBinding {
+    /// The assertion label, such as "c2pa.hash.data".
+    pub label: String,
+    pub alg: String,
+    /// The hex digest, if the assertion records a single digest for the asset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+/// The provenance identifiers of an asset.
+#[derive(Debug, Default, Serialize)]
+pub struct ContentId {
+    /// The instance ID of the active manifest, or from the asset's XMP if it has no manifest.
+    pub instance_id: Option<String>,
+    /// The document ID from the asset's XMP.
+    pub document_id: Option<String>,
+    pub active_manifest: Option<String>,
+    /// The hard bindings of the active manifest, if it is embedded in the asset.
+    pub hard_bindings: Vec<HardBinding>,
+    /// The remote manifest URL from the asset's XMP.
+    pub remote_url: Option<String>,
+}
+
+const XMP_START: &[u8] = b"<x:xmpmeta";
+const XMP_END: &[u8] = b"</x:xmpmeta>";
+// the largest XMP packet read, well beyond what assets carry
+const MAX_XMP_SIZE: usize = 16 * 1024 * 1024;
+
+fn find(data: &[u8], pattern: &[u8]) -> Option<usize> {
+    data.windows(pattern.len()).position(|w| w == pattern)
+}
+
+// Returns the first XMP packet in an asset, reading no further than its end.
+fn read_xmp(stream: &mut impl Read) -> Option<String> {
+    let mut packet = Vec::new();
+    let mut chunk = vec![0; 64 * 1024];
+    let mut started = false;
+    loop {
+        let len = stream.read(&mut chunk).ok()?;
+        if len == 0 {
+            return None;
+        }
+        // the end may straddle the previous chunk
+        let searched = packet.len().saturating_sub(XMP_END.len());
+        packet.extend_from_slice(&chunk[..len]);
+        if !started {
+            match find(&packet, XMP_START) {
+                Some(start) => {
+                    packet.drain(..start);
+                    started = true;
+                }
+                None => {
+                    packet.drain(..packet.len().saturating_sub(XMP_START.len() - 1));
+                    continue;
+                }
+            }
+        }
+        let from = searched.min(packet.len());
+        if let Some(end) = find(&packet[from..], XMP_END) {
+            packet.truncate(from + end + XMP_END.len());
+            return String::from_utf8(packet).ok();
+        }
+        if packet.len() > MAX_XMP_SIZE {
+            return None;
+        }
+    }
+}
+
+// The identifiers of an asset's XMP.
+#[derive(Debug, Default, PartialEq)]
+struct XmpIds {
+    document_id: Option<String>,
+    instance_id: Option<String>,
+    provenance: Option<String>,
+}
+
+impl XmpIds {
+    // Parses an XMP packet, finding the properties written either as attributes or as elements.
+    fn parse(xmp: &str) -> Self {
+        let mut ids = Self::default();
+        let mut reader = XmlReader::from_str(xmp);
+        reader.trim_text(true);
+        let mut buf = Vec::new();
+        // the element whose text is read next
+        let mut element = None;
+        loop {
+            match reader.read_event(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    for attribute in e.attributes().flatten() {
+                        if let Ok(value) = attribute.unescape_and_decode_value(&reader) {
+                            ids.set(attribute.key, value);
+                        }
+                    }
+                    element = Some(e.name().to_vec());
+                }
+                Ok(Event::Text(text)) => {
+                    if let (Some(name), Ok(value)) =
+                        (element.take(), text.unescape_and_decode(&reader))
+                    {
+                        ids.set(&name, value);
+                    }
+                }
+                Ok(Event::End(_)) => element = None,
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        ids
+    }
+
+    fn set(&mut self, name: &[u8], value: String) {
+        let property = match name {
+            b"xmpMM:DocumentID" => &mut self.document_id,
+            b"xmpMM:InstanceID" => &mut self.instance_id,
+            b"dcterms:provenance" => &mut self.provenance,
+            _ => return,
+        };
+        let value = value.trim();
+        if property.is_none() && !value.is_empty() {
+            *property = Some(value.to_string());
+        }
+    }
+
+    fn from_stream(stream: &mut impl Read) -> Self {
+        read_xmp(stream).map_or_else(Self::default, |xmp| Self::parse(&xmp))
+    }
+}
+
+/// Returns the remote manifest URL from the XMP of an asset, if it has one.
+pub(crate) fn remote_url(stream: &mut impl Read) -> Option<String> {
+    XmpIds::from_stream(stream).provenance
+}
+
+fn hard_bindings(report: &Value, label: &str) -> Vec<HardBinding> {
+    let manifest = &report["manifests"][label];
+    let claim_alg = manifest["claim"]["alg"].as_str().unwrap_or("sha256");
+    let Some(assertions) = manifest["assertion_store"].as_object() else {
+        return Vec::new();
+    };
+    let mut bindings: Vec<HardBinding> = assertions
+        .iter()
+        .filter(|(label, _)| {
+            [labels::DATA_HASH, labels::BOX_HASH, labels::BMFF_HASH]
+                .iter()
+                .any(|prefix| label.starts_with(prefix))
+        })
+        .map(|(label, value)| {
+            let hash = value["hash"].as_array().map(|bytes| {
+                let bytes: Vec<u8> = bytes
+                    .iter()
+                    .filter_map(|b| b.as_u64().map(|b| b as u8))
+                    .collect();
+                to_hex(&bytes)
+            });
+            HardBinding {
+                label: label.clone(),
+                alg: value["alg"].as_str().unwrap_or(claim_alg).to_string(),
+                hash,
+            }
+        })
+        .collect();
+    bindings.sort_by(|a, b| a.label.cmp(&b.label));
+    bindings
+}
+
+/// Returns the provenance identifiers of an asset.
+///
+/// Assets without a manifest store return the identifiers found in their XMP,
+/// as do assets whose remote manifest is not fetched by this library.
+pub fn content_id(format: &str, stream: &mut (impl Read + Seek + Send)) -> Result<ContentId> {
+    let io_error = |e: std::io::Error| Error::Io(e.to_string());
+    stream.rewind().map_err(io_error)?;
+    let xmp = XmpIds::from_stream(stream);
+    stream.rewind().map_err(io_error)?;
+
+    let mut id = ContentId {
+        document_id: xmp.document_id,
+        remote_url: xmp.provenance,
+        ..Default::default()
+    };
+    match remote::read_with_source(format, &mut *stream) {
+        Ok((reader, source)) => {
+            if let Some(manifest) = reader.active_manifest() {
+                id.instance_id = Some(manifest.instance_id().to_string());
+            }
+            if let Some(label) = reader.active_label() {
+                let embedded = source.manifest_data.filter(|_| source.embedded);
+                // the low level report includes the hash assertions hidden by the Reader
+                if let Some(report) = embedded.as_deref().and_then(manifest_store_report) {
+                    id.hard_bindings = hard_bindings(&report, label);
+                }
+                id.active_manifest = Some(label.to_string());
+            }
+        }
+        Err(C2paError::JumbfNotFound) | Err(C2paError::RemoteManifestUrl(_)) => {}
+        Err(err) => return Err(Error::from_c2pa_error(err)),
+    }
+    if id.instance_id.is_none() {
+        id.instance_id = xmp.instance_id;
+    }
+    Ok(id)
+}
+
+/// Returns the provenance identifiers of an asset as a JSON string.
+pub fn content_id_json(format: &str, stream: &mut (impl Read + Seek + Send)) -> Result<String> {
+    let id = content_id(format, stream)?;
+    serde_json::to_string_pretty(&id).map_err(|e| Error::Json(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_id() {
+        let mut stream = std::fs::File::open("tests/fixtures/C.jpg").unwrap();
+        let id = content_id("image/jpeg", &mut stream).unwrap();
+        assert!(id.instance_id.is_some());
+        assert!(id.active_manifest.is_some());
+        let binding = &id.hard_bindings[0];
+        assert_eq!(binding.label, labels::DATA_HASH);
+        assert_eq!(binding.alg, "sha256");
+        assert_eq!(binding.hash.as_ref().unwrap().len(), 64);
+
+        let mut stream = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+        let id = content_id("image/jpeg", &mut stream).unwrap();
+        assert!(id.active_manifest.is_none() && id.hard_bindings.is_empty());
+
+        let xmp = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF><rdf:Description xmpMM:DocumentID="xmp.did:1234"><dcterms:provenance>https://example.com/a.c2pa?a=1&amp;b=2</dcterms:provenance></rdf:Description></rdf:RDF></x:xmpmeta>"#;
+        let ids = XmpIds::parse(xmp);
+        assert_eq!(
+            ids,
+            XmpIds {
+                document_id: Some("xmp.did:1234".to_string()),
+                instance_id: None,
+                provenance: Some("https://example.com/a.c2pa?a=1&b=2".to_string()),
+            }
+        );
+        // the packet is found inside other data, even across the chunks it is read in
+        let mut asset = vec![0u8; 64 * 1024 - 4];
+        asset.extend_from_slice(xmp.as_bytes());
+        asset.extend_from_slice(&[0u8; 100]);
+        assert_eq!(XmpIds::from_stream(&mut asset.as_slice()), ids);
+        assert_eq!(
+            XmpIds::from_stream(&mut [0u8; 100].as_slice()),
+            XmpIds::default()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Verifies standalone COSE_Sign1 signatures such as detached claim signatures.
+
+use c2pa::SigningAlg;
+use c2pa_crypto::{base64, raw_signature::validator_for_signing_alg};
+use coset::{
+    cbor::value::Value, iana, CborSerializable, CoseSign1, Header, Label,
+    RegisteredLabelWithPrivate, TaggedCborSerializable,
+};
+use serde::Serialize;
+use x509_parser::prelude::*;
+
+use crate::{certs::pem_certs, trust::TrustList, Error, Result};
+
+/// The result of verifying a COSE_Sign1 signature.
+#[derive(Debug, Serialize)]
+pub struct CoseVerification {
+    /// The signing algorithm.
+    pub alg: String,
+    /// The base64 encoded payload that was signed.
+    pub payload: String,
+    /// The subject of the signing certificate.
+    pub subject: String,
+    /// The common name of the signing certificate, if any.
+    pub common_name: Option<String>,
+    /// The issuer of the signing certificate.
+    pub issuer: String,
+    /// The serial number of the signing certificate.
+    pub serial_number: String,
+    /// Whether the certificate chain is on the configured allowed list or chains to a configured
+    /// trust anchor.
+    pub trusted: bool,
+}
+
+fn signing_alg(sign1: &CoseSign1) -> Result<SigningAlg> {
+    let unsupported = || Error::Signature("unsupported COSE algorithm".to_string());
+    match sign1.protected.header.alg.as_ref() {
+        Some(RegisteredLabelWithPrivate::Assigned(alg)) => match alg {
+            iana::Algorithm::ES256 => Ok(SigningAlg::Es256),
+            iana::Algorithm::ES384 => Ok(SigningAlg::Es384),
+            iana::Algorithm::ES512 => Ok(SigningAlg::Es512),
+            iana::Algorithm::PS256 => Ok(SigningAlg::Ps256),
+            iana::Algorithm::PS384 => Ok(SigningAlg::Ps384),
+            iana::Algorithm::PS512 => Ok(SigningAlg::Ps512),
+            iana::Algorithm::EdDSA => Ok(SigningAlg::Ed25519),
+            _ => Err(unsupported()),
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+// Returns the x5chain certificates from a header.
+fn header_certs(header: &Header) -> Vec<Vec<u8>> {
+    let x5chain = header.rest.iter().find_map(|(label, value)| {
+        (*label == Label::Text("x5chain".to_string())
+            || *label == Label::Int(iana::HeaderParameter::X5Chain as i64))
+        .then_some(value)
+    });
+    match x5chain {
+        Some(Value::Array(certs)) => certs
+            .iter()
+            .filter_map(|cert| match cert {
+                Value::Bytes(der) => Some(der.clone()),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::Bytes(der)) => vec![der.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Verifies a COSE_Sign1 signature.
+///
+/// The payload is required when the signature was made over a detached payload.
+/// If a PEM certificate chain is given, the signature is verified with its first certificate,
+/// which must match the certificate embedded in the signature if there is one.
+/// Otherwise the certificates embedded in the signature are used.
+/// The chain is then checked against the trust settings, and the result reports whether it is
+/// trusted; an untrusted chain is not an error.
+pub fn verify_cose_sign1(
+    cose: &[u8],
+    payload: Option<&[u8]>,
+    certs_pem: Option<&str>,
+) -> Result<CoseVerification> {
+    let (mut verification, chain) = verify_sign1(cose, payload, certs_pem)?;
+    verification.trusted = TrustList::configured()?.trusts(&chain)?;
+    Ok(verification)
+}
+
+// Verifies a COSE_Sign1 signature, also returning the certificate chain it was verified with
+// so the caller can check it against the trust list that applies.
+pub(crate) fn verify_sign1(
+    cose: &[u8],
+    payload: Option<&[u8]>,
+    certs_pem: Option<&str>,
+) -> Result<(CoseVerification, Vec<Vec<u8>>)> {
+    let mut sign1 = <CoseSign1 as TaggedCborSerializable>::from_tagged_slice(cose)
+        .or_else(|_| <CoseSign1 as CborSerializable>::from_slice(cose))
+        .map_err(|e| Error::Signature(format!("invalid COSE_Sign1: {e}")))?;
+    if let Some(payload) = payload {
+        sign1.payload = Some(payload.to_vec());
+    }
+    let payload = sign1
+        .payload
+        .clone()
+        .ok_or_else(|| Error::Signature("missing detached payload".to_string()))?;
+
+    let alg = signing_alg(&sign1)?;
+    let validator = validator_for_signing_alg(alg)
+        .ok_or_else(|| Error::Signature(format!("no validator for {alg}")))?;
+
+    let mut embedded = header_certs(&sign1.protected.header);
+    if embedded.is_empty() {
+        embedded = header_certs(&sign1.unprotected);
+    }
+    let certs = match certs_pem {
+        Some(pem) => {
+            let certs = pem_certs(pem)?;
+            if embedded.first().is_some_and(|leaf| *leaf != certs[0]) {
+                return Err(Error::Signature(
+                    "signing certificate does not match the certificate chain".to_string(),
+                ));
+            }
+            certs
+        }
+        None => embedded,
+    };
+    let leaf = certs
+        .first()
+        .ok_or_else(|| Error::Signature("missing signing certificate".to_string()))?;
+    let (_, cert) = X509Certificate::from_der(leaf)
+        .map_err(|e| Error::Signature(format!("invalid signing certificate: {e}")))?;
+
+    sign1
+        .verify_signature(b"", |sig, data| {
+            validator.validate(sig, data, cert.public_key().raw)
+        })
+        .map_err(|e| Error::Signature(format!("signature verification failed: {e}")))?;
+
+    let verification = CoseVerification {
+        alg: alg.to_string(),
+        payload: base64::encode(&payload),
+        subject: cert.subject().to_string(),
+        common_name: cert
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(String::from),
+        issuer: cert.issuer().to_string(),
+        serial_number: cert.raw_serial_as_string(),
+        trusted: false,
+    };
+    Ok((verification, certs))
+}
+
+/// Verifies a COSE_Sign1 signature and returns the result as a JSON string.
+pub fn verify_cose_sign1_json(
+    cose: &[u8],
+    payload: Option<&[u8]>,
+    certs_pem: Option<&str>,
+) -> Result<String> {
+    let verification = verify_cose_sign1(cose, payload, certs_pem)?;
+    serde_json::to_string_pretty(&verification).map_err(|e| Error::Json(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use c2pa::CallbackSigner;
+    use coset::{CoseSign1Builder, HeaderBuilder};
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    #[test]
+    fn test_verify_cose_sign1() {
+        let certs = std::str::from_utf8(CERTS).unwrap();
+        let chain = pem_certs(certs).unwrap().into_iter().map(Value::Bytes);
+        let protected = HeaderBuilder::new()
+            .algorithm(iana::Algorithm::EdDSA)
+            .value(
+                iana::HeaderParameter::X5Chain as i64,
+                Value::Array(chain.collect()),
+            )
+            .build();
+        let claim = b"detached claim bytes";
+        let cose = CoseSign1Builder::new()
+            .protected(protected)
+            .create_detached_signature(claim, b"", |tbs| {
+                CallbackSigner::ed25519_sign(tbs, PRIVATE_KEY).unwrap()
+            })
+            .build()
+            .to_tagged_vec()
+            .unwrap();
+
+        let verification = verify_cose_sign1(&cose, Some(claim), Some(certs)).unwrap();
+        assert_eq!(verification.alg, "ed25519");
+        assert_eq!(verification.payload, base64::encode(claim));
+        // no trust anchors are configured
+        assert!(!verification.trusted);
+        let (_, chain) = verify_sign1(&cose, Some(claim), None).unwrap();
+        let anchored = TrustList::new(vec![chain[1].clone()], &[]);
+        assert!(anchored.trusts(&chain).unwrap());
+
+        assert!(verify_cose_sign1(&cose, Some(b"tampered"), None).is_err());
+        assert!(verify_cose_sign1(&cose, None, None).is_err());
+    }
+}
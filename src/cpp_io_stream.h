@@ -60,7 +60,7 @@ namespace c2pa
     private:
         static size_t reader(StreamContext *context, void *buffer, size_t size);
         static int writer(StreamContext *context, const void *buffer, int size);
-        static long seeker(StreamContext *context, long int offset, int whence);
+        static int64_t seeker(StreamContext *context, int64_t offset, int whence);
         static int flusher(StreamContext *context);
 
         friend class Reader;
@@ -79,7 +79,7 @@ namespace c2pa
     private:
         static size_t reader(StreamContext *context, void *buffer, size_t size);
         static int writer(StreamContext *context, const void *buffer, int size);
-        static long seeker(StreamContext *context, long int offset, int whence);
+        static int64_t seeker(StreamContext *context, int64_t offset, int whence);
         static int flusher(StreamContext *context);
     };
 
@@ -95,7 +95,7 @@ namespace c2pa
     private:
         static size_t reader(StreamContext *context, void *buffer, size_t size);
         static int writer(StreamContext *context, const void *buffer, int size);
-        static long seeker(StreamContext *context, long int offset, int whence);
+        static int64_t seeker(StreamContext *context, int64_t offset, int whence);
         static int flusher(StreamContext *context);
     };
 }
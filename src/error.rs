@@ -20,6 +20,120 @@ thread_local! {
     static LAST_ERROR: RefCell<Option<Error>> = const { RefCell::new(None) };
 }
 
+/// A stable number for the kind of the last error, returned by c2pa_error_code.
+///
+/// Codes are never renumbered, so callers can branch on them rather than on messages.
+/// Errors from the c2pa library get the most specific code known for them, so new codes
+/// may be added for kinds of error that currently get a more general code.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum C2paErrorCode {
+    /// There is no last error.
+    ErrorNone = 0,
+    /// An error with no more specific code.
+    ErrorOther = 1,
+    /// A required parameter was NULL.
+    ErrorNullParameter = 2,
+    /// Reading or writing a file or stream failed.
+    ErrorIo = 3,
+    /// JSON could not be parsed or did not match what was expected.
+    ErrorJson = 4,
+    /// A file was not found.
+    ErrorFileNotFound = 5,
+    /// The format or operation is not supported.
+    ErrorNotSupported = 6,
+    /// A resource was not found.
+    ErrorResourceNotFound = 7,
+    /// An assertion is invalid.
+    ErrorAssertion = 8,
+    /// An assertion was not found.
+    ErrorAssertionNotFound = 9,
+    /// Data could not be decoded.
+    ErrorDecoding = 10,
+    /// Data could not be encoded.
+    ErrorEncoding = 11,
+    /// A manifest is invalid.
+    ErrorManifest = 12,
+    /// A manifest was not found.
+    ErrorManifestNotFound = 13,
+    /// The asset has no C2PA data.
+    ErrorJumbfNotFound = 14,
+    /// The manifest is remote, at the URL in the message.
+    ErrorRemoteManifestUrl = 15,
+    /// A remote manifest could not be fetched.
+    ErrorRemoteManifestFetch = 16,
+    /// The manifest is remote and fetching remote manifests is disabled.
+    ErrorRemoteFetchDisabled = 17,
+    /// Fetching a remote manifest timed out.
+    ErrorRemoteTimeout = 18,
+    /// Signing failed or a signature is invalid.
+    ErrorSignature = 19,
+    /// A signing certificate is invalid.
+    ErrorCoseInvalidCert = 20,
+    /// A signing certificate had expired when the manifest was signed.
+    ErrorCoseCertExpiration = 21,
+    /// A signing certificate has been revoked.
+    ErrorCoseCertRevoked = 22,
+    /// A signing certificate is not trusted.
+    ErrorCoseCertUntrusted = 23,
+    /// A time stamp is invalid.
+    ErrorCoseInvalidTimeStamp = 24,
+    /// A time stamp is outside the validity of the signing certificate.
+    ErrorCoseTimeStampValidity = 25,
+    /// A time stamp does not match the signed data.
+    ErrorCoseTimeStampMismatch = 26,
+    /// A time stamp could not be generated.
+    ErrorCoseTimeStampGeneration = 27,
+    /// The time stamp authority could not be reached or refused the request.
+    ErrorCoseTimeStampAuthority = 28,
+    /// The space reserved for the signature is too small.
+    ErrorCoseSigboxTooSmall = 29,
+    /// Verification failed.
+    ErrorVerify = 30,
+    /// A hash of the asset does not match the manifest.
+    ErrorHashMismatch = 31,
+    /// The claim failed verification.
+    ErrorClaimVerification = 32,
+    /// A signature was not completed in time.
+    ErrorSignatureTimeout = 33,
+}
+
+// Returns a more specific code for a c2pa error than its converted variant has.
+fn c2pa_code(err: &c2pa::Error) -> Option<C2paErrorCode> {
+    use c2pa::Error::*;
+    use C2paErrorCode::*;
+    Some(match err {
+        JumbfNotFound => ErrorJumbfNotFound,
+        RemoteManifestUrl(_) => ErrorRemoteManifestUrl,
+        RemoteManifestFetch(_) => ErrorRemoteManifestFetch,
+        CoseInvalidCert => ErrorCoseInvalidCert,
+        CoseCertExpiration => ErrorCoseCertExpiration,
+        CoseCertRevoked => ErrorCoseCertRevoked,
+        CoseCertUntrusted => ErrorCoseCertUntrusted,
+        CoseInvalidTimeStamp => ErrorCoseInvalidTimeStamp,
+        CoseTimeStampValidity => ErrorCoseTimeStampValidity,
+        CoseTimeStampMismatch => ErrorCoseTimeStampMismatch,
+        CoseTimeStampGeneration => ErrorCoseTimeStampGeneration,
+        CoseTimeStampAuthority => ErrorCoseTimeStampAuthority,
+        CoseSigboxTooSmall => ErrorCoseSigboxTooSmall,
+        TimeStampError(err) => {
+            use c2pa_crypto::time_stamp::TimeStampError::*;
+            match err {
+                ExpiredCertificate => ErrorCoseTimeStampValidity,
+                InvalidData | NonceMismatch => ErrorCoseTimeStampMismatch,
+                UnsupportedAlgorithm | DecodeError(_) => ErrorCoseInvalidTimeStamp,
+                IoError(_) | HttpErrorResponse(..) | HttpConnectionError(_) => {
+                    ErrorCoseTimeStampAuthority
+                }
+                InternalError(_) => ErrorCoseTimeStampGeneration,
+            }
+        }
+        HashMismatch(_) => ErrorHashMismatch,
+        ClaimVerification(_) => ErrorClaimVerification,
+        _ => return None,
+    })
+}
+
 #[derive(Error, Debug)]
 /// Defines all possible errors that can occur in this library
 pub enum Error {
@@ -49,12 +163,26 @@ pub enum Error {
     NullParameter(String),
     #[error("Remote {0}")]
     RemoteManifest(String),
+    #[error("RemoteFetchDisabled {0}")]
+    RemoteFetchDisabled(String),
+    #[error("RemoteTimeout {0}")]
+    RemoteTimeout(String),
     #[error("ResourceNotFound {0}")]
     ResourceNotFound(String),
     #[error("Signature {0}")]
     Signature(String),
+    #[error("SignatureTimeout {0}")]
+    SignatureTimeout(String),
     #[error("Verify {0}")]
     Verify(String),
+    /// An error from the c2pa library with a more specific code than its variant has,
+    /// or with the messages of the errors that caused it, from the most direct cause.
+    #[error("{error}")]
+    C2pa {
+        error: Box<Error>,
+        code: C2paErrorCode,
+        sources: Vec<String>,
+    },
 }
 
 impl Error {
@@ -62,8 +190,23 @@ impl Error {
     #[allow(unused_variables)]
     pub(crate) fn from_c2pa_error(err: c2pa::Error) -> Self {
         use c2pa::Error::*;
+        let err = match err {
+            // an error of this library that passed through the SDK
+            OtherError(other) => match other.downcast::<Self>() {
+                Ok(error) => return *error,
+                Err(other) => OtherError(other),
+            },
+            err => err,
+        };
         let err_str = err.to_string();
-        match err {
+        let code = c2pa_code(&err);
+        let mut sources = Vec::new();
+        let mut source = std::error::Error::source(&err);
+        while let Some(cause) = source {
+            sources.push(cause.to_string());
+            source = cause.source();
+        }
+        let error = match err {
             c2pa::Error::AssertionMissing { url } => Self::AssertionNotFound("".to_string()),
             AssertionInvalidRedaction
             | AssertionRedactionNotFound
@@ -97,7 +240,8 @@ impl Error {
             | CoseTimeStampGeneration
             | CoseTimeStampAuthority
             | CoseSigboxTooSmall
-            | InvalidEcdsaSignature => Self::Signature(err_str),
+            | InvalidEcdsaSignature
+            | TimeStampError(_) => Self::Signature(err_str),
             RemoteManifestFetch(_) | RemoteManifestUrl(_) => Self::RemoteManifest(err_str),
             JumbfNotFound => Self::ManifestNotFound(err_str),
             BadParam(_) | MissingFeature(_) => Self::Other(err_str),
@@ -108,6 +252,54 @@ impl Error {
             UnsupportedType => Self::NotSupported(err_str),
             ClaimVerification(_) | InvalidClaim(_) | JumbfParseError(_) => Self::Verify(err_str),
             _ => Self::Other(err_str),
+        };
+        match code {
+            Some(code) if code != error.code() => Self::C2pa {
+                error: Box::new(error),
+                code,
+                sources,
+            },
+            _ if !sources.is_empty() => Self::C2pa {
+                code: error.code(),
+                error: Box::new(error),
+                sources,
+            },
+            _ => error,
+        }
+    }
+
+    /// Returns the messages of the errors that caused this one, from the most direct cause.
+    pub fn sources(&self) -> &[String] {
+        match self {
+            Self::C2pa { sources, .. } => sources,
+            _ => &[],
+        }
+    }
+
+    /// Returns the code for the variant of this error
+    pub fn code(&self) -> C2paErrorCode {
+        use C2paErrorCode::*;
+        match self {
+            Self::Assertion(_) => ErrorAssertion,
+            Self::AssertionNotFound(_) => ErrorAssertionNotFound,
+            Self::Decoding(_) => ErrorDecoding,
+            Self::Encoding(_) => ErrorEncoding,
+            Self::FileNotFound(_) => ErrorFileNotFound,
+            Self::Io(_) => ErrorIo,
+            Self::Json(_) => ErrorJson,
+            Self::Manifest(_) => ErrorManifest,
+            Self::ManifestNotFound(_) => ErrorManifestNotFound,
+            Self::NotSupported(_) => ErrorNotSupported,
+            Self::Other(_) => ErrorOther,
+            Self::NullParameter(_) => ErrorNullParameter,
+            Self::RemoteManifest(_) => ErrorRemoteManifestFetch,
+            Self::RemoteFetchDisabled(_) => ErrorRemoteFetchDisabled,
+            Self::RemoteTimeout(_) => ErrorRemoteTimeout,
+            Self::ResourceNotFound(_) => ErrorResourceNotFound,
+            Self::Signature(_) => ErrorSignature,
+            Self::SignatureTimeout(_) => ErrorSignatureTimeout,
+            Self::Verify(_) => ErrorVerify,
+            Self::C2pa { code, .. } => *code,
         }
     }
 
@@ -116,6 +308,24 @@ impl Error {
         LAST_ERROR.with(|prev| prev.borrow().as_ref().map(|e| e.to_string()))
     }
 
+    /// Returns the code of the last error, or ErrorNone if there is none
+    pub fn last_code() -> C2paErrorCode {
+        LAST_ERROR.with(|prev| {
+            prev.borrow()
+                .as_ref()
+                .map_or(C2paErrorCode::ErrorNone, |e| e.code())
+        })
+    }
+
+    /// Returns the code, message and sources of the last error, leaving it set
+    pub(crate) fn last_details() -> Option<(C2paErrorCode, String, Vec<String>)> {
+        LAST_ERROR.with(|prev| {
+            prev.borrow()
+                .as_ref()
+                .map(|e| (e.code(), e.to_string(), e.sources().to_vec()))
+        })
+    }
+
     /// Sets the last error
     pub fn set_last(self) {
         LAST_ERROR.with(|prev| *prev.borrow_mut() = Some(self));
@@ -126,3 +336,48 @@ impl Error {
         LAST_ERROR.with(|prev| prev.borrow_mut().take())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Error)]
+    #[error("PEM parse failed")]
+    struct PemError(#[source] std::io::Error);
+
+    #[test]
+    fn test_last_error_details() {
+        let cause = PemError(std::io::Error::other("tag mismatch at line 3"));
+        let err = c2pa::Error::OtherError(Box::new(cause));
+        // the sources are carried by the error, wherever it is set
+        let err = Error::from_c2pa_error(err);
+        Error::from_c2pa_error(c2pa::Error::CoseCertRevoked);
+        err.set_last();
+        let error = Error::take_last().unwrap();
+        assert_eq!(error.to_string(), "Other PEM parse failed");
+        assert_eq!(error.code(), C2paErrorCode::ErrorOther);
+        assert_eq!(error.sources(), ["tag mismatch at line 3".to_string()]);
+        assert!(Error::take_last().is_none());
+
+        // the specific code is carried by the error, wherever it is set
+        let err = Error::from_c2pa_error(c2pa::Error::CoseTimeStampValidity);
+        Error::from_c2pa_error(c2pa::Error::CoseCertRevoked);
+        assert_eq!(err.code(), C2paErrorCode::ErrorCoseTimeStampValidity);
+        assert_eq!(
+            err.to_string(),
+            "Signature COSE time stamp had expired cert"
+        );
+        err.set_last();
+        assert_eq!(
+            Error::last_code(),
+            C2paErrorCode::ErrorCoseTimeStampValidity
+        );
+        Error::Signature("other".to_string()).set_last();
+        assert_eq!(Error::last_code(), C2paErrorCode::ErrorSignature);
+
+        // errors of this library keep their variant through the SDK
+        let url = "https://example.com/manifest.c2pa".to_string();
+        let err = c2pa::Error::OtherError(Box::new(Error::RemoteTimeout(url.clone())));
+        assert!(matches!(Error::from_c2pa_error(err), Error::RemoteTimeout(u) if u == url));
+    }
+}
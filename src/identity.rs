@@ -0,0 +1,533 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Decodes and validates CAWG identity assertions.
+
+use c2pa::Reader;
+use coset::{cbor::value::Value as CborValue, CborSerializable, CoseSign1, TaggedCborSerializable};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    cose::verify_sign1,
+    json_api::manifest_labels,
+    jumbf::{assertion_cbor, claim_cbor},
+    trust::TrustList,
+    Error, Result,
+};
+
+const IDENTITY_LABEL: &str = "cawg.identity";
+const X509_SIG_TYPE: &str = "cawg.x509.cose";
+const ICA_SIG_TYPE: &str = "cawg.identity_claims_aggregation";
+// the deepest nesting of CBOR arrays, maps and tags that is measured
+const MAX_CBOR_DEPTH: usize = 32;
+
+/// A decoded CAWG identity assertion.
+#[derive(Debug, Serialize)]
+pub struct IdentityAssertion {
+    /// The label of the manifest containing the assertion.
+    pub manifest: String,
+    /// The label of the assertion, such as "cawg.identity__1".
+    pub label: String,
+    /// The signature type, such as "cawg.x509.cose".
+    pub sig_type: String,
+    /// The kind of credential: "x509", "identity_claims_aggregation" or "unknown".
+    pub credential_type: String,
+    /// The roles of the named actor, if any.
+    pub roles: Vec<String>,
+    /// The URLs of the assertions the named actor signed.
+    pub referenced_assertions: Vec<String>,
+    /// The named actor, as described by the credential.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub named_actor: Option<Value>,
+    /// True if the signature was verified, the referenced assertions match the claim
+    /// and the credential is trusted.
+    pub signature_valid: bool,
+    /// Why the signature could not be verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// Returns the major type, argument and size of the head of a CBOR data item.
+// The argument is None for indefinite lengths.
+fn cbor_head(data: &[u8]) -> Option<(u8, Option<u64>, usize)> {
+    let initial = *data.first()?;
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    match info {
+        0..=23 => Some((major, Some(info.into()), 1)),
+        24..=27 => {
+            let size = 1usize << (info - 24);
+            let bytes = data.get(1..1 + size)?;
+            let arg = bytes.iter().fold(0u64, |arg, b| arg << 8 | u64::from(*b));
+            Some((major, Some(arg), 1 + size))
+        }
+        31 if (2..=5).contains(&major) => Some((major, None, 1)),
+        _ => None,
+    }
+}
+
+// Returns the size of the CBOR data item at the start of data.
+fn cbor_item_len(data: &[u8], depth: usize) -> Option<usize> {
+    if depth > MAX_CBOR_DEPTH {
+        return None;
+    }
+    let (major, arg, mut len) = cbor_head(data)?;
+    let Some(arg) = arg else {
+        // indefinite length items end with a break
+        loop {
+            match data.get(len)? {
+                0xff => return Some(len + 1),
+                _ => len += cbor_item_len(&data[len..], depth + 1)?,
+            }
+        }
+    };
+    let items = match major {
+        0 | 1 | 7 => return Some(len),
+        2 | 3 => {
+            return len
+                .checked_add(usize::try_from(arg).ok()?)
+                .filter(|end| *end <= data.len())
+        }
+        4 => arg,
+        5 => arg.checked_mul(2)?,
+        _ => 1,
+    };
+    for _ in 0..items {
+        len += cbor_item_len(data.get(len..)?, depth + 1)?;
+    }
+    Some(len)
+}
+
+// Returns the value of a text key in an encoded CBOR map, exactly as it is encoded.
+fn encoded_value<'a>(map: &'a [u8], key: &str) -> Option<&'a [u8]> {
+    let (major, mut remaining, mut pos) = cbor_head(map)?;
+    if major != 5 {
+        return None;
+    }
+    while remaining != Some(0) && *map.get(pos)? != 0xff {
+        let value = pos + cbor_item_len(&map[pos..], 1)?;
+        let end = value + cbor_item_len(map.get(value..)?, 1)?;
+        let item_key: Option<CborValue> = coset::cbor::de::from_reader(&map[pos..value]).ok();
+        if item_key.as_ref().and_then(CborValue::as_text) == Some(key) {
+            return Some(&map[value..end]);
+        }
+        pos = end;
+        remaining = remaining.map(|n| n - 1);
+    }
+    None
+}
+
+// Returns the value of a text key in a CBOR map.
+fn get<'a>(map: &'a CborValue, key: &str) -> Option<&'a CborValue> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, v)| v)
+}
+
+// Returns the text values of a CBOR array, or of a key in each of its maps.
+fn texts(value: Option<&CborValue>, key: Option<&str>) -> Vec<String> {
+    value
+        .and_then(CborValue::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| key.map_or(Some(item), |k| get(item, k)))
+                .filter_map(CborValue::as_text)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Returns the path of a JUMBF URI in the manifest with the given label,
+// or None if the URI refers to another manifest.
+fn manifest_path<'a>(url: &'a str, manifest: &str) -> Option<&'a str> {
+    let path = url.strip_prefix("self#jumbf=")?;
+    match path.strip_prefix('/') {
+        None => Some(path),
+        Some(absolute) => absolute
+            .strip_prefix("c2pa/")?
+            .strip_prefix(manifest)?
+            .strip_prefix('/'),
+    }
+}
+
+// Checks that each referenced assertion is listed in the claim with the same hash.
+fn check_references(signer_payload: &CborValue, claim: &CborValue, manifest: &str) -> Result<()> {
+    let hashed_uris = |value: Option<&CborValue>| -> Vec<(String, Vec<u8>)> {
+        value
+            .and_then(CborValue::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|uri| {
+                let url = get(uri, "url")?.as_text()?;
+                let hash = get(uri, "hash")?.as_bytes()?;
+                Some((manifest_path(url, manifest)?.to_string(), hash.clone()))
+            })
+            .collect()
+    };
+    // version 1 claims list "assertions", version 2 claims split them in two
+    let listed: Vec<_> = ["assertions", "created_assertions", "gathered_assertions"]
+        .into_iter()
+        .flat_map(|key| hashed_uris(get(claim, key)))
+        .collect();
+    let referenced = get(signer_payload, "referenced_assertions")
+        .and_then(CborValue::as_array)
+        .ok_or_else(|| Error::Signature("missing referenced assertions".to_string()))?;
+    let referenced_uris = hashed_uris(get(signer_payload, "referenced_assertions"));
+    if referenced_uris.len() != referenced.len() {
+        return Err(Error::Signature(
+            "a referenced assertion is not in this manifest".to_string(),
+        ));
+    }
+    match referenced_uris.iter().find(|uri| !listed.contains(uri)) {
+        Some((path, _)) => Err(Error::Signature(format!(
+            "the referenced assertion {path} does not match the claim"
+        ))),
+        None => Ok(()),
+    }
+}
+
+// Returns the payload embedded in a COSE_Sign1 signature, if there is one.
+fn cose_payload(cose: &[u8]) -> Option<Vec<u8>> {
+    <CoseSign1 as TaggedCborSerializable>::from_tagged_slice(cose)
+        .or_else(|_| <CoseSign1 as CborSerializable>::from_slice(cose))
+        .ok()
+        .and_then(|sign1| sign1.payload)
+}
+
+// Verifies an X.509 signature over the encoded signer payload and describes the signer.
+// Also returns why the signer is not trusted, if it is not.
+fn verify_x509(
+    signer_payload: &[u8],
+    signature: &[u8],
+    trust: &TrustList,
+) -> Result<(Value, Option<String>)> {
+    let payload = match cose_payload(signature) {
+        Some(payload) if payload != signer_payload => {
+            return Err(Error::Signature(
+                "signed payload does not match the signer payload".to_string(),
+            ))
+        }
+        Some(_) => None,
+        None => Some(signer_payload),
+    };
+    let (verification, chain) = verify_sign1(signature, payload, None)?;
+    let untrusted =
+        (!trust.trusts(&chain)?).then(|| "the signing certificate is not trusted".to_string());
+    let actor = json!({
+        "subject": verification.subject,
+        "common_name": verification.common_name,
+        "issuer": verification.issuer,
+    });
+    Ok((actor, untrusted))
+}
+
+// Describes the verified identities of an identity claims aggregation credential.
+fn decode_ica(signature: &[u8]) -> Result<Value> {
+    let payload = cose_payload(signature)
+        .ok_or_else(|| Error::Signature("missing identity claims aggregation".to_string()))?;
+    let credential: Value =
+        serde_json::from_slice(&payload).map_err(|e| Error::Json(e.to_string()))?;
+    Ok(json!({
+        "issuer": credential["issuer"],
+        "verified_identities": credential["credentialSubject"]["verifiedIdentities"],
+    }))
+}
+
+// Verifies an identity assertion and describes its named actor.
+// Also returns why the credential is not verified, if it is not.
+fn verify_identity(
+    manifest: &str,
+    sig_type: &str,
+    assertion: &CborValue,
+    cbor: &[u8],
+    claim: &[u8],
+    trust: &TrustList,
+) -> Result<(Value, Option<String>)> {
+    let signature = get(assertion, "signature")
+        .and_then(CborValue::as_bytes)
+        .ok_or_else(|| Error::Signature("missing signature".to_string()))?;
+    let signer_payload = get(assertion, "signer_payload")
+        .ok_or_else(|| Error::Signature("missing signer payload".to_string()))?;
+    let claim: CborValue =
+        coset::cbor::de::from_reader(claim).map_err(|e| Error::Decoding(e.to_string()))?;
+    check_references(signer_payload, &claim, manifest)?;
+    match sig_type {
+        X509_SIG_TYPE => {
+            // the signature is over the signer payload as it was encoded, not as it decodes
+            let signer_payload = encoded_value(cbor, "signer_payload")
+                .ok_or_else(|| Error::Decoding("invalid signer payload".to_string()))?;
+            verify_x509(signer_payload, signature, trust)
+        }
+        ICA_SIG_TYPE => decode_ica(signature).map(|actor| {
+            let unresolved = "the credential issuer was not resolved".to_string();
+            (actor, Some(unresolved))
+        }),
+        sig_type => Err(Error::Signature(format!("unsupported sig_type {sig_type}"))),
+    }
+}
+
+fn decode(
+    manifest: &str,
+    label: &str,
+    cbor: &[u8],
+    claim: &[u8],
+    trust: &TrustList,
+) -> IdentityAssertion {
+    let assertion = coset::cbor::de::from_reader::<CborValue, _>(cbor);
+    let signer_payload = assertion
+        .as_ref()
+        .ok()
+        .and_then(|assertion| get(assertion, "signer_payload"));
+    let field = |key| signer_payload.and_then(|payload| get(payload, key));
+    let sig_type = field("sig_type")
+        .and_then(CborValue::as_text)
+        .unwrap_or_default()
+        .to_string();
+    let mut identity = IdentityAssertion {
+        manifest: manifest.to_string(),
+        label: label.to_string(),
+        credential_type: match sig_type.as_str() {
+            X509_SIG_TYPE => "x509",
+            ICA_SIG_TYPE => "identity_claims_aggregation",
+            _ => "unknown",
+        }
+        .to_string(),
+        sig_type,
+        roles: texts(field("roles"), None),
+        referenced_assertions: texts(field("referenced_assertions"), Some("url")),
+        named_actor: None,
+        signature_valid: false,
+        error: None,
+    };
+
+    let result = assertion
+        .map_err(|e| Error::Decoding(e.to_string()))
+        .and_then(|assertion| {
+            verify_identity(manifest, &identity.sig_type, &assertion, cbor, claim, trust)
+        });
+    match result {
+        Ok((actor, unverified)) => {
+            identity.named_actor = Some(actor);
+            identity.signature_valid = unverified.is_none();
+            identity.error = unverified;
+        }
+        Err(e) => identity.error = Some(e.to_string()),
+    }
+    identity
+}
+
+/// Decodes and validates one CAWG identity assertion.
+///
+/// The assertion and the claim of its manifest are given as CBOR, exactly as they are stored.
+/// The signature is checked over the stored signer payload and each referenced assertion
+/// must be listed in the claim with the same hash.
+/// X.509 credentials must chain to a configured trust anchor or be on the allowed list.
+/// Identity claims aggregation credentials are decoded, but their issuer is not resolved,
+/// so their signature is never reported as valid.
+pub fn decode_identity_assertion(
+    manifest: &str,
+    label: &str,
+    cbor: &[u8],
+    claim: &[u8],
+) -> Result<IdentityAssertion> {
+    let trust = TrustList::configured()?;
+    Ok(decode(manifest, label, cbor, claim, &trust))
+}
+
+/// Returns the CAWG identity assertions in a Reader, in provenance order.
+///
+/// The manifest store is the one the Reader was created from, as returned by
+/// c2pa_reader_manifest_bytes.
+pub fn identity_assertions(reader: &Reader, store: &[u8]) -> Result<Vec<IdentityAssertion>> {
+    let trust = TrustList::configured()?;
+    let mut identities = Vec::new();
+    for label in manifest_labels(reader) {
+        let Some(manifest) = reader.get_manifest(&label) else {
+            continue;
+        };
+        let claim = claim_cbor(store, &label)?;
+        let assertions = manifest
+            .assertions()
+            .iter()
+            .filter(|assertion| assertion.label() == IDENTITY_LABEL);
+        for (index, assertion) in assertions.enumerate() {
+            let cbor = assertion_cbor(store, &label, IDENTITY_LABEL, index + 1)?;
+            let label_with_instance = assertion.label_with_instance();
+            identities.push(decode(&label, &label_with_instance, &cbor, &claim, &trust));
+        }
+    }
+    Ok(identities)
+}
+
+/// Returns the CAWG identity assertions in a Reader as a JSON array.
+pub fn identity_assertions_json(reader: &Reader, store: &[u8]) -> Result<String> {
+    serde_json::to_string_pretty(&identity_assertions(reader, store)?)
+        .map_err(|e| Error::Json(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use c2pa::{jumbf_io, Builder, CallbackSigner, SigningAlg};
+    use coset::{iana, CoseSign1Builder, HeaderBuilder};
+
+    use super::*;
+    use crate::certs::pem_certs;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+    const HASH_DATA: &str = "self#jumbf=c2pa.assertions/c2pa.hash.data";
+
+    fn text(text: &str) -> CborValue {
+        CborValue::Text(text.to_string())
+    }
+
+    fn hashed_uri(url: &str, fill: u8) -> CborValue {
+        CborValue::Map(vec![
+            (text("url"), text(url)),
+            (text("hash"), CborValue::Bytes(vec![fill; 32])),
+        ])
+    }
+
+    fn encode(value: &CborValue) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        coset::cbor::ser::into_writer(value, &mut encoded).unwrap();
+        encoded
+    }
+
+    // a claim listing the data hash assertion
+    fn claim() -> Vec<u8> {
+        encode(&CborValue::Map(vec![(
+            text("assertions"),
+            CborValue::Array(vec![hashed_uri(HASH_DATA, 1)]),
+        )]))
+    }
+
+    // an identity assertion signed with the test certificate
+    fn identity_assertion(tamper: bool) -> CborValue {
+        // the signer payload keys are not sorted, which the signature must survive
+        let signer_payload = CborValue::Map(vec![
+            (text("sig_type"), text(X509_SIG_TYPE)),
+            (
+                text("referenced_assertions"),
+                CborValue::Array(vec![hashed_uri(HASH_DATA, 1)]),
+            ),
+        ]);
+        let mut encoded = encode(&signer_payload);
+        if tamper {
+            encoded.push(0);
+        }
+
+        let chain = pem_certs(std::str::from_utf8(CERTS).unwrap()).unwrap();
+        let protected = HeaderBuilder::new()
+            .algorithm(iana::Algorithm::EdDSA)
+            .value(
+                iana::HeaderParameter::X5Chain as i64,
+                CborValue::Array(chain.into_iter().map(CborValue::Bytes).collect()),
+            )
+            .build();
+        let signature = CoseSign1Builder::new()
+            .protected(protected)
+            .create_detached_signature(&encoded, b"", |tbs| {
+                CallbackSigner::ed25519_sign(tbs, PRIVATE_KEY).unwrap()
+            })
+            .build()
+            .to_tagged_vec()
+            .unwrap();
+        CborValue::Map(vec![
+            (text("signer_payload"), signer_payload),
+            (text("signature"), CborValue::Bytes(signature)),
+            (text("pad1"), CborValue::Bytes(vec![0; 16])),
+        ])
+    }
+
+    // trusts the intermediate certificate of the test chain
+    fn trust() -> TrustList {
+        let chain = pem_certs(std::str::from_utf8(CERTS).unwrap()).unwrap();
+        TrustList::new(vec![chain[1].clone()], &[])
+    }
+
+    #[test]
+    fn test_decode_identity_assertion() {
+        let cbor = encode(&identity_assertion(false));
+        let identity = decode("urn:uuid:1", "cawg.identity", &cbor, &claim(), &trust());
+        assert_eq!(identity.credential_type, "x509");
+        assert_eq!(identity.referenced_assertions, vec![HASH_DATA]);
+        assert!(identity.signature_valid, "{:?}", identity.error);
+        assert!(identity.named_actor.as_ref().unwrap()["subject"].is_string());
+        assert_eq!(
+            encoded_value(&cbor, "pad1"),
+            Some(encode(&CborValue::Bytes(vec![0; 16])).as_slice())
+        );
+
+        let tampered = encode(&identity_assertion(true));
+        let identity = decode("urn:uuid:1", "cawg.identity", &tampered, &claim(), &trust());
+        assert!(!identity.signature_valid);
+        assert!(identity.named_actor.is_none());
+
+        // the claim lists a different hash for the referenced assertion
+        let other_claim = encode(&CborValue::Map(vec![(
+            text("assertions"),
+            CborValue::Array(vec![hashed_uri(HASH_DATA, 2)]),
+        )]));
+        let identity = decode("urn:uuid:1", "cawg.identity", &cbor, &other_claim, &trust());
+        assert!(!identity.signature_valid);
+        assert!(identity.error.unwrap().contains("does not match the claim"));
+
+        let untrusted = TrustList::new(Vec::new(), &[]);
+        let identity = decode("urn:uuid:1", "cawg.identity", &cbor, &claim(), &untrusted);
+        assert!(!identity.signature_valid);
+        assert!(identity.named_actor.is_some());
+        assert_eq!(
+            identity.error.as_deref(),
+            Some("the signing certificate is not trusted")
+        );
+    }
+
+    #[test]
+    fn test_identity_assertions() {
+        let mut builder = Builder::from_json(r#"{"title": "A.jpg"}"#).unwrap();
+        builder
+            .add_assertion(IDENTITY_LABEL, &identity_assertion(false))
+            .unwrap()
+            .add_assertion(IDENTITY_LABEL, &identity_assertion(true))
+            .unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let source = std::fs::read("tests/fixtures/A.jpg").unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        builder
+            .sign(&signer, "image/jpeg", &mut Cursor::new(source), &mut dest)
+            .unwrap();
+
+        dest.set_position(0);
+        let store = jumbf_io::load_jumbf_from_stream("image/jpeg", &mut dest).unwrap();
+        dest.set_position(0);
+        let reader = Reader::from_stream("image/jpeg", dest).unwrap();
+        let identities = identity_assertions(&reader, &store).unwrap();
+        assert_eq!(identities.len(), 2);
+        assert_eq!(identities[0].label, "cawg.identity");
+        assert_eq!(identities[0].credential_type, "x509");
+        assert_eq!(identities[0].referenced_assertions, vec![HASH_DATA]);
+        // the claim holds the real hash of the data hash assertion
+        for identity in &identities {
+            assert!(!identity.signature_valid);
+            assert!(identity.error.is_some());
+        }
+    }
+}
@@ -10,9 +10,19 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use c2pa::{Ingredient, Manifest, Reader};
+use std::{
+    io::{Read, Seek, Write},
+    path::Path,
+};
 
-use crate::{Error, Result, SignerInfo};
+use c2pa::{
+    assertions::DataHash, hash_stream_by_alg, jumbf_io, validation_status::ValidationStatus,
+    Builder, Ingredient, Manifest, ManifestDefinition, ManifestStoreReport, Reader,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{remote, Error, Result, SignerInfo};
 
 /// Returns the version of the c2pa SDK used in this library
 pub fn sdk_version() -> String {
@@ -23,16 +33,25 @@ pub fn sdk_version() -> String {
 ///
 /// If data_dir is provided, any thumbnail or c2pa data will be written to that folder.
 /// Any Validation errors will be reported in the validation_status field.
+/// A sidecar manifest store is used if the file has none, as with [`remote::read_file_with_source`],
+/// and its path is then reported in a sidecar_path field.
 ///
 pub fn read_file(path: &str, data_dir: Option<String>) -> Result<String> {
-    let reader = Reader::from_file(path).map_err(Error::from_c2pa_error)?;
-    Ok(if let Some(dir) = data_dir {
-        let json = reader.to_string();
+    let (reader, source) =
+        remote::read_file_with_source(Path::new(path)).map_err(Error::from_c2pa_error)?;
+    if let Some(dir) = data_dir {
         reader.to_folder(&dir).map_err(Error::from_c2pa_error)?;
-        json
-    } else {
-        reader.to_string()
-    })
+    }
+    let json = reader.to_string();
+    match source.sidecar_path {
+        Some(sidecar_path) => {
+            let json_error = |e: serde_json::Error| Error::Json(e.to_string());
+            let mut value: serde_json::Value = serde_json::from_str(&json).map_err(json_error)?;
+            value["sidecar_path"] = sidecar_path.into();
+            serde_json::to_string_pretty(&value).map_err(json_error)
+        }
+        None => Ok(json),
+    }
 }
 
 /// Returns an Ingredient JSON string from a file path.
@@ -83,10 +102,547 @@ pub fn sign_file(
         .map_err(Error::from_c2pa_error)
 }
 
+/// Describes a failed hard binding or hashed URI check.
+#[derive(Debug, Default, Serialize)]
+pub struct HashFailure {
+    /// The validation status code reported by the SDK.
+    pub code: String,
+    /// The JUMBF URI of the entity that failed to validate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The SDK explanation of the failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// The label of the manifest containing the failing assertion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<String>,
+    /// The label of the failing assertion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assertion: Option<String>,
+    /// The hash algorithm used by the assertion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    /// The hex encoded digest recorded in the assertion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// The hex encoded digest computed from the asset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed: Option<String>,
+    /// The byte ranges excluded from the hash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusions: Option<Value>,
+}
+
+// Returns true if a validation status code refers to a hash check.
+fn is_hash_status(code: &str) -> bool {
+    code.starts_with("assertion.dataHash")
+        || code.starts_with("assertion.bmffHash")
+        || code.starts_with("assertion.boxesHash")
+        || code.ends_with("hashedURI.mismatch")
+}
+
+// Splits a "self#jumbf=/c2pa/<manifest>/c2pa.assertions/<assertion>" URI.
+fn split_jumbf_uri(url: &str) -> (Option<String>, Option<String>) {
+    let path = url.trim_start_matches("self#jumbf=");
+    let mut parts = path.trim_start_matches("/c2pa/").split('/');
+    let manifest = parts.next().filter(|m| !m.is_empty()).map(String::from);
+    let assertion = match parts.next() {
+        Some("c2pa.assertions") => parts.next().map(String::from),
+        _ => None,
+    };
+    (manifest, assertion)
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Describes a binary resource of a manifest, such as a thumbnail or icon.
+#[derive(Debug, Serialize)]
+pub struct ResourceInfo {
+    /// The label of the manifest holding the resource.
+    pub manifest: String,
+    /// The URI to pass to c2pa_reader_resource_to_stream.
+    pub uri: String,
+    /// The mime type of the resource, if the manifest references it with one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The size of the resource in bytes.
+    pub size: usize,
+}
+
+// Collects the formats of every resource reference in a JSON value by identifier.
+fn resource_formats(value: &Value, formats: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            if let (Some(Value::String(id)), Some(format)) =
+                (map.get("identifier"), map.get("format"))
+            {
+                formats.insert(id.clone(), format.clone());
+            }
+            map.values().for_each(|v| resource_formats(v, formats));
+        }
+        Value::Array(values) => values.iter().for_each(|v| resource_formats(v, formats)),
+        _ => {}
+    }
+}
+
+/// Returns the resources of every manifest in a Reader, in provenance order.
+pub fn resource_list(reader: &Reader) -> Vec<ResourceInfo> {
+    let mut list = Vec::new();
+    for label in manifest_labels(reader) {
+        let Some(manifest) = reader.get_manifest(&label) else {
+            continue;
+        };
+        let mut formats = Map::new();
+        if let Ok(value) = serde_json::to_value(manifest) {
+            resource_formats(&value, &mut formats);
+        }
+        let mut resources: Vec<ResourceInfo> = manifest
+            .resources()
+            .resources()
+            .iter()
+            .map(|(uri, data)| ResourceInfo {
+                manifest: label.clone(),
+                uri: uri.clone(),
+                format: formats
+                    .get(uri)
+                    .and_then(|format| format.as_str())
+                    .map(String::from),
+                size: data.len(),
+            })
+            .collect();
+        resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+        list.extend(resources);
+    }
+    list
+}
+
+/// Returns the resources of every manifest in a Reader as a JSON array.
+pub fn resource_list_json(reader: &Reader) -> Result<String> {
+    serde_json::to_string_pretty(&resource_list(reader)).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Writes the thumbnail of an ingredient to a stream and returns its size.
+///
+/// The ingredient is found by its index in the manifest with the given label,
+/// or in the active manifest if no label is given.
+pub fn ingredient_thumbnail(
+    reader: &Reader,
+    label: Option<&str>,
+    index: usize,
+    stream: &mut impl Write,
+) -> Result<usize> {
+    let label = label
+        .or(reader.active_label())
+        .ok_or_else(|| Error::ManifestNotFound("no active manifest".to_string()))?;
+    let manifest = reader
+        .get_manifest(label)
+        .ok_or_else(|| Error::ManifestNotFound(label.to_string()))?;
+    let ingredient = manifest
+        .ingredients()
+        .get(index)
+        .ok_or_else(|| Error::Other(format!("ingredient {index} not found in {label}")))?;
+    let identifier = &ingredient
+        .thumbnail_ref()
+        .ok_or_else(|| Error::ResourceNotFound(format!("thumbnail of ingredient {index}")))?
+        .identifier;
+    // the SDK resolves relative URIs against the active manifest, so look the resource up directly
+    let data = [ingredient.resources(), manifest.resources()]
+        .into_iter()
+        .find(|resources| resources.exists(identifier))
+        .ok_or_else(|| Error::ResourceNotFound(identifier.clone()))?
+        .get(identifier)
+        .map_err(Error::from_c2pa_error)?;
+    stream
+        .write_all(&data)
+        .map_err(|e| Error::Io(e.to_string()))?;
+    Ok(data.len())
+}
+
+/// Returns the low level report of a manifest store as JSON, read from the store alone
+/// as if it were a .c2pa file.
+pub(crate) fn manifest_store_report(manifest_data: &[u8]) -> Option<Value> {
+    ManifestStoreReport::from_bytes("c2pa", manifest_data)
+        .ok()
+        .and_then(|report| serde_json::to_value(report).ok())
+}
+
+/// Returns a list of hash failures found when validating an asset.
+///
+/// For data hash assertions the digest is recomputed from the asset so
+/// the expected and computed values can be compared.
+/// The asset is read from the stream as it is hashed rather than held in memory.
+pub fn hash_failures(
+    format: &str,
+    stream: &mut (impl Read + Seek + Send),
+) -> Result<Vec<HashFailure>> {
+    let io_error = |e: std::io::Error| Error::Io(e.to_string());
+    stream.rewind().map_err(io_error)?;
+    let reader = Reader::from_stream(format, &mut *stream).map_err(Error::from_c2pa_error)?;
+    let statuses = match reader.validation_status() {
+        Some(statuses) => statuses,
+        None => return Ok(Vec::new()),
+    };
+
+    // the low level report includes the hash assertions hidden by the Reader
+    stream.rewind().map_err(io_error)?;
+    let report = jumbf_io::load_jumbf_from_stream(format, &mut *stream)
+        .ok()
+        .and_then(|store| manifest_store_report(&store));
+
+    let mut failures = Vec::new();
+    for status in statuses.iter().filter(|s| is_hash_status(s.code())) {
+        let (manifest, assertion) = status.url().map(split_jumbf_uri).unwrap_or_default();
+        let mut failure = HashFailure {
+            code: status.code().to_string(),
+            url: status.url().map(String::from),
+            explanation: status.explanation().map(String::from),
+            manifest,
+            assertion,
+            ..Default::default()
+        };
+        if let (Some(report), Some(manifest), Some(assertion)) =
+            (&report, &failure.manifest, &failure.assertion)
+        {
+            let value = &report["manifests"][manifest]["assertion_store"][assertion];
+            if assertion.starts_with(DataHash::LABEL) {
+                if let Ok(data_hash) = serde_json::from_value::<DataHash>(value.clone()) {
+                    let alg = data_hash
+                        .alg
+                        .clone()
+                        .unwrap_or_else(|| "sha256".to_string());
+                    failure.expected = Some(to_hex(&data_hash.hash));
+                    stream.rewind().map_err(io_error)?;
+                    failure.computed =
+                        hash_stream_by_alg(&alg, stream, data_hash.exclusions.clone(), true)
+                            .ok()
+                            .map(|h| to_hex(&h));
+                    failure.exclusions = serde_json::to_value(&data_hash.exclusions).ok();
+                    failure.alg = Some(alg);
+                }
+            } else if let Some(alg) = value["alg"].as_str() {
+                failure.alg = Some(alg.to_string());
+            }
+        }
+        failures.push(failure);
+    }
+    Ok(failures)
+}
+
+/// Returns a JSON array describing the hash failures found in an asset.
+pub fn hash_failures_json(format: &str, stream: &mut (impl Read + Seek + Send)) -> Result<String> {
+    let failures = hash_failures(format, stream)?;
+    serde_json::to_string_pretty(&failures).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Returns the validation status entries of a Reader as a JSON array.
+///
+/// Each entry has the status code and, when known, the URL of the manifest part
+/// it refers to and an explanation. The array is empty if the manifest store is valid.
+pub fn validation_status_json(reader: &Reader) -> Result<String> {
+    let statuses = reader.validation_status().unwrap_or_default();
+    serde_json::to_string_pretty(statuses).map_err(|e| Error::Json(e.to_string()))
+}
+
+// Informational codes from the specification; the SDK does not define these yet.
+const INFORMATIONAL_CODES: &[&str] = &[
+    "algorithm.deprecated",
+    "signingCredential.ocsp.inaccessible",
+    "signingCredential.ocsp.skipped",
+    "ingredient.unknownProvenance",
+];
+
+/// Validation statuses of one manifest, grouped by kind.
+#[derive(Debug, Default, Serialize)]
+pub struct StatusCodes {
+    /// Checks that passed.
+    pub success: Vec<ValidationStatus>,
+    /// Checks that do not affect validity, such as a skipped OCSP request.
+    pub informational: Vec<ValidationStatus>,
+    /// Checks that failed.
+    pub failure: Vec<ValidationStatus>,
+}
+
+impl StatusCodes {
+    fn add(&mut self, status: &ValidationStatus) {
+        let statuses = if status.passed() {
+            &mut self.success
+        } else if INFORMATIONAL_CODES.contains(&status.code()) {
+            &mut self.informational
+        } else {
+            &mut self.failure
+        };
+        if !statuses.contains(status) {
+            statuses.push(status.clone());
+        }
+    }
+}
+
+/// The validation statuses recorded for an ingredient when it was added to a manifest.
+#[derive(Debug, Serialize)]
+pub struct IngredientDelta {
+    /// The label of the manifest containing the ingredient.
+    pub manifest: String,
+    /// The instance id of the ingredient.
+    pub instance_id: String,
+    /// The title of the ingredient.
+    pub title: String,
+    /// The label of the ingredient's own manifest, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_manifest: Option<String>,
+    /// The statuses of the ingredient's manifest.
+    pub validation_deltas: StatusCodes,
+}
+
+/// The validation results of a manifest store, in the shape of c2pa.validationResults.
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationResults {
+    /// The statuses of the active manifest.
+    pub active_manifest: StatusCodes,
+    /// The statuses of each ingredient, in provenance order.
+    pub ingredient_deltas: Vec<IngredientDelta>,
+}
+
+/// Returns the validation results of a Reader.
+///
+/// The SDK only reports checks that did not pass, so the success lists are usually empty.
+/// Ingredients are visited breadth first from the active manifest. A status the SDK reports
+/// against an ingredient manifest is added to the deltas of the ingredients that refer to it.
+pub fn validation_results(reader: &Reader) -> ValidationResults {
+    let mut results = ValidationResults::default();
+    let mut labels: Vec<String> = reader
+        .active_label()
+        .map(String::from)
+        .into_iter()
+        .collect();
+    let mut next = 0;
+    while let Some((label, manifest)) = labels
+        .get(next)
+        .and_then(|label| reader.get_manifest(label).map(|m| (label.clone(), m)))
+    {
+        for ingredient in manifest.ingredients() {
+            let mut validation_deltas = StatusCodes::default();
+            for status in ingredient.validation_status().unwrap_or_default() {
+                validation_deltas.add(status);
+            }
+            let active_manifest = ingredient.active_manifest().map(String::from);
+            if let Some(ingredient_label) = &active_manifest {
+                if !labels.contains(ingredient_label)
+                    && reader.get_manifest(ingredient_label).is_some()
+                {
+                    labels.push(ingredient_label.clone());
+                }
+            }
+            results.ingredient_deltas.push(IngredientDelta {
+                manifest: label.clone(),
+                instance_id: ingredient.instance_id().to_string(),
+                title: ingredient.title().to_string(),
+                active_manifest,
+                validation_deltas,
+            });
+        }
+        next += 1;
+    }
+
+    for status in reader.validation_status().unwrap_or_default() {
+        let (manifest, _) = split_jumbf_uri(status.url().unwrap_or_default());
+        let mut deltas = results
+            .ingredient_deltas
+            .iter_mut()
+            .filter(|delta| delta.active_manifest.is_some() && delta.active_manifest == manifest)
+            .peekable();
+        if deltas.peek().is_none() || manifest.as_deref() == reader.active_label() {
+            results.active_manifest.add(status);
+        } else {
+            deltas.for_each(|delta| delta.validation_deltas.add(status));
+        }
+    }
+    results
+}
+
+/// Returns the validation results of a Reader as a JSON string.
+pub fn validation_results_json(reader: &Reader) -> Result<String> {
+    serde_json::to_string_pretty(&validation_results(reader))
+        .map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Returns the labels of the manifests in a Reader, in provenance order.
+///
+/// The active manifest comes first, followed by the manifests of its ingredients breadth first,
+/// then any manifests no ingredient refers to, sorted by label.
+pub fn manifest_labels(reader: &Reader) -> Vec<String> {
+    let mut labels: Vec<String> = reader
+        .active_label()
+        .map(String::from)
+        .into_iter()
+        .collect();
+    let mut next = 0;
+    while let Some(manifest) = labels
+        .get(next)
+        .and_then(|label| reader.get_manifest(label))
+    {
+        for label in manifest
+            .ingredients()
+            .iter()
+            .filter_map(|ingredient| ingredient.active_manifest())
+        {
+            if !labels.iter().any(|l| l == label) && reader.get_manifest(label).is_some() {
+                labels.push(label.to_string());
+            }
+        }
+        next += 1;
+    }
+    let mut rest: Vec<String> = reader
+        .iter_manifests()
+        .filter_map(|manifest| manifest.label())
+        .filter(|label| !labels.iter().any(|l| l == label))
+        .map(String::from)
+        .collect();
+    rest.sort();
+    labels.extend(rest);
+    labels
+}
+
+/// Returns a manifest in a Reader as a JSON string.
+pub fn manifest_json(reader: &Reader, label: &str) -> Result<String> {
+    let manifest = reader
+        .get_manifest(label)
+        .ok_or_else(|| Error::ManifestNotFound(label.to_string()))?;
+    serde_json::to_string_pretty(manifest).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Returns the body of an assertion in a Reader as a JSON string.
+///
+/// The assertion is found by label and instance in the manifest with the given label,
+/// or in the active manifest if no label is given. The first instance of a label is 1.
+pub fn assertion_json(
+    reader: &Reader,
+    manifest_label: Option<&str>,
+    assertion_label: &str,
+    instance: usize,
+) -> Result<String> {
+    let label = manifest_label
+        .or(reader.active_label())
+        .ok_or_else(|| Error::ManifestNotFound("no active manifest".to_string()))?;
+    let manifest = reader
+        .get_manifest(label)
+        .ok_or_else(|| Error::ManifestNotFound(label.to_string()))?;
+    let assertion = manifest
+        .assertions()
+        .iter()
+        .find(|a| a.label() == assertion_label && a.instance() == instance)
+        .ok_or_else(|| {
+            Error::AssertionNotFound(format!("{assertion_label} instance {instance}"))
+        })?;
+    let value = assertion.value().map_err(|_| {
+        Error::Assertion(format!("{assertion_label} is not a JSON or CBOR assertion"))
+    })?;
+    serde_json::to_string_pretty(value).map_err(|e| Error::Json(e.to_string()))
+}
+
+// Recursively sorts the keys of all objects in a JSON value.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Returns a normalized, pretty printed version of a manifest definition JSON string.
+///
+/// The definition is parsed so defaults are filled in and shorthand is expanded,
+/// then object keys are sorted so equivalent definitions produce identical output.
+/// A generated instance_id is omitted so the output is stable across calls.
+pub fn canonicalize_manifest_json(manifest_json: &str) -> Result<String> {
+    let input: Value =
+        serde_json::from_str(manifest_json).map_err(|e| Error::Json(e.to_string()))?;
+    let definition: ManifestDefinition =
+        serde_json::from_value(input.clone()).map_err(|e| Error::Json(e.to_string()))?;
+    let mut value = serde_json::to_value(definition).map_err(|e| Error::Json(e.to_string()))?;
+    if input.get("instance_id").is_none() {
+        if let Some(map) = value.as_object_mut() {
+            map.remove("instance_id");
+        }
+    }
+    serde_json::to_string_pretty(&sort_keys(value)).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Returns the current state of a Builder as a pretty printed JSON string.
+///
+/// This is the manifest definition with any ingredients, actions and assertions added
+/// since the Builder was created, plus its remote_url and no_embed settings.
+/// Resources are only listed by identifier, since their data is kept by the Builder.
+pub fn builder_json(builder: &Builder) -> Result<String> {
+    serde_json::to_string_pretty(builder).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Makes an ingredient from an asset that has already been read, without reading it again.
+///
+/// The title, format and instance_id default to those of the active manifest, and the
+/// manifest store and validation status of the Reader are attached to the ingredient.
+/// The thumbnail of the active manifest is copied if the definition has none,
+/// since no thumbnail can be generated without the asset.
+pub fn ingredient_from_reader(
+    ingredient_json: &str,
+    reader: &Reader,
+    manifest_data: Vec<u8>,
+) -> Result<Ingredient> {
+    let manifest = reader
+        .active_manifest()
+        .ok_or_else(|| Error::ManifestNotFound("no active manifest".to_string()))?;
+    let mut definition: Value =
+        serde_json::from_str(ingredient_json).map_err(|e| Error::Json(e.to_string()))?;
+    let fields = definition
+        .as_object_mut()
+        .ok_or_else(|| Error::Json("the ingredient definition is not an object".to_string()))?;
+    let defaults = [
+        ("title", manifest.title().unwrap_or_default()),
+        ("format", manifest.format()),
+        ("instance_id", manifest.instance_id()),
+    ];
+    for (key, value) in defaults {
+        if !value.is_empty() {
+            fields.entry(key).or_insert_with(|| value.into());
+        }
+    }
+    let has_thumbnail = fields.contains_key("thumbnail");
+
+    let mut ingredient =
+        Ingredient::from_json(&definition.to_string()).map_err(Error::from_c2pa_error)?;
+    ingredient
+        .set_manifest_data(manifest_data)
+        .map_err(Error::from_c2pa_error)?
+        .set_active_manifest(reader.active_label().unwrap_or_default());
+    for status in reader.validation_status().unwrap_or_default() {
+        ingredient.add_validation_status(status.clone());
+    }
+    if let (false, Some((format, data))) = (has_thumbnail, manifest.thumbnail()) {
+        ingredient
+            .set_thumbnail(format, data.into_owned())
+            .map_err(Error::from_c2pa_error)?;
+    }
+    Ok(ingredient)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{fs::remove_dir_all, path::PathBuf};
+    use c2pa::{CallbackSigner, SigningAlg};
+    use std::{fs::remove_dir_all, io::Cursor, path::PathBuf};
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
 
     /// returns a path to a file in the fixtures folder
     pub fn test_path(path: &str) -> String {
@@ -120,4 +676,144 @@ mod tests {
         assert!(PathBuf::from(data_dir).exists());
         assert!(json_report.contains("thumbnail"));
     }
+
+    #[test]
+    fn test_canonicalize_manifest_json() {
+        let a = r#"{"title": "a.jpg", "assertions": [{"label": "org.test", "data": {"b": 1, "a": 2}}]}"#;
+        let b = r#"{"assertions": [{"data": {"a": 2, "b": 1}, "label": "org.test"}], "title": "a.jpg"}"#;
+        let canonical = canonicalize_manifest_json(a).unwrap();
+        assert_eq!(canonical, canonicalize_manifest_json(b).unwrap());
+        assert!(canonical.contains("claim_generator_info"));
+        assert!(!canonical.contains("instance_id"));
+        assert!(canonical.find("\"assertions\"") < canonical.find("\"title\""));
+    }
+
+    #[test]
+    fn test_builder_json() {
+        let mut builder = Builder::from_json(r#"{"title": "a.jpg"}"#).unwrap();
+        builder
+            .add_ingredient_from_stream(
+                r#"{"title": "C.jpg", "relationship": "parentOf"}"#,
+                "image/jpeg",
+                &mut std::fs::File::open(test_path("tests/fixtures/C.jpg")).unwrap(),
+            )
+            .unwrap();
+        builder.set_remote_url("https://example.com/a.c2pa");
+        let json: Value = serde_json::from_str(&builder_json(&builder).unwrap()).unwrap();
+        assert_eq!(json["title"], "a.jpg");
+        assert_eq!(json["ingredients"][0]["title"], "C.jpg");
+        assert_eq!(json["remote_url"], "https://example.com/a.c2pa");
+        assert_eq!(json["no_embed"], false);
+    }
+
+    #[test]
+    fn test_manifest_labels() {
+        let reader = Reader::from_file(test_path("tests/fixtures/C.jpg")).unwrap();
+        let labels = manifest_labels(&reader);
+        assert_eq!(labels.len(), reader.iter_manifests().count());
+        assert_eq!(Some(labels[0].as_str()), reader.active_label());
+        let json = manifest_json(&reader, &labels[0]).unwrap();
+        assert!(json.contains("C.jpg"));
+        assert!(manifest_json(&reader, "missing").is_err());
+    }
+
+    #[test]
+    fn test_resource_list() {
+        let reader = Reader::from_file(test_path("tests/fixtures/C.jpg")).unwrap();
+        let resources = resource_list(&reader);
+        let thumbnail = resources
+            .iter()
+            .find(|resource| resource.uri.contains("thumbnail"))
+            .unwrap();
+        assert_eq!(thumbnail.format.as_deref(), Some("image/jpeg"));
+        let mut stream = Cursor::new(Vec::new());
+        let size = reader
+            .resource_to_stream(&thumbnail.uri, &mut stream)
+            .unwrap();
+        assert_eq!(size, thumbnail.size);
+    }
+
+    #[test]
+    fn test_validation_status_json() {
+        let mut data = std::fs::read(test_path("tests/fixtures/C.jpg")).unwrap();
+        let reader = Reader::from_stream("image/jpeg", Cursor::new(&data)).unwrap();
+        assert_eq!(validation_status_json(&reader).unwrap(), "[]");
+
+        // change a byte of the image data so the hard binding no longer matches
+        let len = data.len();
+        data[len - 10] ^= 0xff;
+        let reader = Reader::from_stream("image/jpeg", Cursor::new(&data)).unwrap();
+        let statuses: Value =
+            serde_json::from_str(&validation_status_json(&reader).unwrap()).unwrap();
+        let statuses = statuses.as_array().unwrap();
+        assert!(!statuses.is_empty());
+        assert!(statuses.iter().all(|status| status["code"].is_string()));
+        assert!(statuses
+            .iter()
+            .any(|status| status["explanation"].is_string() && status["url"].is_string()));
+    }
+
+    #[test]
+    fn test_validation_results() {
+        let mut data = std::fs::read(test_path("tests/fixtures/C.jpg")).unwrap();
+        let reader = Reader::from_stream("image/jpeg", Cursor::new(&data)).unwrap();
+        let results = validation_results(&reader);
+        assert!(results.active_manifest.failure.is_empty());
+        let manifest = reader.active_manifest().unwrap();
+        assert_eq!(
+            results.ingredient_deltas.len(),
+            manifest.ingredients().len()
+        );
+
+        let len = data.len();
+        data[len - 10] ^= 0xff;
+        let reader = Reader::from_stream("image/jpeg", Cursor::new(&data)).unwrap();
+        let results: Value =
+            serde_json::from_str(&validation_results_json(&reader).unwrap()).unwrap();
+        let failures = results["active_manifest"]["failure"].as_array().unwrap();
+        assert!(failures
+            .iter()
+            .any(|status| status["code"] == "assertion.dataHash.mismatch"));
+        assert!(results["active_manifest"]["success"].is_array());
+        assert!(results["ingredient_deltas"].is_array());
+    }
+
+    #[test]
+    fn test_ingredient_thumbnail() {
+        let mut builder = c2pa::Builder::from_json(r#"{"title": "A.jpg"}"#).unwrap();
+        let mut parent = std::fs::File::open(test_path("tests/fixtures/C.jpg")).unwrap();
+        builder
+            .add_ingredient_from_stream(r#"{"title": "C.jpg"}"#, "image/jpeg", &mut parent)
+            .unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let source = std::fs::read(test_path("tests/fixtures/A.jpg")).unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        builder
+            .sign(&signer, "image/jpeg", &mut Cursor::new(source), &mut dest)
+            .unwrap();
+        dest.set_position(0);
+        let reader = Reader::from_stream("image/jpeg", dest).unwrap();
+
+        let mut thumbnail = Vec::new();
+        let size = ingredient_thumbnail(&reader, None, 0, &mut thumbnail).unwrap();
+        assert_eq!(size, thumbnail.len());
+        assert_eq!(&thumbnail[..2], &[0xff, 0xd8]);
+        assert!(ingredient_thumbnail(&reader, None, 100, &mut thumbnail).is_err());
+        assert!(ingredient_thumbnail(&reader, Some("missing"), 0, &mut thumbnail).is_err());
+    }
+
+    #[test]
+    fn test_assertion_json() {
+        let reader = Reader::from_file(test_path("tests/fixtures/C.jpg")).unwrap();
+        let actions: Value =
+            serde_json::from_str(&assertion_json(&reader, None, "c2pa.actions", 1).unwrap())
+                .unwrap();
+        assert!(actions["actions"].is_array());
+        let label = reader.active_label().unwrap();
+        assert!(assertion_json(&reader, Some(label), "c2pa.actions", 1).is_ok());
+        assert!(assertion_json(&reader, None, "c2pa.actions", 2).is_err());
+        assert!(assertion_json(&reader, Some("missing"), "c2pa.actions", 1).is_err());
+    }
 }
@@ -0,0 +1,200 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Finds assertions in the raw JUMBF boxes of a manifest store.
+
+use crate::{Error, Result};
+
+const SUPERBOX: &[u8; 4] = b"jumb";
+const DESCRIPTION: &[u8; 4] = b"jumd";
+const CBOR: &[u8; 4] = b"cbor";
+const ASSERTION_STORE: &str = "c2pa.assertions";
+const CLAIMS: [&str; 2] = ["c2pa.claim.v2", "c2pa.claim"];
+// the description box toggle for a label
+const LABEL_TOGGLE: u8 = 0x02;
+
+// the type and payload of a box
+type JumbfBox<'a> = ([u8; 4], &'a [u8]);
+
+// Returns the type and payload of each box in the data.
+fn boxes(mut data: &[u8]) -> Result<Vec<JumbfBox<'_>>> {
+    let invalid = || Error::Decoding("invalid JUMBF box".to_string());
+    let mut boxes = Vec::new();
+    while data.len() >= 8 {
+        let size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let box_type = [data[4], data[5], data[6], data[7]];
+        let (header, size) = match size {
+            0 => (8, data.len()),
+            1 => {
+                let large = data.get(8..16).ok_or_else(invalid)?;
+                let size = u64::from_be_bytes(large.try_into().map_err(|_| invalid())?);
+                (16, usize::try_from(size).map_err(|_| invalid())?)
+            }
+            size => (8, size),
+        };
+        if size < header || size > data.len() {
+            return Err(invalid());
+        }
+        boxes.push((box_type, &data[header..size]));
+        data = &data[size..];
+    }
+    Ok(boxes)
+}
+
+// Returns the label of a superbox and its content boxes.
+fn superbox(payload: &[u8]) -> Result<(Option<&str>, Vec<JumbfBox<'_>>)> {
+    let mut children = boxes(payload)?.into_iter();
+    let description = match children.next() {
+        Some((box_type, description)) if &box_type == DESCRIPTION => description,
+        _ => return Err(Error::Decoding("missing JUMBF description box".to_string())),
+    };
+    // a 16 byte content type, the toggles and then the label if there is one
+    let label = match description.get(16) {
+        Some(toggles) if toggles & LABEL_TOGGLE != 0 => description[17..]
+            .split(|b| *b == 0)
+            .next()
+            .and_then(|label| std::str::from_utf8(label).ok()),
+        _ => None,
+    };
+    Ok((label, children.collect()))
+}
+
+// Returns the content boxes of the child superbox with the given label.
+fn child<'a>(children: &[JumbfBox<'a>], label: &str) -> Result<Option<Vec<JumbfBox<'a>>>> {
+    for (box_type, payload) in children {
+        if box_type != SUPERBOX {
+            continue;
+        }
+        let (child_label, content) = superbox(payload)?;
+        if child_label == Some(label) {
+            return Ok(Some(content));
+        }
+    }
+    Ok(None)
+}
+
+// Returns the content boxes of the manifest with the given label.
+fn manifest<'a>(store: &'a [u8], manifest_label: &str) -> Result<Vec<JumbfBox<'a>>> {
+    let (_, manifests) = match boxes(store)?.first() {
+        Some((box_type, payload)) if box_type == SUPERBOX => superbox(payload)?,
+        _ => return Err(Error::Decoding("missing manifest store box".to_string())),
+    };
+    child(&manifests, manifest_label)?
+        .ok_or_else(|| Error::ManifestNotFound(manifest_label.to_string()))
+}
+
+// Returns the payload of the CBOR box in a superbox's content boxes.
+fn cbor<'a>(content: &[JumbfBox<'a>]) -> Option<&'a [u8]> {
+    content
+        .iter()
+        .find(|(box_type, _)| box_type == CBOR)
+        .map(|(_, cbor)| *cbor)
+}
+
+/// Returns the CBOR of the claim of a manifest exactly as it is stored in a manifest store.
+pub(crate) fn claim_cbor(store: &[u8], manifest_label: &str) -> Result<Vec<u8>> {
+    let manifest = manifest(store, manifest_label)?;
+    for label in CLAIMS {
+        if let Some(content) = child(&manifest, label)? {
+            return cbor(&content)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| Error::Decoding("the claim is not CBOR".to_string()));
+        }
+    }
+    Err(Error::Decoding(format!(
+        "missing claim in {manifest_label}"
+    )))
+}
+
+/// Returns the CBOR of an assertion exactly as it is stored in a manifest store.
+///
+/// The assertion is found by label and instance in the manifest with the given label.
+/// The first instance of a label is 1.
+pub fn assertion_cbor(
+    store: &[u8],
+    manifest_label: &str,
+    assertion_label: &str,
+    instance: usize,
+) -> Result<Vec<u8>> {
+    let not_found = || Error::AssertionNotFound(format!("{assertion_label} instance {instance}"));
+    let manifest = manifest(store, manifest_label)?;
+    let assertions = child(&manifest, ASSERTION_STORE)?.ok_or_else(not_found)?;
+    // later instances are stored with the number of earlier ones appended to the label
+    let jumbf_label = match instance {
+        0 => return Err(not_found()),
+        1 => assertion_label.to_string(),
+        n => format!("{assertion_label}__{}", n - 1),
+    };
+    let content = child(&assertions, &jumbf_label)?.ok_or_else(not_found)?;
+    cbor(&content)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| Error::Assertion(format!("{assertion_label} is not a CBOR assertion")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use c2pa::{jumbf_io, Builder, CallbackSigner, Reader, SigningAlg};
+    use coset::cbor::value::Value as CborValue;
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    fn digest(fill: u8) -> CborValue {
+        CborValue::Map(vec![(
+            CborValue::Text("digest".to_string()),
+            CborValue::Bytes(vec![fill; 32]),
+        )])
+    }
+
+    #[test]
+    fn test_assertion_cbor() {
+        let mut builder = Builder::from_json(r#"{"title": "A.jpg"}"#).unwrap();
+        builder
+            .add_assertion("org.example.digest", &digest(1))
+            .unwrap()
+            .add_assertion("org.example.digest", &digest(2))
+            .unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let source = std::fs::read("tests/fixtures/A.jpg").unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        builder
+            .sign(&signer, "image/jpeg", &mut Cursor::new(source), &mut dest)
+            .unwrap();
+
+        dest.set_position(0);
+        let store = jumbf_io::load_jumbf_from_stream("image/jpeg", &mut dest).unwrap();
+        dest.set_position(0);
+        let reader = Reader::from_stream("image/jpeg", dest).unwrap();
+        let label = reader.active_label().unwrap();
+        for (instance, fill) in [(1, 1), (2, 2)] {
+            let cbor = assertion_cbor(&store, label, "org.example.digest", instance).unwrap();
+            let value: CborValue = coset::cbor::de::from_reader(cbor.as_slice()).unwrap();
+            assert_eq!(value, digest(fill));
+        }
+        assert!(matches!(
+            assertion_cbor(&store, label, "org.example.digest", 3),
+            Err(Error::AssertionNotFound(_))
+        ));
+        assert!(matches!(
+            assertion_cbor(&store, "urn:uuid:missing", "org.example.digest", 1),
+            Err(Error::ManifestNotFound(_))
+        ));
+        assert!(assertion_cbor(&store[..20], label, "org.example.digest", 1).is_err());
+    }
+}
@@ -10,18 +10,80 @@
 // specific language governing permissions and limitations under
 // each license.
 
+mod async_signer;
+mod batch;
 mod c_api;
 /// This module exports a C2PA library
 mod c_stream;
+mod capabilities;
+mod certs;
+mod content_id;
+mod cose;
 mod error;
+mod identity;
 mod json_api;
+mod jumbf;
+mod logging;
+mod network;
+mod ocsp;
+mod patch;
+#[cfg(feature = "pkcs11")]
+mod pkcs11;
+mod preflight;
+mod progress;
+mod refresh;
+mod remote;
+mod remote_signer;
+mod retry;
+mod riff;
+mod session;
+mod settings;
 mod signer_info;
+mod signer_options;
+mod soft_binding;
+mod stream_buffer;
+mod summary;
+mod thumbnail;
+mod timeline;
+mod trust;
+mod tsa;
+mod verify;
 
+pub use batch::{verify_directory, verify_paths, AssetReport, BatchOptions, BatchReport, Verdict};
 pub use c2pa::{
     AsyncSigner, Builder, Error as C2paError, Reader, Result as C2paResult, Signer, SigningAlg,
 };
 pub use c_api::*;
 pub use c_stream::*;
-pub use error::{Error, Result};
-pub use json_api::{read_file, read_ingredient_file, sdk_version, sign_file};
-pub use signer_info::SignerInfo;
+pub use capabilities::{version_info, VersionInfo};
+pub use certs::{
+    certificate_details, reader_cert_chain, reader_certificates, signature_details,
+    CertificateDetails, ExtensionDetails, SignatureDetails,
+};
+pub use content_id::{content_id, ContentId, HardBinding};
+pub use cose::{verify_cose_sign1, CoseVerification};
+pub use error::{C2paErrorCode, Error, Result};
+pub use identity::{decode_identity_assertion, identity_assertions, IdentityAssertion};
+pub use json_api::{
+    builder_json, canonicalize_manifest_json, hash_failures, hash_failures_json,
+    ingredient_from_reader, read_file, read_ingredient_file, resource_list, sdk_version, sign_file,
+    validation_results, HashFailure, IngredientDelta, ResourceInfo, StatusCodes, ValidationResults,
+};
+pub use jumbf::assertion_cbor;
+pub use patch::{manifest_placeholder, sign_placeholder, ManifestPatch};
+#[cfg(feature = "pkcs11")]
+pub use pkcs11::{pkcs11_signer, Pkcs11Key};
+pub use preflight::{definition_problems, validate_builder};
+pub use remote_signer::{remote_signer, RemotePayload, RemoteSignerOptions};
+pub use retry::RetryPolicy;
+pub use riff::RiffHashOptions;
+pub use session::SigningSession;
+pub use signer_info::{SignerInfo, SignerProblem};
+pub use signer_options::SignerOptions;
+pub use soft_binding::reader_from_soft_binding;
+pub use summary::summary;
+pub use thumbnail::{make_thumbnail, ThumbnailColorSpace, ThumbnailFormat, ThumbnailOptions};
+pub use timeline::{
+    add_action, add_action_json, add_timed_action, Timecode, TimedAction, TimelineEdit,
+};
+pub use tsa::TsaOptions;
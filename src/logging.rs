@@ -0,0 +1,182 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Forwards the log records of this library and the SDK to a host callback.
+
+use std::{cell::Cell, ffi::CString, os::raw::c_void, sync::OnceLock, sync::RwLock};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::{
+    c_api::{C2paLogLevel, LogCallback},
+    Error, Result,
+};
+
+#[derive(Clone, Copy)]
+struct Forwarder {
+    context: *const c_void,
+    callback: LogCallback,
+}
+
+// The host is responsible for making the context usable from any thread.
+unsafe impl Send for Forwarder {}
+unsafe impl Sync for Forwarder {}
+
+static FORWARDER: RwLock<Option<Forwarder>> = RwLock::new(None);
+
+thread_local! {
+    // set while the callback runs, so records it logs itself are dropped rather than recursing
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+// whether our logger was installed, which fails if an application embedding the crate
+// installed its own
+static INSTALLED: OnceLock<bool> = OnceLock::new();
+
+struct CallbackLogger;
+
+static LOGGER: CallbackLogger = CallbackLogger;
+
+impl From<Level> for C2paLogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => C2paLogLevel::LogError,
+            Level::Warn => C2paLogLevel::LogWarn,
+            Level::Info => C2paLogLevel::LogInfo,
+            Level::Debug => C2paLogLevel::LogDebug,
+            Level::Trace => C2paLogLevel::LogTrace,
+        }
+    }
+}
+
+impl From<C2paLogLevel> for LevelFilter {
+    fn from(level: C2paLogLevel) -> Self {
+        match level {
+            C2paLogLevel::LogOff => LevelFilter::Off,
+            C2paLogLevel::LogError => LevelFilter::Error,
+            C2paLogLevel::LogWarn => LevelFilter::Warn,
+            C2paLogLevel::LogInfo => LevelFilter::Info,
+            C2paLogLevel::LogDebug => LevelFilter::Debug,
+            C2paLogLevel::LogTrace => LevelFilter::Trace,
+        }
+    }
+}
+
+// Converts text for C, dropping any NUL characters rather than the whole record.
+fn c_text(text: String) -> CString {
+    CString::new(text.replace('\0', "")).unwrap_or_default()
+}
+
+impl Log for CallbackLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if FORWARDING.get() {
+            return;
+        }
+        // copied out of the lock, so the callback may change the callback itself
+        let Some(Forwarder { context, callback }) = FORWARDER.read().ok().and_then(|f| *f) else {
+            return;
+        };
+        let target = c_text(record.target().to_string());
+        let message = c_text(record.args().to_string());
+        FORWARDING.set(true);
+        unsafe {
+            callback(
+                context,
+                record.level().into(),
+                target.as_ptr(),
+                message.as_ptr(),
+            )
+        };
+        FORWARDING.set(false);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Registers the callback that receives log records at or above a level.
+///
+/// Passing None or LogOff removes the callback.
+pub fn set_callback(
+    context: *const c_void,
+    level: C2paLogLevel,
+    callback: Option<LogCallback>,
+) -> Result<()> {
+    if !*INSTALLED.get_or_init(|| log::set_logger(&LOGGER).is_ok()) {
+        return Err(Error::Other(
+            "another logger is installed in this process".to_string(),
+        ));
+    }
+    let mut forwarder = FORWARDER
+        .write()
+        .map_err(|_| Error::Other("log callback lock poisoned".to_string()))?;
+    *forwarder = callback
+        .filter(|_| level != C2paLogLevel::LogOff)
+        .map(|callback| Forwarder { context, callback });
+    log::set_max_level(match forwarder.is_some() {
+        true => level.into(),
+        false => LevelFilter::Off,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CStr, os::raw::c_char, sync::Mutex};
+
+    use super::*;
+
+    static RECORDS: Mutex<Vec<(C2paLogLevel, String, String)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn record(
+        _context: *const c_void,
+        level: C2paLogLevel,
+        target: *const c_char,
+        message: *const c_char,
+    ) {
+        let target = CStr::from_ptr(target).to_string_lossy().into_owned();
+        // other tests may log while the callback is set
+        if target == module_path!() {
+            let message = CStr::from_ptr(message).to_string_lossy().into_owned();
+            RECORDS.lock().unwrap().push((level, target, message));
+            // neither recurses nor deadlocks
+            log::error!("logged by the callback");
+            set_callback(std::ptr::null(), C2paLogLevel::LogInfo, Some(record)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_log_callback() {
+        set_callback(std::ptr::null(), C2paLogLevel::LogInfo, Some(record)).unwrap();
+        log::info!("fetching {}", "https://example.com/manifest");
+        log::debug!("below the level");
+        set_callback(std::ptr::null(), C2paLogLevel::LogOff, Some(record)).unwrap();
+        log::error!("after the callback is removed");
+
+        let records = RECORDS.lock().unwrap();
+        assert_eq!(
+            *records,
+            vec![(
+                C2paLogLevel::LogInfo,
+                module_path!().to_string(),
+                "fetching https://example.com/manifest".to_string()
+            )]
+        );
+    }
+}
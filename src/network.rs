@@ -0,0 +1,96 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Limits the number of simultaneous outbound requests made by the library.
+
+use std::sync::{Condvar, Mutex};
+
+struct Limit {
+    // zero means unlimited
+    max: usize,
+    active: usize,
+}
+
+static LIMIT: Mutex<Limit> = Mutex::new(Limit { max: 0, active: 0 });
+static RELEASED: Condvar = Condvar::new();
+
+/// Sets the maximum number of simultaneous TSA, OCSP and remote manifest requests.
+///
+/// A max of zero removes the limit.
+pub fn set_max_connections(max: usize) {
+    if let Ok(mut limit) = LIMIT.lock() {
+        limit.max = max;
+    }
+    RELEASED.notify_all();
+}
+
+struct Permit;
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Ok(mut limit) = LIMIT.lock() {
+            limit.active = limit.active.saturating_sub(1);
+        }
+        RELEASED.notify_one();
+    }
+}
+
+fn acquire() -> Option<Permit> {
+    let mut limit = LIMIT.lock().ok()?;
+    while limit.max != 0 && limit.active >= limit.max {
+        limit = RELEASED.wait(limit).ok()?;
+    }
+    limit.active += 1;
+    Some(Permit)
+}
+
+/// Runs an outbound request, waiting while the connection limit is reached.
+pub fn with_connection<T>(f: impl FnOnce() -> T) -> T {
+    let _permit = acquire();
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_connection_limit() {
+        static RUNNING: AtomicUsize = AtomicUsize::new(0);
+        static PEAK: AtomicUsize = AtomicUsize::new(0);
+        set_max_connections(2);
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                thread::spawn(|| {
+                    with_connection(|| {
+                        let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+                        PEAK.fetch_max(running, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(20));
+                        RUNNING.fetch_sub(1, Ordering::SeqCst);
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        set_max_connections(0);
+        assert!(PEAK.load(Ordering::SeqCst) <= 2);
+    }
+}
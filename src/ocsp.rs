@@ -0,0 +1,148 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Caches OCSP responses per signing certificate so they can be stapled
+//! to many manifests without contacting the responder for each one.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use c2pa_crypto::ocsp::{fetch_ocsp_response, OcspResponse};
+use c2pa_status_tracker::DetailedStatusTracker;
+
+use crate::{network, retry::RetryPolicy};
+
+struct CachedResponse {
+    der: Vec<u8>,
+    fetched: SystemTime,
+    next_update: SystemTime,
+}
+
+struct OcspCache {
+    // keyed by the DER of the end-entity certificate
+    responses: HashMap<Vec<u8>, CachedResponse>,
+    max_age: Option<Duration>,
+}
+
+static CACHE: Mutex<Option<OcspCache>> = Mutex::new(None);
+
+impl CachedResponse {
+    fn is_fresh(&self, max_age: Option<Duration>, now: SystemTime) -> bool {
+        if now >= self.next_update {
+            return false;
+        }
+        match max_age {
+            Some(max_age) => now
+                .duration_since(self.fetched)
+                .map(|age| age < max_age)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut OcspCache) -> T) -> Option<T> {
+    let mut cache = CACHE.lock().ok()?;
+    let cache = cache.get_or_insert_with(|| OcspCache {
+        responses: HashMap::new(),
+        max_age: None,
+    });
+    Some(f(cache))
+}
+
+/// Returns an OCSP response for a certificate chain, using the cache when it is still valid.
+///
+/// Responses are kept until their next update time or the configured max age, whichever comes first.
+/// Fetches are retried according to the retry policy.
+pub fn ocsp_response(certs: &[Vec<u8>], retry: &RetryPolicy) -> Option<Vec<u8>> {
+    let key = certs.first()?.clone();
+    let now = SystemTime::now();
+    let cached = with_cache(|cache| {
+        let max_age = cache.max_age;
+        cache
+            .responses
+            .get(&key)
+            .filter(|response| response.is_fresh(max_age, now))
+            .map(|response| response.der.clone())
+    })
+    .flatten();
+    if cached.is_some() {
+        return cached;
+    }
+
+    let der = retry
+        .run("OCSP fetch", || {
+            network::with_connection(|| fetch_ocsp_response(certs)).ok_or("no OCSP response")
+        })
+        .ok()?;
+    let mut log = DetailedStatusTracker::default();
+    let response = OcspResponse::from_der_checked(&der, None, &mut log).ok()?;
+    if response.revoked_at.is_some() {
+        return None;
+    }
+    let next_update: SystemTime = response.next_update.into();
+    with_cache(|cache| {
+        cache.responses.insert(
+            key,
+            CachedResponse {
+                der: der.clone(),
+                fetched: now,
+                next_update,
+            },
+        )
+    });
+    Some(der)
+}
+
+/// Removes all cached OCSP responses.
+pub fn clear() {
+    with_cache(|cache| cache.responses.clear());
+}
+
+/// Removes cached OCSP responses that are no longer valid.
+pub fn purge_expired() {
+    let now = SystemTime::now();
+    with_cache(|cache| {
+        let max_age = cache.max_age;
+        cache
+            .responses
+            .retain(|_, response| response.is_fresh(max_age, now))
+    });
+}
+
+/// Sets the maximum age for cached OCSP responses, or None to use only the response validity.
+pub fn set_max_age(max_age: Option<Duration>) {
+    with_cache(|cache| cache.max_age = max_age);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_response_freshness() {
+        let now = SystemTime::now();
+        let response = CachedResponse {
+            der: Vec::new(),
+            fetched: now - Duration::from_secs(60),
+            next_update: now + Duration::from_secs(3600),
+        };
+        assert!(response.is_fresh(None, now));
+        assert!(response.is_fresh(Some(Duration::from_secs(120)), now));
+        assert!(!response.is_fresh(Some(Duration::from_secs(30)), now));
+        assert!(!response.is_fresh(None, now + Duration::from_secs(7200)));
+    }
+}
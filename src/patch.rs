@@ -0,0 +1,163 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Placeholders and patches for muxers that embed the manifest themselves.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use c2pa::{assertions::DataHash, Builder, HashRange, Signer};
+
+use crate::{Error, Result};
+
+// unchanged runs shorter than this are folded into the surrounding patch
+const MIN_GAP: usize = 16;
+
+/// Bytes to write at an offset of an asset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManifestPatch {
+    pub offset: u64,
+    pub data: Vec<u8>,
+}
+
+/// Returns a placeholder manifest, framed for the format, that reserves space in an asset.
+///
+/// The caller writes the placeholder into the asset unchanged and then calls
+/// [`sign_placeholder`] with the finished asset.
+/// Use "application/c2pa" for an unframed manifest store.
+pub fn manifest_placeholder(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+) -> Result<Vec<u8>> {
+    builder
+        .data_hashed_placeholder(signer.reserve_size(), format)
+        .map_err(Error::from_c2pa_error)
+}
+
+// Returns the runs where the signed bytes differ from the placeholder.
+fn diff(offset: u64, placeholder: &[u8], signed: &[u8]) -> Vec<ManifestPatch> {
+    let mut patches: Vec<ManifestPatch> = Vec::new();
+    let mut end = 0;
+    let mut i = 0;
+    while i < signed.len() {
+        if placeholder[i] == signed[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < signed.len() && placeholder[i] != signed[i] {
+            i += 1;
+        }
+        match patches.last_mut() {
+            Some(patch) if start - end < MIN_GAP => patch.data.extend_from_slice(&signed[end..i]),
+            _ => patches.push(ManifestPatch {
+                offset: offset + start as u64,
+                data: signed[start..i].to_vec(),
+            }),
+        }
+        end = i;
+    }
+    patches
+}
+
+/// Signs an asset containing a placeholder from [`manifest_placeholder`] and
+/// returns the patches that replace the placeholder with the signed manifest.
+///
+/// Everything but the placeholder is hashed, so the asset must be complete.
+/// The patches only write within the placeholder, so the asset size does not change.
+pub fn sign_placeholder(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+    stream: &mut (impl Read + Seek),
+    offset: u64,
+    placeholder_len: usize,
+) -> Result<Vec<ManifestPatch>> {
+    let io_err = |e: std::io::Error| Error::Io(e.to_string());
+    let mut placeholder = vec![0u8; placeholder_len];
+    stream.seek(SeekFrom::Start(offset)).map_err(io_err)?;
+    stream.read_exact(&mut placeholder).map_err(io_err)?;
+
+    let mut data_hash = DataHash::new("jumbf manifest", "sha256");
+    data_hash.add_exclusion(HashRange::new(offset as usize, placeholder_len));
+    stream.rewind().map_err(io_err)?;
+    data_hash
+        .gen_hash_from_stream(stream)
+        .map_err(Error::from_c2pa_error)?;
+    let signed = builder
+        .sign_data_hashed_embeddable(signer, &data_hash, format)
+        .map_err(Error::from_c2pa_error)?;
+    if signed.len() != placeholder_len {
+        return Err(Error::Other(format!(
+            "signed manifest is {} bytes but the placeholder is {placeholder_len}",
+            signed.len()
+        )));
+    }
+    Ok(diff(offset, &placeholder, &signed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use c2pa::{CallbackSigner, Reader, SigningAlg};
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    #[test]
+    fn test_sign_placeholder() {
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let placeholder = manifest_placeholder(&mut builder, &signer, "image/jpeg").unwrap();
+
+        // mux the placeholder in after the start of image marker
+        let source = std::fs::read("tests/fixtures/A.jpg").unwrap();
+        let mut asset = source[..2].to_vec();
+        asset.extend_from_slice(&placeholder);
+        asset.extend_from_slice(&source[2..]);
+        let patches = sign_placeholder(
+            &mut builder,
+            &signer,
+            "image/jpeg",
+            &mut Cursor::new(&asset),
+            2,
+            placeholder.len(),
+        )
+        .unwrap();
+        assert!(!patches.is_empty());
+
+        let size = asset.len();
+        for patch in &patches {
+            let start = patch.offset as usize;
+            assert!(start >= 2 && start + patch.data.len() <= 2 + placeholder.len());
+            asset[start..start + patch.data.len()].copy_from_slice(&patch.data);
+        }
+        assert_eq!(asset.len(), size);
+        let reader = Reader::from_stream("image/jpeg", Cursor::new(&asset)).unwrap();
+        assert!(reader.validation_status().is_none());
+
+        assert_eq!(
+            diff(10, b"aaaaaaaa", b"abbaaaab"),
+            vec![ManifestPatch {
+                offset: 11,
+                data: b"bbaaaab".to_vec()
+            }]
+        );
+    }
+}
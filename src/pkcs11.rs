@@ -0,0 +1,194 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signs with a private key held by a PKCS#11 token, such as an HSM.
+
+use std::{collections::HashMap, io::Cursor, sync::Mutex};
+
+use c2pa::{hash_stream_by_alg, CallbackSigner, SigningAlg};
+use cryptoki::{
+    context::{CInitializeArgs, CInitializeFlags, Pkcs11},
+    error::{Error as Pkcs11Error, RvError},
+    mechanism::{
+        eddsa::{EddsaParams, EddsaSignatureScheme},
+        rsa::{PkcsMgfType, PkcsPssParams},
+        Mechanism, MechanismType,
+    },
+    object::{Attribute, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    slot::Slot,
+    types::AuthPin,
+};
+
+use crate::{signer_info::digest_alg, Error, Result};
+
+// One context per module path, since a module is initialized once for the whole process
+// and its sessions share the login state.
+static CONTEXTS: Mutex<Option<HashMap<String, Pkcs11>>> = Mutex::new(None);
+
+/// Identifies a private key on a PKCS#11 token.
+pub struct Pkcs11Key<'a> {
+    /// The path to the PKCS#11 module of the token.
+    pub module_path: &'a str,
+    /// The id of the slot holding the token.
+    pub slot: u64,
+    /// The user PIN, or None if the token needs no login.
+    pub pin: Option<&'a str>,
+    /// The label of the private key.
+    pub key_label: &'a str,
+}
+
+fn pkcs11_error(err: cryptoki::error::Error) -> Error {
+    Error::Signature(format!("PKCS#11 {err}"))
+}
+
+fn mechanism(alg: SigningAlg) -> Mechanism<'static> {
+    let pss = |hash_alg, mgf, s_len: u64| {
+        Mechanism::RsaPkcsPss(PkcsPssParams {
+            hash_alg,
+            mgf,
+            s_len: s_len.into(),
+        })
+    };
+    match alg {
+        SigningAlg::Es256 | SigningAlg::Es384 | SigningAlg::Es512 => Mechanism::Ecdsa,
+        SigningAlg::Ps256 => pss(MechanismType::SHA256, PkcsMgfType::MGF1_SHA256, 32),
+        SigningAlg::Ps384 => pss(MechanismType::SHA384, PkcsMgfType::MGF1_SHA384, 48),
+        SigningAlg::Ps512 => pss(MechanismType::SHA512, PkcsMgfType::MGF1_SHA512, 64),
+        SigningAlg::Ed25519 => Mechanism::Eddsa(EddsaParams::new(EddsaSignatureScheme::Pure)),
+    }
+}
+
+// Returns the context of a module, loading and initializing the module the first time.
+fn context(module_path: &str) -> Result<Pkcs11> {
+    let mut contexts = CONTEXTS.lock().unwrap_or_else(|err| err.into_inner());
+    let contexts = contexts.get_or_insert_with(HashMap::new);
+    if let Some(pkcs11) = contexts.get(module_path) {
+        return Ok(pkcs11.clone());
+    }
+    let pkcs11 = Pkcs11::new(module_path).map_err(pkcs11_error)?;
+    match pkcs11.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK)) {
+        // initialized by another library in the process
+        Ok(()) | Err(Pkcs11Error::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {}
+        Err(err) => return Err(pkcs11_error(err)),
+    }
+    contexts.insert(module_path.to_string(), pkcs11.clone());
+    Ok(pkcs11)
+}
+
+fn open_session(key: &Pkcs11Key) -> Result<(Session, ObjectHandle)> {
+    let pkcs11 = context(key.module_path)?;
+    let slot = Slot::try_from(key.slot).map_err(pkcs11_error)?;
+    let session = pkcs11.open_ro_session(slot).map_err(pkcs11_error)?;
+    if let Some(pin) = key.pin {
+        // the login is shared by the sessions of another signer for the same token
+        match session.login(UserType::User, Some(&AuthPin::from(pin))) {
+            Ok(()) | Err(Pkcs11Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+            Err(err) => return Err(pkcs11_error(err)),
+        }
+    }
+    let handle = session
+        .find_objects(&[
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::Label(key.key_label.as_bytes().to_vec()),
+        ])
+        .map_err(pkcs11_error)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Signature(format!("no private key labeled {}", key.key_label)))?;
+    Ok((session, handle))
+}
+
+/// Creates a signer that signs with a private key on a PKCS#11 token.
+///
+/// The session stays open, and logged in, for the life of the signer.
+pub fn pkcs11_signer(
+    key: &Pkcs11Key,
+    alg: SigningAlg,
+    certs: &[u8],
+    tsa_url: Option<String>,
+) -> Result<CallbackSigner> {
+    let (session, handle) = open_session(key)?;
+    // sessions must not be used from two threads at once
+    let session = Mutex::new(session);
+    let callback = move |_context: *const (), data: &[u8]| {
+        // ECDSA and RSA-PSS keys sign a digest we compute, since tokens differ in the
+        // combined hash and sign mechanisms they support
+        let digest;
+        let data = match digest_alg(alg) {
+            Some(digest_alg) => {
+                digest = hash_stream_by_alg(digest_alg, &mut Cursor::new(data), None, true)?;
+                &digest
+            }
+            None => data,
+        };
+        let session = session.lock().map_err(|_| c2pa::Error::CoseSignature)?;
+        session
+            .sign(&mechanism(alg), handle, data)
+            .map_err(|_| c2pa::Error::CoseSignature)
+    };
+    let mut signer = CallbackSigner::new(callback, alg, certs.to_vec());
+    if let Some(tsa_url) = tsa_url {
+        signer = signer.set_tsa_url(tsa_url);
+    }
+    Ok(signer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_module() {
+        let key = Pkcs11Key {
+            module_path: "missing-pkcs11-module.so",
+            slot: 0,
+            pin: None,
+            key_label: "c2pa",
+        };
+        // a module that failed to load is not kept, so it is tried again
+        for _ in 0..2 {
+            assert!(matches!(
+                pkcs11_signer(&key, SigningAlg::Es256, b"", None),
+                Err(Error::Signature(_))
+            ));
+        }
+        let contexts = CONTEXTS.lock().unwrap();
+        assert!(!contexts
+            .as_ref()
+            .is_some_and(|contexts| contexts.contains_key(key.module_path)));
+    }
+
+    #[test]
+    fn test_mechanism() {
+        assert!(matches!(mechanism(SigningAlg::Es384), Mechanism::Ecdsa));
+        for (alg, hash_alg, s_len) in [
+            (SigningAlg::Ps256, MechanismType::SHA256, 32),
+            (SigningAlg::Ps384, MechanismType::SHA384, 48),
+            (SigningAlg::Ps512, MechanismType::SHA512, 64),
+        ] {
+            let Mechanism::RsaPkcsPss(params) = mechanism(alg) else {
+                panic!("{alg} is not RSA-PSS");
+            };
+            assert_eq!(params.hash_alg, hash_alg);
+            assert_eq!(u64::from(params.s_len), s_len);
+        }
+        assert!(matches!(
+            mechanism(SigningAlg::Ed25519),
+            Mechanism::Eddsa(_)
+        ));
+        // the token signs a digest for every algorithm but Ed25519
+        assert!(digest_alg(SigningAlg::Es256).is_some());
+        assert!(digest_alg(SigningAlg::Ed25519).is_none());
+    }
+}
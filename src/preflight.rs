@@ -0,0 +1,195 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Checks a manifest definition for spec violations before signing.
+
+use c2pa::{
+    assertions::{c2pa_action, Action, Actions},
+    Builder, ManifestDefinition,
+};
+
+use crate::{Error, Result};
+
+// the action parameter the Builder resolves to ingredient references
+const INGREDIENT_IDS: &str = "org.cai.ingredientIds";
+
+// Returns the ingredient instance_ids an action refers to.
+fn ingredient_ids(action: &Action) -> Vec<String> {
+    match action.get_parameter(INGREDIENT_IDS) {
+        Some(ids) => serde_json::to_value(ids)
+            .ok()
+            .and_then(|ids| serde_json::from_value(ids).ok())
+            .unwrap_or_default(),
+        // the deprecated instance_id is used when there are no ingredient ids
+        #[allow(deprecated)]
+        None => action.instance_id().map(String::from).into_iter().collect(),
+    }
+}
+
+/// Returns the spec violations in a manifest definition, or an empty list if there are none.
+///
+/// Checks that there is at most one parentOf ingredient, that actions assertions can be
+/// decoded, that c2pa.created or c2pa.opened is only used as the first action, that
+/// c2pa.opened refers to the parentOf ingredient and c2pa.placed to an ingredient,
+/// and that every ingredient an action refers to is in the definition.
+pub fn definition_problems(definition: &ManifestDefinition) -> Vec<String> {
+    let mut problems = Vec::new();
+    let parents: Vec<&str> = definition
+        .ingredients
+        .iter()
+        .filter(|ingredient| ingredient.is_parent())
+        .map(|ingredient| ingredient.instance_id())
+        .collect();
+    if parents.len() > 1 {
+        problems.push(format!(
+            "{} ingredients are parentOf, but only one is allowed",
+            parents.len()
+        ));
+    }
+
+    for assertion in definition
+        .assertions
+        .iter()
+        .filter(|assertion| assertion.label.starts_with(Actions::LABEL))
+    {
+        // round trip through JSON so either CBOR or JSON assertion data can be decoded
+        let actions = serde_json::to_value(&assertion.data)
+            .map_err(|e| e.to_string())
+            .and_then(|value| serde_json::from_value::<Actions>(value).map_err(|e| e.to_string()));
+        let actions = match actions {
+            Ok(actions) => actions,
+            Err(e) => {
+                problems.push(format!("{} is malformed: {e}", assertion.label));
+                continue;
+            }
+        };
+        for (index, action) in actions.actions().iter().enumerate() {
+            let name = action.action();
+            if name.is_empty() {
+                problems.push(format!("action {index} has no name"));
+                continue;
+            }
+            let ids = ingredient_ids(action);
+            match name {
+                c2pa_action::CREATED | c2pa_action::OPENED if index > 0 => {
+                    problems.push(format!("{name} must be the first action"));
+                }
+                _ => {}
+            }
+            match name {
+                c2pa_action::CREATED if !parents.is_empty() => {
+                    problems.push(format!("{name} cannot be used with a parentOf ingredient"));
+                }
+                c2pa_action::OPENED if !ids.iter().any(|id| parents.contains(&id.as_str())) => {
+                    problems.push(format!("{name} must refer to the parentOf ingredient"));
+                }
+                c2pa_action::PLACED if ids.is_empty() => {
+                    problems.push(format!("{name} must refer to an ingredient"));
+                }
+                _ => {}
+            }
+            for id in ids.iter().filter(|id| {
+                !definition
+                    .ingredients
+                    .iter()
+                    .any(|ingredient| ingredient.instance_id() == id.as_str())
+            }) {
+                problems.push(format!("{name} refers to a missing ingredient {id}"));
+            }
+        }
+    }
+    problems
+}
+
+/// Checks the manifest definition of a Builder for spec violations before signing.
+///
+/// All of the problems found are reported in one error.
+pub fn validate_builder(builder: &Builder) -> Result<()> {
+    let problems = definition_problems(&builder.definition);
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(Error::Manifest(problems.join("; "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition_problems() {
+        let valid = Builder::from_json(
+            r#"{
+                "ingredients": [
+                    {"title": "parent.jpg", "instance_id": "xmp:iid:1", "relationship": "parentOf"},
+                    {"title": "logo.png", "instance_id": "xmp:iid:2", "relationship": "componentOf"}
+                ],
+                "assertions": [{"label": "c2pa.actions", "data": {"actions": [
+                    {"action": "c2pa.opened", "parameters": {"org.cai.ingredientIds": ["xmp:iid:1"]}},
+                    {"action": "c2pa.placed", "parameters": {"org.cai.ingredientIds": ["xmp:iid:2"]}},
+                    {"action": "c2pa.cropped"}
+                ]}}]
+            }"#,
+        )
+        .unwrap();
+        assert!(validate_builder(&valid).is_ok());
+
+        let invalid = Builder::from_json(
+            r#"{
+                "ingredients": [
+                    {"title": "a.jpg", "instance_id": "xmp:iid:1", "relationship": "parentOf"},
+                    {"title": "b.jpg", "instance_id": "xmp:iid:2", "relationship": "parentOf"}
+                ],
+                "assertions": [{"label": "c2pa.actions.v2", "data": {"actions": [
+                    {"action": "c2pa.cropped"},
+                    {"action": "c2pa.opened", "parameters": {"org.cai.ingredientIds": ["xmp:iid:3"]}},
+                    {"action": "c2pa.placed"}
+                ]}}]
+            }"#,
+        )
+        .unwrap();
+        let problems = definition_problems(&invalid.definition);
+        assert_eq!(
+            problems,
+            vec![
+                "2 ingredients are parentOf, but only one is allowed",
+                "c2pa.opened must be the first action",
+                "c2pa.opened must refer to the parentOf ingredient",
+                "c2pa.opened refers to a missing ingredient xmp:iid:3",
+                "c2pa.placed must refer to an ingredient",
+            ]
+        );
+        assert!(matches!(
+            validate_builder(&invalid),
+            Err(Error::Manifest(message)) if message.starts_with("2 ingredients are parentOf")
+        ));
+
+        let created = Builder::from_json(
+            r#"{
+                "ingredients": [{"title": "a.jpg", "relationship": "parentOf"}],
+                "assertions": [
+                    {"label": "c2pa.actions", "data": {"actions": [{"action": "c2pa.created"}]}},
+                    {"label": "c2pa.actions", "data": {"actions": "c2pa.created"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let problems = definition_problems(&created.definition);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(
+            problems[0],
+            "c2pa.created cannot be used with a parentOf ingredient"
+        );
+        assert!(problems[1].starts_with("c2pa.actions is malformed"));
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Reports progress through the asset streams of long reads and signings.
+
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    os::raw::c_void,
+    sync::RwLock,
+};
+
+use crate::{
+    c_api::{C2paProgressPhase, ProgressCallback},
+    Error, Result,
+};
+
+// the callback is invoked at most once per this many bytes, and at the end of the stream
+const REPORT_INTERVAL: u64 = 1024 * 1024;
+
+/// A progress callback and the context it is invoked with.
+#[derive(Clone, Copy)]
+pub(crate) struct Reporter {
+    context: *const c_void,
+    callback: ProgressCallback,
+}
+
+// The host is responsible for making the context usable from any thread.
+unsafe impl Send for Reporter {}
+unsafe impl Sync for Reporter {}
+
+static REPORTER: RwLock<Option<Reporter>> = RwLock::new(None);
+
+/// Registers the callback that reports progress while reading and signing assets.
+///
+/// Passing None removes the callback. Operations already started keep the callback
+/// that was registered when they started.
+pub fn set_callback(context: *const c_void, callback: Option<ProgressCallback>) -> Result<()> {
+    let mut reporter = REPORTER
+        .write()
+        .map_err(|_| Error::Other("progress callback lock poisoned".to_string()))?;
+    *reporter = callback.map(|callback| Reporter { context, callback });
+    Ok(())
+}
+
+/// Returns the registered callback, for an operation that is starting.
+pub(crate) fn registered() -> Option<Reporter> {
+    REPORTER.read().ok().and_then(|reporter| *reporter)
+}
+
+/// A stream that reports how far through it an operation is to a progress callback.
+///
+/// Progress is the furthest offset read or written, so reading parts of an asset
+/// more than once, as the SDK does, does not count them again.
+pub(crate) struct ProgressStream<S> {
+    inner: S,
+    reporter: Option<Reporter>,
+    phase: C2paProgressPhase,
+    total: u64,
+    position: u64,
+    processed: u64,
+    reported: Option<u64>,
+}
+
+impl<S: Seek> ProgressStream<S> {
+    /// Wraps a stream, taking the total from its length unless one is given.
+    pub(crate) fn new(
+        mut inner: S,
+        reporter: Option<Reporter>,
+        phase: C2paProgressPhase,
+        total: Option<u64>,
+    ) -> io::Result<Self> {
+        let position = inner.stream_position()?;
+        let total = match total {
+            Some(total) => total,
+            None => {
+                let end = inner.seek(SeekFrom::End(0))?;
+                inner.seek(SeekFrom::Start(position))?;
+                end
+            }
+        };
+        Ok(Self {
+            inner,
+            reporter,
+            phase,
+            total,
+            position,
+            processed: 0,
+            reported: None,
+        })
+    }
+
+    /// The total reported to the callback.
+    pub(crate) fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+impl<S> ProgressStream<S> {
+    fn advance(&mut self, len: usize) -> io::Result<()> {
+        self.position += len as u64;
+        let Some(Reporter { context, callback }) = self.reporter else {
+            return Ok(());
+        };
+        let processed = self.position.min(self.total);
+        if processed <= self.processed {
+            return Ok(());
+        }
+        self.processed = processed;
+        let due = match self.reported {
+            Some(reported) => processed - reported >= REPORT_INTERVAL || processed == self.total,
+            None => true,
+        };
+        if !due {
+            return Ok(());
+        }
+        self.reported = Some(processed);
+        if unsafe { callback(context, self.phase, processed, self.total) } != 0 {
+            return Err(io::Error::other("cancelled by the progress callback"));
+        }
+        Ok(())
+    }
+}
+
+impl<S: Read> Read for ProgressStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.advance(len)?;
+        Ok(len)
+    }
+}
+
+impl<S: Write> Write for ProgressStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.advance(len)?;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<S: Seek> Seek for ProgressStream<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, os::raw::c_int};
+
+    use super::*;
+
+    // the reports of one operation, given to the callback as its context
+    type Reports = Vec<(C2paProgressPhase, u64, u64)>;
+
+    unsafe extern "C" fn record(
+        context: *const c_void,
+        phase: C2paProgressPhase,
+        processed: u64,
+        total: u64,
+    ) -> c_int {
+        let reports = &mut *(context as *mut Reports);
+        reports.push((phase, processed, total));
+        // cancels writes
+        (phase == C2paProgressPhase::Write) as c_int
+    }
+
+    fn reporter(reports: &mut Reports) -> Option<Reporter> {
+        Some(Reporter {
+            context: reports as *mut Reports as *const c_void,
+            callback: record,
+        })
+    }
+
+    #[test]
+    fn test_progress_stream() {
+        let total = 3 * REPORT_INTERVAL + 7;
+        let data = vec![0u8; total as usize];
+        let mut reports = Reports::new();
+        let mut stream = ProgressStream::new(
+            Cursor::new(&data),
+            reporter(&mut reports),
+            C2paProgressPhase::Validate,
+            None,
+        )
+        .unwrap();
+        std::io::copy(&mut stream, &mut std::io::sink()).unwrap();
+        // reading again reports nothing more
+        stream.rewind().unwrap();
+        std::io::copy(&mut stream, &mut std::io::sink()).unwrap();
+
+        assert!(reports
+            .iter()
+            .all(|&(phase, _, t)| phase == C2paProgressPhase::Validate && t == total));
+        let processed: Vec<u64> = reports.iter().map(|&(_, processed, _)| processed).collect();
+        assert_eq!(*processed.last().unwrap(), total);
+        // reported once as reading starts, then at most once per interval until the end
+        for pair in processed[..processed.len() - 1].windows(2) {
+            assert!(pair[1] - pair[0] >= REPORT_INTERVAL);
+        }
+
+        let mut reports = Reports::new();
+        let mut dest = ProgressStream::new(
+            Cursor::new(Vec::new()),
+            reporter(&mut reports),
+            C2paProgressPhase::Write,
+            Some(100),
+        )
+        .unwrap();
+        assert!(dest.write_all(b"signed").is_err());
+        assert_eq!(reports, vec![(C2paProgressPhase::Write, 6, 100)]);
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Optional background refresh of trust data and OCSP caches for long running processes.
+
+use std::{
+    io::Read,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{network, ocsp, trust::load_trust, Error, Result};
+
+/// Options passed to c2pa_init.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct InitOptions {
+    /// Enables the background trust refresh when set.
+    pub trust_refresh: Option<TrustRefreshOptions>,
+    /// Runs all refresh work on the caller's thread when c2pa_refresh is called,
+    /// instead of starting a background thread.
+    pub single_threaded: bool,
+}
+
+/// Sources for the trust settings and how often to reload them.
+///
+/// Each source may be a file path or an http(s) URL.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TrustRefreshOptions {
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+    pub trust_anchors: Option<String>,
+    pub private_anchors: Option<String>,
+    pub allowed_list: Option<String>,
+    pub trust_config: Option<String>,
+}
+
+fn default_interval() -> u64 {
+    3600
+}
+
+enum Service {
+    // refreshes on a background thread
+    Thread {
+        stop: Sender<()>,
+        handle: JoinHandle<()>,
+    },
+    // refreshes only when refresh is called
+    Manual(TrustRefreshOptions),
+}
+
+static SERVICE: Mutex<Option<Service>> = Mutex::new(None);
+static SINGLE_THREADED: AtomicBool = AtomicBool::new(false);
+
+/// Returns true if c2pa_init asked for all work to stay on the caller's thread.
+pub fn single_threaded() -> bool {
+    SINGLE_THREADED.load(Ordering::Relaxed)
+}
+
+fn load_source(source: &str) -> Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        network::with_connection(|| {
+            let response = ureq::get(source)
+                .call()
+                .map_err(|e| Error::Other(format!("fetching {source} failed: {e}")))?;
+            let mut text = String::new();
+            response
+                .into_reader()
+                .read_to_string(&mut text)
+                .map_err(|e| Error::Io(e.to_string()))?;
+            Ok(text)
+        })
+    } else {
+        std::fs::read_to_string(source).map_err(|e| Error::Io(format!("{source}: {e}")))
+    }
+}
+
+// Reloads the trust settings from their sources and applies them.
+fn refresh_trust(options: &TrustRefreshOptions) -> Result<()> {
+    let mut trust = Map::new();
+    let sources = [
+        ("trust_anchors", &options.trust_anchors),
+        ("private_anchors", &options.private_anchors),
+        ("allowed_list", &options.allowed_list),
+        ("trust_config", &options.trust_config),
+    ];
+    for (key, source) in sources {
+        if let Some(source) = source {
+            trust.insert(key.to_string(), Value::String(load_source(source)?));
+        }
+    }
+    load_trust(trust)
+}
+
+/// Starts the background services described by the options.
+///
+/// Any running services are stopped first. The trust data is loaded once
+/// before returning so that configuration errors are reported to the caller;
+/// failures during later refreshes keep the last good trust data.
+pub fn init(options: InitOptions) -> Result<()> {
+    shutdown();
+    SINGLE_THREADED.store(options.single_threaded, Ordering::Relaxed);
+    let Some(trust_refresh) = options.trust_refresh else {
+        return Ok(());
+    };
+    refresh_trust(&trust_refresh)?;
+
+    if options.single_threaded {
+        if let Ok(mut service) = SERVICE.lock() {
+            *service = Some(Service::Manual(trust_refresh));
+        }
+        return Ok(());
+    }
+
+    let (stop, stopped) = mpsc::channel::<()>();
+    let interval = Duration::from_secs(trust_refresh.interval_secs.max(1));
+    let handle = std::thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            let _ = refresh_trust(&trust_refresh);
+            ocsp::purge_expired();
+        }
+    });
+    if let Ok(mut service) = SERVICE.lock() {
+        *service = Some(Service::Thread { stop, handle });
+    }
+    Ok(())
+}
+
+/// Runs one refresh cycle on the caller's thread.
+///
+/// Reloads the trust data when single threaded trust refresh is configured
+/// and removes expired OCSP responses from the cache.
+pub fn refresh() -> Result<()> {
+    let trust_refresh = match SERVICE.lock() {
+        Ok(service) => match service.as_ref() {
+            Some(Service::Manual(trust_refresh)) => Some(trust_refresh.clone()),
+            _ => None,
+        },
+        Err(_) => None,
+    };
+    if let Some(trust_refresh) = trust_refresh {
+        refresh_trust(&trust_refresh)?;
+    }
+    ocsp::purge_expired();
+    Ok(())
+}
+
+/// Stops the background services and waits for them to finish.
+pub fn shutdown() {
+    let service = match SERVICE.lock() {
+        Ok(mut service) => service.take(),
+        Err(_) => None,
+    };
+    if let Some(Service::Thread { stop, handle }) = service {
+        let _ = stop.send(());
+        let _ = handle.join();
+    }
+}
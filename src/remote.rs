@@ -0,0 +1,1016 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Routes remote manifest storage and retrieval through host callbacks or the built-in fetcher.
+
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs::{File, OpenOptions},
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+    os::raw::c_void,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        RwLock, RwLockReadGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use c2pa::{
+    format_from_path, hash_stream_by_alg, jumbf_io, settings::load_settings_from_str,
+    validation_status::ValidationStatus, Builder, Error as C2paError, ManifestDefinition, Reader,
+    Result as C2paResult, Signer,
+};
+use serde::Deserialize;
+
+use crate::{
+    c_api::{C2paProgressPhase, FetchCallback, UploadCallback},
+    content_id,
+    json_api::to_hex,
+    network,
+    progress::{self, ProgressStream},
+    retry::RetryPolicy,
+    riff, settings, thumbnail, Error, Result,
+};
+
+// the initial buffer size offered to a fetch callback
+const INITIAL_FETCH_SIZE: usize = 64 * 1024;
+// the largest manifest store accepted from a fetch, as the SDK allows
+const MAX_FETCH_SIZE: usize = 10 * 1024 * 1024;
+
+static SIDECAR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// A host callback and the context it is invoked with.
+#[derive(Clone, Copy)]
+struct HostCallback<F> {
+    context: *const c_void,
+    callback: F,
+}
+
+// The host is responsible for making the context usable from any thread.
+unsafe impl<F> Send for HostCallback<F> {}
+unsafe impl<F> Sync for HostCallback<F> {}
+
+/// Options for the built-in remote manifest fetcher.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FetchOptions {
+    /// Headers added to every remote manifest request, such as Authorization.
+    pub headers: HashMap<String, String>,
+    /// Retries for failed remote manifest requests.
+    pub retry: RetryPolicy,
+    /// The time allowed for each remote manifest request in milliseconds,
+    /// from connecting to reading the whole response. None waits as long as the OS allows.
+    ///
+    /// A get callback is not interrupted, but a manifest store it returns late is discarded
+    /// as timed out.
+    pub timeout_ms: Option<u64>,
+}
+
+struct RemoteConfig {
+    uploader: Option<HostCallback<UploadCallback>>,
+    fetcher: Option<HostCallback<FetchCallback>>,
+    fetch_options: Option<FetchOptions>,
+    fetch_disabled: bool,
+    // set while outbound requests are limited, so the SDK's own fetches don't escape the limit
+    connections_limited: bool,
+}
+
+impl RemoteConfig {
+    // True when remote manifests are fetched here rather than inside the SDK.
+    fn handles_fetch(&self) -> bool {
+        self.fetch_options.is_some() || self.fetcher.is_some() || self.connections_limited
+    }
+
+    // The options of the built-in fetcher, if it is used. The default options stand in
+    // for the SDK's own fetching while connections are limited.
+    fn builtin_options(&self) -> Option<FetchOptions> {
+        match self.connections_limited {
+            true => Some(self.fetch_options.clone().unwrap_or_default()),
+            false => self.fetch_options.clone(),
+        }
+    }
+}
+
+static REMOTE: RwLock<RemoteConfig> = RwLock::new(RemoteConfig {
+    uploader: None,
+    fetcher: None,
+    fetch_options: None,
+    fetch_disabled: false,
+    connections_limited: false,
+});
+
+fn read_config() -> Result<RwLockReadGuard<'static, RemoteConfig>> {
+    REMOTE
+        .read()
+        .map_err(|_| Error::Other("remote config lock poisoned".to_string()))
+}
+
+// Applies a change to the remote config and keeps the SDK fetch setting in sync.
+//
+// The config is the authority on whether remote manifests are fetched, so the SDK setting
+// is derived from it, and only written when the derived value differs.
+fn update_config(f: impl FnOnce(&mut RemoteConfig)) -> Result<()> {
+    let mut config = REMOTE
+        .write()
+        .map_err(|_| Error::Other("remote config lock poisoned".to_string()))?;
+    f(&mut config);
+    // turn off the internal remote manifest fetching when it is handled here or disabled
+    let sdk_fetch = !config.fetch_disabled && !config.handles_fetch();
+    if settings::current()?["verify"]["remote_manifest_fetch"].as_bool() == Some(sdk_fetch) {
+        return Ok(());
+    }
+    let settings = format!(r#"{{"verify": {{"remote_manifest_fetch": {sdk_fetch}}}}}"#);
+    load_settings_from_str(&settings, "json").map_err(Error::from_c2pa_error)
+}
+
+fn url_to_cstring(url: &str) -> Result<CString> {
+    CString::new(url).map_err(|_| Error::RemoteManifest(format!("invalid url {url}")))
+}
+
+/// Registers callbacks used to publish and retrieve remote manifests.
+///
+/// When a put callback is set, manifests signed with a remote URL are published with it.
+/// When a get callback is set, it replaces the internal HTTP client for remote manifests.
+/// Passing None for both callbacks removes the publisher.
+pub fn set_publisher(
+    context: *const c_void,
+    put: Option<UploadCallback>,
+    get: Option<FetchCallback>,
+) -> Result<()> {
+    update_config(|config| {
+        config.uploader = put.map(|callback| HostCallback { context, callback });
+        config.fetcher = get.map(|callback| HostCallback { context, callback });
+    })
+}
+
+/// Registers the callback used to retrieve remote manifests, keeping any put callback.
+///
+/// The callback replaces the internal HTTP client for remote manifests.
+/// Passing None restores the built-in fetching.
+pub fn set_fetcher(context: *const c_void, get: Option<FetchCallback>) -> Result<()> {
+    update_config(|config| {
+        config.fetcher = get.map(|callback| HostCallback { context, callback });
+    })
+}
+
+/// Sets the options used by the built-in remote manifest fetcher from a JSON string.
+///
+/// Passing None restores the default SDK fetching.
+pub fn set_fetch_options(options_json: Option<&str>) -> Result<()> {
+    let options = match options_json {
+        Some(json) => Some(
+            serde_json::from_str::<FetchOptions>(json).map_err(|e| Error::Json(e.to_string()))?,
+        ),
+        None => None,
+    };
+    update_config(|config| config.fetch_options = options)
+}
+
+/// Enables or disables fetching remote manifests.
+///
+/// While disabled no remote manifest requests are made, by the registered get callback,
+/// the built-in fetcher or the SDK, and reading an asset that refers to a remote manifest
+/// fails with a RemoteFetchDisabled error.
+pub fn set_fetch_enabled(enabled: bool) -> Result<()> {
+    update_config(|config| config.fetch_disabled = !enabled)
+}
+
+/// Sets the maximum number of simultaneous outbound requests, see [`network::set_max_connections`].
+///
+/// While a limit is set, remote manifests the SDK would fetch itself are fetched
+/// by the built-in fetcher instead, so that they count against the limit.
+pub fn set_max_connections(max: usize) -> Result<()> {
+    network::set_max_connections(max);
+    update_config(|config| config.connections_limited = max != 0)
+}
+
+/// True if remote manifest fetching was disabled with [`set_fetch_enabled`].
+pub fn fetch_disabled() -> bool {
+    read_config().is_ok_and(|config| config.fetch_disabled)
+}
+
+/// Publishes a manifest store to a remote URL with the registered put callback.
+///
+/// Returns false if no put callback is registered.
+pub fn publish(url: &str, data: &[u8]) -> Result<bool> {
+    // copied out of the lock, so the callback may register callbacks itself
+    let uploader = read_config()?.uploader;
+    match uploader {
+        Some(HostCallback { context, callback }) => {
+            let c_url = url_to_cstring(url)?;
+            let status = unsafe { callback(context, c_url.as_ptr(), data.as_ptr(), data.len()) };
+            if status < 0 {
+                return Err(Error::RemoteManifest(format!(
+                    "upload to {url} failed: {status}"
+                )));
+            }
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+// Retrieves a manifest store through a host fetch callback.
+//
+// The callback is invoked again at most once, with a buffer of the size it asked for.
+fn fetch_with_callback(
+    context: *const c_void,
+    get: FetchCallback,
+    url: &CString,
+) -> std::result::Result<Vec<u8>, String> {
+    let mut buffer = vec![0u8; INITIAL_FETCH_SIZE];
+    for _ in 0..2 {
+        let size = unsafe { get(context, url.as_ptr(), buffer.as_mut_ptr(), buffer.len()) };
+        if size < 0 {
+            return Err(format!("callback returned {size}"));
+        }
+        let size = size as usize;
+        if size <= buffer.len() {
+            buffer.truncate(size);
+            return Ok(buffer);
+        }
+        if size > MAX_FETCH_SIZE {
+            return Err(format!(
+                "manifest store of {size} bytes exceeds the limit of {MAX_FETCH_SIZE}"
+            ));
+        }
+        buffer.resize(size, 0);
+    }
+    Err(format!(
+        "callback asked for a larger buffer than the {} bytes it asked for before",
+        buffer.len()
+    ))
+}
+
+// Retrieves a manifest store over HTTP using the configured options.
+fn fetch_with_options(
+    options: &FetchOptions,
+    url: &str,
+) -> std::result::Result<Vec<u8>, Box<ureq::Error>> {
+    let mut agent = ureq::AgentBuilder::new();
+    if let Some(timeout_ms) = options.timeout_ms {
+        agent = agent.timeout(Duration::from_millis(timeout_ms));
+    }
+    let mut request = agent.build().get(url);
+    for (name, value) in &options.headers {
+        request = request.set(name, value);
+    }
+    let response = request.call().map_err(Box::new)?;
+    let mut data = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_FETCH_SIZE as u64 + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| Box::new(e.into()))?;
+    if data.len() > MAX_FETCH_SIZE {
+        let err = std::io::Error::other(format!("response exceeds {MAX_FETCH_SIZE} bytes"));
+        return Err(Box::new(err.into()));
+    }
+    Ok(data)
+}
+
+// True if a request failed because it ran out of time.
+fn is_timeout(err: &ureq::Error) -> bool {
+    std::error::Error::source(err)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            )
+        })
+}
+
+/// Retrieves a manifest store with the registered get callback or the built-in fetcher.
+///
+/// Failed attempts are retried according to the retry policy in the fetch options, and each
+/// attempt is allowed their timeout_ms, whichever of them fetches.
+/// Fails with a RemoteTimeout error if the last attempt ran out of time, and with
+/// a RemoteFetchDisabled error if fetching is disabled.
+/// Returns None if remote manifests are not fetched here.
+pub fn fetch(url: &str) -> Result<Option<Vec<u8>>> {
+    // copied out of the lock, so the callback may register callbacks itself
+    let (fetch_disabled, fetcher, fetch_options) = {
+        let config = read_config()?;
+        (
+            config.fetch_disabled,
+            config.fetcher,
+            config.builtin_options(),
+        )
+    };
+    if fetch_disabled {
+        log::info!("not fetching the remote manifest {url}, since fetching is disabled");
+        return Err(Error::RemoteFetchDisabled(url.to_string()));
+    }
+    log::info!("fetching the remote manifest {url}");
+    let policy = fetch_options
+        .as_ref()
+        .map(|options| options.retry.clone())
+        .unwrap_or_default();
+    let timeout_ms = fetch_options
+        .as_ref()
+        .and_then(|options| options.timeout_ms);
+    let what = format!("fetch from {url}");
+    let mut timed_out = false;
+    let result = match (fetcher, fetch_options.as_ref()) {
+        (Some(HostCallback { context, callback }), _) => {
+            let c_url = url_to_cstring(url)?;
+            policy.run(&what, || {
+                let started = Instant::now();
+                let result = fetch_with_callback(context, callback, &c_url);
+                let elapsed = started.elapsed();
+                let late = timeout_ms.filter(|&ms| elapsed > Duration::from_millis(ms));
+                timed_out = late.is_some();
+                match late {
+                    Some(ms) => Err(format!("callback took longer than {ms} ms")),
+                    None => result,
+                }
+            })
+        }
+        (_, Some(options)) => policy.run(&what, || {
+            let result = network::with_connection(|| fetch_with_options(options, url));
+            timed_out = result.as_ref().is_err_and(|e| is_timeout(e));
+            result
+        }),
+        _ => return Ok(None),
+    };
+    result.map(Some).map_err(|e| match timed_out {
+        true => Error::RemoteTimeout(e),
+        false => Error::RemoteManifest(e),
+    })
+}
+
+/// Where the manifest store of a Reader was read from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManifestSource {
+    /// The URL the manifest store was fetched from, if it was remote.
+    pub remote_url: Option<String>,
+    /// True if the manifest store was embedded in the asset.
+    pub embedded: bool,
+    /// The manifest store bytes as they were read, if known.
+    pub manifest_data: Option<Vec<u8>>,
+    /// The path of the sidecar file the manifest store was read from, if any.
+    pub sidecar_path: Option<String>,
+}
+
+impl ManifestSource {
+    /// The source of a manifest store embedded in the asset.
+    pub const EMBEDDED: Self = Self {
+        remote_url: None,
+        embedded: true,
+        manifest_data: None,
+        sidecar_path: None,
+    };
+    /// The source of a manifest store supplied apart from the asset, such as a sidecar.
+    pub const DETACHED: Self = Self {
+        remote_url: None,
+        embedded: false,
+        manifest_data: None,
+        sidecar_path: None,
+    };
+}
+
+/// Creates a Reader from a stream, resolving remote manifests through the registered publisher,
+/// and returns where the manifest store was read from.
+pub fn read_with_source(
+    format: &str,
+    stream: impl Read + Seek + Send,
+) -> C2paResult<(Reader, ManifestSource)> {
+    let mut stream = ProgressStream::new(
+        stream,
+        progress::registered(),
+        C2paProgressPhase::Validate,
+        None,
+    )?;
+    // the embedded store is loaded once, then kept for callers that archive it
+    match jumbf_io::load_jumbf_from_stream(format, &mut stream) {
+        Ok(manifest_data) => {
+            stream.seek(SeekFrom::Start(0))?;
+            let reader =
+                Reader::from_manifest_data_and_stream(&manifest_data, format, &mut stream)?;
+            let source = ManifestSource {
+                manifest_data: Some(manifest_data),
+                ..ManifestSource::EMBEDDED
+            };
+            return Ok((reader, source));
+        }
+        Err(C2paError::JumbfNotFound) => stream.seek(SeekFrom::Start(0))?,
+        Err(e) => return Err(e),
+    };
+    match Reader::from_stream(format, &mut stream) {
+        Err(C2paError::RemoteManifestUrl(url)) => match fetch(&url) {
+            Ok(Some(manifest_data)) => {
+                stream.seek(SeekFrom::Start(0))?;
+                let reader =
+                    Reader::from_manifest_data_and_stream(&manifest_data, format, &mut stream)?;
+                let source = ManifestSource {
+                    remote_url: Some(url),
+                    manifest_data: Some(manifest_data),
+                    ..ManifestSource::DETACHED
+                };
+                Ok((reader, source))
+            }
+            Ok(None) => Err(C2paError::RemoteManifestUrl(url)),
+            // carried whole, so the caller gets the RemoteTimeout or RemoteFetchDisabled kind
+            Err(e) => Err(C2paError::OtherError(Box::new(e))),
+        },
+        // the SDK fetched the remote manifest store itself
+        Ok(reader) => {
+            stream.seek(SeekFrom::Start(0))?;
+            let source = ManifestSource {
+                remote_url: content_id::remote_url(&mut stream),
+                ..ManifestSource::DETACHED
+            };
+            Ok((reader, source))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Enables or disables looking for a sidecar manifest store when reading files.
+///
+/// Discovery is disabled by default.
+pub fn set_sidecar_discovery(enabled: bool) {
+    SIDECAR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Creates a Reader from a file, taking the format from the file extension, and returns
+/// where the manifest store was read from.
+///
+/// If the file has no manifest store, one is read from a sidecar file next to it
+/// with the extension ".c2pa", if this was enabled with [`set_sidecar_discovery`].
+pub fn read_file_with_source(path: &Path) -> C2paResult<(Reader, ManifestSource)> {
+    read_file_with_sidecar(path, SIDECAR_ENABLED.load(Ordering::Relaxed))
+}
+
+// Creates a Reader from a file, reading a sidecar manifest store if the file has none
+// and sidecar is true.
+fn read_file_with_sidecar(path: &Path, sidecar: bool) -> C2paResult<(Reader, ManifestSource)> {
+    let format = format_from_path(path).ok_or(C2paError::UnsupportedType)?;
+    let file = File::open(path)?;
+    match read_with_source(&format, file) {
+        Err(C2paError::JumbfNotFound) if sidecar => {
+            let sidecar = path.with_extension("c2pa");
+            if !sidecar.is_file() {
+                return Err(C2paError::JumbfNotFound);
+            }
+            let manifest_data = std::fs::read(&sidecar)?;
+            let mut file = ProgressStream::new(
+                File::open(path)?,
+                progress::registered(),
+                C2paProgressPhase::Validate,
+                None,
+            )?;
+            let reader = Reader::from_manifest_data_and_stream(&manifest_data, &format, &mut file)?;
+            let source = ManifestSource {
+                manifest_data: Some(manifest_data),
+                sidecar_path: Some(sidecar.to_string_lossy().into_owned()),
+                ..ManifestSource::DETACHED
+            };
+            Ok((reader, source))
+        }
+        result => result,
+    }
+}
+
+/// Creates a Reader from the init segment of a fragmented BMFF asset and its fragments,
+/// also returning where the manifest store was read from and the validation statuses
+/// of each fragment, in order.
+///
+/// Each fragment is validated against the manifest store in the init segment. The Reader
+/// of the first fragment that fails validation is returned, or of the last fragment if all pass.
+/// Without fragments only the init segment is read.
+pub fn read_fragments_with_source<R, F>(
+    format: &str,
+    mut init_segment: R,
+    fragments: &mut [F],
+) -> C2paResult<(Reader, ManifestSource, Vec<Vec<ValidationStatus>>)>
+where
+    R: Read + Seek + Send,
+    F: Read + Seek + Send,
+{
+    if fragments.is_empty() {
+        let (reader, source) = read_with_source(format, init_segment)?;
+        return Ok((reader, source, Vec::new()));
+    }
+    // the Reader returned and whether its fragment failed
+    let mut reader = None;
+    let mut statuses = Vec::with_capacity(fragments.len());
+    for fragment in fragments.iter_mut() {
+        init_segment.seek(SeekFrom::Start(0))?;
+        fragment.seek(SeekFrom::Start(0))?;
+        let fragment_reader = Reader::from_fragment(format, &mut init_segment, fragment)?;
+        let status = fragment_reader.validation_status().unwrap_or_default();
+        let failed = !status.is_empty();
+        statuses.push(status.to_vec());
+        if !matches!(reader, Some((_, true))) {
+            reader = Some((fragment_reader, failed));
+        }
+    }
+    init_segment.seek(SeekFrom::Start(0))?;
+    let source = ManifestSource {
+        manifest_data: jumbf_io::load_jumbf_from_stream(format, &mut init_segment).ok(),
+        ..ManifestSource::EMBEDDED
+    };
+    let (reader, _) = reader.ok_or(C2paError::JumbfNotFound)?;
+    Ok((reader, source, statuses))
+}
+
+// Percent-encodes a value as one URL path segment, keeping only the characters RFC 3986
+// allows in a segment as they are. A value of only dots is encoded whole, since "." and
+// ".." would otherwise move to another path.
+fn encode_path_segment(value: &str) -> String {
+    let dots = !value.is_empty() && value.bytes().all(|byte| byte == b'.');
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        let kept = byte.is_ascii_alphanumeric()
+            || (b"-._~!$&'()*+,;=:@".contains(&byte) && !(dots && byte == b'.'));
+        match kept {
+            true => encoded.push(byte as char),
+            false => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Expands the placeholders in a remote URL template.
+///
+/// Supported placeholders are:
+/// * `{instance_id}`: the instance_id of the manifest definition.
+/// * `{filename}`: the title of the manifest definition.
+/// * `{manifest_hash}`: the hex SHA-256 of the manifest definition JSON.
+///
+/// The instance_id and title are percent-encoded as a URL path segment, so that
+/// characters such as `/`, `?`, `#` and spaces cannot change what the URL refers to.
+/// The manifest hash is taken over the definition rather than the signed manifest store,
+/// since the signed store is bound to the asset that carries the expanded URL.
+pub fn expand_remote_url(template: &str, definition: &ManifestDefinition) -> Result<String> {
+    let mut url = template.to_string();
+    if url.contains("{instance_id}") {
+        url = url.replace(
+            "{instance_id}",
+            &encode_path_segment(&definition.instance_id),
+        );
+    }
+    if url.contains("{filename}") {
+        let filename = definition.title.as_deref().unwrap_or_default();
+        url = url.replace("{filename}", &encode_path_segment(filename));
+    }
+    if url.contains("{manifest_hash}") {
+        let json = serde_json::to_vec(definition).map_err(|e| Error::Json(e.to_string()))?;
+        let hash = hash_stream_by_alg("sha256", &mut Cursor::new(json), None, true)
+            .map_err(Error::from_c2pa_error)?;
+        url = url.replace("{manifest_hash}", &to_hex(&hash));
+    }
+    Ok(url)
+}
+
+/// Signs a Builder, expanding any placeholders in its remote URL for this signing.
+///
+/// Returns the manifest store bytes and the expanded remote URL, if one was set.
+/// The Builder keeps the original URL template.
+pub fn sign_builder<R, W>(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+    source: &mut R,
+    dest: &mut W,
+) -> Result<(Vec<u8>, Option<String>)>
+where
+    R: Read + Seek + Send,
+    W: Write + Read + Seek + Send,
+{
+    let template = builder.remote_url.clone();
+    let remote_url = match template.as_deref() {
+        Some(template) => Some(expand_remote_url(template, &builder.definition)?),
+        None => None,
+    };
+    thumbnail::prepare_builder(builder, format, source)?;
+    let io_err = |e: std::io::Error| Error::Io(e.to_string());
+    let reporter = progress::registered();
+    let mut source =
+        ProgressStream::new(source, reporter, C2paProgressPhase::Hash, None).map_err(io_err)?;
+    let total = source.total();
+    let mut dest = ProgressStream::new(dest, reporter, C2paProgressPhase::Write, Some(total))
+        .map_err(io_err)?;
+    builder.remote_url.clone_from(&remote_url);
+    let result = match riff::sign_builder(builder, signer, format, &mut source, &mut dest) {
+        Ok(Some(manifest_bytes)) => Ok(manifest_bytes),
+        Ok(None) => builder
+            .sign(signer, format, &mut source, &mut dest)
+            .map_err(Error::from_c2pa_error),
+        Err(err) => Err(err),
+    };
+    builder.remote_url = template;
+    let manifest_bytes = result?;
+    // the SDK does not flush the destination, which may be holding buffered writes
+    dest.flush().map_err(io_err)?;
+    Ok((manifest_bytes, remote_url))
+}
+
+/// Signs a source file with a Builder and writes the result to a destination file.
+///
+/// The format is taken from the file extensions, which must agree, and the destination
+/// file is created or replaced. The title defaults to the destination file name,
+/// for this sign only.
+pub fn sign_builder_file(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    source_path: &Path,
+    dest_path: &Path,
+) -> Result<(Vec<u8>, Option<String>)> {
+    let format_of = |path: &Path| {
+        format_from_path(path).ok_or_else(|| Error::from_c2pa_error(C2paError::UnsupportedType))
+    };
+    let format = format_of(source_path)?;
+    if format != format_of(dest_path)? {
+        return Err(Error::Other(
+            "source and destination file formats must match".to_string(),
+        ));
+    }
+    let mut source = File::open(source_path).map_err(|e| Error::Io(e.to_string()))?;
+    if let Some(dir) = dest_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| Error::Io(e.to_string()))?;
+    }
+    let mut dest = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dest_path)
+        .map_err(|e| Error::Io(e.to_string()))?;
+    let title = builder.definition.title.clone();
+    if title.is_none() {
+        builder.definition.title = dest_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+    }
+    let result = sign_builder(builder, signer, &format, &mut source, &mut dest);
+    builder.definition.title = title;
+    result
+}
+
+// Numbers the staging directories of fragmented signings in this process.
+static STAGING: AtomicU64 = AtomicU64::new(0);
+
+/// Signs the init segment and fragments of a fragmented BMFF asset with a Builder,
+/// writing the signed copies to an output directory, which is created if needed.
+///
+/// Files already in the directory with the same names are replaced.
+/// The title defaults to the init segment file name.
+pub fn sign_fragmented_files(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    init_path: &Path,
+    fragments: &[PathBuf],
+    output_dir: &Path,
+) -> Result<()> {
+    let io_err = |e: std::io::Error| Error::Io(e.to_string());
+    let extension = init_path
+        .extension()
+        .ok_or_else(|| Error::from_c2pa_error(C2paError::UnsupportedType))?;
+    std::fs::create_dir_all(output_dir).map_err(io_err)?;
+    // the SDK takes the format from the directory it signs into, which must not exist yet,
+    // so the files are signed into a new one named for the format and then moved
+    let staging = output_dir
+        .join(format!(
+            ".c2pa_signing_{}_{}",
+            std::process::id(),
+            STAGING.fetch_add(1, Ordering::Relaxed)
+        ))
+        .with_extension(extension);
+    let title = builder.definition.title.clone();
+    if title.is_none() {
+        builder.definition.title = init_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+    }
+    let result = builder
+        .sign_fragmented_files(signer, init_path, &fragments.to_vec(), &staging)
+        .map_err(Error::from_c2pa_error)
+        .and_then(|_| {
+            for path in fragments.iter().map(PathBuf::as_path).chain([init_path]) {
+                let name = path
+                    .file_name()
+                    .ok_or_else(|| Error::Other(format!("{} has no file name", path.display())))?;
+                std::fs::rename(staging.join(name), output_dir.join(name)).map_err(io_err)?;
+            }
+            Ok(())
+        });
+    builder.definition.title = title;
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Cursor,
+        os::raw::{c_char, c_int, c_uchar},
+        sync::Mutex,
+    };
+
+    use c2pa::{Builder, CallbackSigner, SigningAlg};
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    static STORE: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+
+    unsafe extern "C" fn put(
+        _context: *const c_void,
+        url: *const c_char,
+        data: *const c_uchar,
+        len: usize,
+    ) -> c_int {
+        let url = std::ffi::CStr::from_ptr(url).to_string_lossy().into_owned();
+        let data = std::slice::from_raw_parts(data, len).to_vec();
+        STORE
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(url, data);
+        0
+    }
+
+    unsafe extern "C" fn get(
+        _context: *const c_void,
+        url: *const c_char,
+        buffer: *mut c_uchar,
+        buffer_len: usize,
+    ) -> isize {
+        let url = std::ffi::CStr::from_ptr(url).to_string_lossy();
+        let store = STORE.lock().unwrap();
+        match store.as_ref().and_then(|s| s.get(url.as_ref())) {
+            Some(data) => {
+                if data.len() <= buffer_len {
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+                }
+                data.len() as isize
+            }
+            None => -1,
+        }
+    }
+
+    // a get callback that answers too late
+    unsafe extern "C" fn get_slow(
+        context: *const c_void,
+        url: *const c_char,
+        buffer: *mut c_uchar,
+        buffer_len: usize,
+    ) -> isize {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        get(context, url, buffer, buffer_len)
+    }
+
+    // a put callback that registers callbacks itself
+    unsafe extern "C" fn put_and_register(
+        context: *const c_void,
+        url: *const c_char,
+        data: *const c_uchar,
+        len: usize,
+    ) -> c_int {
+        set_publisher(context, Some(put), Some(get)).unwrap();
+        put(context, url, data, len)
+    }
+
+    #[test]
+    fn test_publisher_round_trip() {
+        set_publisher(std::ptr::null(), Some(put), Some(get)).unwrap();
+
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let url = "http://manifests.example.com/test.c2pa";
+        builder.set_remote_url(url);
+        builder.set_no_embed(true);
+        let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+        let mut output = Cursor::new(Vec::new());
+        let manifest_bytes = builder
+            .sign(&signer, "image/jpeg", &mut source, &mut output)
+            .unwrap();
+        set_publisher(std::ptr::null(), Some(put_and_register), None).unwrap();
+        assert!(publish(url, &manifest_bytes).unwrap());
+
+        output.set_position(0);
+        let (reader, source) = read_with_source("image/jpeg", &mut output).unwrap();
+        assert!(reader.active_label().is_some());
+        assert_eq!(source.remote_url.as_deref(), Some(url));
+        assert!(!source.embedded);
+
+        // a fetcher replaces the get callback and keeps the put callback
+        set_fetcher(std::ptr::null(), None).unwrap();
+        assert!(fetch(url).unwrap().is_none());
+        assert!(publish(url, &manifest_bytes).unwrap());
+        set_fetcher(std::ptr::null(), Some(get)).unwrap();
+        assert!(fetch(url).unwrap().is_some());
+
+        // the timeout covers the get callback too
+        set_fetcher(std::ptr::null(), Some(get_slow)).unwrap();
+        set_fetch_options(Some(r#"{"timeout_ms": 10}"#)).unwrap();
+        assert!(matches!(fetch(url), Err(Error::RemoteTimeout(_))));
+        set_fetch_options(None).unwrap();
+        assert!(fetch(url).unwrap().is_some());
+
+        set_publisher(std::ptr::null(), None, None).unwrap();
+        assert!(!publish(url, &manifest_bytes).unwrap());
+    }
+
+    #[test]
+    fn test_sign_builder_file() {
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let dest = std::env::temp_dir().join("c2pa_c_sign_file").join("A.jpg");
+        let source = Path::new("tests/fixtures/A.jpg");
+        let (manifest_bytes, remote_url) =
+            sign_builder_file(&mut builder, &signer, source, &dest).unwrap();
+        assert!(!manifest_bytes.is_empty());
+        assert!(remote_url.is_none());
+        let reader = Reader::from_file(&dest).unwrap();
+        assert_eq!(reader.active_manifest().unwrap().title(), Some("A.jpg"));
+        // the default title is not kept for the next sign
+        assert!(builder.definition.title.is_none());
+
+        let png = dest.with_extension("png");
+        assert!(sign_builder_file(&mut builder, &signer, source, &png).is_err());
+    }
+
+    #[test]
+    fn test_read_fragments() {
+        let init = std::fs::read("tests/fixtures/C.jpg").unwrap();
+        let no_fragments: &mut [Cursor<Vec<u8>>] = &mut [];
+        let (reader, source, statuses) =
+            read_fragments_with_source("image/jpeg", Cursor::new(&init), no_fragments).unwrap();
+        assert!(reader.active_label().is_some());
+        assert!(source.embedded && source.manifest_data.is_some());
+        assert!(statuses.is_empty());
+
+        // only BMFF assets are fragmented
+        let fragment = Cursor::new(std::fs::read("tests/fixtures/A.jpg").unwrap());
+        assert!(
+            read_fragments_with_source("image/jpeg", Cursor::new(&init), &mut [fragment]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_expand_remote_url() {
+        let mut definition = ManifestDefinition::default();
+        definition.title = Some("A.jpg".to_string());
+        definition.instance_id = "xmp:iid:1234".to_string();
+        let url = expand_remote_url(
+            "https://example.com/{instance_id}/{filename}/{manifest_hash}.c2pa",
+            &definition,
+        )
+        .unwrap();
+        assert!(url.starts_with("https://example.com/xmp:iid:1234/A.jpg/"));
+        assert_eq!(
+            url.len(),
+            "https://example.com/xmp:iid:1234/A.jpg/.c2pa".len() + 64
+        );
+        assert_eq!(
+            expand_remote_url("https://example.com/fixed.c2pa", &definition).unwrap(),
+            "https://example.com/fixed.c2pa"
+        );
+
+        // titles cannot break out of their path segment
+        let expand = |title: &str| {
+            let mut definition = ManifestDefinition::default();
+            definition.title = Some(title.to_string());
+            expand_remote_url("https://example.com/{filename}.c2pa", &definition).unwrap()
+        };
+        assert_eq!(
+            expand("My Photo #1.jpg"),
+            "https://example.com/My%20Photo%20%231.jpg.c2pa"
+        );
+        assert_eq!(expand("a?b%.jpg"), "https://example.com/a%3Fb%25.jpg.c2pa");
+        assert_eq!(
+            expand("../other.c2pa"),
+            "https://example.com/..%2Fother.c2pa.c2pa"
+        );
+        assert_eq!(expand("é.jpg"), "https://example.com/%C3%A9.jpg.c2pa");
+        assert_eq!(encode_path_segment(".."), "%2E%2E");
+    }
+
+    // a get callback that always asks for a larger buffer
+    unsafe extern "C" fn get_growing(
+        _context: *const c_void,
+        _url: *const c_char,
+        _buffer: *mut c_uchar,
+        buffer_len: usize,
+    ) -> isize {
+        (buffer_len + 1) as isize
+    }
+
+    // a get callback that asks for more than a manifest store may be
+    unsafe extern "C" fn get_huge(
+        _context: *const c_void,
+        _url: *const c_char,
+        _buffer: *mut c_uchar,
+        _buffer_len: usize,
+    ) -> isize {
+        isize::MAX
+    }
+
+    #[test]
+    fn test_fetch_with_callback_bounded() {
+        let url = CString::new("https://example.com/manifest.c2pa").unwrap();
+        let err = fetch_with_callback(std::ptr::null(), get_growing, &url).unwrap_err();
+        assert!(err.contains("larger buffer"), "{err}");
+        let err = fetch_with_callback(std::ptr::null(), get_huge, &url).unwrap_err();
+        assert!(err.contains("exceeds the limit"), "{err}");
+    }
+
+    #[test]
+    fn test_connections_limited() {
+        // the config is process-wide, so these are checked on a config of their own
+        let mut config = RemoteConfig {
+            uploader: None,
+            fetcher: None,
+            fetch_options: None,
+            fetch_disabled: false,
+            connections_limited: false,
+        };
+        assert!(!config.handles_fetch());
+        assert!(config.builtin_options().is_none());
+        // the built-in fetcher takes over from the SDK, so its requests count against the limit
+        config.connections_limited = true;
+        assert!(config.handles_fetch());
+        assert!(config.builtin_options().is_some());
+        config.fetch_options = Some(FetchOptions {
+            timeout_ms: Some(100),
+            ..Default::default()
+        });
+        assert_eq!(config.builtin_options().unwrap().timeout_ms, Some(100));
+    }
+
+    #[test]
+    fn test_fetch_options() {
+        let options: FetchOptions =
+            serde_json::from_str(r#"{"headers": {"Authorization": "Bearer token"}}"#).unwrap();
+        assert_eq!(options.headers["Authorization"], "Bearer token");
+        assert!(set_fetch_options(Some("{\"headers\": []}")).is_err());
+    }
+
+    #[test]
+    fn test_sidecar_discovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let asset = dir.path().join("A.jpg");
+        std::fs::copy("tests/fixtures/A.jpg", &asset).unwrap();
+        let mut builder = Builder::from_json(r#"{"title": "A.jpg"}"#).unwrap();
+        builder.set_no_embed(true);
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let manifest = builder
+            .sign(
+                &signer,
+                "image/jpeg",
+                &mut File::open(&asset).unwrap(),
+                &mut Cursor::new(Vec::new()),
+            )
+            .unwrap();
+        std::fs::write(dir.path().join("A.c2pa"), &manifest).unwrap();
+
+        let (reader, source) = read_file_with_sidecar(&asset, true).unwrap();
+        assert!(reader.validation_status().is_none());
+        assert!(!source.embedded);
+        assert!(source.sidecar_path.unwrap().ends_with("A.c2pa"));
+        assert_eq!(source.manifest_data, Some(manifest));
+
+        let result = read_file_with_sidecar(&asset, false);
+        assert!(matches!(result, Err(C2paError::JumbfNotFound)));
+    }
+
+    #[test]
+    fn test_fetch_timeout() {
+        // a server that accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/manifest.c2pa", listener.local_addr().unwrap());
+        let options: FetchOptions = serde_json::from_str(r#"{"timeout_ms": 200}"#).unwrap();
+        let err = fetch_with_options(&options, &url).unwrap_err();
+        assert!(is_timeout(&err), "{err}");
+
+        drop(listener);
+        let err = fetch_with_options(&options, &url).unwrap_err();
+        assert!(!is_timeout(&err), "{err}");
+    }
+}
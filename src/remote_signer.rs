@@ -0,0 +1,334 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signs by posting to a signing service over HTTP.
+
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    net::IpAddr,
+    time::Duration,
+};
+
+use c2pa::{hash_stream_by_alg, CallbackSigner, SigningAlg};
+use serde::Deserialize;
+
+use crate::{network, retry::RetryPolicy, signer_info::digest_alg, Error, Result};
+
+/// What is posted to the signing service.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemotePayload {
+    /// The bytes to sign.
+    #[default]
+    Data,
+    /// The digest of the bytes to sign, for services that sign a prehashed message.
+    Digest,
+}
+
+/// Options for a signer that posts to a signing service.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RemoteSignerOptions {
+    /// The URL of the signing service.
+    pub url: String,
+    /// Headers added to every signing request, such as Authorization.
+    pub headers: HashMap<String, String>,
+    /// What is posted to the signing service.
+    pub payload: RemotePayload,
+    /// Retries for failed signing requests.
+    pub retry: RetryPolicy,
+    /// The time allowed for each signing request in milliseconds.
+    /// None waits as long as the OS allows.
+    pub timeout_ms: Option<u64>,
+}
+
+impl RemoteSignerOptions {
+    /// Create RemoteSignerOptions from a JSON string
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))
+    }
+}
+
+// the largest signature a signing service may return
+const MAX_SIGNATURE_SIZE: usize = 64 * 1024;
+
+// True if a URL is https, or http to this machine, so requests carrying credentials
+// and the data to sign are not sent in the clear.
+fn is_secure_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    if lower.starts_with("https://") {
+        return true;
+    }
+    let Some(rest) = lower.strip_prefix("http://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+// True for errors worth retrying, which excludes requests the service rejected,
+// such as for failed authentication.
+fn is_retryable(e: &ureq::Error) -> bool {
+    !matches!(e, ureq::Error::Status(400..=499, _))
+}
+
+// Posts the payload and returns the response body as the signature.
+fn post(
+    options: &RemoteSignerOptions,
+    payload: &[u8],
+) -> std::result::Result<Vec<u8>, Box<ureq::Error>> {
+    let mut agent = ureq::AgentBuilder::new();
+    if let Some(timeout_ms) = options.timeout_ms {
+        agent = agent.timeout(Duration::from_millis(timeout_ms));
+    }
+    let mut request = agent
+        .build()
+        .post(&options.url)
+        .set("Content-Type", "application/octet-stream");
+    for (name, value) in &options.headers {
+        request = request.set(name, value);
+    }
+    let response = request.send_bytes(payload).map_err(Box::new)?;
+    let mut signature = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_SIGNATURE_SIZE as u64 + 1)
+        .read_to_end(&mut signature)
+        .map_err(|e| Box::new(e.into()))?;
+    if signature.len() > MAX_SIGNATURE_SIZE {
+        let message = format!("signature is larger than {MAX_SIGNATURE_SIZE} bytes");
+        return Err(Box::new(
+            std::io::Error::new(std::io::ErrorKind::InvalidData, message).into(),
+        ));
+    }
+    Ok(signature)
+}
+
+/// Creates a signer that posts to a signing service and uses the response body as the signature.
+///
+/// The request body is the bytes to sign, or their digest, and the response body must be
+/// the raw signature, such as the r and s values of an ECDSA signature.
+/// The url must be https, except for services on this machine.
+/// Requests the service rejects with a 4xx status are not retried.
+pub fn remote_signer(
+    options: RemoteSignerOptions,
+    alg: SigningAlg,
+    certs: &[u8],
+    tsa_url: Option<String>,
+) -> Result<CallbackSigner> {
+    if options.url.is_empty() {
+        return Err(Error::Signature(
+            "the signing service url is missing".to_string(),
+        ));
+    }
+    if !is_secure_url(&options.url) {
+        return Err(Error::Signature(format!(
+            "the signing service url must be https: {}",
+            options.url
+        )));
+    }
+    let digest_alg = match options.payload {
+        RemotePayload::Data => None,
+        RemotePayload::Digest => {
+            let digest_alg = digest_alg(alg).ok_or_else(|| {
+                Error::NotSupported(format!("{alg} signs the data itself, not a digest"))
+            })?;
+            Some(digest_alg)
+        }
+    };
+    let callback = move |_context: *const (), data: &[u8]| {
+        let payload = match digest_alg {
+            Some(alg) => hash_stream_by_alg(alg, &mut Cursor::new(data), None, true)?,
+            None => data.to_vec(),
+        };
+        let what = format!("signing with {}", options.url);
+        options
+            .retry
+            .run_retrying(
+                &what,
+                || network::with_connection(|| post(&options, &payload)),
+                |e| is_retryable(e),
+            )
+            .map_err(|e| c2pa::Error::OtherError(Box::new(Error::Signature(e))))
+    };
+    let mut signer = CallbackSigner::new(callback, alg, certs.to_vec());
+    if let Some(tsa_url) = tsa_url {
+        signer = signer.set_tsa_url(tsa_url);
+    }
+    Ok(signer)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use c2pa::Signer;
+
+    use super::*;
+
+    // Answers one request with the body reversed, so the signature depends on the payload.
+    fn serve_once(listener: TcpListener) -> thread::JoinHandle<Vec<String>> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                headers.push(line.trim().to_lowercase());
+            }
+            let len: usize = headers
+                .iter()
+                .find_map(|h| h.strip_prefix("content-length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let mut body = vec![0u8; len];
+            reader.read_exact(&mut body).unwrap();
+            body.reverse();
+            let mut stream = reader.into_inner();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {len}\r\n\r\n").unwrap();
+            stream.write_all(&body).unwrap();
+            headers
+        })
+    }
+
+    // Answers one request with a status and body, then stops listening.
+    fn respond_once(
+        listener: TcpListener,
+        status: &'static str,
+        body: Vec<u8>,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            drop(listener);
+            let mut reader = BufReader::new(stream);
+            let mut len = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length: ") {
+                    len = value.trim().parse().unwrap();
+                }
+            }
+            reader.read_exact(&mut vec![0u8; len]).unwrap();
+            let mut stream = reader.into_inner();
+            let head = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            // the client may stop reading an oversized body
+            let _ = stream.write_all(&body);
+        })
+    }
+
+    #[test]
+    fn test_remote_signer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/sign", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+        let options = RemoteSignerOptions::from_json(&format!(
+            r#"{{"url": "{url}", "headers": {{"Authorization": "Bearer token"}}}}"#
+        ))
+        .unwrap();
+        let signer = remote_signer(options, SigningAlg::Ed25519, b"", None).unwrap();
+        assert_eq!(signer.sign(b"abc").unwrap(), b"cba");
+        let headers = server.join().unwrap();
+        assert!(headers.contains(&"authorization: bearer token".to_string()));
+    }
+
+    #[test]
+    fn test_remote_signer_digest() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/sign", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+        let options = RemoteSignerOptions {
+            url,
+            payload: RemotePayload::Digest,
+            ..Default::default()
+        };
+        let signer = remote_signer(options.clone(), SigningAlg::Es256, b"", None).unwrap();
+        let mut expected =
+            hash_stream_by_alg("sha256", &mut Cursor::new(b"abc"), None, true).unwrap();
+        expected.reverse();
+        assert_eq!(signer.sign(b"abc").unwrap(), expected);
+        server.join().unwrap();
+
+        assert!(remote_signer(options, SigningAlg::Ed25519, b"", None).is_err());
+        assert!(
+            remote_signer(RemoteSignerOptions::default(), SigningAlg::Es256, b"", None).is_err()
+        );
+    }
+
+    #[test]
+    fn test_remote_signer_rejections() {
+        let options = |url: String| RemoteSignerOptions {
+            url,
+            retry: RetryPolicy {
+                retries: 2,
+                backoff_ms: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // a rejected request is not retried
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/sign", listener.local_addr().unwrap());
+        let server = respond_once(listener, "401 Unauthorized", Vec::new());
+        let signer = remote_signer(options(url), SigningAlg::Ed25519, b"", None).unwrap();
+        let err = signer.sign(b"abc").unwrap_err().to_string();
+        assert!(err.contains("401") && !err.contains("attempt 2"), "{err}");
+        server.join().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/sign", listener.local_addr().unwrap());
+        let server = respond_once(listener, "200 OK", vec![0; MAX_SIGNATURE_SIZE + 1]);
+        let signer = remote_signer(options(url), SigningAlg::Ed25519, b"", None).unwrap();
+        let err = signer.sign(b"abc").unwrap_err().to_string();
+        assert!(err.contains("larger than"), "{err}");
+        server.join().unwrap();
+
+        for url in [
+            "http://sign.example.com/",
+            "ftp://localhost/",
+            "http://127.0.0.1.example.com/",
+        ] {
+            assert!(remote_signer(options(url.into()), SigningAlg::Ed25519, b"", None).is_err());
+        }
+        for url in [
+            "https://sign.example.com/",
+            "http://localhost:8080/",
+            "http://[::1]/",
+        ] {
+            assert!(is_secure_url(url), "{url}");
+        }
+    }
+}
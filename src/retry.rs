@@ -0,0 +1,132 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Retries network operations with exponential backoff.
+
+use std::{fmt::Display, thread, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// How often and how quickly to retry a failed network operation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// The number of retries after the first attempt.
+    pub retries: u32,
+    /// The delay before the first retry in milliseconds, doubled for each further retry.
+    pub backoff_ms: u64,
+    /// The longest delay between retries in milliseconds.
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            backoff_ms: 250,
+            max_backoff_ms: 10_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .backoff_ms
+            .saturating_mul(1u64.checked_shl(retry).unwrap_or(u64::MAX));
+        Duration::from_millis(backoff.min(self.max_backoff_ms))
+    }
+
+    /// Runs an operation until it succeeds or the retries are used up.
+    ///
+    /// On failure the error lists every attempt, so the history is visible in c2pa_error.
+    pub fn run<T, E: Display>(
+        &self,
+        what: &str,
+        f: impl FnMut() -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, String> {
+        self.run_retrying(what, f, |_| true)
+    }
+
+    /// Runs an operation like [`RetryPolicy::run`], but stops at an error that `retryable`
+    /// returns false for, such as a request the server rejected.
+    pub fn run_retrying<T, E: Display>(
+        &self,
+        what: &str,
+        mut f: impl FnMut() -> std::result::Result<T, E>,
+        retryable: impl Fn(&E) -> bool,
+    ) -> std::result::Result<T, String> {
+        let mut history = Vec::new();
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                thread::sleep(self.delay(attempt - 1));
+            }
+            log::debug!("{what}: attempt {}", attempt + 1);
+            match f() {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    log::warn!("{what}: attempt {} failed: {e}", attempt + 1);
+                    history.push(format!("attempt {}: {e}", attempt + 1));
+                    if !retryable(&e) {
+                        break;
+                    }
+                }
+            }
+        }
+        if history.len() == 1 {
+            Err(format!("{what} failed: {}", history[0]))
+        } else {
+            Err(format!(
+                "{what} failed after {} attempts: {}",
+                history.len(),
+                history.join("; ")
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_history() {
+        let policy = RetryPolicy {
+            retries: 2,
+            backoff_ms: 1,
+            max_backoff_ms: 2,
+        };
+        let mut calls = 0;
+        let result: std::result::Result<(), String> = policy.run("fetch", || {
+            calls += 1;
+            Err(format!("error {calls}"))
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(
+            result.unwrap_err(),
+            "fetch failed after 3 attempts: attempt 1: error 1; attempt 2: error 2; attempt 3: error 3"
+        );
+
+        let mut calls = 0;
+        let result = policy.run("fetch", || {
+            calls += 1;
+            if calls < 2 {
+                Err("busy")
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(policy.delay(10), Duration::from_millis(2));
+    }
+}
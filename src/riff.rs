@@ -0,0 +1,280 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Hard bindings for RIFF assets that leave volatile chunks out of the hash.
+
+use std::{
+    io::{Cursor, Read, Seek, Write},
+    ops::Range,
+    sync::RwLock,
+};
+
+use c2pa::{assertions::DataHash, jumbf_io, Builder, HashRange, Signer};
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+// the id of the chunk holding the manifest store
+const C2PA_CHUNK: &[u8; 4] = b"C2PA";
+// the placeholder data hash has room for this many exclusions, one of which is the manifest
+const MAX_EXCLUDED_CHUNKS: usize = 9;
+
+/// Options for hashing RIFF assets such as WAV, AVI and WebP.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RiffHashOptions {
+    /// The ids of top level chunks whose data is left out of the hard binding, such as "bext".
+    ///
+    /// The id and size of each chunk are still hashed, so the chunk may only be rewritten in place.
+    pub exclude_chunks: Vec<String>,
+}
+
+static OPTIONS: RwLock<Option<RiffHashOptions>> = RwLock::new(None);
+
+/// Sets the RIFF hashing options from a JSON string.
+///
+/// Passing None hashes RIFF assets in full again.
+pub fn set_options(options_json: Option<&str>) -> Result<()> {
+    let options = match options_json {
+        Some(json) => {
+            let options = serde_json::from_str::<RiffHashOptions>(json)
+                .map_err(|e| Error::Json(e.to_string()))?;
+            if options.exclude_chunks.len() > MAX_EXCLUDED_CHUNKS {
+                return Err(Error::Other(format!(
+                    "at most {MAX_EXCLUDED_CHUNKS} chunks can be excluded"
+                )));
+            }
+            if let Some(id) = options.exclude_chunks.iter().find(|id| id.len() != 4) {
+                return Err(Error::Other(format!("invalid RIFF chunk id {id}")));
+            }
+            Some(options)
+        }
+        None => None,
+    };
+    let mut current = OPTIONS
+        .write()
+        .map_err(|_| Error::Other("RIFF hash options lock poisoned".to_string()))?;
+    *current = options;
+    Ok(())
+}
+
+// The chunk ids to exclude, or None when RIFF assets are hashed in full.
+fn excluded_chunks() -> Option<Vec<String>> {
+    OPTIONS
+        .read()
+        .ok()
+        .and_then(|options| options.as_ref().map(|o| o.exclude_chunks.clone()))
+        .filter(|chunks| !chunks.is_empty())
+}
+
+// Returns the id and the byte range of each top level chunk, including its header and padding.
+fn chunks(data: &[u8]) -> Result<Vec<([u8; 4], Range<usize>)>> {
+    let invalid = || Error::Other("invalid RIFF asset".to_string());
+    if data.len() < 12 || &data[0..4] != b"RIFF" {
+        return Err(invalid());
+    }
+    let riff_size = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    let end = data.len().min(8 + riff_size);
+    let mut chunks = Vec::new();
+    let mut pos = 12;
+    while pos + 8 <= end {
+        let id = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+            as usize;
+        let next = pos + 8 + size + size % 2;
+        if pos + 8 + size > end {
+            return Err(invalid());
+        }
+        chunks.push((id, pos..next.min(end)));
+        pos = next;
+    }
+    Ok(chunks)
+}
+
+// Returns the ranges left out of the hash: the manifest chunk and the data of the excluded chunks.
+fn exclusions(data: &[u8], exclude_chunks: &[String]) -> Result<Vec<Range<usize>>> {
+    let mut ranges = Vec::new();
+    let mut found_manifest = false;
+    for (id, range) in chunks(data)? {
+        if &id == C2PA_CHUNK {
+            found_manifest = true;
+            ranges.push(range);
+        } else if exclude_chunks.iter().any(|chunk| chunk.as_bytes() == id) {
+            ranges.push(range.start + 8..range.end);
+        }
+    }
+    if !found_manifest {
+        return Err(Error::Other("manifest chunk not found".to_string()));
+    }
+    Ok(ranges)
+}
+
+/// True if the data starts with a RIFF header.
+pub(crate) fn is_riff(source: &mut (impl Read + Seek)) -> Result<bool> {
+    let mut header = [0u8; 4];
+    let result = source.read_exact(&mut header);
+    source.rewind().map_err(|e| Error::Io(e.to_string()))?;
+    Ok(result.is_ok() && &header == b"RIFF")
+}
+
+/// Signs a RIFF asset, leaving the data of the configured chunks out of the hard binding.
+///
+/// Returns None without signing if no chunks are excluded, so the asset is signed by the SDK.
+pub(crate) fn sign_builder<R, W>(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+    source: &mut R,
+    dest: &mut W,
+) -> Result<Option<Vec<u8>>>
+where
+    R: Read + Seek + Send,
+    W: Write + Read + Seek + Send,
+{
+    let Some(exclude_chunks) = excluded_chunks() else {
+        return Ok(None);
+    };
+    if builder.no_embed || builder.remote_url.is_some() || !is_riff(source)? {
+        return Ok(None);
+    }
+    let mut data = Vec::new();
+    source
+        .read_to_end(&mut data)
+        .map_err(|e| Error::Io(e.to_string()))?;
+
+    // the manifest is built unframed and then embedded in the asset's manifest chunk
+    let placeholder = builder
+        .data_hashed_placeholder(signer.reserve_size(), "application/c2pa")
+        .map_err(Error::from_c2pa_error)?;
+    let mut with_placeholder = Cursor::new(Vec::new());
+    jumbf_io::save_jumbf_to_stream(
+        format,
+        &mut Cursor::new(&data),
+        &mut with_placeholder,
+        &placeholder,
+    )
+    .map_err(Error::from_c2pa_error)?;
+
+    let mut data_hash = DataHash::new("jumbf manifest", "sha256");
+    for range in exclusions(with_placeholder.get_ref(), &exclude_chunks)? {
+        data_hash.add_exclusion(HashRange::new(range.start, range.len()));
+    }
+    with_placeholder
+        .rewind()
+        .map_err(|e| Error::Io(e.to_string()))?;
+    data_hash
+        .gen_hash_from_stream(&mut with_placeholder)
+        .map_err(Error::from_c2pa_error)?;
+    let manifest = builder
+        .sign_data_hashed_embeddable(signer, &data_hash, "application/c2pa")
+        .map_err(Error::from_c2pa_error)?;
+    if manifest.len() != placeholder.len() {
+        return Err(Error::Other(
+            "signed manifest does not match the reserved size".to_string(),
+        ));
+    }
+
+    // only the manifest chunk differs from the hashed asset
+    let mut signed = with_placeholder.into_inner();
+    let manifest_range = chunks(&signed)?
+        .into_iter()
+        .find(|(id, _)| id == C2PA_CHUNK)
+        .map(|(_, range)| range.start + 8..range.start + 8 + manifest.len())
+        .ok_or_else(|| Error::Other("manifest chunk not found".to_string()))?;
+    signed[manifest_range].copy_from_slice(&manifest);
+    dest.write_all(&signed)
+        .map_err(|e| Error::Io(e.to_string()))?;
+    Ok(Some(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use c2pa::{CallbackSigner, Reader, SigningAlg};
+
+    use super::*;
+    use crate::remote;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    // a broadcast WAV with a second of 8 kHz mono silence
+    fn broadcast_wav() -> Vec<u8> {
+        let fmt = [
+            1u8, 0, 1, 0, 0x40, 0x1f, 0, 0, 0x80, 0x3e, 0, 0, 2, 0, 16, 0,
+        ];
+        let mut bext = vec![0u8; 602];
+        bext[320..330].copy_from_slice(b"2024-01-01");
+        let mut body = b"WAVE".to_vec();
+        body.extend(chunk(b"fmt ", &fmt));
+        body.extend(chunk(b"bext", &bext));
+        body.extend(chunk(b"data", &[0u8; 16000]));
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        wav.extend(body);
+        wav
+    }
+
+    #[test]
+    fn test_sign_with_excluded_chunks() {
+        let wav = broadcast_wav();
+        assert_eq!(chunks(&wav).unwrap().len(), 3);
+        assert!(set_options(Some(r#"{"exclude_chunks": ["toolong"]}"#)).is_err());
+        set_options(Some(r#"{"exclude_chunks": ["bext"]}"#)).unwrap();
+
+        let mut builder = Builder::from_json(r#"{"title": "broadcast.wav"}"#).unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let mut dest = Cursor::new(Vec::new());
+        remote::sign_builder(
+            &mut builder,
+            &signer,
+            "audio/wav",
+            &mut Cursor::new(&wav),
+            &mut dest,
+        )
+        .unwrap();
+        set_options(None).unwrap();
+
+        // rewrite the origination date the way broadcast tooling does
+        let mut signed = dest.into_inner();
+        let (_, bext) = chunks(&signed)
+            .unwrap()
+            .into_iter()
+            .find(|(id, _)| id == b"bext")
+            .unwrap();
+        signed[bext.start + 8 + 320..bext.start + 8 + 330].copy_from_slice(b"2024-06-30");
+        let reader = Reader::from_stream("audio/wav", Cursor::new(&signed)).unwrap();
+        assert!(reader.validation_status().is_none());
+
+        // the audio data is still bound
+        let (_, data) = chunks(&signed)
+            .unwrap()
+            .into_iter()
+            .find(|(id, _)| id == b"data")
+            .unwrap();
+        signed[data.start + 8] = 1;
+        let reader = Reader::from_stream("audio/wav", Cursor::new(&signed)).unwrap();
+        assert!(reader.validation_status().is_some());
+    }
+}
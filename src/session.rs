@@ -0,0 +1,193 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signing sessions that prepare the invariant parts of a manifest once
+//! and reuse them for every asset signed in the session.
+
+use std::{
+    io::{Cursor, Read, Seek, Write},
+    sync::Mutex,
+};
+
+use c2pa::{Builder, DynamicAssertion, Result as C2paResult, Signer, SigningAlg};
+
+use crate::{remote, Error, Result};
+
+/// A manifest template and signer details shared by many signed assets.
+pub struct SigningSession {
+    // the Builder prepared from the manifest definition, which assets are signed with in turn
+    builder: Mutex<Builder>,
+    certs: Vec<Vec<u8>>,
+    reserve_size: usize,
+}
+
+// A Signer that answers with the certificates and reserve size cached by the session.
+struct SessionSigner<'a> {
+    signer: &'a dyn Signer,
+    certs: &'a [Vec<u8>],
+    reserve_size: usize,
+}
+
+impl Signer for SessionSigner<'_> {
+    fn sign(&self, data: &[u8]) -> C2paResult<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> C2paResult<Vec<Vec<u8>>> {
+        Ok(self.certs.to_vec())
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.signer.time_authority_url()
+    }
+
+    fn timestamp_request_headers(&self) -> Option<Vec<(String, String)>> {
+        self.signer.timestamp_request_headers()
+    }
+
+    fn timestamp_request_body(&self, message: &[u8]) -> C2paResult<Vec<u8>> {
+        self.signer.timestamp_request_body(message)
+    }
+
+    fn send_timestamp_request(&self, message: &[u8]) -> Option<C2paResult<Vec<u8>>> {
+        self.signer.send_timestamp_request(message)
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.signer.ocsp_val()
+    }
+
+    fn direct_cose_handling(&self) -> bool {
+        self.signer.direct_cose_handling()
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        self.signer.dynamic_assertions()
+    }
+}
+
+impl SigningSession {
+    /// Creates a session from a manifest definition and the signer that will be used with it.
+    ///
+    /// The definition is parsed and the signer's certificate chain and reserve size are read once.
+    pub fn new(manifest_json: &str, signer: &dyn Signer) -> Result<Self> {
+        let builder = Builder::from_json(manifest_json).map_err(Error::from_c2pa_error)?;
+        let certs = signer.certs().map_err(Error::from_c2pa_error)?;
+        Ok(Self {
+            builder: Mutex::new(builder),
+            certs,
+            reserve_size: signer.reserve_size(),
+        })
+    }
+
+    /// Adds a resource, such as an icon, that is included with every signed asset.
+    pub fn add_resource(&mut self, uri: &str, data: Vec<u8>) -> Result<()> {
+        self.builder
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner())
+            .add_resource(uri, Cursor::new(data))
+            .map_err(Error::from_c2pa_error)?;
+        Ok(())
+    }
+
+    /// Signs one asset with the session's template.
+    ///
+    /// Assets in a session are signed one at a time with the same Builder.
+    /// The signer must be the one the session was created with.
+    /// Returns the manifest bytes and the remote URL, if the template has one.
+    pub fn sign<R, W>(
+        &self,
+        signer: &dyn Signer,
+        format: &str,
+        source: &mut R,
+        dest: &mut W,
+    ) -> Result<(Vec<u8>, Option<String>)>
+    where
+        R: Read + Seek + Send,
+        W: Write + Read + Seek + Send,
+    {
+        let mut builder = self.builder.lock().unwrap_or_else(|err| err.into_inner());
+        let signer = SessionSigner {
+            signer,
+            certs: &self.certs,
+            reserve_size: self.reserve_size,
+        };
+        // signing sets these for the asset, so they are put back for the next one;
+        // a generated thumbnail is stored under the instance ID and so replaced, not kept
+        let definition = &builder.definition;
+        let (instance_id, format_before, thumbnail) = (
+            definition.instance_id.clone(),
+            definition.format.clone(),
+            definition.thumbnail.clone(),
+        );
+        let result = remote::sign_builder(&mut builder, &signer, format, source, dest);
+        builder.definition.instance_id = instance_id;
+        builder.definition.format = format_before;
+        builder.definition.thumbnail = thumbnail;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use c2pa::{CallbackSigner, Reader};
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    #[test]
+    fn test_signing_session() {
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let session = SigningSession::new(&manifest_json, &signer).unwrap();
+
+        let mut labels = Vec::new();
+        let mut thumbnails = Vec::new();
+        for path in ["tests/fixtures/A.jpg", "tests/fixtures/C.jpg"] {
+            let mut source = std::fs::File::open(path).unwrap();
+            let mut dest = Cursor::new(Vec::new());
+            session
+                .sign(&signer, "image/jpeg", &mut source, &mut dest)
+                .unwrap();
+            dest.set_position(0);
+            let reader = Reader::from_stream("image/jpeg", &mut dest).unwrap();
+            let manifest = reader.active_manifest().unwrap();
+            let thumbnail = manifest.thumbnail_ref().unwrap();
+            thumbnails.push(
+                manifest
+                    .resources()
+                    .get(&thumbnail.identifier)
+                    .unwrap()
+                    .into_owned(),
+            );
+            labels.push(reader.active_label().unwrap().to_string());
+        }
+        assert_ne!(labels[0], labels[1]);
+        // each asset has a thumbnail of its own, not one left from the asset before
+        assert_ne!(thumbnails[0], thumbnails[1]);
+
+        assert!(SigningSession::new("{", &signer).is_err());
+    }
+}
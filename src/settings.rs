@@ -0,0 +1,82 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Loads SDK settings, keeping the settings this library manages in step with them.
+
+use c2pa::settings::{load_settings_from_str, Settings};
+use config::{Config, File, FileFormat};
+use serde::de::DeserializeOwned;
+
+use crate::{remote, thumbnail, Error, Result};
+
+// Returns the value of a setting if the settings text gives one.
+fn given<T: DeserializeOwned>(settings: &str, format: FileFormat, key: &str) -> Result<Option<T>> {
+    let config = Config::builder()
+        .add_source(File::from_str(settings, format))
+        .build()
+        .map_err(|e| Error::Other(e.to_string()))?;
+    match config.get::<Option<T>>(key) {
+        Ok(value) => Ok(value),
+        Err(config::ConfigError::NotFound(_)) => Ok(None),
+        Err(e) => Err(Error::Other(e.to_string())),
+    }
+}
+
+/// Returns the current SDK settings as JSON.
+pub(crate) fn current() -> Result<serde_json::Value> {
+    // loading no settings returns the current ones
+    let settings = Settings::from_string("{}", "json").map_err(Error::from_c2pa_error)?;
+    serde_json::to_value(settings).map_err(|e| Error::Json(e.to_string()))
+}
+
+/// Loads SDK settings from a string in the given format.
+///
+/// verify.remote_manifest_fetch enables or disables fetching remote manifests as
+/// [`remote::set_fetch_enabled`] does, which also covers fetches made by the host get
+/// callback, so it is applied here rather than left to the SDK.
+///
+/// The remote and thumbnail settings of this library are then applied again over the
+/// loaded ones, since they decide what the SDK itself fetches and generates.
+pub fn load_settings(settings: &str, format: &str) -> Result<()> {
+    load_settings_from_str(settings, format).map_err(Error::from_c2pa_error)?;
+    // the SDK accepted the format, so it is one of these
+    let format = match format.to_lowercase().as_str() {
+        "json" => FileFormat::Json,
+        "json5" => FileFormat::Json5,
+        "toml" => FileFormat::Toml,
+        _ => FileFormat::Ron,
+    };
+    // also puts back the SDK setting the remote config keeps when it is not given
+    let enabled = given::<bool>(settings, format, "verify.remote_manifest_fetch")?;
+    remote::set_fetch_enabled(enabled.unwrap_or(!remote::fetch_disabled()))?;
+    thumbnail::settings_loaded(given::<bool>(settings, format, "builder.auto_thumbnail")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_given() {
+        let json = r#"{"verify": {"remote_manifest_fetch": false}}"#;
+        assert_eq!(
+            given::<bool>(json, FileFormat::Json, "verify.remote_manifest_fetch").unwrap(),
+            Some(false)
+        );
+        let toml = "[verify]\nverify_after_sign = true";
+        assert_eq!(
+            given::<bool>(toml, FileFormat::Toml, "verify.remote_manifest_fetch").unwrap(),
+            None
+        );
+    }
+}
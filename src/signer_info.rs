@@ -11,9 +11,61 @@
 // each license.
 
 use c2pa::{create_signer, Signer, SigningAlg};
+use c2pa_crypto::raw_signature::validator_for_signing_alg;
 use serde::Deserialize;
+use x509_parser::{
+    objects::{oid2sn, oid_registry},
+    oid_registry::*,
+    pem::Pem,
+    prelude::*,
+};
 
-use crate::{Error, Result};
+use crate::{
+    certs::{der_certs_pem, pem_certs, pkcs8_pem},
+    Error, Result,
+};
+
+/// A problem with a SignerInfo found by [`SignerInfo::validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignerProblem {
+    /// The signing algorithm is not one of the supported algorithms.
+    UnknownAlgorithm,
+    /// The certificate chain is not PEM, or a certificate in it can't be decoded.
+    CertificateDecode,
+    /// The private key is not PEM, or is a kind of key the crypto library can't decode.
+    PrivateKeyDecode,
+    /// The signing algorithm can't be used with the key type of the signing certificate.
+    AlgorithmMismatch,
+    /// The private key does not belong to the signing certificate.
+    KeyMismatch,
+    /// A certificate in the chain was not issued by the certificate that follows it.
+    BrokenChain,
+}
+
+// Returns true if the signing algorithm can be used with a public key.
+fn alg_matches_key(alg: SigningAlg, key: &SubjectPublicKeyInfo) -> bool {
+    let curve = || {
+        key.algorithm
+            .parameters
+            .as_ref()
+            .and_then(|params| params.as_oid().ok())
+    };
+    let algorithm = &key.algorithm.algorithm;
+    match alg {
+        SigningAlg::Es256 | SigningAlg::Es384 | SigningAlg::Es512 => {
+            let expected = match alg {
+                SigningAlg::Es256 => OID_EC_P256,
+                SigningAlg::Es384 => OID_NIST_EC_P384,
+                _ => OID_NIST_EC_P521,
+            };
+            *algorithm == OID_KEY_TYPE_EC_PUBLIC_KEY && curve() == Some(expected)
+        }
+        SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512 => {
+            *algorithm == OID_PKCS1_RSAENCRYPTION || *algorithm == OID_PKCS1_RSASSAPSS
+        }
+        SigningAlg::Ed25519 => *algorithm == OID_SIG_ED25519,
+    }
+}
 
 /// SignerInfo provides the information needed to create a signer
 /// and sign a manifest.
@@ -38,6 +90,24 @@ impl SignerInfo {
         serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))
     }
 
+    /// Create a SignerInfo from a DER encoded certificate chain and PKCS#8 private key
+    ///
+    /// The certificate chain is the DER encoded certificates concatenated, starting with
+    /// the signing certificate.
+    pub fn from_der(
+        alg: SigningAlg,
+        cert_chain: &[u8],
+        private_key: &[u8],
+        ta_url: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            alg: alg.to_string(),
+            sign_cert: der_certs_pem(cert_chain)?.into_bytes(),
+            private_key: pkcs8_pem(private_key).into_bytes(),
+            ta_url,
+        })
+    }
+
     // Returns the signing algorithm converted from string format
     fn alg(&self) -> Result<SigningAlg> {
         self.alg
@@ -56,4 +126,107 @@ impl SignerInfo {
         )
         .map_err(Error::from_c2pa_error)
     }
+
+    /// Checks the SignerInfo so certificate and key problems are found before signing.
+    ///
+    /// Checks that the certificates and private key decode, that the algorithm can be used
+    /// with the key type of the signing certificate, that each certificate in the chain was
+    /// issued by the one that follows it, and that the private key belongs to the signing
+    /// certificate. The chain may end at a self-signed root or at an intermediate,
+    /// since C2PA signers usually leave out the root that validators hold as a trust anchor.
+    pub fn validate(&self) -> std::result::Result<(), (SignerProblem, Error)> {
+        let problem = |problem, message: String| (problem, Error::Signature(message));
+        let alg = self.alg().map_err(|_| {
+            problem(
+                SignerProblem::UnknownAlgorithm,
+                format!("unknown signing algorithm {}", self.alg),
+            )
+        })?;
+
+        let ders = std::str::from_utf8(&self.sign_cert)
+            .map_err(|e| Error::Signature(format!("certificate chain is not PEM: {e}")))
+            .and_then(pem_certs)
+            .map_err(|e| (SignerProblem::CertificateDecode, e))?;
+        let certs = ders
+            .iter()
+            .enumerate()
+            .map(|(index, der)| {
+                X509Certificate::from_der(der)
+                    .map(|(_, cert)| cert)
+                    .map_err(|e| {
+                        problem(
+                            SignerProblem::CertificateDecode,
+                            format!("certificate {index} can't be decoded: {e}"),
+                        )
+                    })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        match Pem::iter_from_buffer(&self.private_key).next() {
+            Some(Ok(pem)) if pem.label.ends_with("PRIVATE KEY") => {}
+            _ => {
+                return Err(problem(
+                    SignerProblem::PrivateKeyDecode,
+                    "no PEM private key found".to_string(),
+                ))
+            }
+        }
+
+        let public_key = certs[0].public_key();
+        if !alg_matches_key(alg, public_key) {
+            let key_type = oid2sn(&public_key.algorithm.algorithm, oid_registry())
+                .map(String::from)
+                .unwrap_or_else(|_| public_key.algorithm.algorithm.to_id_string());
+            return Err(problem(
+                SignerProblem::AlgorithmMismatch,
+                format!("{alg} can't be used with the {key_type} key of the signing certificate"),
+            ));
+        }
+
+        for (index, pair) in certs.windows(2).enumerate() {
+            if pair[0].issuer() != pair[1].subject() {
+                return Err(problem(
+                    SignerProblem::BrokenChain,
+                    format!(
+                        "certificate {index} was issued by {}, not by the next certificate {}",
+                        pair[0].issuer(),
+                        pair[1].subject()
+                    ),
+                ));
+            }
+        }
+
+        let signer = self.signer().map_err(|e| {
+            problem(
+                SignerProblem::PrivateKeyDecode,
+                format!("private key can't be decoded: {e}"),
+            )
+        })?;
+        let data = b"c2pa signer validation";
+        let matches = signer.sign(data).is_ok_and(|signature| {
+            validator_for_signing_alg(alg).is_some_and(|validator| {
+                validator.validate(&signature, data, public_key.raw).is_ok()
+            })
+        });
+        if !matches {
+            return Err(problem(
+                SignerProblem::KeyMismatch,
+                "the private key does not belong to the signing certificate".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the digest a signing algorithm signs, or None if it signs the data itself.
+///
+/// Signers that send a digest to a key store, rather than the data, hash it with this.
+pub(crate) fn digest_alg(alg: SigningAlg) -> Option<&'static str> {
+    match alg {
+        SigningAlg::Es256 | SigningAlg::Ps256 => Some("sha256"),
+        SigningAlg::Es384 | SigningAlg::Ps384 => Some("sha384"),
+        SigningAlg::Es512 | SigningAlg::Ps512 => Some("sha512"),
+        SigningAlg::Ed25519 => None,
+    }
 }
@@ -0,0 +1,312 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{cell::RefCell, sync::Mutex};
+
+use c2pa::{DynamicAssertion, Result as C2paResult, Signer, SigningAlg};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    network, ocsp,
+    retry::RetryPolicy,
+    tsa::{self, TsaOptions},
+    Error, Result,
+};
+
+/// What to do when the time stamp authority cannot be reached.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TsaFailurePolicy {
+    /// Fail the sign.
+    #[default]
+    Fail,
+    /// Sign without a time stamp and return a warning with the result.
+    Proceed,
+}
+
+/// Options that change how a C2paSigner signs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SignerOptions {
+    /// Staple an OCSP response for the signing certificate to the signature.
+    pub use_ocsp: bool,
+    /// Options for requests to the signer's time stamp authority.
+    pub tsa: Option<TsaOptions>,
+    /// Retries for failed TSA and OCSP requests.
+    pub retry: RetryPolicy,
+    /// What to do when the time stamp request fails after all retries.
+    pub tsa_failure: TsaFailurePolicy,
+}
+
+impl SignerOptions {
+    /// Create SignerOptions from a JSON string
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))
+    }
+
+    /// Returns these options with the ones given in a JSON string changed.
+    ///
+    /// Options the JSON does not give keep their values, and a null option is reset.
+    pub fn merged_json(&self, json: &str) -> Result<Self> {
+        let json_error = |e: serde_json::Error| Error::Json(e.to_string());
+        let mut options = serde_json::to_value(self).map_err(json_error)?;
+        merge(
+            &mut options,
+            serde_json::from_str(json).map_err(json_error)?,
+        );
+        serde_json::from_value(options).map_err(json_error)
+    }
+}
+
+// Merges the fields of one JSON object into another, recursing into objects.
+fn merge(into: &mut Value, from: Value) {
+    match (into, from) {
+        (Value::Object(into), Value::Object(from)) => {
+            for (key, value) in from {
+                match into.get_mut(&key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge(existing, value)
+                    }
+                    _ => {
+                        into.insert(key, value);
+                    }
+                }
+            }
+        }
+        (into, from) => *into = from,
+    }
+}
+
+thread_local! {
+    // the time stamp warning of the last sign on this thread
+    static TIMESTAMP_WARNING: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Returns the warning of the last sign on this thread that proceeded without a time stamp.
+pub fn last_timestamp_warning() -> Option<String> {
+    TIMESTAMP_WARNING.with(|warning| warning.borrow().clone())
+}
+
+/// A Signer that applies SignerOptions on top of another Signer.
+///
+/// Each sign uses a ConfiguredSigner of its own, so its time stamp warning is its own.
+pub struct ConfiguredSigner<'a> {
+    signer: &'a dyn Signer,
+    options: &'a SignerOptions,
+    // set when the time stamp request failed and signing proceeded without one
+    timestamp_warning: Mutex<Option<String>>,
+}
+
+impl<'a> ConfiguredSigner<'a> {
+    pub fn new(signer: &'a dyn Signer, options: &'a SignerOptions) -> Self {
+        Self {
+            signer,
+            options,
+            timestamp_warning: Mutex::new(None),
+        }
+    }
+
+    /// Runs a sign with this signer, returning its result and the warning recorded
+    /// if it proceeded without a time stamp.
+    ///
+    /// The warning is also kept as the last one of the calling thread.
+    pub fn sign_with<T>(&self, sign: impl FnOnce(&Self) -> T) -> (T, Option<String>) {
+        let result = sign(self);
+        let warning = self
+            .timestamp_warning
+            .lock()
+            .ok()
+            .and_then(|mut warning| warning.take());
+        TIMESTAMP_WARNING.with(|last| last.borrow_mut().clone_from(&warning));
+        (result, warning)
+    }
+
+    fn send_timestamp_request_once(&self, message: &[u8]) -> Option<C2paResult<Vec<u8>>> {
+        if self.options.tsa.is_none() {
+            return self.signer.send_timestamp_request(message);
+        }
+        let url = self.time_authority_url()?;
+        let body = match self.timestamp_request_body(message) {
+            Ok(body) => body,
+            Err(e) => return Some(Err(e)),
+        };
+        let headers = self.timestamp_request_headers();
+        log::info!("requesting a time stamp from {url}");
+        Some(
+            c2pa_crypto::time_stamp::default_rfc3161_request(&url, headers, &body, message)
+                .map_err(|e| e.into()),
+        )
+    }
+}
+
+impl Signer for ConfiguredSigner<'_> {
+    fn sign(&self, data: &[u8]) -> C2paResult<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> C2paResult<Vec<Vec<u8>>> {
+        self.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        // the response is cached, so ocsp_val returns the one reserved for here
+        let ocsp_size = match self.options.use_ocsp {
+            true => self.ocsp_val().map_or(0, |ocsp| ocsp.len()),
+            false => 0,
+        };
+        self.signer.reserve_size() + ocsp_size
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.signer.time_authority_url()
+    }
+
+    fn timestamp_request_headers(&self) -> Option<Vec<(String, String)>> {
+        let mut headers = self.signer.timestamp_request_headers();
+        if let Some(tsa) = self.options.tsa.as_ref() {
+            if !tsa.headers.is_empty() {
+                headers.get_or_insert_with(Vec::new).extend(
+                    tsa.headers
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone())),
+                );
+            }
+        }
+        headers
+    }
+
+    fn timestamp_request_body(&self, message: &[u8]) -> C2paResult<Vec<u8>> {
+        match self.options.tsa.as_ref() {
+            Some(tsa) => tsa::timestamp_request_body(tsa, message)
+                .map_err(|e| c2pa::Error::BadParam(e.to_string())),
+            None => self.signer.timestamp_request_body(message),
+        }
+    }
+
+    fn send_timestamp_request(&self, message: &[u8]) -> Option<C2paResult<Vec<u8>>> {
+        self.time_authority_url()?;
+        // the error of the last attempt, returned as it is so its code is kept
+        let mut last_error = None;
+        let result = self.options.retry.run("time stamp request", || {
+            let result = network::with_connection(|| self.send_timestamp_request_once(message))
+                .unwrap_or(Err(c2pa::Error::CoseTimeStampAuthority));
+            result.map_err(|e| {
+                let message = e.to_string();
+                last_error = Some(e);
+                message
+            })
+        });
+        match result.as_ref().err() {
+            Some(warning) if self.options.tsa_failure == TsaFailurePolicy::Proceed => {
+                log::warn!("signing without a time stamp: {warning}");
+                if let Ok(mut timestamp_warning) = self.timestamp_warning.lock() {
+                    *timestamp_warning = Some(warning.clone());
+                }
+                None
+            }
+            _ => {
+                Some(result.map_err(|_| last_error.unwrap_or(c2pa::Error::CoseTimeStampAuthority)))
+            }
+        }
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        if self.options.use_ocsp {
+            let certs = self.signer.certs().ok()?;
+            ocsp::ocsp_response(&certs, &self.options.retry)
+        } else {
+            self.signer.ocsp_val()
+        }
+    }
+
+    fn direct_cose_handling(&self) -> bool {
+        self.signer.direct_cose_handling()
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        self.signer.dynamic_assertions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use c2pa::{Builder, CallbackSigner};
+
+    use super::*;
+    use crate::{error::C2paErrorCode, Error};
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    fn sign_with(options: &SignerOptions) -> (C2paResult<Vec<u8>>, Option<String>) {
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        // nothing listens on this port so the time stamp request fails
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS)
+            .set_tsa_url("http://127.0.0.1:1/");
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        ConfiguredSigner::new(&signer, options)
+            .sign_with(|signer| builder.sign(signer, "image/jpeg", &mut source, &mut dest))
+    }
+
+    #[test]
+    fn test_tsa_failure_policy() {
+        let mut options = SignerOptions::default();
+        let (result, warning) = sign_with(&options);
+        assert!(warning.is_none());
+        let err = result.unwrap_err();
+        // the time stamp error reaches the caller with its own code
+        Error::from_c2pa_error(err).set_last();
+        assert_eq!(
+            Error::last_code(),
+            C2paErrorCode::ErrorCoseTimeStampAuthority
+        );
+
+        options.tsa_failure = TsaFailurePolicy::Proceed;
+        let (result, warning) = sign_with(&options);
+        assert!(result.is_ok());
+        let warning = warning.unwrap();
+        assert!(warning.starts_with("time stamp request failed"));
+        assert_eq!(last_timestamp_warning(), Some(warning));
+    }
+
+    #[test]
+    fn test_merged_json() {
+        let options =
+            SignerOptions::from_json(r#"{"use_ocsp": true, "retry": {"retries": 3}}"#).unwrap();
+        let options = options
+            .merged_json(r#"{"tsa_failure": "proceed", "retry": {"backoff_ms": 10}}"#)
+            .unwrap();
+        assert!(options.use_ocsp);
+        assert_eq!(options.tsa_failure, TsaFailurePolicy::Proceed);
+        assert_eq!(options.retry.retries, 3);
+        assert_eq!(options.retry.backoff_ms, 10);
+
+        let options = options.merged_json(r#"{"tsa": {"nonce": false}}"#).unwrap();
+        assert!(!options.tsa.as_ref().unwrap().nonce);
+        assert_eq!(options.tsa.as_ref().unwrap().digest_alg, "sha256");
+        let options = options.merged_json(r#"{"tsa": null}"#).unwrap();
+        assert!(options.tsa.is_none());
+        assert!(options.use_ocsp);
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Recovers manifests for assets whose manifest store was stripped,
+//! by looking up candidates for a soft binding through a host resolver.
+
+use std::{
+    ffi::CString,
+    io::{Read, Seek, SeekFrom},
+    os::raw::c_void,
+    sync::RwLock,
+};
+
+use c2pa::{assertions::labels, Error as C2paError, Manifest, Reader};
+use serde_json::Value;
+
+use crate::{
+    c_api::SoftBindingCallback,
+    remote::{self, ManifestSource},
+    Error, Result,
+};
+
+// the initial buffer size offered to a resolver callback
+const INITIAL_CANDIDATE_SIZE: usize = 64 * 1024;
+// the largest candidate a resolver callback may return
+const MAX_CANDIDATE_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Clone, Copy)]
+struct Resolver {
+    context: *const c_void,
+    resolve: SoftBindingCallback,
+}
+
+// The host is responsible for making the context usable from any thread.
+unsafe impl Send for Resolver {}
+unsafe impl Sync for Resolver {}
+
+static RESOLVER: RwLock<Option<Resolver>> = RwLock::new(None);
+
+/// Registers the callback used to look up candidate manifest stores for a soft binding.
+///
+/// Passing None removes the resolver.
+pub fn set_resolver(context: *const c_void, resolve: Option<SoftBindingCallback>) -> Result<()> {
+    let mut resolver = RESOLVER
+        .write()
+        .map_err(|_| Error::Other("soft binding resolver lock poisoned".to_string()))?;
+    *resolver = resolve.map(|resolve| Resolver { context, resolve });
+    Ok(())
+}
+
+fn to_cstring(s: &str) -> Result<CString> {
+    CString::new(s).map_err(|_| Error::Other(format!("invalid soft binding {s}")))
+}
+
+// Returns the registered resolver, copied out of the lock so it is not held while the
+// callback runs.
+fn resolver() -> Result<Resolver> {
+    let resolver = RESOLVER
+        .read()
+        .map_err(|_| Error::Other("soft binding resolver lock poisoned".to_string()))?;
+    resolver
+        .as_ref()
+        .copied()
+        .ok_or_else(|| Error::Other("no soft binding resolver is registered".to_string()))
+}
+
+// Asks the resolver for the candidate at an index, or None when there are no more.
+//
+// A resolver that needs a larger buffer is asked once more with the size it returned.
+fn candidate(
+    resolver: &Resolver,
+    alg: &CString,
+    value: &CString,
+    index: usize,
+) -> Result<Option<Vec<u8>>> {
+    let mut buffer = vec![0u8; INITIAL_CANDIDATE_SIZE];
+    for _ in 0..2 {
+        let size = unsafe {
+            (resolver.resolve)(
+                resolver.context,
+                alg.as_ptr(),
+                value.as_ptr(),
+                index,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+            )
+        };
+        if size < 0 {
+            return Err(Error::Other(format!(
+                "soft binding resolver returned {size}"
+            )));
+        }
+        let size = size as usize;
+        if size == 0 {
+            return Ok(None);
+        }
+        if size <= buffer.len() {
+            buffer.truncate(size);
+            return Ok(Some(buffer));
+        }
+        if size > MAX_CANDIDATE_SIZE {
+            return Err(Error::Other(format!(
+                "soft binding candidate is larger than {MAX_CANDIDATE_SIZE} bytes"
+            )));
+        }
+        buffer.resize(size, 0);
+    }
+    Err(Error::Other(
+        "soft binding resolver asked for a larger buffer again".to_string(),
+    ))
+}
+
+// True if the manifest declares the soft binding in one of its soft binding assertions.
+fn has_soft_binding(manifest: &Manifest, alg: &str, value: &str) -> bool {
+    manifest
+        .assertions()
+        .iter()
+        .filter(|assertion| assertion.label().starts_with(labels::SOFT_BINDING))
+        .filter_map(|assertion| assertion.value().ok())
+        .any(|binding: &Value| {
+            binding["alg"].as_str() == Some(alg)
+                && binding["blocks"].as_array().is_some_and(|blocks| {
+                    blocks
+                        .iter()
+                        .any(|block| block["value"].as_str() == Some(value))
+                })
+        })
+}
+
+/// Creates a Reader like [`reader_from_soft_binding`], also returning where
+/// the manifest store was read from.
+pub fn read_with_soft_binding(
+    format: &str,
+    mut stream: impl Read + Seek + Send,
+    alg: &str,
+    value: &str,
+) -> Result<(Reader, ManifestSource)> {
+    match remote::read_with_source(format, &mut stream) {
+        Err(C2paError::JumbfNotFound) => {}
+        result => return result.map_err(Error::from_c2pa_error),
+    }
+    let resolver = resolver()?;
+    let (alg_c, value_c) = (to_cstring(alg)?, to_cstring(value)?);
+    // candidates are asked for one at a time, until one matches
+    let mut index = 0;
+    while let Some(data) = candidate(&resolver, &alg_c, &value_c, index)? {
+        index += 1;
+        stream
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| Error::Io(e.to_string()))?;
+        let Ok(reader) = Reader::from_manifest_data_and_stream(&data, format, &mut stream) else {
+            continue;
+        };
+        if reader
+            .active_manifest()
+            .is_some_and(|manifest| has_soft_binding(manifest, alg, value))
+        {
+            let source = ManifestSource {
+                manifest_data: Some(data),
+                ..ManifestSource::DETACHED
+            };
+            return Ok((reader, source));
+        }
+    }
+    Err(Error::from_c2pa_error(C2paError::JumbfNotFound))
+}
+
+/// Creates a Reader for an asset, recovering its manifest through a soft binding if it has none.
+///
+/// The soft binding is computed by the caller, for example by decoding a watermark.
+/// If the asset has no manifest store, each candidate returned by the resolver is validated
+/// against the asset and the first whose active manifest declares the soft binding is used.
+/// Hard binding failures are expected in the validation status of a recovered manifest,
+/// since the asset was changed when its manifest store was removed.
+pub fn reader_from_soft_binding(
+    format: &str,
+    stream: impl Read + Seek + Send,
+    alg: &str,
+    value: &str,
+) -> Result<Reader> {
+    read_with_soft_binding(format, stream, alg, value).map(|(reader, _)| reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::CStr,
+        io::Cursor,
+        os::raw::c_char,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            OnceLock,
+        },
+    };
+
+    use c2pa::{Builder, CallbackSigner, SigningAlg};
+    use serde_json::json;
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    static CANDIDATES: OnceLock<Vec<Vec<u8>>> = OnceLock::new();
+    // the number of candidates asked for with the value "abc"
+    static ASKED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn resolve_candidate(
+        _context: *const c_void,
+        alg: *const c_char,
+        value: *const c_char,
+        index: usize,
+        buffer: *mut u8,
+        buffer_len: usize,
+    ) -> isize {
+        if CStr::from_ptr(alg).to_str() != Ok("com.example.watermark") {
+            return 0;
+        }
+        if CStr::from_ptr(value).to_str() == Ok("abc") && buffer_len == INITIAL_CANDIDATE_SIZE {
+            ASKED.fetch_add(1, Ordering::SeqCst);
+        }
+        match CANDIDATES
+            .get()
+            .and_then(|candidates| candidates.get(index))
+        {
+            Some(data) => {
+                if data.len() <= buffer_len {
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+                }
+                data.len() as isize
+            }
+            None => 0,
+        }
+    }
+
+    fn sign_with_binding(value: &str) -> Vec<u8> {
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let binding = json!({
+            "alg": "com.example.watermark",
+            "blocks": [{"scope": {}, "value": value}]
+        });
+        builder
+            .add_assertion(labels::SOFT_BINDING, &binding)
+            .unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        builder
+            .sign(&signer, "image/jpeg", &mut source, &mut dest)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reader_from_soft_binding() {
+        CANDIDATES.get_or_init(|| {
+            vec![
+                sign_with_binding("other"),
+                sign_with_binding("abc"),
+                sign_with_binding("abc"),
+            ]
+        });
+        set_resolver(std::ptr::null(), Some(resolve_candidate)).unwrap();
+
+        let stripped = std::fs::read("tests/fixtures/A.jpg").unwrap();
+        let reader = reader_from_soft_binding(
+            "image/jpeg",
+            Cursor::new(&stripped),
+            "com.example.watermark",
+            "abc",
+        )
+        .unwrap();
+        let manifest = reader.active_manifest().unwrap();
+        assert!(has_soft_binding(manifest, "com.example.watermark", "abc"));
+        // the third candidate is not asked for once the second matches
+        assert_eq!(ASKED.load(Ordering::SeqCst), 2);
+        assert!(reader_from_soft_binding(
+            "image/jpeg",
+            Cursor::new(&stripped),
+            "com.example.watermark",
+            "missing",
+        )
+        .is_err());
+
+        set_resolver(std::ptr::null(), None).unwrap();
+        assert!(resolver().is_err());
+    }
+
+    #[test]
+    fn test_candidate_bounded() {
+        unsafe extern "C" fn growing(
+            _context: *const c_void,
+            _alg: *const c_char,
+            _value: *const c_char,
+            _index: usize,
+            _buffer: *mut u8,
+            buffer_len: usize,
+        ) -> isize {
+            (buffer_len + 1) as isize
+        }
+        unsafe extern "C" fn huge(
+            _context: *const c_void,
+            _alg: *const c_char,
+            _value: *const c_char,
+            _index: usize,
+            _buffer: *mut u8,
+            _buffer_len: usize,
+        ) -> isize {
+            (MAX_CANDIDATE_SIZE + 1) as isize
+        }
+        let (alg, value) = (to_cstring("alg").unwrap(), to_cstring("value").unwrap());
+        for resolve in [growing, huge] {
+            let resolver = Resolver {
+                context: std::ptr::null(),
+                resolve,
+            };
+            assert!(candidate(&resolver, &alg, &value, 0).is_err());
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Coalesces small reads and writes into blocks, for streams whose callbacks are slow,
+//! such as those backed by a network or marshaled through a managed language.
+
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+/// The buffered state of a stream, applied to the unbuffered stream passed to each call.
+#[derive(Debug)]
+pub(crate) struct StreamBuffer {
+    size: usize,
+    // the position of the unbuffered stream, once known
+    pos: Option<u64>,
+    // bytes read ahead of the caller, ending at pos
+    read: Vec<u8>,
+    // the index of the next unread byte in read
+    read_pos: usize,
+    // bytes written but not yet passed on, starting at pos
+    write: Vec<u8>,
+}
+
+impl StreamBuffer {
+    pub(crate) fn new(size: usize) -> Self {
+        Self {
+            size,
+            pos: None,
+            read: Vec::new(),
+            read_pos: 0,
+            write: Vec::new(),
+        }
+    }
+
+    fn unread(&self) -> usize {
+        self.read.len() - self.read_pos
+    }
+
+    fn advance(&mut self, n: usize) {
+        if let Some(pos) = self.pos.as_mut() {
+            *pos += n as u64;
+        }
+    }
+
+    fn position(&mut self, stream: &mut impl Seek) -> Result<u64> {
+        match self.pos {
+            Some(pos) => Ok(pos),
+            None => {
+                let pos = stream.stream_position()?;
+                self.pos = Some(pos);
+                Ok(pos)
+            }
+        }
+    }
+
+    // Passes on the buffered writes.
+    fn flush_writes(&mut self, stream: &mut impl Write) -> Result<()> {
+        let mut written = 0;
+        let result = loop {
+            if written == self.write.len() {
+                break Ok(());
+            }
+            match stream.write(&self.write[written..]) {
+                Ok(0) => break Err(Error::from(ErrorKind::WriteZero)),
+                Ok(n) => written += n,
+                Err(err) => break Err(err),
+            }
+        };
+        self.write.drain(..written);
+        self.advance(written);
+        result
+    }
+
+    // Drops the bytes read ahead, moving the unbuffered stream back to the caller's position.
+    fn drop_read_ahead(&mut self, stream: &mut impl Seek) -> Result<()> {
+        let unread = self.unread();
+        self.read.clear();
+        self.read_pos = 0;
+        if unread > 0 {
+            self.pos = None;
+            self.pos = Some(stream.seek(SeekFrom::Current(-(unread as i64)))?);
+        }
+        Ok(())
+    }
+
+    /// Writes any buffered bytes and drops any bytes read ahead.
+    pub(crate) fn sync(&mut self, stream: &mut (impl Write + Seek)) -> Result<()> {
+        self.flush_writes(stream)?;
+        self.drop_read_ahead(stream)
+    }
+
+    pub(crate) fn read(
+        &mut self,
+        stream: &mut (impl Read + Write),
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        self.flush_writes(stream)?;
+        if self.unread() == 0 {
+            self.read.clear();
+            self.read_pos = 0;
+            // large reads gain nothing from the buffer
+            if buf.len() >= self.size {
+                let n = stream.read(buf)?;
+                self.advance(n);
+                return Ok(n);
+            }
+            self.read.resize(self.size, 0);
+            let n = stream
+                .read(&mut self.read)
+                .inspect_err(|_| self.read.clear())?;
+            self.read.truncate(n);
+            self.advance(n);
+        }
+        let n = buf.len().min(self.unread());
+        buf[..n].copy_from_slice(&self.read[self.read_pos..self.read_pos + n]);
+        self.read_pos += n;
+        Ok(n)
+    }
+
+    pub(crate) fn write(&mut self, stream: &mut (impl Write + Seek), buf: &[u8]) -> Result<usize> {
+        self.drop_read_ahead(stream)?;
+        if self.write.len() + buf.len() > self.size {
+            self.flush_writes(stream)?;
+        }
+        // large writes gain nothing from the buffer
+        if buf.len() >= self.size {
+            let n = stream.write(buf)?;
+            self.advance(n);
+            return Ok(n);
+        }
+        self.write.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    pub(crate) fn flush(&mut self, stream: &mut impl Write) -> Result<()> {
+        self.flush_writes(stream)?;
+        stream.flush()
+    }
+
+    pub(crate) fn seek(&mut self, stream: &mut (impl Write + Seek), from: SeekFrom) -> Result<u64> {
+        self.flush_writes(stream)?;
+        let target = match from {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => {
+                let pos = self.position(stream)? - self.unread() as u64;
+                Some(pos.checked_add_signed(offset).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        "seek before the start of the stream",
+                    )
+                })?)
+            }
+            SeekFrom::End(_) => None,
+        };
+        // seeks within the bytes read ahead, such as asking for the position, stay in the buffer
+        if let (Some(target), Some(pos)) = (target, self.pos) {
+            let start = pos - self.read.len() as u64;
+            if (start..=pos).contains(&target) {
+                self.read_pos = (target - start) as usize;
+                return Ok(target);
+            }
+        }
+        self.read.clear();
+        self.read_pos = 0;
+        self.pos = None;
+        let pos = stream.seek(target.map_or(from, SeekFrom::Start))?;
+        self.pos = Some(pos);
+        Ok(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    // A stream that counts the calls made to it.
+    struct Counted {
+        inner: Cursor<Vec<u8>>,
+        calls: usize,
+    }
+
+    impl Read for Counted {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            self.calls += 1;
+            self.inner.read(buf)
+        }
+    }
+
+    impl Write for Counted {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.calls += 1;
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for Counted {
+        fn seek(&mut self, from: SeekFrom) -> Result<u64> {
+            self.calls += 1;
+            self.inner.seek(from)
+        }
+    }
+
+    #[test]
+    fn test_buffered_reads() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut stream = Counted {
+            inner: Cursor::new(data.clone()),
+            calls: 0,
+        };
+        let mut buffer = StreamBuffer::new(64);
+        let mut byte = [0u8; 1];
+        for expected in 0..100 {
+            assert_eq!(buffer.read(&mut stream, &mut byte).unwrap(), 1);
+            assert_eq!(byte[0], expected);
+            assert_eq!(
+                buffer.seek(&mut stream, SeekFrom::Current(0)).unwrap(),
+                expected as u64 + 1
+            );
+        }
+        // two blocks read and one position lookup
+        assert_eq!(stream.calls, 3);
+
+        assert_eq!(buffer.seek(&mut stream, SeekFrom::Start(10)).unwrap(), 10);
+        buffer.read(&mut stream, &mut byte).unwrap();
+        assert_eq!(byte[0], 10);
+        assert_eq!(buffer.seek(&mut stream, SeekFrom::End(-1)).unwrap(), 255);
+        buffer.read(&mut stream, &mut byte).unwrap();
+        assert_eq!(byte[0], 255);
+    }
+
+    #[test]
+    fn test_buffered_writes() {
+        let mut stream = Counted {
+            inner: Cursor::new(b"0123456789".to_vec()),
+            calls: 0,
+        };
+        let mut buffer = StreamBuffer::new(64);
+        let mut bytes = [0u8; 2];
+        buffer.read(&mut stream, &mut bytes).unwrap();
+        for byte in b"abcd" {
+            assert_eq!(buffer.write(&mut stream, &[*byte]).unwrap(), 1);
+        }
+        assert_eq!(buffer.seek(&mut stream, SeekFrom::Current(0)).unwrap(), 6);
+        buffer.read(&mut stream, &mut bytes).unwrap();
+        assert_eq!(&bytes, b"67");
+        buffer.flush(&mut stream).unwrap();
+        assert_eq!(stream.inner.get_ref(), b"01abcd6789");
+        // two reads ahead, a seek back to the caller's position and one write of the four bytes
+        assert_eq!(stream.calls, 4);
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Short human-readable summaries of the provenance in a Reader.
+
+use c2pa::{
+    assertions::{c2pa_action, Action, Actions, SoftwareAgent},
+    Manifest, Reader, ValidationState,
+};
+
+use crate::{Error, Result};
+
+// the digital source types that indicate generative AI was used
+const AI_SOURCE_TYPES: &[&str] = &[
+    "trainedAlgorithmicMedia",
+    "compositeWithTrainedAlgorithmicMedia",
+    "compositeSynthetic",
+];
+
+// The sentences of a summary in one language, with {placeholders} for values.
+struct Strings {
+    created_with: &'static str,
+    created_with_on: &'static str,
+    signed_by: &'static str,
+    edited: &'static str,
+    edited_with: &'static str,
+    ai_tools: &'static str,
+    none: &'static str,
+    unknown: &'static str,
+    ingredients: &'static str,
+    invalid: &'static str,
+}
+
+const EN: Strings = Strings {
+    created_with: "Created with {tool}.",
+    created_with_on: "Created with {tool} on {date}.",
+    signed_by: "Signed by {issuer}.",
+    edited: "Edited.",
+    edited_with: "Edited with {tools}.",
+    ai_tools: "AI tools used: {tools}.",
+    none: "none",
+    unknown: "unknown",
+    ingredients: "Includes {count} ingredient(s).",
+    invalid: "The content credentials could not be verified.",
+};
+
+const ES: Strings = Strings {
+    created_with: "Creado con {tool}.",
+    created_with_on: "Creado con {tool} el {date}.",
+    signed_by: "Firmado por {issuer}.",
+    edited: "Editado.",
+    edited_with: "Editado con {tools}.",
+    ai_tools: "Herramientas de IA utilizadas: {tools}.",
+    none: "ninguna",
+    unknown: "desconocida",
+    ingredients: "Incluye {count} ingrediente(s).",
+    invalid: "No se pudieron verificar las credenciales de contenido.",
+};
+
+const FR: Strings = Strings {
+    created_with: "Créé avec {tool}.",
+    created_with_on: "Créé avec {tool} le {date}.",
+    signed_by: "Signé par {issuer}.",
+    edited: "Modifié.",
+    edited_with: "Modifié avec {tools}.",
+    ai_tools: "Outils d'IA utilisés : {tools}.",
+    none: "aucun",
+    unknown: "inconnu",
+    ingredients: "Contient {count} ingrédient(s).",
+    invalid: "Les identifiants de contenu n'ont pas pu être vérifiés.",
+};
+
+const DE: Strings = Strings {
+    created_with: "Erstellt mit {tool}.",
+    created_with_on: "Erstellt mit {tool} am {date}.",
+    signed_by: "Signiert von {issuer}.",
+    edited: "Bearbeitet.",
+    edited_with: "Bearbeitet mit {tools}.",
+    ai_tools: "Verwendete KI-Werkzeuge: {tools}.",
+    none: "keine",
+    unknown: "unbekannt",
+    ingredients: "Enthält {count} Zutat(en).",
+    invalid: "Die Inhaltsnachweise konnten nicht überprüft werden.",
+};
+
+// Finds the strings for a locale such as "fr" or "fr-CA", falling back to English.
+// Summaries are available in English, French, German and Spanish.
+fn strings(locale: &str) -> &'static Strings {
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match language.as_str() {
+        "de" => &DE,
+        "es" => &ES,
+        "fr" => &FR,
+        _ => &EN,
+    }
+}
+
+fn agent_name(agent: &SoftwareAgent) -> String {
+    match agent {
+        SoftwareAgent::String(name) => name.clone(),
+        SoftwareAgent::ClaimGeneratorInfo(info) => match &info.version {
+            Some(version) => format!("{} {version}", info.name),
+            None => info.name.clone(),
+        },
+    }
+}
+
+fn push_unique(names: &mut Vec<String>, name: String) {
+    if !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+fn manifest_actions(manifest: &Manifest) -> Vec<Action> {
+    manifest
+        .assertions()
+        .iter()
+        .filter(|assertion| assertion.label().starts_with(Actions::LABEL))
+        .filter_map(|assertion| assertion.to_assertion::<Actions>().ok())
+        .flat_map(|actions| actions.actions)
+        .collect()
+}
+
+fn manifest_summary(manifest: &Manifest, strings: &Strings) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let actions = manifest_actions(manifest);
+
+    let tool = actions
+        .iter()
+        .find(|action| action.action() == c2pa_action::CREATED)
+        .and_then(|action| action.software_agent())
+        .map(agent_name)
+        .or_else(|| {
+            manifest
+                .claim_generator_info
+                .as_ref()
+                .and_then(|info| info.first())
+                .map(|info| agent_name(&SoftwareAgent::ClaimGeneratorInfo(info.clone())))
+        })
+        .unwrap_or_else(|| manifest.claim_generator().to_string());
+    // only the date of the signing time is shown
+    let date = manifest
+        .time()
+        .map(|time| time.split('T').next().unwrap_or_default().to_string());
+    sentences.push(match date {
+        Some(date) => strings
+            .created_with_on
+            .replace("{tool}", &tool)
+            .replace("{date}", &date),
+        None => strings.created_with.replace("{tool}", &tool),
+    });
+
+    if let Some(issuer) = manifest.issuer() {
+        sentences.push(strings.signed_by.replace("{issuer}", &issuer));
+    }
+
+    let mut editors = Vec::new();
+    let mut edited = false;
+    let mut ai_tools = Vec::new();
+    for action in &actions {
+        let agent = action.software_agent().map(agent_name);
+        if action.action() != c2pa_action::CREATED && action.action() != c2pa_action::OPENED {
+            edited = true;
+            if let Some(agent) = agent.clone() {
+                push_unique(&mut editors, agent);
+            }
+        }
+        let ai = action.source_type().is_some_and(|source_type| {
+            AI_SOURCE_TYPES
+                .iter()
+                .any(|ai_type| source_type.ends_with(ai_type))
+        });
+        if ai {
+            push_unique(
+                &mut ai_tools,
+                agent.unwrap_or_else(|| strings.unknown.to_string()),
+            );
+        }
+    }
+    if !editors.is_empty() {
+        sentences.push(strings.edited_with.replace("{tools}", &editors.join(", ")));
+    } else if edited {
+        sentences.push(strings.edited.to_string());
+    }
+    let ai_tools = if ai_tools.is_empty() {
+        strings.none.to_string()
+    } else {
+        ai_tools.join(", ")
+    };
+    sentences.push(strings.ai_tools.replace("{tools}", &ai_tools));
+
+    let ingredients = manifest.ingredients().len();
+    if ingredients > 0 {
+        sentences.push(
+            strings
+                .ingredients
+                .replace("{count}", &ingredients.to_string()),
+        );
+    }
+    sentences
+}
+
+/// Returns a short summary of the active manifest of a Reader, such as
+/// "Created with X on DATE. Signed by Y. AI tools used: none."
+///
+/// The locale selects the language by its language code, such as "fr" or "fr-CA".
+/// Locales without a translation use English.
+pub fn summary(reader: &Reader, locale: &str) -> Result<String> {
+    let strings = strings(locale);
+    let manifest = reader
+        .active_manifest()
+        .ok_or_else(|| Error::Other("no active manifest".to_string()))?;
+    let mut sentences = Vec::new();
+    if reader.validation_state() == ValidationState::Invalid {
+        sentences.push(strings.invalid.to_string());
+    }
+    sentences.extend(manifest_summary(manifest, strings));
+    Ok(sentences.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use c2pa::{Builder, CallbackSigner, SigningAlg};
+    use serde_json::json;
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    #[test]
+    fn test_summary() {
+        let mut stream = std::fs::File::open("tests/fixtures/C.jpg").unwrap();
+        let reader = Reader::from_stream("image/jpeg", &mut stream).unwrap();
+        let text = summary(&reader, "en-US").unwrap();
+        assert!(text.starts_with("Created with Make Test Images 0.26.0"));
+        assert!(text.ends_with("AI tools used: none."));
+
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let actions = json!({"actions": [
+            {
+                "action": "c2pa.created",
+                "softwareAgent": "Image Generator",
+                "digitalSourceType": "http://cv.iptc.org/newscodes/digitalsourcetype/trainedAlgorithmicMedia"
+            },
+            {"action": "c2pa.color_adjustments", "softwareAgent": "Photo Editor"}
+        ]});
+        builder.add_assertion(Actions::LABEL, &actions).unwrap();
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        builder
+            .sign(&signer, "image/jpeg", &mut source, &mut dest)
+            .unwrap();
+        dest.set_position(0);
+        let reader = Reader::from_stream("image/jpeg", &mut dest).unwrap();
+
+        let text = summary(&reader, "en").unwrap();
+        // there is no signing time without a time stamp
+        assert!(text.starts_with("Created with Image Generator. Signed by "));
+        assert!(text.contains("Edited with Photo Editor."));
+        assert!(text.contains("AI tools used: Image Generator."));
+        let text = summary(&reader, "fr_FR").unwrap();
+        assert!(text.contains("Modifié avec Photo Editor."));
+    }
+}
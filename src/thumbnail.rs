@@ -0,0 +1,685 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Color managed thumbnails for assets with an embedded ICC profile.
+//!
+//! The SDK thumbnails drop the source profile, so wide gamut assets look wrong.
+//! Thumbnails made here either keep the source profile or are converted to sRGB.
+//! They are also made here when the host asks for a size, quality or format the SDK does not use.
+
+use std::{
+    io::{Cursor, Read, Seek, SeekFrom},
+    sync::RwLock,
+};
+
+use c2pa::{settings::load_settings_from_str, Builder};
+use image::{DynamicImage, ImageOutputFormat};
+use img_parts::{Bytes, DynImage, ImageICC};
+use serde::Deserialize;
+
+use crate::{settings, Error, Result};
+
+/// The color space thumbnails are written in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailColorSpace {
+    /// Keep the source pixels and embed the source ICC profile.
+    #[default]
+    Source,
+    /// Convert to sRGB, embedding the source profile if it cannot be converted.
+    Srgb,
+}
+
+/// The image format thumbnails are written in.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailFormat {
+    Jpeg,
+    Png,
+    /// Lossless WebP, so the quality does not apply.
+    Webp,
+}
+
+/// Options for thumbnails generated while signing and adding ingredients.
+///
+/// The format, longest edge and quality apply to JPEG, PNG and WebP assets. Thumbnails of
+/// other assets are made by the SDK in its own format and size.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ThumbnailOptions {
+    /// Makes claim thumbnails when signing, unless the manifest definition has one.
+    pub enabled: bool,
+    pub color_space: ThumbnailColorSpace,
+    /// The thumbnail format, or None for JPEG, and PNG for PNG sources with transparency.
+    pub format: Option<ThumbnailFormat>,
+    /// The maximum width or height of a thumbnail in pixels.
+    pub longest_edge: u32,
+    /// The quality of JPEG thumbnails, from 1 to 100.
+    pub quality: u8,
+}
+
+impl Default for ThumbnailOptions {
+    fn default() -> Self {
+        // the SDK thumbnail defaults
+        Self {
+            enabled: true,
+            color_space: ThumbnailColorSpace::Source,
+            format: None,
+            longest_edge: 1024,
+            quality: 80,
+        }
+    }
+}
+
+impl ThumbnailOptions {
+    // True if the SDK would not make the thumbnail these options ask for.
+    fn customized(&self) -> bool {
+        let defaults = Self::default();
+        self.format.is_some()
+            || self.longest_edge != defaults.longest_edge
+            || self.quality != defaults.quality
+    }
+}
+
+struct ThumbnailState {
+    options: Option<ThumbnailOptions>,
+    // the SDK auto_thumbnail setting from before the options turned it off
+    saved_auto_thumbnail: Option<bool>,
+}
+
+static STATE: RwLock<ThumbnailState> = RwLock::new(ThumbnailState {
+    options: None,
+    saved_auto_thumbnail: None,
+});
+
+// True if the options turn off the claim thumbnails the SDK makes.
+fn disables(options: Option<&ThumbnailOptions>) -> bool {
+    options.is_some_and(|options| !options.enabled)
+}
+
+/// Sets the thumbnail options from a JSON string.
+///
+/// Color managed thumbnails are only made once options are set.
+/// Passing None leaves thumbnails to the SDK again.
+///
+/// The SDK builder.auto_thumbnail setting is only written when thumbnails are turned off,
+/// and is put back as it was when they are turned on again or the options are cleared.
+pub fn set_options(options_json: Option<&str>) -> Result<()> {
+    let options = match options_json {
+        Some(json) => Some(
+            serde_json::from_str::<ThumbnailOptions>(json)
+                .map_err(|e| Error::Json(e.to_string()))?,
+        ),
+        None => None,
+    };
+    let mut state = STATE
+        .write()
+        .map_err(|_| Error::Other("thumbnail options lock poisoned".to_string()))?;
+    let auto_thumbnail = match (disables(state.options.as_ref()), disables(options.as_ref())) {
+        (false, true) => {
+            let current = settings::current()?["builder"]["auto_thumbnail"].as_bool();
+            state.saved_auto_thumbnail = current;
+            Some(false)
+        }
+        (true, false) => Some(state.saved_auto_thumbnail.take().unwrap_or(true)),
+        _ => None,
+    };
+    if let Some(auto_thumbnail) = auto_thumbnail {
+        let settings = format!(r#"{{"builder": {{"auto_thumbnail": {auto_thumbnail}}}}}"#);
+        load_settings_from_str(&settings, "json").map_err(Error::from_c2pa_error)?;
+    }
+    state.options = options;
+    Ok(())
+}
+
+/// Keeps thumbnails turned off after SDK settings are loaded, if the options turn them off.
+///
+/// A loaded builder.auto_thumbnail value is put back when thumbnails are turned on again.
+pub(crate) fn settings_loaded(auto_thumbnail: Option<bool>) -> Result<()> {
+    let mut state = STATE
+        .write()
+        .map_err(|_| Error::Other("thumbnail options lock poisoned".to_string()))?;
+    if !disables(state.options.as_ref()) {
+        return Ok(());
+    }
+    if auto_thumbnail.is_some() {
+        state.saved_auto_thumbnail = auto_thumbnail;
+    }
+    load_settings_from_str(r#"{"builder": {"auto_thumbnail": false}}"#, "json")
+        .map_err(Error::from_c2pa_error)
+}
+
+// The options set by the host, or None to leave thumbnails to the SDK.
+fn options() -> Option<ThumbnailOptions> {
+    STATE.read().ok().and_then(|state| state.options.clone())
+}
+
+// A transfer function from an ICC curve.
+enum Curve {
+    Gamma(f32),
+    Table(Vec<f32>),
+    // ICC parametric curve parameters g, a, b, c, d, e, f
+    Parametric([f32; 7]),
+}
+
+impl Curve {
+    fn linear(&self, x: f32) -> f32 {
+        match self {
+            Curve::Gamma(gamma) => x.powf(*gamma),
+            Curve::Table(table) => {
+                let pos = x * (table.len() - 1) as f32;
+                let i = (pos as usize).min(table.len() - 2);
+                let t = pos - i as f32;
+                table[i] + (table[i + 1] - table[i]) * t
+            }
+            Curve::Parametric([g, a, b, c, d, e, f]) => {
+                if x >= *d {
+                    (a * x + b).max(0.0).powf(*g) + e
+                } else {
+                    c * x + f
+                }
+            }
+        }
+    }
+}
+
+// A matrix/TRC RGB ICC profile, the kind used by Display P3, Adobe RGB and sRGB.
+struct MatrixProfile {
+    // columns are the red, green and blue colorants in PCS XYZ
+    matrix: [[f32; 3]; 3],
+    curves: [Curve; 3],
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn s15_fixed16(data: &[u8], offset: usize) -> Option<f32> {
+    Some(be_u32(data, offset)? as i32 as f32 / 65536.0)
+}
+
+impl MatrixProfile {
+    fn parse(icc: &[u8]) -> Option<Self> {
+        if icc.get(16..20)? != b"RGB " || icc.get(20..24)? != b"XYZ " {
+            return None;
+        }
+        let count = be_u32(icc, 128)? as usize;
+        let tag = |signature: &[u8]| {
+            (0..count).find_map(|i| {
+                let entry = 132 + i * 12;
+                if icc.get(entry..entry + 4)? != signature {
+                    return None;
+                }
+                let offset = be_u32(icc, entry + 4)? as usize;
+                let size = be_u32(icc, entry + 8)? as usize;
+                icc.get(offset..offset.checked_add(size)?)
+            })
+        };
+        let xyz = |signature: &[u8]| -> Option<[f32; 3]> {
+            let data = tag(signature)?;
+            if data.get(0..4)? != b"XYZ " {
+                return None;
+            }
+            Some([
+                s15_fixed16(data, 8)?,
+                s15_fixed16(data, 12)?,
+                s15_fixed16(data, 16)?,
+            ])
+        };
+        let curve = |signature: &[u8]| -> Option<Curve> {
+            let data = tag(signature)?;
+            match data.get(0..4)? {
+                b"curv" => match be_u32(data, 8)? as usize {
+                    0 => Some(Curve::Gamma(1.0)),
+                    1 => Some(Curve::Gamma(be_u16(data, 12)? as f32 / 256.0)),
+                    n => (0..n)
+                        .map(|i| be_u16(data, 12 + i * 2).map(|v| v as f32 / 65535.0))
+                        .collect::<Option<Vec<f32>>>()
+                        .map(Curve::Table),
+                },
+                b"para" => {
+                    let param_count = match be_u16(data, 8)? {
+                        0 => 1,
+                        1 => 3,
+                        2 => 4,
+                        3 => 5,
+                        4 => 7,
+                        _ => return None,
+                    };
+                    let mut p = [0f32; 7];
+                    for (i, value) in p.iter_mut().enumerate().take(param_count) {
+                        *value = s15_fixed16(data, 12 + i * 4)?;
+                    }
+                    let [g, a, b, c, d, e, f] = p;
+                    // express every function type as type 4
+                    Some(Curve::Parametric(match param_count {
+                        1 => [g, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                        3 => [g, a, b, 0.0, -b / a, 0.0, 0.0],
+                        4 => [g, a, b, 0.0, -b / a, c, c],
+                        5 => [g, a, b, c, d, 0.0, 0.0],
+                        _ => [g, a, b, c, d, e, f],
+                    }))
+                }
+                _ => None,
+            }
+        };
+        let (r, g, b) = (xyz(b"rXYZ")?, xyz(b"gXYZ")?, xyz(b"bXYZ")?);
+        Some(Self {
+            matrix: [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]],
+            curves: [curve(b"rTRC")?, curve(b"gTRC")?, curve(b"bTRC")?],
+        })
+    }
+}
+
+// the sRGB colorants adapted to the D50 PCS, by column
+const SRGB_D50: [[f32; 3]; 3] = [
+    [0.436_074_7, 0.385_064_9, 0.143_080_4],
+    [0.222_504_5, 0.716_878_6, 0.060_616_9],
+    [0.013_932_2, 0.097_104_5, 0.714_173_3],
+];
+
+fn multiply(a: &[[f32; 3]; 3], b: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut m = [[0f32; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    m
+}
+
+fn invert(m: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+        (m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]) / det
+    };
+    [
+        [
+            cofactor(1, 2, 1, 2),
+            -cofactor(0, 2, 1, 2),
+            cofactor(0, 1, 1, 2),
+        ],
+        [
+            -cofactor(1, 2, 0, 2),
+            cofactor(0, 2, 0, 2),
+            -cofactor(0, 1, 0, 2),
+        ],
+        [
+            cofactor(1, 2, 0, 1),
+            -cofactor(0, 2, 0, 1),
+            cofactor(0, 1, 0, 1),
+        ],
+    ]
+}
+
+fn srgb_encode(v: f32) -> u8 {
+    let v = v.clamp(0.0, 1.0);
+    let v = if v <= 0.003_130_8 {
+        12.92 * v
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (v * 255.0).round() as u8
+}
+
+// Converts the pixels of an image from a matrix/TRC profile to sRGB.
+fn convert_to_srgb(img: DynamicImage, profile: &MatrixProfile) -> DynamicImage {
+    let matrix = multiply(&invert(&SRGB_D50), &profile.matrix);
+    let luts: Vec<[f32; 256]> = profile
+        .curves
+        .iter()
+        .map(|curve| std::array::from_fn(|i| curve.linear(i as f32 / 255.0)))
+        .collect();
+    let convert = |pixel: &mut [u8]| {
+        let linear: [f32; 3] = std::array::from_fn(|c| luts[c][pixel[c] as usize]);
+        for (c, row) in matrix.iter().enumerate() {
+            pixel[c] = srgb_encode((0..3).map(|k| row[k] * linear[k]).sum());
+        }
+    };
+    if img.color().has_alpha() {
+        let mut rgba = img.into_rgba8();
+        rgba.chunks_exact_mut(4).for_each(convert);
+        DynamicImage::ImageRgba8(rgba)
+    } else {
+        let mut rgb = img.into_rgb8();
+        rgb.chunks_exact_mut(3).for_each(convert);
+        DynamicImage::ImageRgb8(rgb)
+    }
+}
+
+fn is_png(format: &str) -> bool {
+    matches!(format.to_lowercase().as_str(), "png" | "image/png")
+}
+
+fn is_supported(format: &str) -> bool {
+    is_png(format)
+        || matches!(
+            format.to_lowercase().as_str(),
+            "jpg" | "jpeg" | "image/jpeg" | "webp" | "image/webp"
+        )
+}
+
+/// Makes a color managed thumbnail for an asset using the given options.
+///
+/// Returns the thumbnail format and bytes, or None if the asset is not a JPEG, PNG or WebP,
+/// or if it has no embedded ICC profile and the options ask for the SDK's own thumbnail,
+/// in which case the SDK thumbnail is already correct.
+pub fn make_thumbnail(
+    format: &str,
+    data: &[u8],
+    options: &ThumbnailOptions,
+) -> Result<Option<(String, Vec<u8>)>> {
+    if !is_supported(format) {
+        return Ok(None);
+    }
+    let profile = DynImage::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| Error::Other(format!("invalid image: {e}")))?
+        .and_then(|image| image.icc_profile());
+    if profile.is_none() && !options.customized() {
+        return Ok(None);
+    }
+
+    let mut img =
+        image::load_from_memory(data).map_err(|e| Error::Other(format!("invalid image: {e}")))?;
+    let edge = options.longest_edge.max(1);
+    if img.width() > edge || img.height() > edge {
+        img = img.thumbnail(edge, edge);
+    }
+    let mut embed = profile.clone();
+    if options.color_space == ThumbnailColorSpace::Srgb {
+        if let Some(matrix_profile) = profile.as_deref().and_then(MatrixProfile::parse) {
+            img = convert_to_srgb(img, &matrix_profile);
+            // untagged thumbnails are displayed as sRGB
+            embed = None;
+        }
+    }
+
+    // use png thumbnails for png sources with transparency, as the SDK does
+    let thumbnail_format = match options.format {
+        Some(thumbnail_format) => thumbnail_format,
+        None if is_png(format) && img.color().has_alpha() => ThumbnailFormat::Png,
+        None => ThumbnailFormat::Jpeg,
+    };
+    let (output_format, mime) = match thumbnail_format {
+        ThumbnailFormat::Jpeg => {
+            img = DynamicImage::ImageRgb8(img.into_rgb8());
+            (
+                ImageOutputFormat::Jpeg(options.quality.clamp(1, 100)),
+                "image/jpeg",
+            )
+        }
+        ThumbnailFormat::Png => (ImageOutputFormat::Png, "image/png"),
+        ThumbnailFormat::Webp => {
+            // the WebP encoder only takes 8 bit images
+            img = match img.color().has_alpha() {
+                true => DynamicImage::ImageRgba8(img.into_rgba8()),
+                false => DynamicImage::ImageRgb8(img.into_rgb8()),
+            };
+            (ImageOutputFormat::WebP, "image/webp")
+        }
+    };
+    let mut encoded = Cursor::new(Vec::new());
+    img.write_to(&mut encoded, output_format)
+        .map_err(|e| Error::Other(format!("thumbnail encoding failed: {e}")))?;
+
+    let encoded = Bytes::from(encoded.into_inner());
+    let mut thumbnail = DynImage::from_bytes(encoded)
+        .map_err(|e| Error::Other(format!("thumbnail encoding failed: {e}")))?
+        .ok_or_else(|| Error::Other("thumbnail encoding failed".to_string()))?;
+    thumbnail.set_icc_profile(embed);
+    Ok(Some((
+        mime.to_string(),
+        thumbnail.encoder().bytes().to_vec(),
+    )))
+}
+
+// Makes a thumbnail from a stream, leaving the stream at its start.
+fn thumbnail_from_stream<R>(
+    format: &str,
+    stream: &mut R,
+    options: Option<ThumbnailOptions>,
+) -> Option<(String, Vec<u8>)>
+where
+    R: Read + Seek + ?Sized,
+{
+    let options = options.filter(|options| options.enabled)?;
+    if !is_supported(format) {
+        return None;
+    }
+    let mut data = Vec::new();
+    stream.seek(SeekFrom::Start(0)).ok()?;
+    let read = stream.read_to_end(&mut data);
+    stream.seek(SeekFrom::Start(0)).ok()?;
+    read.ok()?;
+    // the SDK thumbnail is used if one cannot be made here
+    make_thumbnail(format, &data, &options).ok().flatten()
+}
+
+/// Sets a color managed claim thumbnail on a Builder that does not have one.
+pub fn prepare_builder<R>(builder: &mut Builder, format: &str, source: &mut R) -> Result<()>
+where
+    R: Read + Seek + Send,
+{
+    if builder.definition.thumbnail.is_some() {
+        return Ok(());
+    }
+    if let Some((format, data)) = thumbnail_from_stream(format, source, options()) {
+        builder
+            .set_thumbnail(&format, &mut Cursor::new(data))
+            .map_err(Error::from_c2pa_error)?;
+    }
+    Ok(())
+}
+
+/// Adds an ingredient from a stream to a Builder with a color managed thumbnail,
+/// unless the ingredient definition or its manifest provides a thumbnail.
+pub fn add_ingredient_from_stream<R>(
+    builder: &mut Builder,
+    ingredient_json: &str,
+    format: &str,
+    stream: &mut R,
+) -> Result<()>
+where
+    R: Read + Seek + Send,
+{
+    builder
+        .add_ingredient_from_stream(ingredient_json, format, stream)
+        .map_err(Error::from_c2pa_error)?;
+    let has_thumbnail = serde_json::from_str::<serde_json::Value>(ingredient_json)
+        .is_ok_and(|definition| !definition["thumbnail"].is_null());
+    if has_thumbnail {
+        return Ok(());
+    }
+    // an ingredient with a manifest may reference the thumbnail of its claim
+    let ingredient = builder
+        .definition
+        .ingredients
+        .last_mut()
+        .filter(|ingredient| ingredient.manifest_data().is_none());
+    if let (Some(ingredient), Some((format, data))) =
+        (ingredient, thumbnail_from_stream(format, stream, options()))
+    {
+        ingredient
+            .set_thumbnail(format, data)
+            .map_err(Error::from_c2pa_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+
+    use super::*;
+
+    // Builds a minimal matrix/TRC profile with sRGB colorants and a gamma curve.
+    fn gamma_profile(gamma: f32) -> Vec<u8> {
+        let mut tags: Vec<(&[u8; 4], Vec<u8>)> = Vec::new();
+        for (signature, column) in [(b"rXYZ", 0), (b"gXYZ", 1), (b"bXYZ", 2)] {
+            let mut data = b"XYZ \0\0\0\0".to_vec();
+            for row in SRGB_D50 {
+                data.extend(((row[column] * 65536.0).round() as i32).to_be_bytes());
+            }
+            tags.push((signature, data));
+        }
+        for signature in [b"rTRC", b"gTRC", b"bTRC"] {
+            let mut data = b"curv\0\0\0\0".to_vec();
+            data.extend(1u32.to_be_bytes());
+            data.extend(((gamma * 256.0) as u16).to_be_bytes());
+            data.extend([0, 0]);
+            tags.push((signature, data));
+        }
+        let mut header = vec![0u8; 128];
+        header[12..16].copy_from_slice(b"mntr");
+        header[16..20].copy_from_slice(b"RGB ");
+        header[20..24].copy_from_slice(b"XYZ ");
+        header[36..40].copy_from_slice(b"acsp");
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut offset = 128 + 4 + tags.len() * 12;
+        let mut body = Vec::new();
+        for (signature, data) in &tags {
+            table.extend(*signature);
+            table.extend((offset as u32).to_be_bytes());
+            table.extend((data.len() as u32).to_be_bytes());
+            offset += data.len();
+            body.extend(data);
+        }
+        let mut profile = [header, table, body].concat();
+        let size = profile.len() as u32;
+        profile[0..4].copy_from_slice(&size.to_be_bytes());
+        profile
+    }
+
+    fn jpeg_with_profile(profile: &[u8]) -> Vec<u8> {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 48, Rgb([128, 128, 128])));
+        let mut encoded = Cursor::new(Vec::new());
+        img.write_to(&mut encoded, ImageOutputFormat::Jpeg(95))
+            .unwrap();
+        let mut jpeg = DynImage::from_bytes(Bytes::from(encoded.into_inner()))
+            .unwrap()
+            .unwrap();
+        jpeg.set_icc_profile(Some(Bytes::copy_from_slice(profile)));
+        jpeg.encoder().bytes().to_vec()
+    }
+
+    #[test]
+    fn test_make_thumbnail() {
+        let profile = gamma_profile(1.0);
+        let source = jpeg_with_profile(&profile);
+
+        let options = ThumbnailOptions {
+            longest_edge: 32,
+            ..Default::default()
+        };
+        let (format, data) = make_thumbnail("image/jpeg", &source, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(format, "image/jpeg");
+        let thumbnail = DynImage::from_bytes(Bytes::from(data.clone()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            thumbnail.icc_profile().unwrap().as_ref(),
+            profile.as_slice()
+        );
+        let img = image::load_from_memory(&data).unwrap();
+        assert_eq!((img.width(), img.height()), (32, 24));
+
+        let options = ThumbnailOptions {
+            color_space: ThumbnailColorSpace::Srgb,
+            ..Default::default()
+        };
+        let (_, data) = make_thumbnail("jpg", &source, &options).unwrap().unwrap();
+        let thumbnail = DynImage::from_bytes(Bytes::from(data.clone()))
+            .unwrap()
+            .unwrap();
+        assert!(thumbnail.icc_profile().is_none());
+        // linear mid gray is lighter once encoded for sRGB
+        let pixel = image::load_from_memory(&data).unwrap().to_rgb8()[(10, 10)];
+        assert!(pixel.0.iter().all(|v| (184..=190).contains(v)), "{pixel:?}");
+
+        // the fixture carries a standard sRGB profile
+        let tagged = std::fs::read("tests/fixtures/A.jpg").unwrap();
+        let (_, data) = make_thumbnail("jpg", &tagged, &options).unwrap().unwrap();
+        let thumbnail = DynImage::from_bytes(Bytes::from(data)).unwrap().unwrap();
+        assert!(thumbnail.icc_profile().is_none());
+
+        let untagged = std::fs::read("tests/fixtures/C.jpg").unwrap();
+        assert!(make_thumbnail("image/jpeg", &untagged, &options)
+            .unwrap()
+            .is_none());
+        assert!(make_thumbnail("image/tiff", &source, &options)
+            .unwrap()
+            .is_none());
+
+        // untagged assets get a thumbnail once the options differ from the SDK's
+        let options: ThumbnailOptions =
+            serde_json::from_str(r#"{"format": "webp", "longest_edge": 16}"#).unwrap();
+        let (format, data) = make_thumbnail("image/jpeg", &untagged, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(format, "image/webp");
+        let img = image::load_from_memory(&data).unwrap();
+        assert_eq!(img.width().max(img.height()), 16);
+        let (_, data) = make_thumbnail("jpg", &source, &options).unwrap().unwrap();
+        let thumbnail = DynImage::from_bytes(Bytes::from(data)).unwrap().unwrap();
+        assert_eq!(
+            thumbnail.icc_profile().unwrap().as_ref(),
+            profile.as_slice()
+        );
+    }
+
+    #[test]
+    fn test_disable_thumbnails() {
+        let disabled: ThumbnailOptions = serde_json::from_str(r#"{"enabled": false}"#).unwrap();
+        let mut source = Cursor::new(jpeg_with_profile(&gamma_profile(1.0)));
+        assert!(thumbnail_from_stream("image/jpeg", &mut source, Some(disabled.clone())).is_none());
+        let enabled = ThumbnailOptions::default();
+        assert!(thumbnail_from_stream("image/jpeg", &mut source, Some(enabled.clone())).is_some());
+        assert_eq!(source.position(), 0);
+
+        // only turning thumbnails off or back on changes the SDK setting
+        assert!(disables(Some(&disabled)));
+        assert!(!disables(Some(&enabled)));
+        assert!(!disables(None));
+    }
+
+    #[test]
+    fn test_webp_thumbnail() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 48, Rgb([128, 128, 128])));
+        let mut webp = Cursor::new(Vec::new());
+        img.write_to(&mut webp, ImageOutputFormat::WebP).unwrap();
+        let options = ThumbnailOptions {
+            format: Some(ThumbnailFormat::Jpeg),
+            longest_edge: 16,
+            ..Default::default()
+        };
+        let (format, data) = make_thumbnail("image/webp", webp.get_ref(), &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(format, "image/jpeg");
+        let thumbnail = image::load_from_memory(&data).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (16, 12));
+        assert!(make_thumbnail("image/gif", webp.get_ref(), &options)
+            .unwrap()
+            .is_none());
+    }
+}
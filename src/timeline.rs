@@ -0,0 +1,249 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Records edits, including edits over time ranges, in the actions assertion of a Builder.
+
+use c2pa::{
+    assertions::{
+        c2pa_action,
+        region_of_interest::{Range, RangeType, RegionOfInterest, Role, Time, TimeType},
+        Action, Actions,
+    },
+    Builder,
+};
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+const ACTIONS_V2: &str = "c2pa.actions.v2";
+
+// the action parameter the Builder resolves to ingredient references
+const INGREDIENT_IDS: &str = "org.cai.ingredientIds";
+
+/// The kinds of timeline edits with a predefined action and region role.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineEdit {
+    /// The range is what remains after trimming.
+    Trim,
+    /// An ingredient was spliced in over the range.
+    Splice,
+    /// An ingredient was overlaid on the range.
+    Overlay,
+}
+
+impl TimelineEdit {
+    fn action_and_role(self) -> (&'static str, Role) {
+        match self {
+            TimelineEdit::Trim => (c2pa_action::CROPPED, Role::Cropped),
+            TimelineEdit::Splice => (c2pa_action::PLACED, Role::Placed),
+            TimelineEdit::Overlay => (c2pa_action::EDITED, Role::Placed),
+        }
+    }
+}
+
+/// A time in the asset, as seconds or as a Normal Play Time string such as "00:01:05.5".
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Timecode {
+    Seconds(f64),
+    Npt(String),
+}
+
+impl Timecode {
+    fn to_npt(&self) -> Result<String> {
+        match self {
+            Timecode::Seconds(seconds) if seconds.is_finite() && *seconds >= 0.0 => {
+                Ok(seconds.to_string())
+            }
+            Timecode::Npt(npt)
+                if !npt.is_empty()
+                    && npt.split(':').count() <= 3
+                    && npt
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || c == ':' || c == '.') =>
+            {
+                Ok(npt.clone())
+            }
+            _ => Err(Error::Other(format!("invalid timecode {self:?}"))),
+        }
+    }
+}
+
+/// An action that applies to a time range of the asset.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TimedAction {
+    /// A predefined edit, which sets the action and region role.
+    pub edit: Option<TimelineEdit>,
+    /// The action label, required when no edit is given and otherwise overriding the edit's action.
+    pub action: Option<String>,
+    /// The start of the range, or the start of the asset if not present.
+    pub start: Option<Timecode>,
+    /// The end of the range, or the end of the asset if not present.
+    pub end: Option<Timecode>,
+    /// The instance_id of an ingredient added to the Builder, such as the spliced clip.
+    pub ingredient: Option<String>,
+    /// A description of the edited range.
+    pub description: Option<String>,
+}
+
+impl TimedAction {
+    /// Create a TimedAction from a JSON string
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))
+    }
+
+    fn to_action(&self) -> Result<Action> {
+        let (default_action, role) = match self.edit {
+            Some(edit) => {
+                let (action, role) = edit.action_and_role();
+                (Some(action), role)
+            }
+            None => (None, Role::Edited),
+        };
+        let label =
+            self.action.as_deref().or(default_action).ok_or_else(|| {
+                Error::Other("a timed action needs an edit or action".to_string())
+            })?;
+
+        let time = Time {
+            time_type: TimeType::Npt,
+            start: self.start.as_ref().map(Timecode::to_npt).transpose()?,
+            end: self.end.as_ref().map(Timecode::to_npt).transpose()?,
+        };
+        let region = RegionOfInterest {
+            region: vec![Range {
+                range_type: RangeType::Temporal,
+                time: Some(time),
+                ..Default::default()
+            }],
+            role: Some(role),
+            description: self.description.clone(),
+            ..Default::default()
+        };
+
+        let mut action = Action::new(label).add_change(region);
+        if let Some(ingredient) = self.ingredient.as_deref() {
+            action = action
+                .set_parameter(INGREDIENT_IDS, vec![ingredient])
+                .map_err(Error::from_c2pa_error)?;
+        }
+        Ok(action)
+    }
+}
+
+/// Adds an action to the Builder's actions assertion, creating the assertion if needed.
+///
+/// Actions already in the manifest definition are kept, so edits can be recorded as they happen.
+pub fn add_action(builder: &mut Builder, action: Action) -> Result<()> {
+    let assertions = &mut builder.definition.assertions;
+    let existing = assertions
+        .iter()
+        .position(|assertion| assertion.label.starts_with(Actions::LABEL));
+    let actions = match existing {
+        Some(index) => {
+            let assertion = assertions.remove(index);
+            // round trip through JSON so either CBOR or JSON assertion data can be decoded
+            let value =
+                serde_json::to_value(&assertion.data).map_err(|e| Error::Json(e.to_string()))?;
+            serde_json::from_value::<Actions>(value).map_err(|e| Error::Json(e.to_string()))?
+        }
+        None => Actions::new(),
+    };
+    // time ranges are only defined for version 2 actions
+    builder
+        .add_assertion(ACTIONS_V2, &actions.add_action(action))
+        .map_err(Error::from_c2pa_error)?;
+    Ok(())
+}
+
+/// Adds an action from a JSON string, such as `{"action": "c2pa.cropped"}`,
+/// to the Builder's actions assertion.
+pub fn add_action_json(builder: &mut Builder, action_json: &str) -> Result<()> {
+    let action =
+        serde_json::from_str::<Action>(action_json).map_err(|e| Error::Json(e.to_string()))?;
+    add_action(builder, action)
+}
+
+/// Adds a timed action to the Builder's actions assertion, creating the assertion if needed.
+pub fn add_timed_action(builder: &mut Builder, timed_action: &TimedAction) -> Result<()> {
+    add_action(builder, timed_action.to_action()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use c2pa::{CallbackSigner, Reader, SigningAlg};
+
+    use super::*;
+
+    const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+    const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+    #[test]
+    fn test_add_timed_action() {
+        let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+        let mut builder = Builder::from_json(&manifest_json).unwrap();
+        let trim =
+            TimedAction::from_json(r#"{"edit": "trim", "start": 5, "end": "00:00:10.5"}"#).unwrap();
+        add_timed_action(&mut builder, &trim).unwrap();
+        let edit =
+            TimedAction::from_json(r#"{"action": "c2pa.filtered", "start": "1:02"}"#).unwrap();
+        add_timed_action(&mut builder, &edit).unwrap();
+        assert!(add_timed_action(&mut builder, &TimedAction::default()).is_err());
+        let bad = TimedAction::from_json(r#"{"edit": "trim", "start": "5s"}"#).unwrap();
+        assert!(add_timed_action(&mut builder, &bad).is_err());
+        add_action_json(
+            &mut builder,
+            r#"{"action": "c2pa.color_adjustments", "softwareAgent": "Editor 1.0"}"#,
+        )
+        .unwrap();
+        assert!(add_action_json(&mut builder, r#"{"softwareAgent": "Editor 1.0"}"#).is_err());
+
+        let ed_signer =
+            |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+        let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+        let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+        let mut dest = Cursor::new(Vec::new());
+        builder
+            .sign(&signer, "image/jpeg", &mut source, &mut dest)
+            .unwrap();
+        dest.set_position(0);
+        let reader = Reader::from_stream("image/jpeg", &mut dest).unwrap();
+        let actions: Actions = reader
+            .active_manifest()
+            .unwrap()
+            .find_assertion(ACTIONS_V2)
+            .unwrap();
+        let trimmed = actions
+            .actions()
+            .iter()
+            .find(|action| action.action() == c2pa_action::CROPPED)
+            .unwrap();
+        let time = trimmed.changes().unwrap()[0].region[0]
+            .time
+            .clone()
+            .unwrap();
+        assert_eq!(time.start.as_deref(), Some("5"));
+        assert_eq!(time.end.as_deref(), Some("00:00:10.5"));
+        assert!(actions
+            .actions()
+            .iter()
+            .any(|action| action.action() == c2pa_action::FILTERED));
+        let adjusted = actions.actions().last().unwrap();
+        assert_eq!(adjusted.action(), c2pa_action::COLOR_ADJUSTMENTS);
+        assert!(adjusted.software_agent().is_some());
+    }
+}
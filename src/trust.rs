@@ -0,0 +1,182 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Sets the trust lists that readers check signing certificates against.
+
+use std::collections::HashSet;
+
+use c2pa::settings::{load_settings_from_str, Settings};
+use c2pa_crypto::{base64, hash::sha256, openssl::OpenSslMutex};
+use openssl::{
+    stack::Stack,
+    x509::{store::X509StoreBuilder, verify::X509VerifyFlags, X509StoreContext, X509},
+};
+use serde_json::{Map, Value};
+
+use crate::{certs::pem_certs, Error, Result};
+
+/// Applies trust settings, keyed by their names in the trust section of the SDK settings.
+///
+/// A null value clears a setting.
+pub(crate) fn load_trust(trust: Map<String, Value>) -> Result<()> {
+    if trust.is_empty() {
+        return Ok(());
+    }
+    let settings = serde_json::json!({ "trust": trust }).to_string();
+    load_settings_from_str(&settings, "json").map_err(Error::from_c2pa_error)
+}
+
+/// Sets or, given None, clears one trust setting.
+///
+/// Setting trust anchors or an allowed list also turns on the verify_trust setting,
+/// without which they are not checked. Clearing them leaves verify_trust as it is.
+pub fn set_trust(key: &str, value: Option<&str>) -> Result<()> {
+    let checked = value.is_some() && key != "trust_config";
+    let value = value.map_or(Value::Null, |value| Value::String(value.to_string()));
+    load_trust(Map::from_iter([(key.to_string(), value)]))?;
+    if checked {
+        load_settings_from_str(r#"{"verify": {"verify_trust": true}}"#, "json")
+            .map_err(Error::from_c2pa_error)?;
+    }
+    Ok(())
+}
+
+/// The certificates trusted to sign what the SDK does not validate itself,
+/// such as identity assertions and standalone COSE signatures.
+pub(crate) struct TrustList {
+    anchors: Vec<Vec<u8>>,
+    // base64 SHA-256 hashes of allowed end-entity certificates
+    allowed: HashSet<String>,
+}
+
+impl TrustList {
+    pub(crate) fn new(anchors: Vec<Vec<u8>>, allowed: &[Vec<u8>]) -> Self {
+        let allowed = allowed.iter().map(|der| base64::encode(&sha256(der)));
+        Self {
+            anchors,
+            allowed: allowed.collect(),
+        }
+    }
+
+    /// Loads the trust anchors and allowed list from the SDK settings.
+    pub(crate) fn configured() -> Result<Self> {
+        let settings = Settings::from_string("{}", "json").map_err(Error::from_c2pa_error)?;
+        let settings = serde_json::to_value(settings).map_err(|e| Error::Json(e.to_string()))?;
+        let trust = &settings["trust"];
+        let mut anchors = Vec::new();
+        for key in ["trust_anchors", "private_anchors"] {
+            if let Some(pem) = trust[key].as_str().filter(|pem| pem.contains("-----BEGIN")) {
+                anchors.extend(pem_certs(pem)?);
+            }
+        }
+        let mut list = Self::new(anchors, &[]);
+        if let Some(allowed) = trust["allowed_list"].as_str() {
+            // PEM certificates or the base64 SHA-256 hashes of certificates, as the SDK accepts
+            if allowed.contains("-----BEGIN") {
+                list.allowed.extend(
+                    pem_certs(allowed)?
+                        .iter()
+                        .map(|der| base64::encode(&sha256(der))),
+                );
+            }
+            let mut in_pem = false;
+            for line in allowed.lines().map(str::trim) {
+                if line.starts_with("-----BEGIN") {
+                    in_pem = true;
+                } else if line.starts_with("-----END") {
+                    in_pem = false;
+                } else if !in_pem && !line.is_empty() && base64::decode(line).is_ok() {
+                    list.allowed.insert(line.to_string());
+                }
+            }
+        }
+        Ok(list)
+    }
+
+    /// Returns true if a certificate chain, leaf first, is on the allowed list
+    /// or chains to a trust anchor.
+    ///
+    /// Validity periods are not checked, since signatures outlive their certificates.
+    pub(crate) fn trusts(&self, chain: &[Vec<u8>]) -> Result<bool> {
+        let Some(leaf) = chain.first() else {
+            return Ok(false);
+        };
+        if self.allowed.contains(&base64::encode(&sha256(leaf))) {
+            return Ok(true);
+        }
+        if self.anchors.is_empty() {
+            return Ok(false);
+        }
+        let invalid = |e: openssl::error::ErrorStack| Error::Signature(e.to_string());
+        let _openssl = OpenSslMutex::acquire().map_err(|e| Error::Other(e.to_string()))?;
+        let leaf = X509::from_der(leaf).map_err(invalid)?;
+        let mut intermediates = Stack::new().map_err(invalid)?;
+        for der in &chain[1..] {
+            intermediates
+                .push(X509::from_der(der).map_err(invalid)?)
+                .map_err(invalid)?;
+        }
+        let mut store = X509StoreBuilder::new().map_err(invalid)?;
+        for der in &self.anchors {
+            store
+                .add_cert(X509::from_der(der).map_err(invalid)?)
+                .map_err(invalid)?;
+        }
+        // an anchor may be an intermediate certificate
+        store
+            .set_flags(X509VerifyFlags::PARTIAL_CHAIN | X509VerifyFlags::NO_CHECK_TIME)
+            .map_err(invalid)?;
+        let store = store.build();
+        let mut context = X509StoreContext::new().map_err(invalid)?;
+        context
+            .init(&store, &leaf, &intermediates, |context| {
+                context.verify_cert()
+            })
+            .map_err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERTS: &str = include_str!("../tests/fixtures/ed25519.pub");
+    const OTHER_CERTS: &str = include_str!("../tests/fixtures/es256_certs.pem");
+
+    #[test]
+    fn test_trust_list() {
+        let chain = pem_certs(CERTS).unwrap();
+        let other = pem_certs(OTHER_CERTS).unwrap();
+        // anchored at the intermediate certificate
+        let anchored = TrustList::new(vec![chain[1].clone()], &[]);
+        assert!(anchored.trusts(&chain).unwrap());
+        assert!(!anchored.trusts(&other).unwrap());
+        assert!(anchored.trusts(&chain[..1]).unwrap());
+        let allowed = TrustList::new(Vec::new(), &other[..1]);
+        assert!(allowed.trusts(&other).unwrap());
+        assert!(!allowed.trusts(&chain).unwrap());
+        assert!(!TrustList::new(Vec::new(), &[]).trusts(&chain).unwrap());
+    }
+
+    #[test]
+    fn test_set_trust_clears() {
+        set_trust("trust_config", Some("1.3.6.1.5.5.7.3.4\n")).unwrap();
+        let settings = c2pa::settings::Settings::from_string("{}", "json").unwrap();
+        let json = serde_json::to_value(&settings).unwrap();
+        assert_eq!(json["trust"]["trust_config"], "1.3.6.1.5.5.7.3.4\n");
+        set_trust("trust_config", None).unwrap();
+        let settings = c2pa::settings::Settings::from_string("{}", "json").unwrap();
+        let json = serde_json::to_value(&settings).unwrap();
+        assert!(json["trust"]["trust_config"].is_null());
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Builds RFC 3161 time stamp requests with caller supplied options.
+
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use c2pa::hash_stream_by_alg;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Options for the requests sent to a time stamp authority.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TsaOptions {
+    /// The digest algorithm for the message imprint: sha256, sha384 or sha512.
+    pub digest_alg: String,
+    /// The TSA policy OID to request, in dotted form.
+    pub policy_oid: Option<String>,
+    /// Include a nonce in the request.
+    pub nonce: bool,
+    /// Additional HTTP headers sent with the request.
+    pub headers: HashMap<String, String>,
+}
+
+impl Default for TsaOptions {
+    fn default() -> Self {
+        Self {
+            digest_alg: "sha256".to_string(),
+            policy_oid: None,
+            nonce: true,
+            headers: HashMap::new(),
+        }
+    }
+}
+
+// DER tags used in a TimeStampReq
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+// Encodes an unsigned integer as a positive DER INTEGER.
+fn der_unsigned(bytes: &[u8]) -> Vec<u8> {
+    let mut content: Vec<u8> = bytes.iter().copied().skip_while(|b| *b == 0).collect();
+    if content.first().is_none_or(|b| b & 0x80 != 0) {
+        content.insert(0, 0);
+    }
+    der(TAG_INTEGER, &content)
+}
+
+fn der_oid(oid: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::Other(format!("invalid OID {oid}"));
+    let arcs = oid
+        .split('.')
+        .map(|arc| arc.parse::<u64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<u64>>>()?;
+    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+        return Err(invalid());
+    }
+    let mut content = Vec::new();
+    let first = arcs[0] * 40 + arcs[1];
+    for arc in std::iter::once(first).chain(arcs[2..].iter().copied()) {
+        let mut encoded = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            encoded.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        encoded.reverse();
+        content.extend(encoded);
+    }
+    Ok(der(TAG_OID, &content))
+}
+
+fn digest_oid(alg: &str) -> Result<&'static str> {
+    match alg {
+        "sha256" => Ok("2.16.840.1.101.3.4.2.1"),
+        "sha384" => Ok("2.16.840.1.101.3.4.2.2"),
+        "sha512" => Ok("2.16.840.1.101.3.4.2.3"),
+        _ => Err(Error::Other(format!(
+            "unsupported TSA digest algorithm {alg}"
+        ))),
+    }
+}
+
+// A nonce only has to be unique per request.
+fn nonce(message: &[u8]) -> Result<Vec<u8>> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_be_bytes()
+        .to_vec();
+    seed.extend(COUNTER.fetch_add(1, Ordering::Relaxed).to_be_bytes());
+    seed.extend_from_slice(message);
+    let hash = hash_stream_by_alg("sha256", &mut Cursor::new(seed), None, true)
+        .map_err(Error::from_c2pa_error)?;
+    Ok(hash[..8].to_vec())
+}
+
+/// Creates a DER encoded TimeStampReq for the message using the options.
+pub fn timestamp_request_body(options: &TsaOptions, message: &[u8]) -> Result<Vec<u8>> {
+    let alg = options.digest_alg.to_lowercase();
+    let mut algorithm = der_oid(digest_oid(&alg)?)?;
+    let digest = hash_stream_by_alg(&alg, &mut Cursor::new(message), None, true)
+        .map_err(Error::from_c2pa_error)?;
+
+    algorithm.extend(der(TAG_NULL, &[]));
+    let mut imprint = der(TAG_SEQUENCE, &algorithm);
+    imprint.extend(der(TAG_OCTET_STRING, &digest));
+
+    let mut request = der_unsigned(&[1]);
+    request.extend(der(TAG_SEQUENCE, &imprint));
+    if let Some(policy_oid) = options.policy_oid.as_deref() {
+        request.extend(der_oid(policy_oid)?);
+    }
+    if options.nonce {
+        request.extend(der_unsigned(&nonce(message)?));
+    }
+    // certReq
+    request.extend(der(TAG_BOOLEAN, &[0xff]));
+    Ok(der(TAG_SEQUENCE, &request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_der_oid() {
+        assert_eq!(
+            der_oid("2.16.840.1.101.3.4.2.1").unwrap(),
+            [0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01]
+        );
+        assert!(der_oid("1.99").is_err());
+        assert!(der_oid("abc").is_err());
+    }
+
+    #[test]
+    fn test_timestamp_request_body() {
+        let options = TsaOptions {
+            policy_oid: Some("1.2.3.4".to_string()),
+            nonce: false,
+            ..Default::default()
+        };
+        let body = timestamp_request_body(&options, b"hello").unwrap();
+        // SEQUENCE, version 1, imprint with a 32 byte digest, policy and certReq
+        assert_eq!(body[0], TAG_SEQUENCE);
+        assert_eq!(&body[2..5], &[TAG_INTEGER, 0x01, 0x01]);
+        assert_eq!(body.len(), 2 + 3 + 51 + 5 + 3);
+        assert!(body.ends_with(&[TAG_BOOLEAN, 0x01, 0xff]));
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! The verify settings of the SDK, which apply to every Reader and Builder in the process
+//! unless an operation is given its own.
+
+use std::sync::{Mutex, MutexGuard};
+
+use c2pa::settings::{load_settings_from_str, Settings};
+use serde::{Deserialize, Serialize};
+
+use crate::{c_api::C2paVerifyOptions, remote, Error, Result};
+
+// The verify section of the SDK settings, except remote_manifest_fetch,
+// which the remote module keeps in step with its own fetching.
+#[derive(Deserialize, Serialize)]
+struct VerifySettings {
+    verify_after_reading: bool,
+    verify_after_sign: bool,
+    verify_trust: bool,
+    ocsp_fetch: bool,
+    check_ingredient_trust: bool,
+}
+
+// Held while the verify settings are read or changed, and for the whole of an operation
+// with settings of its own, so a get sees one consistent set and no change is lost.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn current() -> Result<C2paVerifyOptions> {
+    // loading no settings returns the current ones
+    let settings = Settings::from_string("{}", "json").map_err(Error::from_c2pa_error)?;
+    let verify = serde_json::to_value(settings)
+        .and_then(|settings| serde_json::from_value::<VerifySettings>(settings["verify"].clone()))
+        .map_err(|e| Error::Json(e.to_string()))?;
+    Ok(C2paVerifyOptions {
+        verify_after_reading: verify.verify_after_reading,
+        verify_after_sign: verify.verify_after_sign,
+        verify_trust: verify.verify_trust,
+        ocsp_fetch: verify.ocsp_fetch,
+        fetch_remote: !remote::fetch_disabled(),
+        check_ingredient_trust: verify.check_ingredient_trust,
+    })
+}
+
+fn apply(options: &C2paVerifyOptions) -> Result<()> {
+    let verify = VerifySettings {
+        verify_after_reading: options.verify_after_reading,
+        verify_after_sign: options.verify_after_sign,
+        verify_trust: options.verify_trust,
+        ocsp_fetch: options.ocsp_fetch,
+        check_ingredient_trust: options.check_ingredient_trust,
+    };
+    let settings = serde_json::json!({ "verify": verify }).to_string();
+    load_settings_from_str(&settings, "json").map_err(Error::from_c2pa_error)?;
+    remote::set_fetch_enabled(options.fetch_remote)
+}
+
+/// Returns the current verify settings.
+pub fn verify_options() -> Result<C2paVerifyOptions> {
+    let _lock = lock();
+    current()
+}
+
+/// Applies verify settings.
+pub fn set_verify_options(options: &C2paVerifyOptions) -> Result<()> {
+    let _lock = lock();
+    apply(options)
+}
+
+/// Runs an operation, such as reading or signing one asset, with its own verify settings,
+/// then puts back the settings it replaced.
+///
+/// Operations with their own settings run one at a time. Since the SDK keeps one set of
+/// settings for the process, operations without settings of their own that run at the
+/// same time also see these.
+pub fn with_verify_options<T>(
+    options: &C2paVerifyOptions,
+    operation: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let _lock = lock();
+    let previous = current()?;
+    apply(options)?;
+    let result = operation();
+    let restored = apply(&previous);
+    let value = result?;
+    restored.map(|_| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_options() {
+        // the settings are process-wide, so this only sets the values already in place
+        let options = verify_options().unwrap();
+        set_verify_options(&options).unwrap();
+        assert_eq!(verify_options().unwrap(), options);
+        // the operation sees the settings it was given
+        assert_eq!(with_verify_options(&options, current).unwrap(), options);
+        assert_eq!(verify_options().unwrap(), options);
+    }
+}
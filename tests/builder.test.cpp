@@ -167,6 +167,51 @@ TEST(Builder, SignStreamCloudUrl)
     };
 }
 
+TEST(Builder, SignStreamDetached)
+{
+    try
+    {
+        fs::path current_dir = fs::path(__FILE__).parent_path();
+
+        // Construct the paths relative to the current directory
+        fs::path manifest_path = current_dir / "../tests/fixtures/training.json";
+        fs::path certs_path = current_dir / "../tests/fixtures/es256_certs.pem";
+        fs::path signed_image_path = current_dir / "../tests/fixtures/A.jpg";
+
+        auto manifest = read_text_file(manifest_path);
+        auto certs = read_text_file(certs_path);
+
+        // create a signer
+        c2pa::Signer signer = c2pa::Signer(&test_signer, Es256, certs, "http://timestamp.digicert.com");
+
+        auto builder = c2pa::Builder(manifest);
+        builder.set_no_embed();
+
+        std::ifstream source(signed_image_path, std::ios::binary);
+        if (!source)
+        {
+            FAIL() << "Failed to open file: " << signed_image_path << std::endl;
+        }
+
+        // Create a memory buffer
+        std::stringstream memory_buffer(std::ios::in | std::ios::out | std::ios::binary);
+        std::iostream &dest = memory_buffer;
+        auto manifest_data = builder.sign("image/jpeg", source, dest, signer);
+        source.close();
+
+        // validate the detached manifest against the asset
+        dest.flush();
+        dest.seekp(0, std::ios::beg);
+        auto reader = c2pa::Reader("image/jpeg", manifest_data, dest);
+        auto json = reader.json();
+        ASSERT_TRUE(json.find("cawg.training-mining") != std::string::npos);
+    }
+    catch (c2pa::Exception const &e)
+    {
+        FAIL() << "Failed: C2pa::Builder: " << e.what() << endl;
+    };
+}
+
 TEST(Builder, SignDataHashedEmbedded)
 {
     try
@@ -0,0 +1,131 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+// Whether remote manifests are fetched is a process-wide setting, so these tests run
+// in their own process, one after another in a single test.
+
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    io::Cursor,
+    sync::Mutex,
+};
+
+use c2pa::{CallbackSigner, SigningAlg};
+use c2pa_c::C2paErrorCode;
+
+const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+const URL: &str = "http://manifests.example.com/test.c2pa";
+
+// the manifest store served for URL
+static MANIFEST: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+unsafe extern "C" fn get(
+    _context: *const c_void,
+    url: *const c_char,
+    buffer: *mut u8,
+    buffer_len: usize,
+) -> isize {
+    if CStr::from_ptr(url).to_str() != Ok(URL) {
+        return -1;
+    }
+    let manifest = MANIFEST.lock().unwrap();
+    if manifest.len() <= buffer_len {
+        std::ptr::copy_nonoverlapping(manifest.as_ptr(), buffer, manifest.len());
+    }
+    manifest.len() as isize
+}
+
+// Signs A.jpg with its manifest store kept at URL rather than embedded.
+fn signed_asset() -> Vec<u8> {
+    let ed_signer =
+        |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+    let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+    let manifest_json = std::fs::read_to_string("tests/fixtures/training.json").unwrap();
+    let mut builder = c2pa::Builder::from_json(&manifest_json).unwrap();
+    builder.set_remote_url(URL);
+    builder.set_no_embed(true);
+    let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+    let mut dest = Cursor::new(Vec::new());
+    let manifest = builder
+        .sign(&signer, "image/jpeg", &mut source, &mut dest)
+        .unwrap();
+    *MANIFEST.lock().unwrap() = manifest;
+    dest.into_inner()
+}
+
+// Reads an asset, returning the URL its manifest store was fetched from or the error code.
+unsafe fn read(asset: &[u8]) -> Result<String, C2paErrorCode> {
+    let format = CString::new("image/jpeg").unwrap();
+    let stream = c2pa_c::c2pa_stream_from_memory(asset.as_ptr(), asset.len());
+    let reader = c2pa_c::c2pa_reader_from_stream(format.as_ptr(), stream);
+    c2pa_c::c2pa_release_stream(stream);
+    if reader.is_null() {
+        return Err(c2pa_c::c2pa_error_code());
+    }
+    let url = c2pa_c::c2pa_reader_remote_url(reader);
+    c2pa_c::c2pa_reader_free(reader);
+    assert!(!url.is_null());
+    let remote_url = CStr::from_ptr(url).to_string_lossy().into_owned();
+    c2pa_c::c2pa_string_free(url);
+    Ok(remote_url)
+}
+
+unsafe fn fetch_remote() -> bool {
+    let mut options = std::mem::MaybeUninit::uninit();
+    assert_eq!(c2pa_c::c2pa_get_verify_options(options.as_mut_ptr()), 0);
+    options.assume_init().fetch_remote
+}
+
+unsafe fn load_settings(settings: &str, format: &str) {
+    let settings = CString::new(settings).unwrap();
+    let format = CString::new(format).unwrap();
+    assert_eq!(
+        c2pa_c::c2pa_load_settings(settings.as_ptr(), format.as_ptr()),
+        0
+    );
+}
+
+#[test]
+fn test_fetch_enabled() {
+    let asset = signed_asset();
+    unsafe {
+        assert_eq!(
+            c2pa_c::c2pa_set_remote_fetcher(std::ptr::null(), Some(get)),
+            0
+        );
+        assert_eq!(read(&asset).as_deref(), Ok(URL));
+
+        // no request is made while fetching is disabled
+        assert_eq!(c2pa_c::c2pa_set_remote_fetch_enabled(0), 0);
+        assert!(!fetch_remote());
+        assert_eq!(read(&asset), Err(C2paErrorCode::ErrorRemoteFetchDisabled));
+        assert_eq!(c2pa_c::c2pa_set_remote_fetch_enabled(1), 0);
+        assert_eq!(read(&asset).as_deref(), Ok(URL));
+
+        // the loaded fetch setting also covers the get callback
+        load_settings(r#"{"verify": {"remote_manifest_fetch": false}}"#, "json");
+        assert!(!fetch_remote());
+        assert_eq!(read(&asset), Err(C2paErrorCode::ErrorRemoteFetchDisabled));
+        load_settings("[verify]\nremote_manifest_fetch = true", "toml");
+        assert!(fetch_remote());
+        assert_eq!(read(&asset).as_deref(), Ok(URL));
+
+        // settings without it leave fetching as it was
+        assert_eq!(c2pa_c::c2pa_set_remote_fetch_enabled(0), 0);
+        load_settings(r#"{"verify": {"verify_after_reading": true}}"#, "json");
+        assert!(!fetch_remote());
+        assert_eq!(c2pa_c::c2pa_set_remote_fetch_enabled(1), 0);
+
+        assert_eq!(c2pa_c::c2pa_set_remote_fetcher(std::ptr::null(), None), 0);
+    }
+}
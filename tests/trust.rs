@@ -0,0 +1,133 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+// The trust settings are process-wide, so these tests run in their own process,
+// one after another in a single test.
+
+use std::{ffi::CString, io::Cursor};
+
+use c2pa::{CallbackSigner, SigningAlg};
+
+const CERTS: &[u8] = include_bytes!("../tests/fixtures/ed25519.pub");
+const PRIVATE_KEY: &[u8] = include_bytes!("../tests/fixtures/ed25519.pem");
+
+// Signs A.jpg with the ed25519 test certificates.
+fn signed_asset() -> Vec<u8> {
+    let ed_signer =
+        |_context: *const (), data: &[u8]| CallbackSigner::ed25519_sign(data, PRIVATE_KEY);
+    let signer = CallbackSigner::new(ed_signer, SigningAlg::Ed25519, CERTS);
+    let mut builder = c2pa::Builder::from_json(r#"{"title": "A.jpg"}"#).unwrap();
+    let mut source = std::fs::File::open("tests/fixtures/A.jpg").unwrap();
+    let mut dest = Cursor::new(Vec::new());
+    builder
+        .sign(&signer, "image/jpeg", &mut source, &mut dest)
+        .unwrap();
+    dest.into_inner()
+}
+
+// The validation status codes of reading an asset.
+fn status_codes(asset: &[u8]) -> Vec<String> {
+    let reader = c2pa_c::Reader::from_stream("image/jpeg", Cursor::new(asset)).unwrap();
+    reader
+        .validation_status()
+        .unwrap_or_default()
+        .iter()
+        .map(|status| status.code().to_string())
+        .collect()
+}
+
+// The trust section of the current settings.
+fn trust_settings() -> serde_json::Value {
+    let settings = c2pa::settings::Settings::from_string("{}", "json").unwrap();
+    serde_json::to_value(settings).unwrap()["trust"].clone()
+}
+
+#[test]
+fn test_set_trust() {
+    let asset = signed_asset();
+    let pem = std::fs::read_to_string("tests/fixtures/ed25519.pub").unwrap();
+    let end = "-----END CERTIFICATE-----";
+    // the signing certificate, without the intermediate
+    let leaf = CString::new(&pem[..pem.find(end).unwrap() + end.len()]).unwrap();
+    let other = CString::new(std::fs::read("tests/fixtures/es256_certs.pem").unwrap()).unwrap();
+    let not_pem = CString::new("-----BEGIN CERTIFICATE-----\nnot base64").unwrap();
+    let config = CString::new("//id-kp-emailProtection\n1.3.6.1.5.5.7.3.4\n").unwrap();
+    let previous = trust_settings();
+    unsafe {
+        let mut verify_options = std::mem::MaybeUninit::uninit();
+        assert_eq!(
+            c2pa_c::c2pa_get_verify_options(verify_options.as_mut_ptr()),
+            0
+        );
+        let verify_options = verify_options.assume_init();
+
+        // setting anchors turns on trust checks
+        assert_eq!(c2pa_c::c2pa_set_trust_anchors(other.as_ptr()), 0);
+        let codes = status_codes(&asset);
+        assert!(
+            codes
+                .iter()
+                .any(|code| code == "signingCredential.untrusted"),
+            "{codes:?}"
+        );
+
+        assert_eq!(c2pa_c::c2pa_set_trust_anchors(not_pem.as_ptr()), -1);
+        assert_eq!(c2pa_c::c2pa_set_trust_config(config.as_ptr()), 0);
+        assert_eq!(c2pa_c::c2pa_set_trust_config(std::ptr::null()), 0);
+
+        // an allowed certificate is trusted whatever it chains to
+        assert_eq!(c2pa_c::c2pa_set_allowed_list(leaf.as_ptr()), 0);
+        let codes = status_codes(&asset);
+        assert!(codes.is_empty(), "{codes:?}");
+
+        assert_eq!(c2pa_c::c2pa_set_trust_anchors(std::ptr::null()), 0);
+        assert_eq!(c2pa_c::c2pa_set_allowed_list(std::ptr::null()), 0);
+
+        // c2pa_init loads the trust settings from their sources
+        let options = CString::new(
+            r#"{"trust_refresh": {"interval_secs": 1, "trust_anchors": "tests/fixtures/es256_certs.pem"}}"#,
+        )
+        .unwrap();
+        assert_eq!(c2pa_c::c2pa_init(options.as_ptr()), 0);
+        c2pa_c::c2pa_shutdown();
+        assert_eq!(
+            trust_settings()["trust_anchors"].as_str(),
+            other.to_str().ok()
+        );
+        assert_eq!(c2pa_c::c2pa_set_trust_anchors(std::ptr::null()), 0);
+
+        let options = CString::new(
+            r#"{"single_threaded": true, "trust_refresh": {"trust_anchors": "tests/fixtures/es256_certs.pem"}}"#,
+        )
+        .unwrap();
+        assert_eq!(c2pa_c::c2pa_init(options.as_ptr()), 0);
+        assert_eq!(c2pa_c::c2pa_set_trust_anchors(std::ptr::null()), 0);
+        // the manual refresh loads them again
+        assert_eq!(c2pa_c::c2pa_refresh(), 0);
+        assert!(trust_settings()["trust_anchors"].is_string());
+        c2pa_c::c2pa_shutdown();
+
+        let options =
+            CString::new(r#"{"trust_refresh": {"trust_anchors": "missing.pem"}}"#).unwrap();
+        assert_eq!(c2pa_c::c2pa_init(options.as_ptr()), -1);
+
+        // put back the settings this test replaced
+        let settings = CString::new(serde_json::json!({ "trust": previous }).to_string()).unwrap();
+        let format = CString::new("json").unwrap();
+        assert_eq!(
+            c2pa_c::c2pa_load_settings(settings.as_ptr(), format.as_ptr()),
+            0
+        );
+        assert_eq!(c2pa_c::c2pa_set_verify_options(&verify_options), 0);
+        assert_eq!(trust_settings(), previous);
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+// The verify settings are process-wide, so these tests run in their own process,
+// one after another in a single test.
+
+use std::ffi::{CStr, CString};
+
+use c2pa_c::C2paVerifyOptions;
+
+const CERTS: &str = include_str!("../tests/fixtures/ed25519.pub");
+const PRIVATE_KEY: &str = include_str!("../tests/fixtures/ed25519.pem");
+
+unsafe fn verify_options() -> C2paVerifyOptions {
+    let mut options = std::mem::MaybeUninit::<C2paVerifyOptions>::uninit();
+    assert_eq!(c2pa_c::c2pa_get_verify_options(options.as_mut_ptr()), 0);
+    options.assume_init()
+}
+
+// Reads an asset with verify settings of its own, returning its validation status codes.
+unsafe fn status_codes(asset: &[u8], options: &C2paVerifyOptions) -> Vec<String> {
+    let format = CString::new("image/jpeg").unwrap();
+    let stream = c2pa_c::c2pa_stream_from_memory(asset.as_ptr(), asset.len());
+    let reader =
+        c2pa_c::c2pa_reader_from_stream_with_verify_options(format.as_ptr(), stream, options);
+    c2pa_c::c2pa_release_stream(stream);
+    assert!(!reader.is_null());
+    let status = c2pa_c::c2pa_reader_validation_status(reader);
+    c2pa_c::c2pa_reader_free(reader);
+    let codes = match status.is_null() {
+        true => Vec::new(),
+        false => {
+            let json = CStr::from_ptr(status).to_string_lossy().into_owned();
+            c2pa_c::c2pa_string_free(status);
+            let status: serde_json::Value = serde_json::from_str(&json).unwrap();
+            status
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|status| status["code"].as_str().map(str::to_string))
+                .collect()
+        }
+    };
+    codes
+}
+
+#[test]
+fn test_verify_options_scoped() {
+    let alg = CString::new("ed25519").unwrap();
+    let sign_cert = CString::new(CERTS).unwrap();
+    let private_key = CString::new(PRIVATE_KEY).unwrap();
+    let signer_info = c2pa_c::C2paSignerInfo {
+        alg: alg.as_ptr(),
+        sign_cert: sign_cert.as_ptr(),
+        private_key: private_key.as_ptr(),
+        ta_url: std::ptr::null(),
+    };
+    let manifest = CString::new(r#"{"title": "A.jpg"}"#).unwrap();
+    let format = CString::new("image/jpeg").unwrap();
+    let source = std::fs::read("tests/fixtures/A.jpg").unwrap();
+    unsafe {
+        let defaults = verify_options();
+        let untrusted = C2paVerifyOptions {
+            verify_trust: true,
+            ..defaults
+        };
+        let unchecked = C2paVerifyOptions {
+            verify_trust: false,
+            ..defaults
+        };
+
+        let signer = c2pa_c::c2pa_signer_from_info(&signer_info);
+        let builder = c2pa_c::c2pa_builder_from_json(manifest.as_ptr());
+        let source_stream = c2pa_c::c2pa_stream_from_memory(source.as_ptr(), source.len());
+        let dest = c2pa_c::c2pa_stream_memory_writer_create();
+        let mut manifest_bytes = std::ptr::null();
+        let len = c2pa_c::c2pa_builder_sign_with_verify_options(
+            builder,
+            format.as_ptr(),
+            source_stream,
+            dest,
+            signer,
+            &unchecked,
+            &mut manifest_bytes,
+        );
+        assert!(len > 0);
+        c2pa_c::c2pa_manifest_bytes_free(manifest_bytes);
+        // the settings are put back after signing
+        assert_eq!(verify_options(), defaults);
+
+        let mut data = std::ptr::null();
+        let mut data_len = 0;
+        assert_eq!(
+            c2pa_c::c2pa_stream_memory_writer_data(dest, &mut data, &mut data_len),
+            0
+        );
+        let asset = std::slice::from_raw_parts(data, data_len).to_vec();
+        c2pa_c::c2pa_release_stream(dest);
+        c2pa_c::c2pa_release_stream(source_stream);
+        c2pa_c::c2pa_builder_free(builder);
+        c2pa_c::c2pa_signer_free(signer);
+
+        // the test certificates are not trusted when trust is checked
+        let codes = status_codes(&asset, &untrusted);
+        assert!(
+            codes
+                .iter()
+                .any(|code| code == "signingCredential.untrusted"),
+            "{codes:?}"
+        );
+        assert_eq!(verify_options(), defaults);
+        let codes = status_codes(&asset, &unchecked);
+        assert!(codes.is_empty(), "{codes:?}");
+        assert_eq!(verify_options(), defaults);
+
+        let stream = c2pa_c::c2pa_stream_from_memory(asset.as_ptr(), asset.len());
+        let reader = c2pa_c::c2pa_reader_from_stream_with_verify_options(
+            format.as_ptr(),
+            stream,
+            std::ptr::null(),
+        );
+        assert!(reader.is_null());
+        c2pa_c::c2pa_release_stream(stream);
+    }
+}